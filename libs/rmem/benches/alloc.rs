@@ -0,0 +1,86 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use rmem::{free, malloc, zfree, zmalloc, zrealloc, zrealloc_with_headroom};
+
+/// Sizes (from 8B to 1MB) of memory benchmarked.
+const SIZES: [usize; 7] = [8, 64, 512, 4 << 10, 32 << 10, 256 << 10, 1 << 20];
+
+/// Number of allocations live at the same time in churn benchmarks.
+const CHURN_DEPTH: usize = 64;
+
+fn bench_malloc_free(c: &mut Criterion) {
+    let mut group = c.benchmark_group("malloc_free");
+    for &size in SIZES.iter() {
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            b.iter(|| {
+                let (ptr, size) = malloc(black_box(size));
+                free(black_box(ptr), size);
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_malloc_churn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("malloc_churn");
+    for &size in SIZES.iter() {
+        group.throughput(Throughput::Bytes((size * CHURN_DEPTH) as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            let mut live = Vec::with_capacity(CHURN_DEPTH);
+            b.iter(|| {
+                for _ in 0..CHURN_DEPTH {
+                    live.push(malloc(black_box(size)));
+                }
+                // Deallocate in the interleaved order, rather than LIFO.
+                for idx in (0..CHURN_DEPTH)
+                    .step_by(2)
+                    .chain((1..CHURN_DEPTH).step_by(2))
+                {
+                    let (ptr, size) = live[idx];
+                    free(ptr, size);
+                }
+                live.clear();
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_zrealloc_growth(c: &mut Criterion) {
+    let mut group = c.benchmark_group("zrealloc_growth");
+    for &size in SIZES.iter().skip(1) {
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("by_8", size), &size, |b, &size| {
+            b.iter(|| {
+                let (mut ptr, _) = zmalloc(8);
+                let mut needed = 8;
+                while needed < size {
+                    needed += 8;
+                    ptr = zrealloc(ptr, black_box(needed)).0;
+                }
+                zfree(ptr);
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("headroom", size), &size, |b, &size| {
+            b.iter(|| {
+                let (mut ptr, _) = zmalloc(8);
+                let mut needed = 8;
+                while needed < size {
+                    needed += 8;
+                    ptr = zrealloc_with_headroom(ptr, black_box(needed)).0;
+                }
+                zfree(ptr);
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_malloc_free,
+    bench_malloc_churn,
+    bench_zrealloc_growth
+);
+criterion_main!(benches);
@@ -0,0 +1,46 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use rmem::{mem_copy, mem_find};
+
+/// Sizes (from 8B to 1MB) of memory benchmarked.
+const SIZES: [usize; 7] = [8, 64, 512, 4 << 10, 32 << 10, 256 << 10, 1 << 20];
+
+fn bench_mem_copy(c: &mut Criterion) {
+    let mut group = c.benchmark_group("mem_copy");
+    for &size in SIZES.iter() {
+        let src = vec![0xA5u8; size];
+        let mut dst = vec![0u8; size];
+
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("rmem", size), &size, |b, &size| {
+            b.iter(|| unsafe { mem_copy(black_box(src.as_ptr()), dst.as_mut_ptr(), size) })
+        });
+        group.bench_with_input(BenchmarkId::new("std", size), &size, |b, &size| {
+            b.iter(|| unsafe {
+                std::ptr::copy_nonoverlapping(black_box(src.as_ptr()), dst.as_mut_ptr(), size)
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_mem_find(c: &mut Criterion) {
+    let mut group = c.benchmark_group("mem_find");
+    for &size in SIZES.iter() {
+        // The byte searched for is the last one, that the whole memory is scanned.
+        let mut hay = vec![b'a'; size];
+        hay[size - 1] = b'z';
+
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("rmem", size), &size, |b, &size| {
+            b.iter(|| unsafe { mem_find(black_box(hay.as_ptr()), size, b'z') })
+        });
+        group.bench_with_input(BenchmarkId::new("std", size), &size, |b, _| {
+            b.iter(|| black_box(&hay).iter().position(|&byte| byte == b'z'))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_mem_copy, bench_mem_find);
+criterion_main!(benches);
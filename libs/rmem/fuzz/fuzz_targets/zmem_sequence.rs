@@ -0,0 +1,114 @@
+#![no_main]
+
+//! Replay arbitrary interleavings of ZMEM-style allocations, where ALL live memory is
+//! fully written & verified, thus header arithmetic bugs are caught (by ASAN under
+//! `cargo fuzz run zmem_sequence`) as buffer overruns or corrupted content.
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+
+use rmem::{mem_set, zcalloc, zfree, zmalloc, zmem_size_of, zmem_usable_size, zrealloc};
+
+/// MAX size of memory (re)allocated, that the fuzzer doesn't run out of memory.
+const MAX_SIZE: usize = 1 << 16;
+/// MAX number of memory blocks live at the same time.
+const MAX_LIVE: usize = 64;
+
+#[derive(Arbitrary, Debug)]
+enum Op {
+    Malloc(u16),
+    Calloc(u16),
+    Realloc { slot: u8, size: u16 },
+    ReallocNull(u16),
+    Free(u8),
+    FreeNull,
+    SizeOf(u8),
+}
+
+/// A live memory block, filled with its `fill` byte.
+struct Block {
+    ptr: *mut u8,
+    size: usize,
+    fill: u8,
+}
+
+impl Block {
+    fn new(ptr: *mut u8, size: usize, fill: u8) -> Block {
+        assert!(!ptr.is_null());
+        assert_eq!(zmem_size_of(ptr), size);
+        assert!(zmem_usable_size(ptr) >= size);
+        unsafe { mem_set(ptr, fill, size) };
+        Block { ptr, size, fill }
+    }
+
+    /// Verify the first `len` bytes of the block.
+    fn verify(&self, len: usize) {
+        let bytes = unsafe { std::slice::from_raw_parts(self.ptr, len) };
+        assert!(bytes.iter().all(|&byte| byte == self.fill));
+    }
+}
+
+fn size_of(size: u16) -> usize {
+    size as usize % MAX_SIZE
+}
+
+fuzz_target!(|ops: Vec<Op>| {
+    let mut live: Vec<Block> = Vec::new();
+    let mut fill = 0u8;
+
+    for op in ops {
+        fill = fill.wrapping_add(1);
+        let full = live.len() >= MAX_LIVE;
+        match op {
+            Op::Malloc(size) if !full => {
+                let (ptr, size) = zmalloc(size_of(size));
+                live.push(Block::new(ptr, size, fill));
+            }
+            Op::Calloc(size) if !full => {
+                let (ptr, size) = zcalloc(size_of(size));
+                let zeroed = Block { ptr, size, fill: 0 };
+                zeroed.verify(size);
+                live.push(Block::new(ptr, size, fill));
+            }
+            Op::Realloc { slot, size } => {
+                if !live.is_empty() {
+                    let block = live.swap_remove(slot as usize % live.len());
+                    block.verify(block.size);
+                    let (ptr, new_size) = zrealloc(block.ptr, size_of(size));
+                    let kept = Block {
+                        ptr,
+                        size: new_size,
+                        fill: block.fill,
+                    };
+                    kept.verify(std::cmp::min(block.size, new_size));
+                    live.push(Block::new(ptr, new_size, fill));
+                }
+            }
+            Op::ReallocNull(size) if !full => {
+                let (ptr, size) = zrealloc(std::ptr::null_mut(), size_of(size));
+                live.push(Block::new(ptr, size, fill));
+            }
+            Op::Free(slot) => {
+                if !live.is_empty() {
+                    let block = live.swap_remove(slot as usize % live.len());
+                    block.verify(block.size);
+                    zfree(block.ptr);
+                }
+            }
+            Op::FreeNull => zfree(std::ptr::null_mut()),
+            Op::SizeOf(slot) => {
+                assert_eq!(zmem_size_of(std::ptr::null_mut()), 0);
+                if !live.is_empty() {
+                    let block = &live[slot as usize % live.len()];
+                    assert_eq!(zmem_size_of(block.ptr), block.size);
+                }
+            }
+            Op::Malloc(_) | Op::Calloc(_) | Op::ReallocNull(_) => {}
+        }
+    }
+
+    for block in live {
+        block.verify(block.size);
+        zfree(block.ptr);
+    }
+});
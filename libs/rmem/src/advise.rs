@@ -0,0 +1,281 @@
+use std::io;
+
+////////////////////////////////////////////////////////////////////////////////
+// Memory Advice
+////////////////////////////////////////////////////////////////////////////////
+
+/// Transparent huge page (THP) policy of the current process.
+///
+/// Fork-based snapshots on THP-enabled kernels cause large copy-on-write spikes
+/// (a whole huge page is copied once a single byte of it is written), thus THP SHOULD be
+/// disabled for a process forking for snapshots.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ThpPolicy {
+    /// Follow the system-wide setting (`/sys/kernel/mm/transparent_hugepage/enabled`).
+    System,
+    /// Disable THP for the process (and its children forked), regardless of the system-wide
+    /// setting.
+    Disabled,
+}
+
+/// Get the size (in bytes) of memory pages of the system.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::page_size;
+///
+/// assert!(page_size().is_power_of_two());
+/// ```
+#[inline]
+pub fn page_size() -> usize {
+    sys::page_size()
+}
+
+/// Tell the kernel that memory of the region `[ptr, ptr + len)` is NOT needed any more,
+/// that its pages are released (and RSS dropped) immediately, while the region stays VALID.
+///
+/// ONLY the whole pages within the region are affected, which read as ZERO (on Linux)
+/// once accessed again.
+///
+/// # Errors
+///
+/// An `Unsupported` error is returned on platforms without `madvise` (or with the `no-libc`
+/// feature enabled), and the OS error is returned if `madvise` fails.
+///
+/// # Safety
+///
+/// The region MUST be a part of one VALID memory block, and content of the whole pages
+/// within the region is discarded, which MUST NOT be used any more.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{free, madvise_dontneed, malloc, page_size};
+/// # use std::io::ErrorKind;
+///
+/// let (ptr, size) = malloc(page_size() * 4);
+/// match unsafe { madvise_dontneed(ptr, size) } {
+///     Ok(()) => {}
+///     Err(err) => assert_eq!(err.kind(), ErrorKind::Unsupported),
+/// }
+///
+/// free(ptr, size);
+/// ```
+pub unsafe fn madvise_dontneed(ptr: *mut u8, len: usize) -> io::Result<()> {
+    let page = page_size();
+    let start = (ptr as usize + page - 1) & !(page - 1);
+    let end = (ptr as usize + len) & !(page - 1);
+    if start >= end {
+        return Ok(());
+    }
+    sys::madvise_dontneed(start, end - start)
+}
+
+/// Disable transparent huge pages for the pages covering the region `[ptr, ptr + len)`.
+///
+/// It has no effect on platforms without THP (anything but Linux).
+///
+/// # Errors
+///
+/// The OS error is returned if `madvise` fails (such as kernels built without THP).
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{disable_thp_for_region, free, malloc};
+///
+/// let (ptr, size) = malloc(4 << 20);
+/// let _ = disable_thp_for_region(ptr, size);
+///
+/// free(ptr, size);
+/// ```
+pub fn disable_thp_for_region(ptr: *mut u8, len: usize) -> io::Result<()> {
+    if len == 0 {
+        return Ok(());
+    }
+    let page = page_size();
+    let start = ptr as usize & !(page - 1);
+    let end = (ptr as usize + len + page - 1) & !(page - 1);
+    sys::madvise_nohugepage(start, end - start)
+}
+
+/// Set the transparent huge page (THP) policy of the current process, which is inherited
+/// by the children forked.
+///
+/// It has no effect on platforms without THP (anything but Linux).
+///
+/// # Errors
+///
+/// The OS error is returned if `prctl` fails (such as kernels older than 3.15).
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{set_thp_policy, ThpPolicy};
+///
+/// // Disable THP before forking for snapshots.
+/// if let Err(err) = set_thp_policy(ThpPolicy::Disabled) {
+///     eprintln!("failed to disable THP: {}", err);
+/// }
+/// # let _ = set_thp_policy(ThpPolicy::System);
+/// ```
+pub fn set_thp_policy(policy: ThpPolicy) -> io::Result<()> {
+    sys::set_thp_disabled(policy == ThpPolicy::Disabled)
+}
+
+/// Get the transparent huge page (THP) policy of the current process.
+#[inline]
+pub fn thp_policy() -> ThpPolicy {
+    match sys::thp_disabled() {
+        true => ThpPolicy::Disabled,
+        false => ThpPolicy::System,
+    }
+}
+
+#[cfg(all(unix, not(any(feature = "no-libc", miri))))]
+mod sys {
+    use std::io;
+
+    #[inline]
+    pub(super) fn page_size() -> usize {
+        unsafe { libc::sysconf(libc::_SC_PAGESIZE) as usize }
+    }
+
+    #[inline]
+    fn madvise(addr: usize, len: usize, advice: libc::c_int) -> io::Result<()> {
+        match unsafe { libc::madvise(addr as *mut libc::c_void, len, advice) } {
+            0 => Ok(()),
+            _ => Err(io::Error::last_os_error()),
+        }
+    }
+
+    #[inline]
+    pub(super) fn madvise_dontneed(addr: usize, len: usize) -> io::Result<()> {
+        madvise(addr, len, libc::MADV_DONTNEED)
+    }
+
+    #[inline]
+    pub(super) fn madvise_nohugepage(addr: usize, len: usize) -> io::Result<()> {
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        return madvise(addr, len, libc::MADV_NOHUGEPAGE);
+        #[cfg(not(any(target_os = "linux", target_os = "android")))]
+        return {
+            let _ = (addr, len);
+            Ok(())
+        };
+    }
+
+    #[inline]
+    pub(super) fn set_thp_disabled(disabled: bool) -> io::Result<()> {
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        return match unsafe {
+            libc::prctl(libc::PR_SET_THP_DISABLE, disabled as libc::c_ulong, 0, 0, 0)
+        } {
+            0 => Ok(()),
+            _ => Err(io::Error::last_os_error()),
+        };
+        #[cfg(not(any(target_os = "linux", target_os = "android")))]
+        return {
+            let _ = disabled;
+            Ok(())
+        };
+    }
+
+    #[inline]
+    pub(super) fn thp_disabled() -> bool {
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        return unsafe { libc::prctl(libc::PR_GET_THP_DISABLE, 0, 0, 0, 0) } > 0;
+        #[cfg(not(any(target_os = "linux", target_os = "android")))]
+        return false;
+    }
+}
+
+#[cfg(not(all(unix, not(any(feature = "no-libc", miri)))))]
+mod sys {
+    use std::io;
+
+    /// The most common page size, assumed without `libc`.
+    #[inline]
+    pub(super) fn page_size() -> usize {
+        4096
+    }
+
+    #[inline]
+    pub(super) fn madvise_dontneed(addr: usize, len: usize) -> io::Result<()> {
+        let _ = (addr, len);
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "madvise is unavailable",
+        ))
+    }
+
+    #[inline]
+    pub(super) fn madvise_nohugepage(addr: usize, len: usize) -> io::Result<()> {
+        let _ = (addr, len);
+        Ok(())
+    }
+
+    #[inline]
+    pub(super) fn set_thp_disabled(disabled: bool) -> io::Result<()> {
+        let _ = disabled;
+        Ok(())
+    }
+
+    #[inline]
+    pub(super) fn thp_disabled() -> bool {
+        false
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Unit Tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod advise_tests {
+    use super::*;
+
+    use crate::{free, malloc, mem_set};
+
+    #[cfg(all(target_os = "linux", not(any(feature = "no-libc", miri))))]
+    #[test]
+    fn release_whole_pages_only() {
+        let page = page_size();
+        let (ptr, size) = malloc(page * 4);
+        unsafe {
+            mem_set(ptr, 0xAB, size);
+            madvise_dontneed(ptr.add(1), size - 2).unwrap();
+
+            // Partial pages at both ends are kept as is.
+            assert_eq!(*ptr, 0xAB);
+            assert_eq!(*ptr.add(size - 1), 0xAB);
+            let start = (ptr as usize + 1 + page - 1) & !(page - 1);
+            assert_eq!(*(start as *const u8), 0);
+        }
+        free(ptr, size);
+    }
+
+    #[cfg(all(target_os = "linux", not(any(feature = "no-libc", miri))))]
+    #[test]
+    fn switch_thp_policy() {
+        set_thp_policy(ThpPolicy::Disabled).unwrap();
+        assert_eq!(thp_policy(), ThpPolicy::Disabled);
+
+        set_thp_policy(ThpPolicy::System).unwrap();
+        assert_eq!(thp_policy(), ThpPolicy::System);
+    }
+
+    #[test]
+    fn advise_empty_region() {
+        let (ptr, size) = malloc(16);
+        unsafe {
+            mem_set(ptr, 1, size);
+            assert!(madvise_dontneed(ptr, 0).is_ok());
+            assert!(madvise_dontneed(ptr, size).is_ok());
+            assert_eq!(*ptr.add(15), 1);
+        }
+        assert!(disable_thp_for_region(ptr, 0).is_ok());
+        free(ptr, size);
+    }
+}
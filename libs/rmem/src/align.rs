@@ -6,7 +6,6 @@
 ///
 /// `System-Dependent Alignment` is defined as the alignment of `pointer/isize/usize` type,
 /// that it values 4 bytes on 32-bit OS while 8 bytes on 64-bit OS.
-
 pub const BYTE_ALIGN_SIZE: usize = 1usize;
 pub const SYS_ALIGN_SIZE: usize = align_of::<usize>();
 
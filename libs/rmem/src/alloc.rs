@@ -1,6 +1,18 @@
 use std::alloc::{handle_alloc_error, Layout};
-
-use crate::{align_of, size_of, size_of_aligned, BYTE_ALIGN_SIZE};
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::RwLock;
+
+use crate::guard::{checked_zmem_header, fill_freed, guarded_usable_size};
+use crate::guard::{guarded_alloc, guarded_alloc_zeroed, guarded_dealloc, guarded_realloc};
+#[cfg(feature = "leak-track")]
+use crate::leak::{track_alloc, track_free, track_resize};
+use crate::tag::tag_stat_add;
+use crate::thread::{cache_keep, cache_take};
+use crate::thread::{thread_stat_alloc, thread_stat_free, thread_stat_realloc};
+#[cfg(feature = "alloc-trace")]
+use crate::trace::{trace_alloc, trace_free, trace_realloc};
+use crate::{align_of, mem_copy, mem_set, size_of, size_of_aligned, BYTE_ALIGN_SIZE};
 
 ////////////////////////////////////////////////////////////////////////////////
 // Memory Layout
@@ -36,6 +48,365 @@ const unsafe fn layout_of_aligned(size: usize, align: usize) -> Layout {
     Layout::from_size_align_unchecked(size, align)
 }
 
+/// Create a layout (for `memory allocation/deallocation`) for amount of bytes/buffer,
+/// failing if `size` is too large to be represented by a valid layout.
+#[inline]
+fn try_layout_of_bytes(size: usize) -> Result<Layout, AllocError> {
+    Layout::from_size_align(size, BYTE_ALIGN_SIZE).map_err(|_| AllocError::new(size))
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Memory Allocation Error
+////////////////////////////////////////////////////////////////////////////////
+
+/// The error returned by fallible memory allocation (`try_*`) functions, while memory
+/// allocation errors/failures occur (such as OOM or a size too large to be represented),
+/// or the memory limit (set with `set_memory_limit`) is exceeded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AllocError {
+    size: usize,
+    over_limit: bool,
+}
+
+impl AllocError {
+    #[inline]
+    pub(crate) const fn new(size: usize) -> Self {
+        AllocError {
+            size,
+            over_limit: false,
+        }
+    }
+
+    #[inline]
+    const fn over_limit(size: usize) -> Self {
+        AllocError {
+            size,
+            over_limit: true,
+        }
+    }
+
+    /// Get the size (in bytes) of the memory failed to be allocated.
+    #[inline]
+    pub const fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Check if the allocation is rejected because of the memory limit exceeded.
+    #[inline]
+    pub const fn is_over_limit(&self) -> bool {
+        self.over_limit
+    }
+}
+
+impl fmt::Display for AllocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.over_limit {
+            true => write!(
+                f,
+                "memory allocation of {} bytes exceeds the memory limit",
+                self.size
+            ),
+            false => write!(f, "memory allocation of {} bytes failed", self.size),
+        }
+    }
+}
+
+impl std::error::Error for AllocError {}
+
+////////////////////////////////////////////////////////////////////////////////
+// Memory Usage Statistics
+////////////////////////////////////////////////////////////////////////////////
+
+/// Total bytes of memory currently allocated (and not yet deallocated) via `rmem`.
+static USED_MEMORY: AtomicUsize = AtomicUsize::new(0);
+/// The MAX value `USED_MEMORY` has ever reached.
+static USED_MEMORY_PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// Account `size` bytes of newly allocated memory.
+#[inline]
+pub(crate) fn stat_alloc(size: usize) {
+    stat_used_add(size);
+    thread_stat_alloc(size);
+    tag_stat_add(size as isize);
+    histogram_add(size);
+}
+
+/// Account `size` bytes of deallocated memory.
+#[inline]
+pub(crate) fn stat_free(size: usize) {
+    stat_used_sub(size);
+    thread_stat_free(size);
+    tag_stat_add(-(size as isize));
+    histogram_sub(size);
+}
+
+/// Account memory resized from `old_size` bytes to `new_size` bytes.
+#[inline]
+pub(crate) fn stat_realloc(old_size: usize, new_size: usize) {
+    if new_size > old_size {
+        stat_used_add(new_size - old_size);
+    } else {
+        stat_used_sub(old_size - new_size);
+    }
+    thread_stat_realloc(old_size, new_size);
+    tag_stat_add(new_size as isize - old_size as isize);
+    if bucket_of(old_size) != bucket_of(new_size) {
+        histogram_sub(old_size);
+        histogram_add(new_size);
+    }
+}
+
+#[inline]
+fn stat_used_add(size: usize) {
+    let used = USED_MEMORY.fetch_add(size, Ordering::Relaxed) + size;
+    USED_MEMORY_PEAK.fetch_max(used, Ordering::Relaxed);
+}
+
+#[inline]
+fn stat_used_sub(size: usize) {
+    USED_MEMORY.fetch_sub(size, Ordering::Relaxed);
+}
+
+/// Get total bytes of memory currently allocated via `rmem` (ZMEM headers included).
+///
+/// All allocation/deallocation functions (`malloc`/`calloc`/`realloc`/`free`,
+/// their typed `*_for` variants and ZMEM-style ones) are accounted.
+///
+/// # Notes
+///
+/// The counter is updated atomically but with relaxed ordering, that it is accurate
+/// for reporting purpose, while it SHOULD NOT be used for synchronization.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{used_memory, zfree, zmalloc};
+///
+/// let (ptr, _) = zmalloc(1024);
+/// assert!(used_memory() >= 1024);
+///
+/// zfree(ptr);
+/// ```
+#[inline]
+pub fn used_memory() -> usize {
+    USED_MEMORY.load(Ordering::Relaxed)
+}
+
+/// Get the peak value of `used_memory` since the process started.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{free, malloc, used_memory, used_memory_peak};
+///
+/// let (ptr, size) = malloc(4096);
+/// free(ptr, size);
+///
+/// assert!(used_memory_peak() >= 4096);
+/// assert!(used_memory_peak() >= used_memory());
+/// ```
+#[inline]
+pub fn used_memory_peak() -> usize {
+    USED_MEMORY_PEAK.load(Ordering::Relaxed)
+}
+
+/// Number of buckets of the live allocation size histogram.
+pub const SIZE_HISTOGRAM_BUCKETS: usize = usize::BITS as usize + 1;
+
+/// Number of live allocations in each bucket of the size histogram.
+#[allow(clippy::declare_interior_mutable_const)]
+const BUCKET_INIT: AtomicUsize = AtomicUsize::new(0);
+static SIZE_HISTOGRAM: [AtomicUsize; SIZE_HISTOGRAM_BUCKETS] =
+    [BUCKET_INIT; SIZE_HISTOGRAM_BUCKETS];
+
+/// Locate the histogram bucket of a size, which is `ceil(log2(size))`.
+#[inline]
+fn bucket_of(size: usize) -> usize {
+    (usize::BITS - size.saturating_sub(1).leading_zeros()) as usize
+}
+
+#[inline]
+fn histogram_add(size: usize) {
+    SIZE_HISTOGRAM[bucket_of(size)].fetch_add(1, Ordering::Relaxed);
+}
+
+#[inline]
+fn histogram_sub(size: usize) {
+    SIZE_HISTOGRAM[bucket_of(size)].fetch_sub(1, Ordering::Relaxed);
+}
+
+/// Get the log2-bucketed histogram of live allocation sizes, where the bucket `i` is
+/// the number of live allocations with sizes in `(2^(i-1), 2^i]` bytes (`[0, 1]` for
+/// the bucket `0`).
+///
+/// Allocations are accounted as `used_memory` does.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{free, malloc, size_histogram};
+///
+/// let (ptr, size) = malloc(3000);
+/// assert!(size_histogram()[12] >= 1);
+///
+/// free(ptr, size);
+/// ```
+pub fn size_histogram() -> [usize; SIZE_HISTOGRAM_BUCKETS] {
+    let mut histogram = [0; SIZE_HISTOGRAM_BUCKETS];
+    for (count, bucket) in histogram.iter_mut().zip(SIZE_HISTOGRAM.iter()) {
+        *count = bucket.load(Ordering::Relaxed);
+    }
+    histogram
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Memory Limit
+////////////////////////////////////////////////////////////////////////////////
+
+/// MAX bytes of memory permitted to be allocated via `rmem`, and ZERO means no limit.
+static MEMORY_LIMIT: AtomicUsize = AtomicUsize::new(0);
+/// HOOK to run while the memory limit is exceeded by an infallible allocation.
+static MEMORY_LIMIT_HOOK: RwLock<Option<MemoryLimitHook>> = RwLock::new(None);
+
+/// The HOOK to run while the memory limit is exceeded by an infallible allocation,
+/// with the `size` required and the `used_memory` (before allocation) provided.
+pub type MemoryLimitHook = fn(size: usize, used: usize);
+
+/// Set the MAX bytes of memory (as `used_memory` reports) permitted to be allocated,
+/// and ZERO means no limit (by DEFAULT).
+///
+/// Once the memory limit is exceeded:
+///   1) Fallible allocations (`try_*` functions) fail with an over-limit `AllocError`.
+///   2) Infallible allocations run the HOOK set with `set_memory_limit_hook` (such as
+///      to trigger key eviction), and then proceed with the allocation.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{set_memory_limit, try_malloc, used_memory};
+///
+/// set_memory_limit(used_memory() + 1024);
+///
+/// let err = try_malloc(4096).unwrap_err();
+/// assert!(err.is_over_limit());
+///
+/// set_memory_limit(0);
+/// ```
+#[inline]
+pub fn set_memory_limit(limit: usize) {
+    MEMORY_LIMIT.store(limit, Ordering::Relaxed);
+}
+
+/// Get the MAX bytes of memory permitted to be allocated, and ZERO means no limit.
+#[inline]
+pub fn memory_limit() -> usize {
+    MEMORY_LIMIT.load(Ordering::Relaxed)
+}
+
+/// Register the HOOK to run while the memory limit is exceeded by an infallible allocation,
+/// replacing the previous one.
+///
+/// # Notes
+///
+/// The HOOK runs within the allocation, thus it SHOULD be quick, and MUST NOT
+/// set/take the HOOK itself.
+pub fn set_memory_limit_hook(hook: MemoryLimitHook) {
+    *MEMORY_LIMIT_HOOK.write().unwrap_or_else(|e| e.into_inner()) = Some(hook);
+}
+
+/// Unregister the HOOK previously registered (if any) and return it.
+pub fn take_memory_limit_hook() -> Option<MemoryLimitHook> {
+    MEMORY_LIMIT_HOOK
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .take()
+}
+
+/// Check if allocating extra `size` bytes of memory exceeds the memory limit.
+#[inline]
+fn exceeds_memory_limit(size: usize) -> bool {
+    match memory_limit() {
+        0 => false,
+        limit => used_memory().saturating_add(size) > limit,
+    }
+}
+
+/// Reject allocating extra `size` bytes of memory if the memory limit is exceeded.
+#[inline]
+fn check_memory_limit(size: usize) -> Result<(), AllocError> {
+    match exceeds_memory_limit(size) {
+        true => Err(AllocError::over_limit(size)),
+        false => Ok(()),
+    }
+}
+
+/// Run the memory limit HOOK if allocating extra `size` bytes of memory exceeds the limit.
+#[inline]
+fn notify_memory_limit(size: usize) {
+    if exceeds_memory_limit(size) {
+        let hook = *MEMORY_LIMIT_HOOK.read().unwrap_or_else(|e| e.into_inner());
+        if let Some(hook) = hook {
+            hook(size, used_memory());
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Out-Of-Memory Handling
+////////////////////////////////////////////////////////////////////////////////
+
+/// HOOK to run before ABORTING the process on memory allocation failures.
+static OOM_HOOK: RwLock<Option<OomHook>> = RwLock::new(None);
+
+/// The HOOK to run before ABORTING the process on memory allocation failures (such as OOM),
+/// with the `layout` failed to be allocated and the `used_memory` provided.
+pub type OomHook = fn(layout: Layout, used: usize);
+
+/// Register the HOOK to run before ABORTING the process on memory allocation failures,
+/// replacing the previous one.
+///
+/// The HOOK is the last chance to log the offending size, dump memory statistics and/or
+/// attempt an emergency eviction, before `handle_alloc_error` aborts the process.
+///
+/// # Notes
+///
+/// Memory is (nearly) exhausted while the HOOK runs, thus it SHOULD allocate as little as
+/// possible, and MUST NOT set/take the HOOK itself.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{set_oom_hook, take_oom_hook};
+/// use std::alloc::Layout;
+///
+/// fn on_oom(layout: Layout, used: usize) {
+///     eprintln!("OOM allocating {} bytes ({} bytes used)", layout.size(), used);
+/// }
+///
+/// set_oom_hook(on_oom);
+/// assert!(take_oom_hook().is_some());
+/// ```
+pub fn set_oom_hook(hook: OomHook) {
+    *OOM_HOOK.write().unwrap_or_else(|e| e.into_inner()) = Some(hook);
+}
+
+/// Unregister the HOOK previously registered (if any) and return it.
+pub fn take_oom_hook() -> Option<OomHook> {
+    OOM_HOOK.write().unwrap_or_else(|e| e.into_inner()).take()
+}
+
+/// Handle memory allocation failures, by running the OOM HOOK (if any)
+/// and then directing to `handle_alloc_error` to ABORT the process.
+#[cold]
+fn handle_oom(layout: Layout) -> ! {
+    let hook = *OOM_HOOK.read().unwrap_or_else(|e| e.into_inner());
+    if let Some(hook) = hook {
+        hook(layout, used_memory());
+    }
+
+    handle_alloc_error(layout)
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // Memory Allocation/Deallocation
 ////////////////////////////////////////////////////////////////////////////////
@@ -55,19 +426,45 @@ const unsafe fn layout_of_aligned(size: usize, align: usize) -> Layout {
 ///
 /// # Notes
 ///
-/// While memory allocation fails, it will direct to `handle_alloc_error` to do some
-/// resource saving/clearing & logging/reporting jobs before ABORTING the process.
+/// While memory allocation fails, it will run the OOM HOOK (registered with `set_oom_hook`)
+/// and then direct to `handle_alloc_error` before ABORTING the process.
 ///
 /// The DEFAULT behavior of `handle_alloc_error` is just to print error message to `stderr`.
-/// And it can be replaced with HOOKs -- `set_alloc_error_hook` & `take_alloc_error_hook`.
+#[cfg_attr(feature = "leak-track", track_caller)]
 fn malloc_with_layout(layout: Layout) -> (*mut u8, usize) {
+    notify_memory_limit(layout.size());
+    try_malloc_with_layout(layout).unwrap_or_else(|_| handle_oom(layout))
+}
+
+/// Allocate memory based on a valid layout, returning an error instead of aborting
+/// while memory allocation errors/failures occur (such as OOM).
+///
+/// # Panics
+///
+/// ZERO-sized layout is NOT supported/permitted.
+#[cfg_attr(feature = "leak-track", track_caller)]
+fn try_malloc_with_layout(layout: Layout) -> Result<(*mut u8, usize), AllocError> {
+    if let Some(ptr) = cache_take(layout) {
+        stat_alloc(layout.size());
+        #[cfg(feature = "leak-track")]
+        track_alloc(ptr, layout.size());
+        #[cfg(feature = "alloc-trace")]
+        trace_alloc(ptr, layout.size());
+        return Ok((ptr, layout.size()));
+    }
+
     unsafe {
-        let ptr = std::alloc::alloc(layout);
+        let ptr = guarded_alloc(layout);
         if ptr.is_null() {
-            handle_alloc_error(layout);
+            return Err(AllocError::new(layout.size()));
         }
+        stat_alloc(layout.size());
+        #[cfg(feature = "leak-track")]
+        track_alloc(ptr, layout.size());
+        #[cfg(feature = "alloc-trace")]
+        trace_alloc(ptr, layout.size());
 
-        (ptr, layout.size())
+        Ok((ptr, layout.size()))
     }
 }
 
@@ -87,9 +484,17 @@ fn malloc_with_layout(layout: Layout) -> (*mut u8, usize) {
 /// before taking use of it each time.
 fn free_with_layout(ptr: *mut u8, layout: Layout) {
     if !ptr.is_null() {
-        unsafe {
-            std::alloc::dealloc(ptr, layout);
+        unsafe { fill_freed(ptr, layout.size()) };
+        if !cache_keep(ptr, layout) {
+            unsafe {
+                guarded_dealloc(ptr, layout);
+            }
         }
+        stat_free(layout.size());
+        #[cfg(feature = "leak-track")]
+        track_free(ptr);
+        #[cfg(feature = "alloc-trace")]
+        trace_free(ptr, layout.size());
     }
 }
 
@@ -108,14 +513,32 @@ fn free_with_layout(ptr: *mut u8, layout: Layout) {
 /// # Aborts
 ///
 /// It will abort while memory allocation errors/failures occur (such as OOM).
+#[cfg_attr(feature = "leak-track", track_caller)]
 fn calloc_with_layout(layout: Layout) -> (*mut u8, usize) {
+    notify_memory_limit(layout.size());
+    try_calloc_with_layout(layout).unwrap_or_else(|_| handle_oom(layout))
+}
+
+/// Allocate memory with zero-initialized based on a valid layout, returning an error
+/// instead of aborting while memory allocation errors/failures occur (such as OOM).
+///
+/// # Panics
+///
+/// ZERO-sized layout is NOT supported/permitted.
+#[cfg_attr(feature = "leak-track", track_caller)]
+fn try_calloc_with_layout(layout: Layout) -> Result<(*mut u8, usize), AllocError> {
     unsafe {
-        let ptr = std::alloc::alloc_zeroed(layout);
+        let ptr = guarded_alloc_zeroed(layout);
         if ptr.is_null() {
-            handle_alloc_error(layout);
+            return Err(AllocError::new(layout.size()));
         }
+        stat_alloc(layout.size());
+        #[cfg(feature = "leak-track")]
+        track_alloc(ptr, layout.size());
+        #[cfg(feature = "alloc-trace")]
+        trace_alloc(ptr, layout.size());
 
-        (ptr, layout.size())
+        Ok((ptr, layout.size()))
     }
 }
 
@@ -143,21 +566,51 @@ fn calloc_with_layout(layout: Layout) -> (*mut u8, usize) {
 /// # Aborts
 ///
 /// It will abort while memory reallocation errors/failures occur (such as OOM).
+#[cfg_attr(feature = "leak-track", track_caller)]
 fn realloc_with_layout(ptr: *mut u8, old_layout: Layout, new_layout: Layout) -> (*mut u8, usize) {
+    notify_memory_limit(new_layout.size().saturating_sub(old_layout.size()));
+    try_realloc_with_layout(ptr, old_layout, new_layout).unwrap_or_else(|_| handle_oom(new_layout))
+}
+
+/// Reallocate memory with another layout, returning an error instead of aborting
+/// while memory reallocation errors/failures occur (such as OOM).
+///
+/// The original memory stays VALID and untouched if reallocation fails.
+///
+/// # Panics
+///
+/// The `new_layout` with ZERO size is NOT supported/permitted.
+#[cfg_attr(feature = "leak-track", track_caller)]
+fn try_realloc_with_layout(
+    ptr: *mut u8,
+    old_layout: Layout,
+    new_layout: Layout,
+) -> Result<(*mut u8, usize), AllocError> {
     if new_layout.size() == old_layout.size() {
-        return (ptr, new_layout.size());
+        return Ok((ptr, new_layout.size()));
     }
 
     unsafe {
-        let ptr = match ptr.is_null() {
-            true => std::alloc::alloc(new_layout),
-            false => std::alloc::realloc(ptr, old_layout, new_layout.size()),
+        let (new_ptr, old_size) = match ptr.is_null() {
+            true => (guarded_alloc(new_layout), 0),
+            false => (
+                guarded_realloc(ptr, old_layout, new_layout.size()),
+                old_layout.size(),
+            ),
         };
-        if ptr.is_null() {
-            handle_alloc_error(new_layout);
+        if new_ptr.is_null() {
+            return Err(AllocError::new(new_layout.size()));
         }
+        stat_realloc(old_size, new_layout.size());
+        #[cfg(feature = "leak-track")]
+        {
+            track_free(ptr);
+            track_alloc(new_ptr, new_layout.size());
+        }
+        #[cfg(feature = "alloc-trace")]
+        trace_realloc(ptr, new_ptr, old_size, new_layout.size());
 
-        (ptr, new_layout.size())
+        Ok((new_ptr, new_layout.size()))
     }
 }
 
@@ -198,6 +651,7 @@ fn realloc_with_layout(ptr: *mut u8, old_layout: Layout, new_layout: Layout) ->
 /// ptr = std::ptr::null_mut();
 /// ```
 #[inline]
+#[cfg_attr(feature = "leak-track", track_caller)]
 pub fn malloc(size: usize) -> (*mut u8, usize) {
     malloc_with_layout(layout_of_bytes(size))
 }
@@ -242,6 +696,7 @@ pub fn free(ptr: *mut u8, size: usize) {
 /// ptr = std::ptr::null_mut();
 /// ```
 #[inline]
+#[cfg_attr(feature = "leak-track", track_caller)]
 pub fn calloc(size: usize) -> (*mut u8, usize) {
     calloc_with_layout(layout_of_bytes(size))
 }
@@ -284,6 +739,7 @@ pub fn calloc(size: usize) -> (*mut u8, usize) {
 /// ptr = std::ptr::null_mut();
 /// ```
 #[inline]
+#[cfg_attr(feature = "leak-track", track_caller)]
 pub fn realloc(ptr: *mut u8, old_size: usize, new_size: usize) -> (*mut u8, usize) {
     realloc_with_layout(ptr, layout_of_bytes(old_size), layout_of_bytes(new_size))
 }
@@ -317,6 +773,7 @@ pub fn realloc(ptr: *mut u8, old_size: usize, new_size: usize) -> (*mut u8, usiz
 /// ptr = std::ptr::null_mut();
 /// ```
 #[inline]
+#[cfg_attr(feature = "leak-track", track_caller)]
 pub fn malloc_for<T>() -> (*mut T, usize) {
     let (ptr, msize) = malloc_with_layout(layout_of::<T>());
     (ptr as _, msize)
@@ -363,47 +820,45 @@ pub fn free_for<T>(ptr: *mut T) {
 /// ptr = std::ptr::null_mut();
 /// ```
 #[inline]
+#[cfg_attr(feature = "leak-track", track_caller)]
 pub fn calloc_for<T>() -> (*mut T, usize) {
     let (ptr, msize) = calloc_with_layout(layout_of::<T>());
     (ptr as _, msize)
 }
 
-////////////////////////////////////////////////////////////////////////////////
-// ZMEM-Style Memory Allocation/Deallocation
-////////////////////////////////////////////////////////////////////////////////
-
-/// ZMEM is a size-aware memory allocation/deallocation style (introduced from Redis).
-///
-/// ZMEM-style memory (aligned with type `usize`) contains two parts:
-///   1) Header Part (`usize`): The size of the allocated body part.
-///   2) Body Part (`*mut u8`): The real allocated memory required.
-///
-/// Once required memory is allocated, the `pointer` (of body part) and `size` will be returned
-/// with header part invisible to user, which acts just like what `malloc` does.
+/// Create a layout (for `memory allocation/deallocation`) for an array of `n` elements
+/// with a certain type, whose size is computed with overflow checked.
 ///
-/// However, the `size` can be extracted with only `pointer`, as the size (header part) is
-/// binded with the pointer (body part).
+/// # Panics
 ///
-/// Moreover, the `size` (of body part) MAYBE larger than the provided/required one
-/// because of memory alignment (based on the alignment of `usize`).
+/// ZERO-sized arrays are NOT supported/permitted, and `n * size_of::<T>()` MUST NOT overflow.
+#[inline]
+fn layout_of_array<T>(n: usize) -> Layout {
+    match Layout::array::<T>(n) {
+        Ok(layout) if layout.size() != 0 => layout,
+        Ok(_) => panic!("ZERO-sized array of {} elements", n),
+        Err(_) => panic!(
+            "array of {} elements ({} bytes each) overflows",
+            n,
+            size_of::<T>()
+        ),
+    }
+}
+
+/// Allocate memory for an array of `n` elements with a certain type.
 ///
-/// # Notes
+/// The total size (`n * size_of::<T>()`) is computed with overflow checked, and the memory
+/// is aligned for the element type, thus the multiplication SHOULD NOT be hand-rolled
+/// at call sites.
 ///
-/// Allocated memory with zero size in ZMEM-style, also contains valid memory with
-/// `size_of::<usize>()` bytes as its header part.
+/// A valid element `pointer` with the number of elements will be returned.
 ///
-/// In other words, allocating memory in ZMEM-style will SURELY result in valid pointer,
-/// except for allocation failures (such as OOM) which will cause process aborting.
-
-const ZMEM_HEADER_SIZE: usize = size_of::<usize>();
-const ZMEM_ALIGN_SIZE: usize = align_of::<usize>();
-
-/// Allocate ZMEM-style memory/buffer with required size.
+/// `malloc_array_for` & `free_array_for` SHOULD work as pairs for memory allocation
+/// & deallocation separately, with the same number of elements provided.
 ///
-/// A valid memory/buffer `pointer` with its `size` will be returned.
-/// The `size` of allocated memory MAYBE larger than the provided one because of memory alignment.
+/// # Panics
 ///
-/// `zmalloc` & `zfree` SHOULD work as pairs for memory allocation & deallocation separately.
+/// ZERO-sized arrays are NOT supported/permitted, and the total size MUST NOT overflow.
 ///
 /// # Aborts
 ///
@@ -412,49 +867,37 @@ const ZMEM_ALIGN_SIZE: usize = align_of::<usize>();
 /// # Examples
 ///
 /// ```
-/// # #[allow(unused_assignments)]
-/// # use rmem::{zmalloc, zfree, zmem_size_of};
+/// # use rmem::{align_of, free_array_for, malloc_array_for};
 ///
-/// let (mut ptr, size) = zmalloc(6);
+/// let (ptr, n) = malloc_array_for::<u64>(100);
 /// assert!(!ptr.is_null());
-/// assert_eq!(size, 8);
-/// assert_eq!(zmem_size_of(ptr), 8);
+/// assert_eq!(n, 100);
+/// assert_eq!(ptr as usize % align_of::<u64>(), 0);
 ///
-/// // Do works with ptr...
+/// unsafe {
+///     for idx in 0..n {
+///         *ptr.add(idx) = idx as u64;
+///     }
+///     assert_eq!(*ptr.add(99), 99);
+/// }
 ///
-/// zfree(ptr);
-/// // Reassign NULL to ptr after memory deallocation. (Can be ignored if it unused anymore)
-/// ptr = std::ptr::null_mut();
+/// free_array_for(ptr, n);
 /// ```
-pub fn zmalloc(size: usize) -> (*mut u8, usize) {
-    let bsize = size_of_aligned(size, ZMEM_ALIGN_SIZE);
-    let (ptr, _) = malloc(ZMEM_HEADER_SIZE + bsize);
-
-    unsafe {
-        *(ptr as *mut usize) = bsize;
-        (ptr.offset(ZMEM_HEADER_SIZE as _), bsize)
-    }
+#[inline]
+#[cfg_attr(feature = "leak-track", track_caller)]
+pub fn malloc_array_for<T>(n: usize) -> (*mut T, usize) {
+    let (ptr, _) = malloc_with_layout(layout_of_array::<T>(n));
+    (ptr as _, n)
 }
 
-/// Deallocate ZMEM-style memory/buffer previously allocated.
+/// Allocate memory with zero-initialized for an array of `n` elements with a certain type.
 ///
-/// `zmalloc` & `zfree` SHOULD work as pairs for memory allocation & deallocation separately.
-pub fn zfree(ptr: *mut u8) {
-    if !ptr.is_null() {
-        unsafe {
-            let ptr = (ptr as *const usize).offset(-1);
-            let bsize = *ptr;
-
-            free(ptr as _, ZMEM_HEADER_SIZE + bsize);
-        }
-    }
-}
-
-/// Allocate ZMEM-style memory/buffer with zero-initialized with required size.
+/// `calloc_array_for` acts similarly with `malloc_array_for`, except that
+/// it will initialize ALL elements with zero.
 ///
-/// `zcalloc` acts similarly with `zmalloc`, except that it will initialize the memory with zero.
+/// # Panics
 ///
-/// `zcalloc` & `zfree` SHOULD work as pairs for memory allocation & deallocation separately.
+/// ZERO-sized arrays are NOT supported/permitted, and the total size MUST NOT overflow.
 ///
 /// # Aborts
 ///
@@ -463,35 +906,696 @@ pub fn zfree(ptr: *mut u8) {
 /// # Examples
 ///
 /// ```
-/// # #[allow(unused_assignments)]
-/// # use rmem::{zcalloc, zfree};
-///
-/// let (mut ptr, size) = zcalloc(8);
-/// assert!(!ptr.is_null());
-/// assert_eq!(size, 8);
-/// assert_eq!(unsafe { *(ptr as *const u64) }, 0);
+/// # use rmem::{calloc_array_for, free_array_for};
 ///
-/// // Do works with ptr...
+/// let (ptr, n) = calloc_array_for::<u32>(16);
+/// assert!(unsafe { std::slice::from_raw_parts(ptr, n) }.iter().all(|&v| v == 0));
 ///
-/// zfree(ptr);
+/// free_array_for(ptr, n);
+/// ```
+#[inline]
+#[cfg_attr(feature = "leak-track", track_caller)]
+pub fn calloc_array_for<T>(n: usize) -> (*mut T, usize) {
+    let (ptr, _) = calloc_with_layout(layout_of_array::<T>(n));
+    (ptr as _, n)
+}
+
+/// Deallocate memory for an array of `n` elements with its type provided.
+///
+/// `free_array_for` SHOULD work as pairs with `malloc_array_for` or `calloc_array_for`
+/// for memory allocation & deallocation works.
+#[inline]
+pub fn free_array_for<T>(ptr: *mut T, n: usize) {
+    free_with_layout(ptr as _, layout_of_array::<T>(n));
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Aligned Memory Allocation/Deallocation
+////////////////////////////////////////////////////////////////////////////////
+
+/// Create a layout (for `memory allocation/deallocation`) for amount of bytes/buffer
+/// with an explicit alignment.
+///
+/// # Panics
+///
+/// `align` MUST be the power of 2, and `size` (rounded up to `align`) MUST NOT overflow.
+#[inline]
+fn layout_of_bytes_aligned(size: usize, align: usize) -> Layout {
+    match Layout::from_size_align(size, align) {
+        Ok(layout) => layout,
+        Err(_) => panic!("invalid alignment {} for {} bytes", align, size),
+    }
+}
+
+/// Allocate memory/buffer with a certain size and an explicit alignment, such as
+/// the cache-line (64 bytes) or SIMD (16/32 bytes) alignment.
+///
+/// A valid `pointer` (aligned with `align`) as well as the `size` of the allocated memory
+/// will be returned.
+///
+/// `malloc_aligned` & `free_aligned` SHOULD work as pairs for memory allocation & deallocation
+/// separately, with the same `align` provided.
+///
+/// # Panics
+///
+/// ZERO size is NOT supported/permitted, and `align` MUST be the power of 2.
+///
+/// # Aborts
+///
+/// It will abort while memory allocation errors/failures occur (such as OOM).
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{free_aligned, malloc_aligned};
+///
+/// let (ptr, size) = malloc_aligned(100, 64);
+/// assert!(!ptr.is_null());
+/// assert_eq!(ptr as usize % 64, 0);
+/// assert_eq!(size, 100);
+///
+/// free_aligned(ptr, size, 64);
+/// ```
+#[inline]
+#[cfg_attr(feature = "leak-track", track_caller)]
+pub fn malloc_aligned(size: usize, align: usize) -> (*mut u8, usize) {
+    malloc_with_layout(layout_of_bytes_aligned(size, align))
+}
+
+/// Allocate memory/buffer with zero-initialized with a certain size and an explicit alignment.
+///
+/// `calloc_aligned` acts similarly with `malloc_aligned`, except that it will initialize
+/// the memory with zero.
+///
+/// # Panics
+///
+/// ZERO size is NOT supported/permitted, and `align` MUST be the power of 2.
+///
+/// # Aborts
+///
+/// It will abort while memory allocation errors/failures occur (such as OOM).
+#[inline]
+#[cfg_attr(feature = "leak-track", track_caller)]
+pub fn calloc_aligned(size: usize, align: usize) -> (*mut u8, usize) {
+    calloc_with_layout(layout_of_bytes_aligned(size, align))
+}
+
+/// Deallocate memory with the same size & alignment previously provided.
+///
+/// `free_aligned` SHOULD work as pairs with `malloc_aligned` or `calloc_aligned`
+/// for memory allocation & deallocation works.
+#[inline]
+pub fn free_aligned(ptr: *mut u8, size: usize, align: usize) {
+    free_with_layout(ptr, layout_of_bytes_aligned(size, align));
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Fallible Memory Allocation
+////////////////////////////////////////////////////////////////////////////////
+
+/// Allocate memory/buffer with a certain size, returning an error instead of aborting
+/// while memory allocation errors/failures occur (such as OOM).
+///
+/// `try_malloc` acts similarly with `malloc`, and SHOULD work as pairs with `free`.
+///
+/// It also fails while the memory limit (set with `set_memory_limit`) is exceeded.
+///
+/// It's useful for allocating memory with an untrusted size (such as the one
+/// required by a client request), that the request can be rejected gracefully.
+///
+/// # Panics
+///
+/// ZERO size is NOT supported/permitted.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{free, try_malloc};
+///
+/// let (ptr, size) = try_malloc(8).unwrap();
+/// assert!(!ptr.is_null());
+/// assert_eq!(size, 8);
+///
+/// free(ptr, size);
+///
+/// assert!(try_malloc(usize::MAX).is_err());
+/// ```
+#[inline]
+#[cfg_attr(feature = "leak-track", track_caller)]
+pub fn try_malloc(size: usize) -> Result<(*mut u8, usize), AllocError> {
+    check_memory_limit(size)?;
+    try_malloc_with_layout(try_layout_of_bytes(size)?)
+}
+
+/// Allocate memory/buffer with zero-initialized with a certain size, returning an error
+/// instead of aborting while memory allocation errors/failures occur (such as OOM).
+///
+/// `try_calloc` acts similarly with `calloc`, and SHOULD work as pairs with `free`.
+///
+/// # Panics
+///
+/// ZERO size is NOT supported/permitted.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{free, try_calloc};
+///
+/// let (ptr, size) = try_calloc(8).unwrap();
+/// assert!(!ptr.is_null());
+/// assert_eq!(unsafe { *(ptr as *const u64) }, 0);
+///
+/// free(ptr, size);
+/// ```
+#[inline]
+#[cfg_attr(feature = "leak-track", track_caller)]
+pub fn try_calloc(size: usize) -> Result<(*mut u8, usize), AllocError> {
+    check_memory_limit(size)?;
+    try_calloc_with_layout(try_layout_of_bytes(size)?)
+}
+
+/// Reallocate memory/buffer with another size, returning an error instead of aborting
+/// while memory reallocation errors/failures occur (such as OOM).
+///
+/// `try_realloc` acts similarly with `realloc`, and SHOULD work as pairs with `free`.
+/// The original memory stays VALID (and SHOULD be freed as well) if reallocation fails.
+///
+/// # Panics
+///
+/// The `new_size` with ZERO size is NOT supported/permitted.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{free, malloc, try_realloc};
+///
+/// let (ptr, size) = malloc(8);
+///
+/// assert!(try_realloc(ptr, size, usize::MAX).is_err());
+///
+/// let (ptr, size) = try_realloc(ptr, size, 16).unwrap();
+/// assert_eq!(size, 16);
+///
+/// free(ptr, size);
+/// ```
+#[inline]
+#[cfg_attr(feature = "leak-track", track_caller)]
+pub fn try_realloc(
+    ptr: *mut u8,
+    old_size: usize,
+    new_size: usize,
+) -> Result<(*mut u8, usize), AllocError> {
+    let old_size = if ptr.is_null() { 0 } else { old_size };
+    check_memory_limit(new_size.saturating_sub(old_size))?;
+    try_realloc_with_layout(
+        ptr,
+        layout_of_bytes(old_size),
+        try_layout_of_bytes(new_size)?,
+    )
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// ZMEM-Style Memory Allocation/Deallocation
+////////////////////////////////////////////////////////////////////////////////
+
+/// ZMEM is a size-aware memory allocation/deallocation style (introduced from Redis).
+///
+/// ZMEM-style memory (aligned with type `usize`) contains two parts:
+///   1) Header Part (`usize`): The size of the allocated body part.
+///   2) Body Part (`*mut u8`): The real allocated memory required.
+///
+/// Once required memory is allocated, the `pointer` (of body part) and `size` will be returned
+/// with header part invisible to user, which acts just like what `malloc` does.
+///
+/// However, the `size` can be extracted with only `pointer`, as the size (header part) is
+/// binded with the pointer (body part).
+///
+/// Moreover, the `size` (of body part) MAYBE larger than the provided/required one
+/// because of memory alignment (based on the alignment of `usize`), as well as the usable
+/// size of the memory block reported by the allocator backend (such as glibc & jemalloc),
+/// that the slack already paid for can be taken use of.
+///
+/// # Notes
+///
+/// Allocated memory with zero size in ZMEM-style, also contains valid memory with
+/// `size_of::<usize>()` bytes as its header part.
+///
+/// In other words, allocating memory in ZMEM-style will SURELY result in valid pointer,
+/// except for allocation failures (such as OOM) which will cause process aborting.
+///
+/// With the `zmem-align16` feature enabled, ALL body parts are 16-byte aligned (with sizes
+/// of multiples of 16 bytes), by padding the header part to 16 bytes (the size is stored
+/// in the last `usize` of the header part), thus SIMD routines can use aligned loads safely.
+#[cfg(not(feature = "zmem-align16"))]
+pub const ZMEM_BODY_ALIGN: usize = align_of::<usize>();
+#[cfg(feature = "zmem-align16")]
+pub const ZMEM_BODY_ALIGN: usize = 16;
+
+const ZMEM_HEADER_SIZE: usize = size_of_aligned(size_of::<usize>(), ZMEM_BODY_ALIGN);
+const ZMEM_ALIGN_SIZE: usize = ZMEM_BODY_ALIGN;
+
+/// Alignment of the whole ZMEM-style memory block required to the allocator backend.
+#[cfg(not(feature = "zmem-align16"))]
+const ZMEM_BLOCK_ALIGN: usize = BYTE_ALIGN_SIZE;
+#[cfg(feature = "zmem-align16")]
+const ZMEM_BLOCK_ALIGN: usize = ZMEM_BODY_ALIGN;
+
+/// Create a layout for the whole ZMEM-style memory block with `msize` bytes.
+#[inline]
+const fn zmem_layout(msize: usize) -> Layout {
+    unsafe { layout_of_aligned(msize, ZMEM_BLOCK_ALIGN) }
+}
+
+/// Initialize ZMEM-style memory with `msize` bytes newly (re)allocated, and the body part
+/// is extended to the usable size of the memory block.
+///
+/// The `pointer` & `size` of the body part will be returned.
+#[inline]
+unsafe fn zmem_init(ptr: *mut u8, msize: usize) -> (*mut u8, usize) {
+    zmem_init_with(ptr, msize, ZMEM_HEADER_SIZE)
+}
+
+/// Initialize ZMEM-style memory with a header part of `header` bytes, where the size of
+/// body part is ALWAYS stored in the last `usize` of the header part.
+unsafe fn zmem_init_with(ptr: *mut u8, msize: usize, header: usize) -> (*mut u8, usize) {
+    let usable = guarded_usable_size(ptr, zmem_layout(msize));
+    let bsize = std::cmp::max(usable, msize) - header;
+    let bsize = bsize & !(ZMEM_ALIGN_SIZE - 1);
+    stat_realloc(msize, header + bsize);
+    #[cfg(feature = "leak-track")]
+    track_resize(ptr, header + bsize);
+
+    let body = ptr.add(header);
+    *(body as *mut usize).offset(-1) = bsize;
+    (body, bsize)
+}
+
+/// Read the header (the size of body part) of ZMEM-style memory, which is verified against
+/// the freed pattern with the `poison-on-free` feature enabled.
+#[inline]
+fn zmem_header_of(ptr: *mut u8) -> usize {
+    checked_zmem_header(ptr, unsafe { *(ptr as *const usize).offset(-1) })
+}
+
+/// Extract the `pointer` & `size` of the whole ZMEM-style memory (header part included).
+#[inline]
+fn zmem_block_of(ptr: *mut u8) -> (*mut u8, usize) {
+    zmem_block_of_with(ptr, ZMEM_HEADER_SIZE)
+}
+
+/// Extract the `pointer` & `size` of the whole ZMEM-style memory with a header part
+/// of `header` bytes.
+#[inline]
+fn zmem_block_of_with(ptr: *mut u8, header: usize) -> (*mut u8, usize) {
+    let bsize = zmem_header_of(ptr);
+    unsafe { (ptr.sub(header), header + bsize) }
+}
+
+/// Allocate ZMEM-style memory/buffer with required size.
+///
+/// A valid memory/buffer `pointer` with its `size` will be returned.
+/// The `size` of allocated memory MAYBE larger than the provided one because of memory alignment.
+///
+/// `zmalloc` & `zfree` SHOULD work as pairs for memory allocation & deallocation separately.
+///
+/// # Aborts
+///
+/// It will abort while memory allocation errors/failures occur (such as OOM).
+///
+/// # Examples
+///
+/// ```
+/// # #[allow(unused_assignments)]
+/// # use rmem::{zmalloc, zfree, zmem_size_of};
+///
+/// let (mut ptr, size) = zmalloc(6);
+/// assert!(!ptr.is_null());
+/// assert!(size >= 8);
+/// assert_eq!(zmem_size_of(ptr), size);
+///
+/// // Do works with ptr...
+///
+/// zfree(ptr);
+/// // Reassign NULL to ptr after memory deallocation. (Can be ignored if it unused anymore)
+/// ptr = std::ptr::null_mut();
+/// ```
+#[cfg_attr(feature = "leak-track", track_caller)]
+pub fn zmalloc(size: usize) -> (*mut u8, usize) {
+    let bsize = size_of_aligned(size, ZMEM_ALIGN_SIZE);
+    let (ptr, msize) = malloc_with_layout(zmem_layout(ZMEM_HEADER_SIZE + bsize));
+
+    unsafe { zmem_init(ptr, msize) }
+}
+
+/// Allocate ZMEM-style memory/buffer with required size, returning an error instead of
+/// aborting while memory allocation errors/failures occur (such as OOM).
+///
+/// `try_zmalloc` acts similarly with `zmalloc`, and SHOULD work as pairs with `zfree`.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{try_zmalloc, zfree, zmem_size_of};
+///
+/// let (ptr, size) = try_zmalloc(6).unwrap();
+/// assert!(size >= 8);
+/// assert_eq!(zmem_size_of(ptr), size);
+///
+/// zfree(ptr);
+///
+/// assert!(try_zmalloc(usize::MAX).is_err());
+/// ```
+#[cfg_attr(feature = "leak-track", track_caller)]
+pub fn try_zmalloc(size: usize) -> Result<(*mut u8, usize), AllocError> {
+    let bsize = size
+        .checked_add(ZMEM_ALIGN_SIZE - 1)
+        .map(|size| size & !(ZMEM_ALIGN_SIZE - 1))
+        .ok_or_else(|| AllocError::new(size))?;
+    let msize = ZMEM_HEADER_SIZE
+        .checked_add(bsize)
+        .ok_or_else(|| AllocError::new(size))?;
+    check_memory_limit(msize)?;
+    let layout =
+        Layout::from_size_align(msize, ZMEM_BLOCK_ALIGN).map_err(|_| AllocError::new(size))?;
+    let (ptr, msize) = try_malloc_with_layout(layout)?;
+
+    unsafe { Ok(zmem_init(ptr, msize)) }
+}
+
+/// Deallocate ZMEM-style memory/buffer previously allocated.
+///
+/// `zmalloc` & `zfree` SHOULD work as pairs for memory allocation & deallocation separately.
+pub fn zfree(ptr: *mut u8) {
+    if !ptr.is_null() {
+        let (ptr, msize) = zmem_block_of(ptr);
+        free_with_layout(ptr, zmem_layout(msize));
+    }
+}
+
+/// Allocate ZMEM-style memory/buffer with zero-initialized with required size.
+///
+/// `zcalloc` acts similarly with `zmalloc`, except that it will initialize the memory with zero.
+///
+/// `zcalloc` & `zfree` SHOULD work as pairs for memory allocation & deallocation separately.
+///
+/// # Aborts
+///
+/// It will abort while memory allocation errors/failures occur (such as OOM).
+///
+/// # Examples
+///
+/// ```
+/// # #[allow(unused_assignments)]
+/// # use rmem::{zcalloc, zfree};
+///
+/// let (mut ptr, size) = zcalloc(8);
+/// assert!(!ptr.is_null());
+/// assert!(size >= 8);
+/// assert_eq!(unsafe { *(ptr as *const u64) }, 0);
+///
+/// // Do works with ptr...
+///
+/// zfree(ptr);
+/// ptr = std::ptr::null_mut();
+/// ```
+#[cfg_attr(feature = "leak-track", track_caller)]
+pub fn zcalloc(size: usize) -> (*mut u8, usize) {
+    let bsize = size_of_aligned(size, ZMEM_ALIGN_SIZE);
+    let (ptr, msize) = calloc_with_layout(zmem_layout(ZMEM_HEADER_SIZE + bsize));
+
+    unsafe {
+        let (ptr, size) = zmem_init(ptr, msize);
+        // The slack beyond the required size is NOT guaranteed to be zero-initialized.
+        mem_set(ptr.add(bsize), 0, size - bsize);
+        (ptr, size)
+    }
+}
+
+/// Allocate ZMEM-style memory/buffer and copy `len` bytes from `src` into it in one call
+/// (introduced from Redis `zmemdup`).
+///
+/// A valid memory/buffer `pointer` with its `size` (at least `len`) will be returned.
+///
+/// `zmemdup` & `zfree` SHOULD work as pairs for memory allocation & deallocation separately.
+///
+/// # Safety
+///
+/// `src` MUST be valid for reads of `len` bytes.
+///
+/// # Aborts
+///
+/// It will abort while memory allocation errors/failures occur (such as OOM).
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{zfree, zmemdup};
+///
+/// let src = b"RuchDB";
+/// let (ptr, size) = unsafe { zmemdup(src.as_ptr(), src.len()) };
+/// assert!(size >= 6);
+/// assert_eq!(unsafe { std::slice::from_raw_parts(ptr, 6) }, src);
+///
+/// zfree(ptr);
+/// ```
+#[cfg_attr(feature = "leak-track", track_caller)]
+pub unsafe fn zmemdup(src: *const u8, len: usize) -> (*mut u8, usize) {
+    let (ptr, size) = zmalloc(len);
+    mem_copy(src, ptr, len);
+    (ptr, size)
+}
+
+/// Allocate ZMEM-style memory/buffer holding a copy of `bytes`
+/// (introduced from Redis `zstrdup`).
+///
+/// `zstrdup` acts similarly with `zmemdup`, and SHOULD work as pairs with `zfree`.
+///
+/// # Aborts
+///
+/// It will abort while memory allocation errors/failures occur (such as OOM).
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{zfree, zstrdup};
+///
+/// let (ptr, size) = zstrdup(b"hello");
+/// assert!(size >= 5);
+/// assert_eq!(unsafe { std::slice::from_raw_parts(ptr, 5) }, b"hello");
+///
+/// zfree(ptr);
+/// ```
+#[inline]
+#[cfg_attr(feature = "leak-track", track_caller)]
+pub fn zstrdup(bytes: &[u8]) -> (*mut u8, usize) {
+    unsafe { zmemdup(bytes.as_ptr(), bytes.len()) }
+}
+
+/// Reallocate ZMEM-style memory/buffer with another size for memory scaling purpose.
+///
+/// It will allocate new memory block with `size` if original NULL `pointer` is provided,
+/// otherwise will reallocate enough memory based on the original one.
+///
+/// Reallocation is skipped (with the original `pointer` returned) if the required size grows
+/// within the usable size of the original memory block, while memory is ALWAYS reallocated
+/// to be shrunk (see `zrealloc_shrink` for shrinking with hysteresis).
+///
+/// `zrealloc` & `zfree` SHOULD work as pairs for memory reallocation & deallocation separately.
+///
+/// # Aborts
+///
+/// It will abort while memory reallocation errors/failures occur (such as OOM).
+///
+/// # Examples
+///
+/// ```
+/// # #[allow(unused_assignments)]
+/// # use rmem::{zmalloc, zrealloc, zfree};
+///
+/// let (ptr, size) = zmalloc(8);
+/// assert!(!ptr.is_null());
+/// assert!(size >= 8);
+///
+/// // Do works with ptr...
+///
+/// let (mut ptr, size) = zrealloc(ptr, 16);
+/// assert!(!ptr.is_null());
+/// assert!(size >= 16);
+///
+/// // Do further works with ptr...
+///
+/// zfree(ptr);
 /// ptr = std::ptr::null_mut();
 /// ```
-pub fn zcalloc(size: usize) -> (*mut u8, usize) {
+#[inline]
+#[cfg_attr(feature = "leak-track", track_caller)]
+pub fn zrealloc(ptr: *mut u8, new_size: usize) -> (*mut u8, usize) {
+    zrealloc_with(ptr, new_size, ZMEM_HEADER_SIZE)
+}
+
+/// Reallocate ZMEM-style memory with a header part of `header` bytes, where the whole
+/// header part is kept as is (except for the size of body part).
+#[cfg_attr(feature = "leak-track", track_caller)]
+fn zrealloc_with(ptr: *mut u8, new_size: usize, header: usize) -> (*mut u8, usize) {
+    let new_bsize = size_of_aligned(new_size, ZMEM_ALIGN_SIZE);
+
+    let (old_ptr, old_msize) = match ptr.is_null() {
+        true => (std::ptr::null_mut::<u8>(), 0usize),
+        false => zmem_block_of_with(ptr, header),
+    };
+    if !ptr.is_null() && new_bsize >= zmem_size_of(ptr) {
+        let usable = unsafe { guarded_usable_size(old_ptr, zmem_layout(old_msize)) };
+        if header + new_bsize <= std::cmp::max(usable, old_msize) {
+            return unsafe { zmem_init_with(old_ptr, old_msize, header) };
+        }
+    }
+
+    let (new_ptr, new_msize) = realloc_with_layout(
+        old_ptr,
+        zmem_layout(old_msize),
+        zmem_layout(header + new_bsize),
+    );
+
+    unsafe { zmem_init_with(new_ptr, new_msize, header) }
+}
+
+/// ZMEM-style memory is ONLY shrunk by `zrealloc_shrink` while the required size is less than
+/// `1 / ZMEM_SHRINK_RATIO` of its current (usable) size.
+pub const ZMEM_SHRINK_RATIO: usize = 4;
+
+/// Shrink ZMEM-style memory/buffer to a smaller size with hysteresis, that values oscillating
+/// in size do NOT thrash the allocator with reallocation.
+///
+/// The memory is ONLY reallocated while `new_size` is less than `1 / ZMEM_SHRINK_RATIO` of
+/// its current size, otherwise the original `pointer` & `size` are returned as is, and
+/// the logical size SHOULD be recorded by the caller (such as the length of a string).
+///
+/// It acts as `zrealloc` does if `new_size` is larger than the current size
+/// (or NULL `pointer` provided).
+///
+/// # Aborts
+///
+/// It will abort while memory reallocation errors/failures occur (such as OOM).
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{zfree, zmalloc, zrealloc_shrink};
+///
+/// let (ptr, size) = zmalloc(1000);
+///
+/// // Shrinking a little is skipped.
+/// assert_eq!(zrealloc_shrink(ptr, 600), (ptr, size));
+///
+/// let (ptr, size) = zrealloc_shrink(ptr, 100);
+/// assert!((100..250).contains(&size));
+///
+/// zfree(ptr);
+/// ```
+#[cfg_attr(feature = "leak-track", track_caller)]
+pub fn zrealloc_shrink(ptr: *mut u8, new_size: usize) -> (*mut u8, usize) {
+    let size = zmem_size_of(ptr);
+    if ptr.is_null() || new_size > size || new_size < size / ZMEM_SHRINK_RATIO {
+        return zrealloc(ptr, new_size);
+    }
+    (ptr, size)
+}
+
+/// Allocate ZMEM-style memory/buffer with required size, and the body part aligned with
+/// an explicit alignment (such as the cache-line alignment).
+///
+/// The header part is placed right before the body part as usual, thus `zmem_size_of` works
+/// on the returned `pointer`, while the memory block is padded to keep the body aligned.
+///
+/// `zmalloc_aligned` & `zfree_aligned` SHOULD work as pairs for memory allocation & deallocation
+/// separately, with the same `align` provided. It MUST NOT be deallocated with `zfree`.
+///
+/// # Panics
+///
+/// `align` MUST be the power of 2.
+///
+/// # Aborts
+///
+/// It will abort while memory allocation errors/failures occur (such as OOM).
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{zfree_aligned, zmalloc_aligned, zmem_size_of};
+///
+/// let (ptr, size) = zmalloc_aligned(100, 64);
+/// assert_eq!(ptr as usize % 64, 0);
+/// assert!(size >= 100);
+/// assert_eq!(zmem_size_of(ptr), size);
+///
+/// zfree_aligned(ptr, 64);
+/// ```
+#[cfg_attr(feature = "leak-track", track_caller)]
+pub fn zmalloc_aligned(size: usize, align: usize) -> (*mut u8, usize) {
+    let offset = zmem_offset_aligned(align);
     let bsize = size_of_aligned(size, ZMEM_ALIGN_SIZE);
-    let (ptr, _) = calloc(ZMEM_HEADER_SIZE + bsize);
+    let (ptr, _) = malloc_aligned(offset + bsize, std::cmp::max(align, ZMEM_ALIGN_SIZE));
 
     unsafe {
-        *(ptr as *mut usize) = bsize;
-        (ptr.offset(ZMEM_HEADER_SIZE as _), bsize)
+        let ptr = ptr.add(offset);
+        *(ptr as *mut usize).offset(-1) = bsize;
+        (ptr, bsize)
     }
 }
 
-/// Reallocate ZMEM-style memory/buffer with another size for memory scaling purpose.
+/// Deallocate ZMEM-style memory/buffer previously allocated with `zmalloc_aligned`.
 ///
-/// It will allocate new memory block with `size` if original NULL `pointer` is provided,
-/// otherwise will reallocate enough memory based on the original one.
+/// `align` MUST be the same as the one provided for allocation.
+pub fn zfree_aligned(ptr: *mut u8, align: usize) {
+    if !ptr.is_null() {
+        let (ptr, msize) = zmem_block_of_aligned(ptr, align);
+        free_aligned(ptr, msize, std::cmp::max(align, ZMEM_ALIGN_SIZE));
+    }
+}
+
+/// Get the offset of the body part from the start of an aligned ZMEM-style memory block,
+/// which is large enough to hold the header part while keeping the body aligned.
+#[inline]
+fn zmem_offset_aligned(align: usize) -> usize {
+    size_of_aligned(ZMEM_HEADER_SIZE, std::cmp::max(align, ZMEM_ALIGN_SIZE))
+}
+
+/// Extract the `pointer` & `size` of the whole aligned ZMEM-style memory (padding included).
+#[inline]
+fn zmem_block_of_aligned(ptr: *mut u8, align: usize) -> (*mut u8, usize) {
+    let offset = zmem_offset_aligned(align);
+    unsafe { (ptr.sub(offset), offset + zmem_header_of(ptr)) }
+}
+
+/// DEFAULT MAX size (in bytes) of headroom preallocated by `zrealloc_with_headroom`.
+pub const ZMEM_MAX_PREALLOC: usize = 1024 * 1024;
+
+static ZMEM_MAX_PREALLOC_SIZE: AtomicUsize = AtomicUsize::new(ZMEM_MAX_PREALLOC);
+
+/// Set the MAX size (in bytes) of headroom preallocated by `zrealloc_with_headroom`.
 ///
-/// `zrealloc` & `zfree` SHOULD work as pairs for memory reallocation & deallocation separately.
+/// ZERO disables preallocation, that memory grows to exactly the required size.
+#[inline]
+pub fn set_zmem_max_prealloc(size: usize) {
+    ZMEM_MAX_PREALLOC_SIZE.store(size, Ordering::Relaxed);
+}
+
+/// Get the MAX size (in bytes) of headroom preallocated by `zrealloc_with_headroom`,
+/// which is `ZMEM_MAX_PREALLOC` by DEFAULT.
+#[inline]
+pub fn zmem_max_prealloc() -> usize {
+    ZMEM_MAX_PREALLOC_SIZE.load(Ordering::Relaxed)
+}
+
+/// Grow ZMEM-style memory/buffer to hold (at least) `needed` bytes, with headroom
+/// preallocated for further growth (introduced from Redis SDS).
+///
+/// The memory is doubled while `needed` is less than the MAX preallocation size
+/// (set with `set_zmem_max_prealloc`), otherwise it grows linearly by the MAX
+/// preallocation size, thus appending repeatedly costs amortized O(1) only.
+///
+/// The original `pointer` & `size` are returned if it already holds `needed` bytes,
+/// as memory is NEVER shrunk by `zrealloc_with_headroom`.
 ///
 /// # Aborts
 ///
@@ -500,52 +1604,194 @@ pub fn zcalloc(size: usize) -> (*mut u8, usize) {
 /// # Examples
 ///
 /// ```
-/// # #[allow(unused_assignments)]
-/// # use rmem::{zmalloc, zrealloc, zfree};
+/// # use rmem::{zfree, zmalloc, zrealloc_with_headroom};
 ///
-/// let (ptr, size) = zmalloc(8);
-/// assert!(!ptr.is_null());
-/// assert_eq!(size, 8);
+/// let (ptr, _) = zmalloc(8);
 ///
-/// // Do works with ptr...
+/// let (ptr, size) = zrealloc_with_headroom(ptr, 100);
+/// assert!(size >= 200);
 ///
-/// let (mut ptr, size) = zrealloc(ptr, 16);
-/// assert!(!ptr.is_null());
-/// assert_eq!(size, 16);
+/// // Enough headroom already.
+/// let (new_ptr, new_size) = zrealloc_with_headroom(ptr, 150);
+/// assert_eq!(new_ptr, ptr);
+/// assert_eq!(new_size, size);
 ///
-/// // Do further works with ptr...
+/// zfree(new_ptr);
+/// ```
+#[cfg_attr(feature = "leak-track", track_caller)]
+pub fn zrealloc_with_headroom(ptr: *mut u8, needed: usize) -> (*mut u8, usize) {
+    let size = zmem_size_of(ptr);
+    if !ptr.is_null() && needed <= size {
+        return (ptr, size);
+    }
+    zrealloc(ptr, zmem_headroom_target(needed))
+}
+
+/// Get the size to grow to for holding (at least) `needed` bytes, with headroom.
+#[inline]
+fn zmem_headroom_target(needed: usize) -> usize {
+    let max_prealloc = zmem_max_prealloc();
+    match needed < max_prealloc {
+        true => needed.saturating_mul(2),
+        false => needed.saturating_add(max_prealloc),
+    }
+}
+
+/// Extract size (of body part) of ZMEM-style memory.
+#[inline]
+pub fn zmem_size_of(ptr: *mut u8) -> usize {
+    match ptr.is_null() {
+        true => 0usize,
+        false => zmem_header_of(ptr),
+    }
+}
+
+/// ZMEM-style memory with length is allocated with a two-field header part (introduced from
+/// Redis SDS), where the length (bytes in use) of the body part is stored right before
+/// its size (capacity):
 ///
-/// zfree(ptr);
-/// ptr = std::ptr::null_mut();
+/// ```text
+/// | padding (optional) | length | size | body ...
+///                                      ^ pointer
 /// ```
-pub fn zrealloc(ptr: *mut u8, new_size: usize) -> (*mut u8, usize) {
-    let (old_ptr, old_msize) = if ptr.is_null() {
-        (std::ptr::null_mut::<u8>(), 0usize)
-    } else {
-        unsafe {
-            let ptr = (ptr as *const usize).offset(-1);
-            (ptr as _, *ptr)
-        }
-    };
+///
+/// Thus owners (such as strings) can be represented by the body `pointer` ONLY, with
+/// length & capacity read from the header part (with `zmem_len` & `zmem_size_of`).
+///
+/// Memory with length MUST be (re)allocated & deallocated with the `*_with_len` functions,
+/// rather than mixed with the plain ZMEM ones.
+const ZMEM_LEN_HEADER_SIZE: usize = size_of_aligned(2 * size_of::<usize>(), ZMEM_BODY_ALIGN);
 
-    let new_bsize = size_of_aligned(new_size, ZMEM_ALIGN_SIZE);
-    let (new_ptr, _) = realloc(old_ptr, old_msize, ZMEM_HEADER_SIZE + new_bsize);
+/// Allocate ZMEM-style memory/buffer with length, with required size & ZERO length.
+///
+/// A valid memory/buffer `pointer` with its `size` will be returned.
+///
+/// `zmalloc_with_len` SHOULD work as pairs with `zfree_with_len`.
+///
+/// # Aborts
+///
+/// It will abort while memory allocation errors/failures occur (such as OOM).
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{zfree_with_len, zmalloc_with_len, zmem_len, zmem_set_len, zmem_size_of};
+///
+/// let (ptr, size) = zmalloc_with_len(10);
+/// assert!(size >= 10);
+/// assert_eq!(zmem_len(ptr), 0);
+///
+/// zmem_set_len(ptr, 6);
+/// assert_eq!(zmem_len(ptr), 6);
+/// assert_eq!(zmem_size_of(ptr), size);
+///
+/// zfree_with_len(ptr);
+/// ```
+#[cfg_attr(feature = "leak-track", track_caller)]
+pub fn zmalloc_with_len(size: usize) -> (*mut u8, usize) {
+    let bsize = size_of_aligned(size, ZMEM_ALIGN_SIZE);
+    let (ptr, msize) = malloc_with_layout(zmem_layout(ZMEM_LEN_HEADER_SIZE + bsize));
 
     unsafe {
-        *(new_ptr as *mut usize) = new_bsize;
-        (new_ptr.offset(ZMEM_HEADER_SIZE as _), new_bsize)
+        let (ptr, size) = zmem_init_with(ptr, msize, ZMEM_LEN_HEADER_SIZE);
+        *(ptr as *mut usize).offset(-2) = 0;
+        (ptr, size)
     }
 }
 
-/// Extract size (of body part) of ZMEM-style memory.
+/// Reallocate ZMEM-style memory/buffer with length with another size, where the length is
+/// kept (truncated to the new size if shrunk).
+///
+/// It will allocate new memory block with `size` & ZERO length if original NULL `pointer`
+/// is provided, and reallocation is skipped in the same way as `zrealloc`.
+///
+/// # Aborts
+///
+/// It will abort while memory reallocation errors/failures occur (such as OOM).
+#[cfg_attr(feature = "leak-track", track_caller)]
+pub fn zrealloc_with_len(ptr: *mut u8, new_size: usize) -> (*mut u8, usize) {
+    if ptr.is_null() {
+        return zmalloc_with_len(new_size);
+    }
+
+    let len = zmem_len(ptr);
+    let (ptr, size) = zrealloc_with(ptr, new_size, ZMEM_LEN_HEADER_SIZE);
+    unsafe { *(ptr as *mut usize).offset(-2) = std::cmp::min(len, size) };
+    (ptr, size)
+}
+
+/// Grow ZMEM-style memory/buffer with length to hold (at least) `needed` bytes, with headroom
+/// preallocated in the same way as `zrealloc_with_headroom`.
+///
+/// # Aborts
+///
+/// It will abort while memory reallocation errors/failures occur (such as OOM).
+#[cfg_attr(feature = "leak-track", track_caller)]
+pub fn zrealloc_with_len_headroom(ptr: *mut u8, needed: usize) -> (*mut u8, usize) {
+    let size = zmem_size_of(ptr);
+    if !ptr.is_null() && needed <= size {
+        return (ptr, size);
+    }
+    zrealloc_with_len(ptr, zmem_headroom_target(needed))
+}
+
+/// Deallocate ZMEM-style memory/buffer with length previously allocated.
+pub fn zfree_with_len(ptr: *mut u8) {
+    if !ptr.is_null() {
+        let (ptr, msize) = zmem_block_of_with(ptr, ZMEM_LEN_HEADER_SIZE);
+        free_with_layout(ptr, zmem_layout(msize));
+    }
+}
+
+/// Extract length (of body part) of ZMEM-style memory with length.
 #[inline]
-pub fn zmem_size_of(ptr: *mut u8) -> usize {
+pub fn zmem_len(ptr: *mut u8) -> usize {
     match ptr.is_null() {
         true => 0usize,
-        false => unsafe { *(ptr as *const usize).offset(-1) },
+        false => unsafe { *(ptr as *const usize).offset(-2) },
+    }
+}
+
+/// Set length (of body part) of ZMEM-style memory with length.
+///
+/// # Panics
+///
+/// `len` MUST NOT exceed the size (of body part) of the memory.
+#[inline]
+pub fn zmem_set_len(ptr: *mut u8, len: usize) {
+    let size = zmem_size_of(ptr);
+    assert!(len <= size, "length {} exceeds size {}", len, size);
+    if !ptr.is_null() {
+        unsafe { *(ptr as *mut usize).offset(-2) = len };
     }
 }
 
+/// Query the usable size (of body part) of ZMEM-style memory from the allocator backend.
+///
+/// It's normally the same as `zmem_size_of`, as ZMEM-style memory is ALWAYS extended to
+/// the usable size of its memory block while (re)allocated.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{zfree, zmalloc, zmem_size_of, zmem_usable_size};
+///
+/// let (ptr, size) = zmalloc(6);
+/// assert!(zmem_usable_size(ptr) >= size);
+/// assert_eq!(zmem_usable_size(ptr), zmem_size_of(ptr));
+///
+/// zfree(ptr);
+/// ```
+pub fn zmem_usable_size(ptr: *mut u8) -> usize {
+    if ptr.is_null() {
+        return 0usize;
+    }
+
+    let (ptr, msize) = zmem_block_of(ptr);
+    let usable = unsafe { guarded_usable_size(ptr, zmem_layout(msize)) };
+    std::cmp::max(usable, msize) - ZMEM_HEADER_SIZE
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // Unit Tests
 ////////////////////////////////////////////////////////////////////////////////
@@ -739,6 +1985,175 @@ mod mem_alloc_tests {
     }
 }
 
+#[cfg(test)]
+mod mem_array_alloc_tests {
+    use super::*;
+
+    #[test]
+    fn alloc_array_aligned() {
+        let (ptr, n) = calloc_array_for::<u128>(10);
+        assert_eq!(n, 10);
+        assert_eq!(ptr as usize % align_of::<u128>(), 0);
+        assert_eq!(unsafe { *ptr.add(9) }, 0);
+        free_array_for(ptr, n);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflows")]
+    fn alloc_array_overflow() {
+        malloc_array_for::<u64>(usize::MAX / 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "ZERO-sized array")]
+    fn alloc_array_empty() {
+        malloc_array_for::<u64>(0);
+    }
+}
+
+#[cfg(test)]
+mod mem_aligned_alloc_tests {
+    use super::*;
+
+    #[test]
+    fn aligned_alloc_with_size() {
+        for align in [1, 8, 16, 32, 64, 4096] {
+            let (ptr, size) = malloc_aligned(100, align);
+            assert!(!ptr.is_null());
+            assert_eq!(ptr as usize % align, 0);
+            assert_eq!(size, 100);
+            free_aligned(ptr, size, align);
+        }
+
+        let (ptr, size) = calloc_aligned(64, 64);
+        assert_eq!(ptr as usize % 64, 0);
+        assert_eq!(unsafe { *(ptr as *const u64) }, 0);
+        free_aligned(ptr, size, 64);
+    }
+
+    #[test]
+    #[should_panic]
+    fn aligned_alloc_with_invalid_alignment() {
+        malloc_aligned(8, 24);
+    }
+
+    #[test]
+    fn zmem_aligned_alloc_with_size() {
+        for align in [1, 8, 16, 64] {
+            let (ptr, size) = zmalloc_aligned(6, align);
+            assert_eq!(ptr as usize % align, 0);
+            assert_eq!(size, size_of_aligned(6, ZMEM_BODY_ALIGN));
+            assert_eq!(zmem_size_of(ptr), size);
+            zfree_aligned(ptr, align);
+        }
+    }
+}
+
+#[cfg(test)]
+mod mem_try_alloc_tests {
+    use super::*;
+
+    #[test]
+    fn try_alloc_with_size() {
+        let (ptr, size) = try_malloc(8).unwrap();
+        assert!(!ptr.is_null());
+        assert_eq!(size, 8);
+
+        let (ptr, size) = try_realloc(ptr, size, 16).unwrap();
+        assert!(!ptr.is_null());
+        assert_eq!(size, 16);
+        free(ptr, size);
+
+        let (ptr, size) = try_calloc(8).unwrap();
+        assert_eq!(unsafe { *(ptr as *const u64) }, 0);
+        free(ptr, size);
+    }
+
+    #[test]
+    fn try_alloc_with_oversized() {
+        assert_eq!(try_malloc(usize::MAX), Err(AllocError::new(usize::MAX)));
+        assert_eq!(try_calloc(usize::MAX), Err(AllocError::new(usize::MAX)));
+        assert_eq!(
+            try_zmalloc(usize::MAX - 1),
+            Err(AllocError::new(usize::MAX - 1))
+        );
+
+        // Reallocation failure keeps the original memory untouched.
+        let (ptr, size) = malloc(8);
+        unsafe { *(ptr as *mut u64) = 42 };
+        assert!(try_realloc(ptr, size, usize::MAX).is_err());
+        assert_eq!(unsafe { *(ptr as *const u64) }, 42);
+        free(ptr, size);
+    }
+
+    #[test]
+    fn try_zmem_alloc_with_size() {
+        let (ptr, size) = try_zmalloc(6).unwrap();
+        assert!(!ptr.is_null());
+        assert!(size >= 8);
+        assert_eq!(zmem_size_of(ptr), size);
+
+        zfree(ptr);
+    }
+}
+
+#[cfg(test)]
+mod oom_hook_tests {
+    use super::*;
+
+    fn on_oom(_layout: Layout, _used: usize) {}
+
+    #[test]
+    fn register_oom_hook() {
+        set_oom_hook(on_oom);
+        assert!(take_oom_hook().is_some());
+        assert!(take_oom_hook().is_none());
+    }
+}
+
+#[cfg(test)]
+mod mem_stats_tests {
+    use super::*;
+
+    #[test]
+    fn account_alloc_and_free() {
+        let (ptr, size) = malloc(1024);
+        assert!(used_memory() >= 1024);
+        assert!(used_memory_peak() >= used_memory());
+
+        let (ptr, size) = realloc(ptr, size, 4096);
+        assert!(used_memory() >= 4096);
+        assert!(used_memory_peak() >= 4096);
+
+        free(ptr, size);
+        assert!(used_memory_peak() >= 4096);
+    }
+
+    #[test]
+    fn locate_histogram_bucket() {
+        assert_eq!(bucket_of(0), 0);
+        assert_eq!(bucket_of(1), 0);
+        assert_eq!(bucket_of(2), 1);
+        assert_eq!(bucket_of(3), 2);
+        assert_eq!(bucket_of(4), 2);
+        assert_eq!(bucket_of(5), 3);
+        assert_eq!(bucket_of(1 << 20), 20);
+        assert_eq!(bucket_of((1 << 20) + 1), 21);
+        assert_eq!(bucket_of(usize::MAX), SIZE_HISTOGRAM_BUCKETS - 1);
+    }
+
+    #[test]
+    fn account_live_sizes() {
+        let (ptr, size) = malloc(3 << 20);
+        assert!(size_histogram()[22] >= 1);
+
+        let (ptr, size) = realloc(ptr, size, 5 << 20);
+        assert!(size_histogram()[23] >= 1);
+
+        free(ptr, size);
+    }
+}
+
 #[cfg(test)]
 #[allow(unused_assignments)]
 mod zmem_alloc_tests {
@@ -748,8 +2163,9 @@ mod zmem_alloc_tests {
     fn zmem_alloc_with_size() {
         let (mut ptr, size) = zmalloc(6);
         assert!(!ptr.is_null());
-        assert_eq!(size, 8);
-        assert_eq!(zmem_size_of(ptr), 8);
+        assert!(size >= 8);
+        assert_eq!(zmem_size_of(ptr), size);
+        assert_eq!(zmem_usable_size(ptr), size);
 
         zfree(ptr);
         ptr = std::ptr::null_mut();
@@ -759,9 +2175,11 @@ mod zmem_alloc_tests {
     fn zmem_calloc_with_size() {
         let (mut ptr, size) = zcalloc(8);
         assert!(!ptr.is_null());
-        assert_eq!(size, 8);
-        assert_eq!(zmem_size_of(ptr), 8);
-        assert_eq!(unsafe { *(ptr as *const u64) }, 0);
+        assert!(size >= 8);
+        assert_eq!(zmem_size_of(ptr), size);
+        for i in 0..size {
+            assert_eq!(unsafe { *ptr.add(i) }, 0);
+        }
 
         zfree(ptr);
         ptr = std::ptr::null_mut();
@@ -771,13 +2189,13 @@ mod zmem_alloc_tests {
     fn zmem_realloc_with_size() {
         let (ptr, size) = zmalloc(8);
         assert!(!ptr.is_null());
-        assert_eq!(size, 8);
-        assert_eq!(zmem_size_of(ptr), 8);
+        assert!(size >= 8);
+        assert_eq!(zmem_size_of(ptr), size);
 
-        let (mut ptr, size) = zrealloc(ptr, 16);
+        let (mut ptr, size) = zrealloc(ptr, 64);
         assert!(!ptr.is_null());
-        assert_eq!(size, 16);
-        assert_eq!(zmem_size_of(ptr), 16);
+        assert!(size >= 64);
+        assert_eq!(zmem_size_of(ptr), size);
 
         zfree(ptr);
         ptr = std::ptr::null_mut();
@@ -787,10 +2205,139 @@ mod zmem_alloc_tests {
     fn zmem_realloc_for_null_pointer() {
         let (mut ptr, size) = zrealloc(std::ptr::null_mut(), 8);
         assert!(!ptr.is_null());
-        assert_eq!(size, 8);
-        assert_eq!(zmem_size_of(ptr), 8);
+        assert!(size >= 8);
+        assert_eq!(zmem_size_of(ptr), size);
 
         zfree(ptr);
         ptr = std::ptr::null_mut();
     }
+
+    #[test]
+    fn zmem_realloc_within_usable_size() {
+        let (ptr, size) = zmalloc(1000);
+
+        // Fits in the original memory block.
+        let (new_ptr, new_size) = zrealloc(ptr, size);
+        assert_eq!(new_ptr, ptr);
+        assert_eq!(new_size, size);
+
+        // ALWAYS shrunk, even a little.
+        let (ptr, new_size) = zrealloc(new_ptr, 600);
+        assert!((600..size).contains(&new_size));
+        let (ptr, size) = zrealloc(ptr, 8);
+        assert!((8..64).contains(&size));
+        assert_eq!(zmem_size_of(ptr), size);
+
+        zfree(ptr);
+    }
+
+    #[test]
+    fn zmem_realloc_with_headroom() {
+        let (ptr, size) = zrealloc_with_headroom(std::ptr::null_mut(), 100);
+        assert!(size >= 200);
+
+        let (new_ptr, new_size) = zrealloc_with_headroom(ptr, size);
+        assert_eq!(new_ptr, ptr);
+        assert_eq!(new_size, size);
+
+        // Grow linearly beyond the MAX preallocation size.
+        let (ptr, size) = zrealloc_with_headroom(new_ptr, ZMEM_MAX_PREALLOC);
+        assert!((2 * ZMEM_MAX_PREALLOC..3 * ZMEM_MAX_PREALLOC).contains(&size));
+        assert_eq!(zmem_size_of(ptr), size);
+
+        zfree(ptr);
+    }
+
+    #[test]
+    fn zmem_shrink_with_hysteresis() {
+        let (ptr, size) = zmalloc(4000);
+        assert_eq!(zrealloc_shrink(ptr, size / ZMEM_SHRINK_RATIO), (ptr, size));
+
+        let (ptr, size) = zrealloc_shrink(ptr, 100);
+        assert!(size < 1000);
+        assert_eq!(zmem_size_of(ptr), size);
+
+        let (ptr, size) = zrealloc_shrink(ptr, 2000);
+        assert!(size >= 2000);
+
+        zfree(ptr);
+    }
+
+    #[test]
+    fn zmem_body_aligned() {
+        let (ptr, size) = zmalloc(7);
+        assert_eq!(ptr as usize % ZMEM_BODY_ALIGN, 0);
+        assert_eq!(size % ZMEM_BODY_ALIGN, 0);
+
+        let (ptr, size) = zrealloc(ptr, 1000);
+        assert_eq!(ptr as usize % ZMEM_BODY_ALIGN, 0);
+        assert_eq!(zmem_size_of(ptr), size);
+        zfree(ptr);
+
+        let (ptr, size) = zcalloc(33);
+        assert_eq!(ptr as usize % ZMEM_BODY_ALIGN, 0);
+        assert_eq!(zmem_usable_size(ptr), size);
+        zfree(ptr);
+
+        #[cfg(feature = "zmem-align16")]
+        assert_eq!(ZMEM_BODY_ALIGN, 16);
+    }
+
+    #[test]
+    fn zmem_with_len() {
+        let (ptr, size) = zmalloc_with_len(20);
+        assert_eq!(ptr as usize % ZMEM_BODY_ALIGN, 0);
+        assert_eq!(zmem_size_of(ptr), size);
+        zmem_set_len(ptr, 20);
+
+        let (ptr, size) = zrealloc_with_len(ptr, 1000);
+        assert!(size >= 1000);
+        assert_eq!(zmem_len(ptr), 20);
+
+        let (ptr, shrunk) = zrealloc_with_len(ptr, 8);
+        assert!(shrunk < 20);
+        assert_eq!(zmem_len(ptr), shrunk);
+
+        let (ptr, size) = zrealloc_with_len_headroom(ptr, 100);
+        assert!(size >= 200);
+        assert_eq!(zmem_len(ptr), shrunk);
+        zfree_with_len(ptr);
+
+        let (ptr, _) = zrealloc_with_len(std::ptr::null_mut(), 8);
+        assert_eq!(zmem_len(ptr), 0);
+        zfree_with_len(ptr);
+
+        assert_eq!(zmem_len(std::ptr::null_mut()), 0);
+        zmem_set_len(std::ptr::null_mut(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds size")]
+    fn zmem_set_len_over_size() {
+        let (ptr, size) = zmalloc_with_len(16);
+        zmem_set_len(ptr, size + 1);
+    }
+
+    #[test]
+    fn zmem_dup_bytes() {
+        let (ptr, size) = zstrdup(b"0123456789");
+        assert!(size >= 10);
+        assert_eq!(zmem_size_of(ptr), size);
+        assert_eq!(
+            unsafe { std::slice::from_raw_parts(ptr, 10) },
+            b"0123456789"
+        );
+
+        let (dup, _) = unsafe { zmemdup(ptr, 4) };
+        assert_ne!(dup, ptr);
+        assert_eq!(unsafe { std::slice::from_raw_parts(dup, 4) }, b"0123");
+
+        zfree(dup);
+        zfree(ptr);
+
+        let (ptr, size) = zstrdup(b"");
+        assert!(!ptr.is_null());
+        assert_eq!(zmem_size_of(ptr), size);
+        zfree(ptr);
+    }
 }
@@ -0,0 +1,266 @@
+use crate::poison::{poison, unpoison};
+use crate::{align_of, free, malloc, size_of, size_of_aligned, SYS_ALIGN_SIZE};
+
+////////////////////////////////////////////////////////////////////////////////
+// Arena (Bump) Allocation
+////////////////////////////////////////////////////////////////////////////////
+
+/// DEFAULT size (in bytes) of each chunk allocated by an arena.
+pub const ARENA_CHUNK_SIZE: usize = 4096;
+
+/// A memory chunk (allocated with `malloc`) owned by an arena.
+struct Chunk {
+    ptr: *mut u8,
+    size: usize,
+}
+
+/// Arena is a bump allocator that carves memory out of large chunks,
+/// and deallocates ALL of them at once (with `reset` or on drop).
+///
+/// It's designed for lots of short-lived allocations sharing the same lifetime,
+/// such as buffers produced while parsing a client command, that it eliminates
+/// the per-allocation overhead of `malloc` & `free`.
+///
+/// # Notes
+///
+/// Memory allocated from an arena MUST NOT be deallocated with `free`/`zfree`,
+/// and the `pointer` becomes INVALID once the arena is reset or dropped.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::Arena;
+///
+/// let mut arena = Arena::new();
+///
+/// let (ptr, size) = arena.alloc(6);
+/// assert!(!ptr.is_null());
+/// assert_eq!(size, 6);
+///
+/// let (ptr, _) = arena.alloc_for::<u64>();
+/// unsafe { *ptr = 10 };
+///
+/// // Deallocate ALL memory at once.
+/// arena.reset();
+/// assert_eq!(arena.used_bytes(), 0);
+/// ```
+pub struct Arena {
+    chunks: Vec<Chunk>,
+    chunk_size: usize,
+    /// Offset of the first free byte in the current (last) chunk.
+    cursor: usize,
+    used: usize,
+}
+
+impl Arena {
+    /// Create an empty arena with the DEFAULT chunk size.
+    ///
+    /// No memory will be allocated until the first allocation required.
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_chunk_size(ARENA_CHUNK_SIZE)
+    }
+
+    /// Create an empty arena allocating chunks with (at least) `chunk_size` bytes.
+    #[inline]
+    pub fn with_chunk_size(chunk_size: usize) -> Self {
+        Arena {
+            chunks: Vec::new(),
+            chunk_size: std::cmp::max(chunk_size, SYS_ALIGN_SIZE),
+            cursor: 0,
+            used: 0,
+        }
+    }
+
+    /// Allocate memory/buffer with a certain size (aligned with `System-Dependent Alignment`).
+    ///
+    /// A valid `pointer` as well as the `size` of the allocated memory will be returned.
+    ///
+    /// # Aborts
+    ///
+    /// It will abort while memory allocation errors/failures occur (such as OOM).
+    #[inline]
+    #[cfg_attr(feature = "leak-track", track_caller)]
+    pub fn alloc(&mut self, size: usize) -> (*mut u8, usize) {
+        self.alloc_aligned(size, SYS_ALIGN_SIZE)
+    }
+
+    /// Allocate memory/element with a certain type.
+    ///
+    /// # Notes
+    ///
+    /// The element is NEVER dropped by the arena.
+    ///
+    /// # Aborts
+    ///
+    /// It will abort while memory allocation errors/failures occur (such as OOM).
+    #[inline]
+    #[cfg_attr(feature = "leak-track", track_caller)]
+    pub fn alloc_for<T>(&mut self) -> (*mut T, usize) {
+        let (ptr, size) = self.alloc_aligned(size_of::<T>(), align_of::<T>());
+        (ptr as _, size)
+    }
+
+    /// Allocate memory/buffer with a certain size and alignment.
+    ///
+    /// # Notes
+    ///
+    /// `align` MUST be the power of 2.
+    ///
+    /// # Aborts
+    ///
+    /// It will abort while memory allocation errors/failures occur (such as OOM).
+    #[cfg_attr(feature = "leak-track", track_caller)]
+    pub fn alloc_aligned(&mut self, size: usize, align: usize) -> (*mut u8, usize) {
+        if let Some(ptr) = self.bump(size, align) {
+            return (ptr, size);
+        }
+
+        // Reserve extra space for alignment padding, as chunks are only byte-aligned.
+        let csize = std::cmp::max(self.chunk_size, size + align - 1);
+        let (ptr, csize) = malloc(csize);
+        // Headroom of the chunk stays poisoned until carved out.
+        poison(ptr, csize);
+        self.chunks.push(Chunk { ptr, size: csize });
+        self.cursor = 0;
+
+        match self.bump(size, align) {
+            Some(ptr) => (ptr, size),
+            None => unreachable!("new arena chunk is always large enough"),
+        }
+    }
+
+    /// Carve memory out of the current chunk, if it has enough space left.
+    fn bump(&mut self, size: usize, align: usize) -> Option<*mut u8> {
+        let chunk = self.chunks.last()?;
+
+        let base = chunk.ptr as usize;
+        let start = size_of_aligned(base + self.cursor, align) - base;
+        if start > chunk.size || chunk.size - start < size {
+            return None;
+        }
+
+        self.cursor = start + size;
+        self.used += size;
+        let ptr = unsafe { chunk.ptr.add(start) };
+        unpoison(ptr, size);
+        Some(ptr)
+    }
+
+    /// Deallocate ALL memory allocated from the arena at once.
+    ///
+    /// The current chunk is kept (and rewound) for further allocations,
+    /// while the others are returned back to the allocator.
+    pub fn reset(&mut self) {
+        if let Some(last) = self.chunks.pop() {
+            self.release();
+            poison(last.ptr, last.size);
+            self.chunks.push(last);
+        }
+        self.cursor = 0;
+        self.used = 0;
+    }
+
+    /// Get total bytes handed out by the arena (alignment padding excluded).
+    #[inline]
+    pub fn used_bytes(&self) -> usize {
+        self.used
+    }
+
+    /// Get total bytes of chunks allocated by the arena.
+    #[inline]
+    pub fn allocated_bytes(&self) -> usize {
+        self.chunks.iter().map(|chunk| chunk.size).sum()
+    }
+
+    /// Deallocate ALL chunks owned by the arena.
+    fn release(&mut self) {
+        for chunk in self.chunks.drain(..) {
+            unpoison(chunk.ptr, chunk.size);
+            free(chunk.ptr, chunk.size);
+        }
+    }
+}
+
+impl Drop for Arena {
+    #[inline]
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+impl Default for Arena {
+    #[inline]
+    fn default() -> Arena {
+        Arena::new()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Unit Tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod arena_tests {
+    use super::*;
+
+    #[test]
+    fn bump_alloc_in_chunk() {
+        let mut arena = Arena::with_chunk_size(64);
+
+        let (ptr1, size) = arena.alloc(6);
+        assert_eq!(size, 6);
+        let (ptr2, _) = arena.alloc(8);
+        assert_eq!(ptr2 as usize % SYS_ALIGN_SIZE, 0);
+        assert!(ptr2 as usize >= ptr1 as usize + 6);
+
+        assert_eq!(arena.used_bytes(), 14);
+        assert_eq!(arena.allocated_bytes(), 64);
+    }
+
+    #[test]
+    fn alloc_new_chunk_when_full() {
+        let mut arena = Arena::with_chunk_size(64);
+
+        for _ in 0..16 {
+            arena.alloc(16);
+        }
+        assert!(arena.allocated_bytes() >= 16 * 16);
+
+        // Oversized allocation gets a dedicated chunk.
+        let (ptr, size) = arena.alloc(1024);
+        assert!(!ptr.is_null());
+        assert_eq!(size, 1024);
+        unsafe { crate::mem_set(ptr, 0xFF, size) };
+    }
+
+    #[test]
+    fn alloc_aligned_elem() {
+        let mut arena = Arena::new();
+
+        arena.alloc(1);
+        let (ptr, size) = arena.alloc_for::<u64>();
+        assert_eq!(ptr as usize % align_of::<u64>(), 0);
+        assert_eq!(size, 8);
+
+        let (ptr, _) = arena.alloc_aligned(32, 64);
+        assert_eq!(ptr as usize % 64, 0);
+    }
+
+    #[test]
+    fn reset_keeps_current_chunk() {
+        let mut arena = Arena::with_chunk_size(64);
+        for _ in 0..10 {
+            arena.alloc(32);
+        }
+        assert!(arena.allocated_bytes() > 64);
+
+        arena.reset();
+        assert_eq!(arena.used_bytes(), 0);
+        assert_eq!(arena.allocated_bytes(), 64);
+
+        let (ptr, _) = arena.alloc(32);
+        assert!(!ptr.is_null());
+        assert_eq!(arena.allocated_bytes(), 64);
+    }
+}
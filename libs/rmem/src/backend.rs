@@ -0,0 +1,368 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::OnceLock;
+
+use crate::alloc::{stat_alloc, stat_free, stat_realloc};
+use crate::{mem_set, SYS_ALIGN_SIZE};
+
+////////////////////////////////////////////////////////////////////////////////
+// Allocator Backend
+////////////////////////////////////////////////////////////////////////////////
+
+/// An allocator backend which ALL `rmem` memory allocation/deallocation is routed to.
+///
+/// The backend can be replaced (ONLY ONCE, before any memory allocated) with `set_allocator`,
+/// so that embedders can swap in jemalloc, a test allocator or an instrumented allocator
+/// without touching the upper layers (such as `rtypes`).
+///
+/// Memory usage statistics are maintained by `rmem` itself, on top of the backend,
+/// thus a backend does NOT need to take care of them.
+///
+/// # Safety
+///
+/// The implementation MUST follow the same contract as `std::alloc::GlobalAlloc`:
+///
+/// - A NULL pointer is returned ONLY while memory allocation errors/failures occur.
+/// - It MUST NOT unwind, and MUST be safe to be called from multiple threads.
+/// - Memory allocated by the backend stays VALID until deallocated by the same backend.
+pub unsafe trait Allocator: Sync {
+    /// Allocate memory based on a valid layout.
+    ///
+    /// # Safety
+    ///
+    /// ZERO-sized layout is NOT supported/permitted.
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8;
+
+    /// Allocate memory with zero-initialized based on a valid layout.
+    ///
+    /// # Safety
+    ///
+    /// ZERO-sized layout is NOT supported/permitted.
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.alloc(layout);
+        if !ptr.is_null() {
+            mem_set(ptr, 0, layout.size());
+        }
+        ptr
+    }
+
+    /// Reallocate memory (previously allocated with `layout`) to `new_size` bytes.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` MUST be allocated by the same backend with `layout`,
+    /// and the `new_size` with ZERO size is NOT supported/permitted.
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8;
+
+    /// Deallocate memory previously allocated with `layout`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` MUST be allocated by the same backend with `layout`.
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout);
+
+    /// Get the usable size (in bytes) of memory previously allocated with `layout`,
+    /// which MAYBE larger than the required size (based on the allocator bin sizes).
+    ///
+    /// The whole usable size can be taken use of, and the memory can be reallocated or
+    /// deallocated with a layout of any size in range [required size, usable size].
+    ///
+    /// The DEFAULT implementation just takes the size of `layout`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` MUST be allocated by the same backend with `layout`.
+    unsafe fn usable_size(&self, _ptr: *mut u8, layout: Layout) -> usize {
+        layout.size()
+    }
+
+    /// Get bytes of memory taken by the backend itself for bookkeeping (allocator metadata),
+    /// which is reported in `MemoryOverview`.
+    ///
+    /// The DEFAULT implementation just takes ZERO (unknown).
+    fn metadata_size(&self) -> usize {
+        0
+    }
+}
+
+/// The DEFAULT allocator backend, which directs to the system allocator (`std::alloc::System`)
+/// regardless of the `#[global_allocator]` registered by the binary.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemAllocator;
+
+unsafe impl Allocator for SystemAllocator {
+    #[inline]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        System.alloc(layout)
+    }
+
+    #[inline]
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        System.alloc_zeroed(layout)
+    }
+
+    #[inline]
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        System.realloc(ptr, layout, new_size)
+    }
+
+    #[inline]
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    #[inline]
+    unsafe fn usable_size(&self, ptr: *mut u8, layout: Layout) -> usize {
+        // The system allocator directs to `malloc` for memory with small alignment only.
+        if layout.align() > SYS_ALIGN_SIZE || layout.align() > layout.size() {
+            return layout.size();
+        }
+
+        #[cfg(all(
+            target_os = "linux",
+            target_env = "gnu",
+            not(any(feature = "no-libc", miri))
+        ))]
+        return libc::malloc_usable_size(ptr as _);
+        #[cfg(all(target_os = "macos", not(any(feature = "no-libc", miri))))]
+        return libc::malloc_size(ptr as _);
+        #[cfg(any(
+            not(any(all(target_os = "linux", target_env = "gnu"), target_os = "macos")),
+            feature = "no-libc",
+            miri
+        ))]
+        return {
+            let _ = ptr;
+            layout.size()
+        };
+    }
+}
+
+/// The allocator backend directing to mimalloc, available with the `mimalloc` feature.
+///
+/// mimalloc can be used on platforms where jemalloc is unavailable (such as Windows/musl),
+/// and it becomes the DEFAULT backend once the `mimalloc` feature enabled.
+#[cfg(feature = "mimalloc")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MiMallocAllocator;
+
+#[cfg(feature = "mimalloc")]
+unsafe impl Allocator for MiMallocAllocator {
+    #[inline]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        libmimalloc_sys::mi_malloc_aligned(layout.size(), layout.align()) as _
+    }
+
+    #[inline]
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        libmimalloc_sys::mi_zalloc_aligned(layout.size(), layout.align()) as _
+    }
+
+    #[inline]
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        libmimalloc_sys::mi_realloc_aligned(ptr as _, new_size, layout.align()) as _
+    }
+
+    #[inline]
+    unsafe fn dealloc(&self, ptr: *mut u8, _layout: Layout) {
+        libmimalloc_sys::mi_free(ptr as _)
+    }
+
+    #[inline]
+    unsafe fn usable_size(&self, ptr: *mut u8, _layout: Layout) -> usize {
+        libmimalloc_sys::mi_usable_size(ptr as _)
+    }
+}
+
+#[cfg(not(feature = "mimalloc"))]
+type DefaultAllocator = SystemAllocator;
+#[cfg(feature = "mimalloc")]
+type DefaultAllocator = MiMallocAllocator;
+
+static ALLOCATOR: OnceLock<&'static dyn Allocator> = OnceLock::new();
+
+/// Install an allocator backend for ALL `rmem` memory allocation/deallocation.
+///
+/// The backend can ONLY be installed once, and MUST be installed before any memory allocated
+/// via `rmem`, otherwise the DEFAULT backend (`SystemAllocator`, or `MiMallocAllocator` with
+/// the `mimalloc` feature enabled) is already settled and
+/// the provided one will be returned back as an error.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{malloc, free, set_allocator, SystemAllocator};
+///
+/// static BACKEND: SystemAllocator = SystemAllocator;
+///
+/// assert!(set_allocator(&BACKEND).is_ok());
+///
+/// let (ptr, size) = malloc(8);
+/// free(ptr, size);
+///
+/// // It's too late to replace the backend.
+/// assert!(set_allocator(&BACKEND).is_err());
+/// ```
+pub fn set_allocator(allocator: &'static dyn Allocator) -> Result<(), &'static dyn Allocator> {
+    ALLOCATOR.set(allocator)
+}
+
+/// Get the allocator backend in use, and settle the DEFAULT one if none installed yet.
+#[inline]
+pub(crate) fn allocator() -> &'static dyn Allocator {
+    *ALLOCATOR.get_or_init(|| &DefaultAllocator {})
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tracked Global Allocator
+////////////////////////////////////////////////////////////////////////////////
+
+/// A `GlobalAlloc` wrapper that accounts ALL memory allocated/deallocated through it
+/// in the same memory usage statistics (`used_memory`) as `rmem` allocations.
+///
+/// Registered as the `#[global_allocator]`, every Rust allocation (`Vec`, `String`,
+/// `HashMap` and so on) is counted, while memory is actually served by the inner allocator.
+///
+/// # Notes
+///
+/// The DEFAULT `rmem` backend (`SystemAllocator`) does NOT go through the global allocator,
+/// thus `rmem` allocations are NEVER accounted twice.
+///
+/// # Examples
+///
+/// ```
+/// use rmem::{used_memory, TrackedAllocator};
+/// use std::alloc::System;
+///
+/// #[global_allocator]
+/// static GLOBAL: TrackedAllocator<System> = TrackedAllocator::new(System);
+///
+/// let bytes: Vec<u8> = Vec::with_capacity(4096);
+/// assert!(used_memory() >= 4096);
+/// # drop(bytes);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TrackedAllocator<A = System> {
+    inner: A,
+}
+
+impl<A> TrackedAllocator<A> {
+    /// Create a tracked allocator wrapping the `inner` one.
+    #[inline]
+    pub const fn new(inner: A) -> Self {
+        TrackedAllocator { inner }
+    }
+
+    /// Get the inner allocator.
+    #[inline]
+    pub const fn inner(&self) -> &A {
+        &self.inner
+    }
+}
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for TrackedAllocator<A> {
+    #[inline]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.inner.alloc(layout);
+        if !ptr.is_null() {
+            stat_alloc(layout.size());
+        }
+        ptr
+    }
+
+    #[inline]
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.inner.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            stat_alloc(layout.size());
+        }
+        ptr
+    }
+
+    #[inline]
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = self.inner.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            stat_realloc(layout.size(), new_size);
+        }
+        new_ptr
+    }
+
+    #[inline]
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.inner.dealloc(ptr, layout);
+        stat_free(layout.size());
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Unit Tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod backend_tests {
+    use super::*;
+
+    struct PlainSystem;
+
+    unsafe impl Allocator for PlainSystem {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            System.alloc(layout)
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            System.realloc(ptr, layout, new_size)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[test]
+    fn default_backend_methods() {
+        let layout = Layout::from_size_align(16, 1).unwrap();
+        unsafe {
+            let ptr = PlainSystem.alloc_zeroed(layout);
+            assert!(!ptr.is_null());
+            assert_eq!(*(ptr as *const u64), 0);
+            assert_eq!(PlainSystem.usable_size(ptr, layout), 16);
+
+            PlainSystem.dealloc(ptr, layout);
+        }
+    }
+
+    #[test]
+    fn system_backend_usable_size() {
+        let layout = Layout::from_size_align(20, 1).unwrap();
+        unsafe {
+            let ptr = SystemAllocator.alloc(layout);
+            assert!(SystemAllocator.usable_size(ptr, layout) >= 20);
+
+            SystemAllocator.dealloc(ptr, layout);
+        }
+    }
+
+    #[cfg(feature = "mimalloc")]
+    #[test]
+    fn mimalloc_backend() {
+        let layout = Layout::from_size_align(24, 8).unwrap();
+        unsafe {
+            let ptr = MiMallocAllocator.alloc_zeroed(layout);
+            assert!(!ptr.is_null());
+            assert_eq!(*(ptr as *const u64), 0);
+            assert!(MiMallocAllocator.usable_size(ptr, layout) >= 24);
+
+            let ptr = MiMallocAllocator.realloc(ptr, layout, 4096);
+            assert!(!ptr.is_null());
+            assert_eq!(*(ptr as *const u64), 0);
+
+            MiMallocAllocator.dealloc(ptr, Layout::from_size_align(4096, 8).unwrap());
+        }
+    }
+
+    #[test]
+    fn default_backend_settled() {
+        let _ = allocator();
+        assert!(set_allocator(&SystemAllocator).is_err());
+    }
+}
@@ -0,0 +1,282 @@
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+use crate::{mem_copy, mem_set, zfree, zmalloc, zmem_size_of, zrealloc_with_headroom};
+
+////////////////////////////////////////////////////////////////////////////////
+// Owning Memory Buffer
+////////////////////////////////////////////////////////////////////////////////
+
+/// MemBuf is a growable buffer owning ZMEM-style memory (allocated with `zmalloc`),
+/// which is deallocated automatically on drop.
+///
+/// It derefs to `&[u8]` / `&mut [u8]` (of its length), thus raw pointers are NOT required
+/// to be juggled for common works, while the `capacity` is tracked by the ZMEM header.
+///
+/// # Notes
+///
+/// No memory will be allocated for an empty buffer created with `new`.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::MemBuf;
+///
+/// let mut buf = MemBuf::new();
+/// assert!(buf.is_empty());
+///
+/// buf.extend_from_slice(b"hello");
+/// buf.resize(8, b'!');
+/// assert_eq!(&buf[..], b"hello!!!");
+/// assert!(buf.capacity() >= 8);
+///
+/// buf[0] = b'H';
+/// assert_eq!(&buf[..5], b"Hello");
+/// ```
+pub struct MemBuf {
+    ptr: *mut u8,
+    len: usize,
+}
+
+// MemBuf owns its memory exclusively, just like `Vec<u8>` does.
+unsafe impl Send for MemBuf {}
+unsafe impl Sync for MemBuf {}
+
+impl MemBuf {
+    /// Create an empty buffer, WITHOUT memory allocated.
+    #[inline]
+    pub const fn new() -> Self {
+        MemBuf {
+            ptr: std::ptr::null_mut(),
+            len: 0,
+        }
+    }
+
+    /// Create an empty buffer with (at least) `capacity` bytes allocated.
+    ///
+    /// # Aborts
+    ///
+    /// It will abort while memory allocation errors/failures occur (such as OOM).
+    #[inline]
+    #[cfg_attr(feature = "leak-track", track_caller)]
+    pub fn with_capacity(capacity: usize) -> Self {
+        let (ptr, _) = zmalloc(capacity);
+        MemBuf { ptr, len: 0 }
+    }
+
+    /// Get length (in bytes) of the buffer.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if the buffer is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get capacity (in bytes) of the buffer, which MAYBE larger than the required one.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        zmem_size_of(self.ptr)
+    }
+
+    /// Get the raw `pointer` of the buffer, which is NULL if no memory allocated yet.
+    #[inline]
+    pub fn as_ptr(&self) -> *const u8 {
+        self.ptr
+    }
+
+    /// Get the raw mutable `pointer` of the buffer, which is NULL if no memory allocated yet.
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.ptr
+    }
+
+    /// Reserve capacity for (at least) `extra` more bytes.
+    ///
+    /// Headroom is preallocated (as `zrealloc_with_headroom` does) for further growth.
+    ///
+    /// # Aborts
+    ///
+    /// It will abort while memory allocation errors/failures occur (such as OOM).
+    #[cfg_attr(feature = "leak-track", track_caller)]
+    pub fn reserve(&mut self, extra: usize) {
+        let required = self.len.checked_add(extra).expect("capacity overflow");
+        if required > self.capacity() {
+            let (ptr, _) = zrealloc_with_headroom(self.ptr, required);
+            self.ptr = ptr;
+        }
+    }
+
+    /// Resize the buffer to `new_len` bytes, filling the extended part with `value`
+    /// or truncating the buffer.
+    ///
+    /// # Aborts
+    ///
+    /// It will abort while memory allocation errors/failures occur (such as OOM).
+    #[cfg_attr(feature = "leak-track", track_caller)]
+    pub fn resize(&mut self, new_len: usize, value: u8) {
+        if new_len > self.len {
+            self.reserve(new_len - self.len);
+            unsafe { mem_set(self.ptr.add(self.len), value, new_len - self.len) };
+        }
+        self.len = new_len;
+    }
+
+    /// Append ALL bytes of `bytes` to the end of the buffer.
+    ///
+    /// # Aborts
+    ///
+    /// It will abort while memory allocation errors/failures occur (such as OOM).
+    #[cfg_attr(feature = "leak-track", track_caller)]
+    pub fn extend_from_slice(&mut self, bytes: &[u8]) {
+        if bytes.is_empty() {
+            return;
+        }
+
+        self.reserve(bytes.len());
+        unsafe { mem_copy(bytes.as_ptr(), self.ptr.add(self.len), bytes.len()) };
+        self.len += bytes.len();
+    }
+
+    /// Shorten the buffer to `len` bytes, which has no effect if `len` is greater
+    /// than the current one. The capacity is kept as is.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        self.len = std::cmp::min(self.len, len);
+    }
+
+    /// Clear the buffer, with the capacity kept as is.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl Drop for MemBuf {
+    #[inline]
+    fn drop(&mut self) {
+        zfree(self.ptr);
+    }
+}
+
+impl Default for MemBuf {
+    #[inline]
+    fn default() -> MemBuf {
+        MemBuf::new()
+    }
+}
+
+impl Deref for MemBuf {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        match self.ptr.is_null() {
+            true => &[],
+            false => unsafe { std::slice::from_raw_parts(self.ptr, self.len) },
+        }
+    }
+}
+
+impl DerefMut for MemBuf {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [u8] {
+        match self.ptr.is_null() {
+            true => &mut [],
+            false => unsafe { std::slice::from_raw_parts_mut(self.ptr, self.len) },
+        }
+    }
+}
+
+impl From<&[u8]> for MemBuf {
+    #[inline]
+    fn from(bytes: &[u8]) -> MemBuf {
+        let mut buf = MemBuf::with_capacity(bytes.len());
+        buf.extend_from_slice(bytes);
+        buf
+    }
+}
+
+impl Clone for MemBuf {
+    #[inline]
+    fn clone(&self) -> MemBuf {
+        MemBuf::from(&self[..])
+    }
+}
+
+impl PartialEq for MemBuf {
+    #[inline]
+    fn eq(&self, other: &MemBuf) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl Eq for MemBuf {}
+
+impl fmt::Debug for MemBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemBuf")
+            .field("len", &self.len)
+            .field("capacity", &self.capacity())
+            .field("bytes", &&self[..])
+            .finish()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Unit Tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod buf_tests {
+    use super::*;
+
+    #[test]
+    fn empty_buf_without_memory() {
+        let buf = MemBuf::new();
+        assert!(buf.as_ptr().is_null());
+        assert_eq!(buf.capacity(), 0);
+        assert_eq!(&buf[..], b"");
+
+        let buf = MemBuf::with_capacity(100);
+        assert!(!buf.as_ptr().is_null());
+        assert!(buf.capacity() >= 100);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn grow_and_shrink_buf() {
+        let mut buf = MemBuf::new();
+        for _ in 0..100 {
+            buf.extend_from_slice(b"0123456789");
+        }
+        assert_eq!(buf.len(), 1000);
+        assert!(buf.capacity() >= 1000);
+        assert_eq!(&buf[990..], b"0123456789");
+
+        buf.resize(1004, 0);
+        assert_eq!(&buf[998..], &[b'8', b'9', 0, 0, 0, 0]);
+
+        let capacity = buf.capacity();
+        buf.truncate(10);
+        assert_eq!(&buf[..], b"0123456789");
+        buf.clear();
+        assert!(buf.is_empty());
+        assert_eq!(buf.capacity(), capacity);
+    }
+
+    #[test]
+    fn clone_buf() {
+        let buf = MemBuf::from(&b"hello"[..]);
+        let mut other = buf.clone();
+        assert_eq!(other, buf);
+        assert_ne!(other.as_ptr(), buf.as_ptr());
+
+        other[0] = b'j';
+        assert_eq!(&other[..], b"jello");
+        assert_eq!(&buf[..], b"hello");
+    }
+}
@@ -0,0 +1,270 @@
+use std::alloc::Layout;
+
+use crate::backend::allocator;
+
+////////////////////////////////////////////////////////////////////////////////
+// Guarded Memory Allocation
+////////////////////////////////////////////////////////////////////////////////
+
+/// With the `mem-guard` feature enabled, ALL `rmem` memory is allocated with canary bytes
+/// (`GUARD_BYTE` repeatedly) placed right before and after the region handed out,
+/// which are verified once deallocated (or reallocated).
+///
+/// A corrupted canary byte (caused by buffer overrun/underrun) results in a PANIC reporting
+/// the offset (relative to the start of the region) of the FIRST corrupted byte, rather than
+/// a silent heap corruption.
+///
+/// Without the feature, memory is directed to the allocator backend as is.
+#[cfg(feature = "mem-guard")]
+pub const GUARD_SIZE: usize = 16;
+#[cfg(feature = "mem-guard")]
+pub const GUARD_BYTE: u8 = 0xFD;
+
+/// Allocate memory from the allocator backend, guarded with canary bytes if enabled.
+#[inline]
+pub(crate) unsafe fn guarded_alloc(layout: Layout) -> *mut u8 {
+    #[cfg(not(feature = "mem-guard"))]
+    return allocator().alloc(layout);
+    #[cfg(feature = "mem-guard")]
+    return match guard_layout(layout) {
+        Some(glayout) => guard_wrap(allocator().alloc(glayout), layout),
+        None => std::ptr::null_mut(),
+    };
+}
+
+/// Allocate memory with zero-initialized from the allocator backend,
+/// guarded with canary bytes if enabled.
+#[inline]
+pub(crate) unsafe fn guarded_alloc_zeroed(layout: Layout) -> *mut u8 {
+    #[cfg(not(feature = "mem-guard"))]
+    return allocator().alloc_zeroed(layout);
+    #[cfg(feature = "mem-guard")]
+    return match guard_layout(layout) {
+        Some(glayout) => guard_wrap(allocator().alloc_zeroed(glayout), layout),
+        None => std::ptr::null_mut(),
+    };
+}
+
+/// Reallocate memory from the allocator backend, with canary bytes verified & rewritten
+/// if enabled.
+///
+/// # Panics
+///
+/// It will panic while canary bytes of the original memory are corrupted.
+#[inline]
+pub(crate) unsafe fn guarded_realloc(ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+    #[cfg(not(feature = "mem-guard"))]
+    return allocator().realloc(ptr, layout, new_size);
+    #[cfg(feature = "mem-guard")]
+    return {
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+        match (guard_layout(layout), guard_layout(new_layout)) {
+            (Some(glayout), Some(new_glayout)) => {
+                let block = guard_unwrap(ptr, layout);
+                let block = allocator().realloc(block, glayout, new_glayout.size());
+                guard_wrap(block, new_layout)
+            }
+            _ => std::ptr::null_mut(),
+        }
+    };
+}
+
+/// Deallocate memory to the allocator backend, with canary bytes verified if enabled.
+///
+/// # Panics
+///
+/// It will panic while canary bytes of the memory are corrupted.
+#[inline]
+pub(crate) unsafe fn guarded_dealloc(ptr: *mut u8, layout: Layout) {
+    #[cfg(not(feature = "mem-guard"))]
+    allocator().dealloc(ptr, layout);
+    #[cfg(feature = "mem-guard")]
+    if let Some(glayout) = guard_layout(layout) {
+        allocator().dealloc(guard_unwrap(ptr, layout), glayout);
+    }
+}
+
+/// Verify canary bytes of memory (if guarded), which stays VALID after verification.
+///
+/// # Panics
+///
+/// It will panic while canary bytes of the memory are corrupted.
+#[inline]
+pub(crate) unsafe fn guarded_check(ptr: *mut u8, layout: Layout) {
+    #[cfg(not(feature = "mem-guard"))]
+    let _ = (ptr, layout);
+    #[cfg(feature = "mem-guard")]
+    guard_unwrap(ptr, layout);
+}
+
+/// Get the usable size of memory from the allocator backend, which is exactly the required
+/// size if guarded with canary bytes (that the trailing canary bytes MUST NOT be used).
+#[inline]
+pub(crate) unsafe fn guarded_usable_size(ptr: *mut u8, layout: Layout) -> usize {
+    #[cfg(not(feature = "mem-guard"))]
+    return allocator().usable_size(ptr, layout);
+    #[cfg(feature = "mem-guard")]
+    return {
+        let _ = ptr;
+        layout.size()
+    };
+}
+
+/// With the `poison-on-free` feature enabled, ALL `rmem` memory is filled with `FREED_BYTE`
+/// once deallocated, and ZMEM headers read as the pattern (memory already deallocated)
+/// result in a PANIC, rather than a silent use-after-free.
+#[cfg(feature = "poison-on-free")]
+pub const FREED_BYTE: u8 = 0xDB;
+
+/// Fill memory to be deallocated with the freed pattern if enabled.
+#[inline]
+pub(crate) unsafe fn fill_freed(ptr: *mut u8, size: usize) {
+    #[cfg(not(feature = "poison-on-free"))]
+    let _ = (ptr, size);
+    #[cfg(feature = "poison-on-free")]
+    crate::mem_set(ptr, FREED_BYTE, size);
+}
+
+/// Verify a ZMEM header (the size of body part) read from memory if enabled, and return it.
+///
+/// # Panics
+///
+/// It will panic while the header reads as the freed pattern (use-after-free).
+#[inline]
+pub(crate) fn checked_zmem_header(ptr: *const u8, header: usize) -> usize {
+    #[cfg(feature = "poison-on-free")]
+    if header == usize::from_ne_bytes([FREED_BYTE; crate::size_of::<usize>()]) {
+        panic!("use-after-free detected on ZMEM memory {:p}", ptr);
+    }
+    let _ = ptr;
+    header
+}
+
+/// Get size of the leading canary bytes, which keeps the region aligned as required.
+#[cfg(feature = "mem-guard")]
+#[inline]
+fn guard_front_size(layout: Layout) -> usize {
+    std::cmp::max(GUARD_SIZE, layout.align())
+}
+
+/// Create the layout of the whole guarded memory block (canary bytes included).
+#[cfg(feature = "mem-guard")]
+#[inline]
+fn guard_layout(layout: Layout) -> Option<Layout> {
+    let size = layout
+        .size()
+        .checked_add(guard_front_size(layout) + GUARD_SIZE)?;
+    Layout::from_size_align(size, layout.align()).ok()
+}
+
+/// Place canary bytes around the region within a newly (re)allocated memory block,
+/// and return the `pointer` of the region (NULL if the block is NULL).
+#[cfg(feature = "mem-guard")]
+unsafe fn guard_wrap(block: *mut u8, layout: Layout) -> *mut u8 {
+    if block.is_null() {
+        return block;
+    }
+
+    let front = guard_front_size(layout);
+    crate::mem_set(block, GUARD_BYTE, front);
+    crate::mem_set(block.add(front + layout.size()), GUARD_BYTE, GUARD_SIZE);
+    block.add(front)
+}
+
+/// Verify canary bytes around the region, and return the `pointer` of the whole memory block.
+///
+/// # Panics
+///
+/// It will panic while any canary byte is corrupted.
+#[cfg(feature = "mem-guard")]
+unsafe fn guard_unwrap(ptr: *mut u8, layout: Layout) -> *mut u8 {
+    let front = guard_front_size(layout);
+    let block = ptr.sub(front);
+
+    let corrupted = (0..front)
+        .find(|&idx| *block.add(idx) != GUARD_BYTE)
+        .map(|idx| idx as isize - front as isize)
+        .or_else(|| {
+            (0..GUARD_SIZE)
+                .find(|&idx| *ptr.add(layout.size() + idx) != GUARD_BYTE)
+                .map(|idx| (layout.size() + idx) as isize)
+        });
+    if let Some(offset) = corrupted {
+        panic!(
+            "memory corruption detected at offset {} of {} bytes memory {:p}",
+            offset,
+            layout.size(),
+            ptr
+        );
+    }
+    block
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Unit Tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(all(test, feature = "poison-on-free"))]
+mod freed_tests {
+    use super::*;
+
+    #[test]
+    fn fill_freed_memory() {
+        let mut bytes = [0u8; 10];
+        unsafe { fill_freed(bytes.as_mut_ptr(), 8) };
+        assert_eq!(
+            bytes,
+            [0xDB, 0xDB, 0xDB, 0xDB, 0xDB, 0xDB, 0xDB, 0xDB, 0, 0]
+        );
+        assert_eq!(checked_zmem_header(bytes.as_ptr(), 16), 16);
+    }
+
+    #[test]
+    #[should_panic(expected = "use-after-free detected on ZMEM memory")]
+    fn detect_freed_zmem_header() {
+        let header = usize::from_ne_bytes([FREED_BYTE; crate::size_of::<usize>()]);
+        checked_zmem_header(std::ptr::null(), header);
+    }
+}
+
+#[cfg(all(test, feature = "mem-guard"))]
+mod guard_tests {
+    use super::*;
+
+    #[test]
+    fn guard_aligned_memory() {
+        let layout = Layout::from_size_align(100, 64).unwrap();
+        unsafe {
+            let ptr = guarded_alloc(layout);
+            assert_eq!(ptr as usize % 64, 0);
+            assert_eq!(*ptr.sub(1), GUARD_BYTE);
+            assert_eq!(*ptr.add(100), GUARD_BYTE);
+
+            let ptr = guarded_realloc(ptr, layout, 200);
+            assert_eq!(*ptr.add(200), GUARD_BYTE);
+            guarded_dealloc(ptr, Layout::from_size_align(200, 64).unwrap());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "memory corruption detected at offset 8 of 8 bytes")]
+    fn detect_buffer_overrun() {
+        let layout = Layout::from_size_align(8, 1).unwrap();
+        unsafe {
+            let ptr = guarded_alloc(layout);
+            *ptr.add(8) = 0;
+            guarded_dealloc(ptr, layout);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "memory corruption detected at offset -1 of 8 bytes")]
+    fn detect_buffer_underrun() {
+        let layout = Layout::from_size_align(8, 1).unwrap();
+        unsafe {
+            let ptr = guarded_alloc(layout);
+            *ptr.sub(1) = 0;
+            guarded_dealloc(ptr, layout);
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::panic::Location;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+////////////////////////////////////////////////////////////////////////////////
+// Leak Tracking
+////////////////////////////////////////////////////////////////////////////////
+
+/// A live (allocated but NOT deallocated yet) memory block, recorded with the `leak-track`
+/// feature enabled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LiveAllocation {
+    /// Address of the memory block (the header part included for ZMEM-style memory).
+    pub addr: usize,
+    /// Size (in bytes) of the memory block.
+    pub size: usize,
+    /// The moment the memory block was (re)allocated.
+    pub timestamp: Instant,
+    /// The call site which (re)allocated the memory block.
+    pub caller: &'static Location<'static>,
+}
+
+impl LiveAllocation {
+    /// Get the time elapsed since the memory block was (re)allocated.
+    #[inline]
+    pub fn age(&self) -> Duration {
+        self.timestamp.elapsed()
+    }
+}
+
+impl fmt::Display for LiveAllocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:#x} ({} bytes) allocated at {} {:?} ago",
+            self.addr,
+            self.size,
+            self.caller,
+            self.age()
+        )
+    }
+}
+
+/// ALL live memory blocks, keyed by address.
+static LIVE_ALLOCATIONS: Mutex<BTreeMap<usize, LiveAllocation>> = Mutex::new(BTreeMap::new());
+
+#[inline]
+fn lock() -> MutexGuard<'static, BTreeMap<usize, LiveAllocation>> {
+    LIVE_ALLOCATIONS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Record a memory block (re)allocated by the caller.
+#[track_caller]
+pub(crate) fn track_alloc(ptr: *mut u8, size: usize) {
+    let addr = ptr as usize;
+    let allocation = LiveAllocation {
+        addr,
+        size,
+        timestamp: Instant::now(),
+        caller: Location::caller(),
+    };
+    lock().insert(addr, allocation);
+}
+
+/// Update the size of a memory block extended in place (such as ZMEM-style memory extended
+/// to its usable size).
+#[inline]
+pub(crate) fn track_resize(ptr: *mut u8, size: usize) {
+    if let Some(allocation) = lock().get_mut(&(ptr as usize)) {
+        allocation.size = size;
+    }
+}
+
+/// Forget a memory block deallocated (or moved by reallocation).
+#[inline]
+pub(crate) fn track_free(ptr: *mut u8) {
+    lock().remove(&(ptr as usize));
+}
+
+/// Verify that the region `[ptr, ptr + len)` does NOT straddle the boundary of any live
+/// memory block, i.e. it lies entirely inside a live memory block, or outside ALL of them
+/// (such as memory NOT allocated via `rmem`).
+///
+/// # Panics
+///
+/// It will panic while the region overruns (or underruns) a live memory block.
+pub(crate) fn check_live_region(ptr: *const u8, len: usize) {
+    if len == 0 {
+        return;
+    }
+
+    let (start, end) = (ptr as usize, (ptr as usize).saturating_add(len));
+    let live = lock();
+    if let Some((_, allocation)) = live.range(..=start).next_back() {
+        let alloc_end = allocation.addr + allocation.size;
+        if start < alloc_end && end > alloc_end {
+            panic!(
+                "region {:#x} of {} bytes overruns live allocation {}",
+                start, len, allocation
+            );
+        }
+    }
+    if let Some((_, allocation)) = live.range(start + 1..end).next() {
+        panic!(
+            "region {:#x} of {} bytes underruns live allocation {}",
+            start, len, allocation
+        );
+    }
+}
+
+/// Get ALL live memory blocks allocated via `rmem`, in the order of allocation time.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{free, live_allocations, malloc};
+///
+/// let (ptr, size) = malloc(64);
+/// let live = live_allocations();
+/// let allocation = live.iter().find(|a| a.addr == ptr as usize).unwrap();
+/// assert_eq!(allocation.size, 64);
+/// assert_eq!(allocation.caller.file(), file!());
+///
+/// free(ptr, size);
+/// assert!(live_allocations().iter().all(|a| a.addr != ptr as usize));
+/// ```
+pub fn live_allocations() -> Vec<LiveAllocation> {
+    let mut live: Vec<LiveAllocation> = lock().values().copied().collect();
+    live.sort_by_key(|allocation| allocation.timestamp);
+    live
+}
+
+/// Dump ALL live memory blocks allocated via `rmem` to `stderr`, grouped by call site
+/// (with the largest total size first), and return the number of live memory blocks.
+///
+/// It's designed for long-running tests to prove which subsystem is leaking.
+pub fn dump_live_allocations() -> usize {
+    let live = live_allocations();
+
+    let mut sites: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+    for allocation in live.iter() {
+        let site = sites.entry(allocation.caller.to_string()).or_default();
+        site.0 += 1;
+        site.1 += allocation.size;
+    }
+    let mut sites: Vec<(String, (usize, usize))> = sites.into_iter().collect();
+    sites.sort_by_key(|(_, (_, size))| std::cmp::Reverse(*size));
+
+    eprintln!(
+        "rmem: {} live allocations ({} bytes)",
+        live.len(),
+        live.iter().map(|allocation| allocation.size).sum::<usize>()
+    );
+    for (site, (count, size)) in sites.iter() {
+        eprintln!("  {}: {} allocations ({} bytes)", site, count, size);
+    }
+    live.len()
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Unit Tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod leak_tests {
+    use super::*;
+
+    use crate::{free, malloc, realloc, zfree, zmalloc};
+
+    fn find(addr: usize) -> Option<LiveAllocation> {
+        live_allocations().into_iter().find(|a| a.addr == addr)
+    }
+
+    #[test]
+    fn track_alloc_call_site() {
+        let line = line!() + 1;
+        let (ptr, size) = malloc(100);
+        let allocation = find(ptr as usize).unwrap();
+        assert_eq!(allocation.size, 100);
+        assert_eq!(allocation.caller.file(), file!());
+        assert_eq!(allocation.caller.line(), line);
+
+        let (new_ptr, new_size) = realloc(ptr, size, 100000);
+        assert_eq!(find(new_ptr as usize).unwrap().size, 100000);
+
+        free(new_ptr, new_size);
+        assert_eq!(find(new_ptr as usize), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "bytes overruns live allocation")]
+    fn check_region_overrun() {
+        let (ptr, size) = malloc(64);
+        check_live_region(ptr, size);
+        check_live_region(unsafe { ptr.add(8) }, size - 8);
+        check_live_region(unsafe { ptr.add(8) }, size);
+    }
+
+    #[test]
+    #[should_panic(expected = "bytes underruns live allocation")]
+    fn check_region_underrun() {
+        let (ptr, size) = malloc(64);
+        check_live_region(unsafe { ptr.sub(8) }, 8);
+        check_live_region(unsafe { ptr.sub(8) }, size);
+    }
+
+    #[test]
+    fn track_zmem_call_site() {
+        let (ptr, _) = zmalloc(10);
+        let header = crate::size_of_aligned(crate::size_of::<usize>(), crate::ZMEM_BODY_ALIGN);
+        let allocation = find(ptr as usize - header).unwrap();
+        assert_eq!(allocation.caller.file(), file!());
+
+        zfree(ptr);
+        assert!(dump_live_allocations() < usize::MAX);
+    }
+}
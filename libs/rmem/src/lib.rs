@@ -1,12 +1,89 @@
+mod advise;
 mod align;
 mod alloc;
+mod arena;
+mod backend;
+mod buf;
+mod guard;
+#[cfg(feature = "leak-track")]
+mod leak;
 mod mem;
+mod object;
+mod poison;
+mod pool;
+mod proc;
+mod ring;
+#[cfg(all(unix, not(miri)))]
+mod shm;
+mod simd;
+mod slice;
+mod tag;
+mod thread;
+#[cfg(feature = "alloc-trace")]
+mod trace;
+
+pub use advise::{disable_thp_for_region, madvise_dontneed, page_size};
+pub use advise::{set_thp_policy, thp_policy, ThpPolicy};
 
 pub use align::{align_of, size_of, size_of_aligned, size_of_sys_aligned};
 pub use align::{BYTE_ALIGN_SIZE, SYS_ALIGN_SIZE};
 
 pub use alloc::{calloc, calloc_for, free, free_for, malloc, malloc_for, realloc};
-pub use alloc::{zcalloc, zfree, zmalloc, zmem_size_of, zrealloc};
+pub use alloc::{calloc_aligned, free_aligned, malloc_aligned};
+pub use alloc::{calloc_array_for, free_array_for, malloc_array_for};
+pub use alloc::{memory_limit, set_memory_limit, MemoryLimitHook};
+pub use alloc::{set_memory_limit_hook, take_memory_limit_hook};
+pub use alloc::{set_oom_hook, take_oom_hook, OomHook};
+pub use alloc::{set_zmem_max_prealloc, zmem_max_prealloc, zrealloc_with_headroom};
+pub use alloc::{size_histogram, used_memory, used_memory_peak, SIZE_HISTOGRAM_BUCKETS};
+pub use alloc::{try_calloc, try_malloc, try_realloc, try_zmalloc, AllocError};
+pub use alloc::{zcalloc, zfree, zmalloc, zmem_size_of, zmem_usable_size, zrealloc};
+pub use alloc::{zfree_aligned, zmalloc_aligned, ZMEM_BODY_ALIGN, ZMEM_MAX_PREALLOC};
+pub use alloc::{zfree_with_len, zmalloc_with_len, zmem_len, zmem_set_len};
+pub use alloc::{zmemdup, zstrdup};
+pub use alloc::{zrealloc_shrink, ZMEM_SHRINK_RATIO};
+pub use alloc::{zrealloc_with_len, zrealloc_with_len_headroom};
+
+pub use arena::{Arena, ARENA_CHUNK_SIZE};
+
+#[cfg(feature = "mimalloc")]
+pub use backend::MiMallocAllocator;
+pub use backend::{set_allocator, Allocator, SystemAllocator, TrackedAllocator};
+
+pub use buf::MemBuf;
+
+#[cfg(feature = "poison-on-free")]
+pub use guard::FREED_BYTE;
+#[cfg(feature = "mem-guard")]
+pub use guard::{GUARD_BYTE, GUARD_SIZE};
+
+#[cfg(feature = "leak-track")]
+pub use leak::{dump_live_allocations, live_allocations, LiveAllocation};
+
+pub use mem::{mem_cmp, mem_cmp_ct, mem_copy, mem_find, mem_find_pattern, mem_move, mem_set};
+pub use mem::{mem_copy_for, mem_move_for, mem_reverse_for, mem_swap_for};
+pub use mem::{mem_reverse, mem_rfind, mem_rfind_pattern, mem_secure_zero, mem_swap};
+
+pub use object::{ObjectPool, OBJECT_POOL_HIGH_WATERMARK, OBJECT_POOL_LOW_WATERMARK};
+
+pub use pool::{pool_free, pool_malloc, pool_purge, pool_stats, PoolClassStats};
+pub use pool::{POOL_MAX_CACHED_BLOCKS, POOL_MAX_CLASS_SIZE, POOL_MIN_CLASS_SIZE};
+
+pub use proc::{memory_overview, proc_peak_rss, proc_rss, MemoryOverview};
+
+pub use ring::RingBuf;
+
+#[cfg(all(unix, not(miri)))]
+pub use shm::SharedRegion;
+
+pub use slice::{alloc_slice, alloc_slice_zeroed, OwnedSlice};
+
+pub use tag::{current_tag, tag_scope, tag_stats, tagged_memory, Tag, TagScope};
+pub use tag::{free_tagged, malloc_tagged};
+
+pub use thread::{flush_thread_cache, thread_stats, ThreadStats};
 
-pub use mem::{mem_cmp, mem_copy, mem_find, mem_move, mem_set};
-pub use mem::{mem_copy_for, mem_move_for};
+#[cfg(feature = "alloc-trace")]
+pub use trace::DEFAULT_TRACE_THRESHOLD;
+#[cfg(feature = "alloc-trace")]
+pub use trace::{set_trace_sample_rate, set_trace_threshold, trace_sample_rate, trace_threshold};
@@ -1,41 +1,406 @@
 use std::cmp::Ordering;
 
+use crate::simd::{simd_cmp, simd_find, simd_find_pattern, SIMD_MAX_PATTERN_SIZE};
 use crate::size_of;
 
 ////////////////////////////////////////////////////////////////////////////////
 // Memory (Byte-Leveled) Operations
 ////////////////////////////////////////////////////////////////////////////////
 
+/// Copy `count` bytes from `src` to `dst`.
+///
+/// # Safety
+///
+/// Both `src` & `dst` MUST be valid for `count` bytes, and the two memory regions
+/// MUST NOT overlap (use `mem_move` for overlapping regions instead).
+///
+/// # Panics
+///
+/// In debug builds, it will panic while the two memory regions overlap, as well as
+/// (with the `leak-track` feature enabled) while either of them overruns a live memory block.
 #[inline]
 pub unsafe fn mem_copy(src: *const u8, dst: *mut u8, count: usize) {
-    libc::memcpy(dst as _, src as _, count);
+    debug_check_copy(src, dst, count);
+    sys::copy(src, dst, count);
 }
 
+/// Verify the regions of `mem_copy` (in debug builds ONLY).
+#[inline]
+fn debug_check_copy(src: *const u8, dst: *const u8, count: usize) {
+    debug_assert!(
+        count == 0
+            || (src as usize).saturating_add(count) <= dst as usize
+            || (dst as usize).saturating_add(count) <= src as usize,
+        "mem_copy on overlapping regions {:p} & {:p} of {} bytes (use mem_move instead)",
+        src,
+        dst,
+        count
+    );
+    #[cfg(all(debug_assertions, feature = "leak-track"))]
+    {
+        crate::leak::check_live_region(src, count);
+        crate::leak::check_live_region(dst, count);
+    }
+}
+
+/// Move `count` bytes from `src` to `dst`, the two memory regions MAY overlap.
+///
+/// # Safety
+///
+/// Both `src` & `dst` MUST be valid for `count` bytes.
 #[inline]
 pub unsafe fn mem_move(src: *const u8, dst: *mut u8, count: usize) {
-    libc::memmove(dst as _, src as _, count);
+    sys::move_(src, dst, count);
 }
 
+/// Fill `count` bytes starting from `ptr` with `value`.
+///
+/// # Safety
+///
+/// `ptr` MUST be valid for writes of `count` bytes.
 #[inline]
 pub unsafe fn mem_set(ptr: *mut u8, value: u8, count: usize) {
-    libc::memset(ptr as _, value as _, count);
+    sys::set(ptr, value, count);
 }
 
+/// Fill `count` bytes starting from `ptr` with ZERO, which is NEVER elided by the compiler
+/// (even if the memory is deallocated right after), for wiping sensitive data such as
+/// passwords and key material.
+///
+/// # Safety
+///
+/// `ptr` MUST be valid for writes of `count` bytes.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::mem_secure_zero;
+///
+/// let mut password = *b"foobared";
+/// unsafe { mem_secure_zero(password.as_mut_ptr(), password.len()) };
+/// assert_eq!(password, [0; 8]);
+/// ```
+#[inline(never)]
+pub unsafe fn mem_secure_zero(ptr: *mut u8, count: usize) {
+    for idx in 0..count {
+        std::ptr::write_volatile(ptr.add(idx), 0);
+    }
+    // Prevent the writes from being reordered after the deallocation.
+    std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+}
+
+/// Compare the first `count` bytes of two memory regions lexicographically.
+///
+/// AVX2 (x86_64) or NEON (aarch64) is taken use of if detected at runtime.
+///
+/// # Safety
+///
+/// Both `ptr1` & `ptr2` MUST be valid for reads of `count` bytes.
 #[inline]
 pub unsafe fn mem_cmp(ptr1: *const u8, ptr2: *const u8, count: usize) -> Ordering {
-    match libc::memcmp(ptr1 as _, ptr2 as _, count) {
-        v if v < 0 => Ordering::Less,
-        v if v > 0 => Ordering::Greater,
-        _ => Ordering::Equal,
+    match simd_cmp(ptr1, ptr2, count) {
+        Some(ord) => ord,
+        None => sys::cmp(ptr1, ptr2, count),
     }
 }
 
+/// Check if the first `count` bytes of two memory regions are equal, in constant time.
+///
+/// Unlike `mem_cmp`, ALL `count` bytes are ALWAYS examined, thus the running time does NOT
+/// depend on the position of the first differing byte, and secrets (such as passwords)
+/// can NOT be guessed byte by byte through timing.
+///
+/// # Notes
+///
+/// Only the contents are protected, while `count` itself is NOT a secret.
+///
+/// # Safety
+///
+/// Both `ptr1` & `ptr2` MUST be valid for reads of `count` bytes.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::mem_cmp_ct;
+///
+/// let (secret, input) = (b"foobared", b"foobarex");
+/// assert!(unsafe { mem_cmp_ct(secret.as_ptr(), secret.as_ptr(), 8) });
+/// assert!(!unsafe { mem_cmp_ct(secret.as_ptr(), input.as_ptr(), 8) });
+/// ```
+#[inline(never)]
+pub unsafe fn mem_cmp_ct(ptr1: *const u8, ptr2: *const u8, count: usize) -> bool {
+    let mut diff = 0u8;
+    for idx in 0..count {
+        // Prevent the compiler from short-circuiting once a difference found.
+        diff = std::hint::black_box(diff | (*ptr1.add(idx) ^ *ptr2.add(idx)));
+    }
+    diff == 0
+}
+
+/// Find the offset of the first byte equal to `value` within `len` bytes from `ptr`.
+///
+/// AVX2 (x86_64) or NEON (aarch64) is taken use of if detected at runtime.
+///
+/// # Safety
+///
+/// `ptr` MUST be valid for reads of `len` bytes.
 #[inline]
 pub unsafe fn mem_find(ptr: *const u8, len: usize, value: u8) -> Option<usize> {
-    let pch = libc::memchr(ptr as _, value as _, len) as *const u8;
-    match pch.is_null() {
-        true => None,
-        false => Some(pch as usize - ptr as usize),
+    match simd_find(ptr, len, value) {
+        Some(pos) => pos,
+        None => sys::find(ptr, len, value),
+    }
+}
+
+/// Swap `count` bytes between two memory regions.
+///
+/// # Safety
+///
+/// Both `ptr1` & `ptr2` MUST be valid for reads & writes of `count` bytes,
+/// and the two memory regions MUST NOT overlap.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::mem_swap;
+///
+/// let (mut a, mut b) = (*b"left", *b"right");
+/// unsafe { mem_swap(a.as_mut_ptr(), b.as_mut_ptr(), 4) };
+/// assert_eq!(&a, b"righ");
+/// assert_eq!(&b, b"leftt");
+/// ```
+#[inline]
+pub unsafe fn mem_swap(ptr1: *mut u8, ptr2: *mut u8, count: usize) {
+    std::ptr::swap_nonoverlapping(ptr1, ptr2, count);
+}
+
+/// Reverse the order of `count` bytes starting from `ptr` in place.
+///
+/// # Safety
+///
+/// `ptr` MUST be valid for reads & writes of `count` bytes.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::mem_reverse;
+///
+/// let mut bytes = 0x12345678u32.to_le_bytes();
+/// unsafe { mem_reverse(bytes.as_mut_ptr(), 4) };
+/// assert_eq!(u32::from_le_bytes(bytes), 0x78563412);
+/// ```
+#[inline]
+pub unsafe fn mem_reverse(ptr: *mut u8, count: usize) {
+    mem_reverse_for(ptr, count);
+}
+
+/// Find the offset of the first occurrence of the pattern (`needle_len` bytes from `needle`)
+/// within `hay_len` bytes from `hay` (acts just like `memmem`).
+///
+/// An empty pattern is ALWAYS found at offset ZERO.
+///
+/// The Two-Way string matching algorithm (Crochemore & Perrin) is adopted, which runs in
+/// linear time & constant space, even for pathological patterns.
+/// Short patterns are located with AVX2 (x86_64) or NEON (aarch64) if detected at runtime.
+///
+/// # Safety
+///
+/// `hay` MUST be valid for reads of `hay_len` bytes, and
+/// `needle` MUST be valid for reads of `needle_len` bytes.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::mem_find_pattern;
+///
+/// let buf = b"*2\r\n$3\r\nGET\r\n";
+/// let pos = unsafe { mem_find_pattern(buf.as_ptr(), buf.len(), b"\r\n".as_ptr(), 2) };
+/// assert_eq!(pos, Some(2));
+/// ```
+pub unsafe fn mem_find_pattern(
+    hay: *const u8,
+    hay_len: usize,
+    needle: *const u8,
+    needle_len: usize,
+) -> Option<usize> {
+    match needle_len {
+        0 => Some(0),
+        1 => mem_find(hay, hay_len, *needle),
+        _ if needle_len > hay_len => None,
+        _ => {
+            let hay = std::slice::from_raw_parts(hay, hay_len);
+            let needle = std::slice::from_raw_parts(needle, needle_len);
+            if needle_len <= SIMD_MAX_PATTERN_SIZE {
+                if let Some(pos) = simd_find_pattern(hay, needle) {
+                    return pos;
+                }
+            }
+            two_way_find(&hay, &needle)
+        }
+    }
+}
+
+/// A read-only view of bytes searched by the Two-Way algorithm, either forward or backward.
+trait ByteView {
+    fn len(&self) -> usize;
+    fn at(&self, idx: usize) -> u8;
+}
+
+impl ByteView for &[u8] {
+    #[inline]
+    fn len(&self) -> usize {
+        <[u8]>::len(self)
+    }
+
+    #[inline]
+    fn at(&self, idx: usize) -> u8 {
+        self[idx]
+    }
+}
+
+/// Bytes viewed in the reversed order.
+struct Reversed<'a>(&'a [u8]);
+
+impl ByteView for Reversed<'_> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[inline]
+    fn at(&self, idx: usize) -> u8 {
+        self.0[self.0.len() - 1 - idx]
+    }
+}
+
+/// Compute the maximal suffix of `needle` (in the order of `cmp`), returning
+/// the position right before the suffix (-1 for the whole needle) and its period.
+fn maximal_suffix<V: ByteView>(needle: &V, cmp: fn(u8, u8) -> bool) -> (isize, usize) {
+    let (mut ip, mut jp, mut k, mut p) = (-1isize, 0usize, 1usize, 1usize);
+    while jp + k < needle.len() {
+        let (a, b) = (needle.at((ip + k as isize) as usize), needle.at(jp + k));
+        if a == b {
+            if k == p {
+                jp += p;
+                k = 1;
+            } else {
+                k += 1;
+            }
+        } else if cmp(a, b) {
+            jp += k;
+            k = 1;
+            p = (jp as isize - ip) as usize;
+        } else {
+            ip = jp as isize;
+            jp += 1;
+            k = 1;
+            p = 1;
+        }
+    }
+    (ip, p)
+}
+
+/// Find the first occurrence of `needle` (with 2+ bytes) within `hay` with the Two-Way algorithm.
+fn two_way_find<V: ByteView>(hay: &V, needle: &V) -> Option<usize> {
+    let len = needle.len();
+
+    // Critical factorization: needle = needle[..=ms] + needle[ms + 1..].
+    let (ms1, p1) = maximal_suffix(needle, |a, b| a > b);
+    let (ms2, p2) = maximal_suffix(needle, |a, b| a < b);
+    let (ms, p) = if ms2 > ms1 { (ms2, p2) } else { (ms1, p1) };
+    let split = (ms + 1) as usize;
+
+    // Periodic needle is searched with memory of the prefix already matched.
+    let (p, mem0) = match (0..split).all(|idx| needle.at(idx) == needle.at(p + idx)) {
+        true => (p, len - p),
+        false => (std::cmp::max(split, len - split) + 1, 0),
+    };
+
+    let (mut pos, mut mem) = (0usize, 0usize);
+    while hay.len() - pos >= len {
+        // Compare the right half.
+        let mut k = std::cmp::max(split, mem);
+        while k < len && needle.at(k) == hay.at(pos + k) {
+            k += 1;
+        }
+        if k < len {
+            pos += k + 1 - split;
+            mem = 0;
+            continue;
+        }
+
+        // Compare the left half.
+        let mut k = split;
+        while k > mem && needle.at(k - 1) == hay.at(pos + k - 1) {
+            k -= 1;
+        }
+        if k <= mem {
+            return Some(pos);
+        }
+        pos += p;
+        mem = mem0;
+    }
+    None
+}
+
+/// Find the offset of the last byte equal to `value` within `len` bytes from `ptr`.
+///
+/// # Safety
+///
+/// `ptr` MUST be valid for reads of `len` bytes.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::mem_rfind;
+///
+/// let path = b"/usr/local/bin";
+/// assert_eq!(unsafe { mem_rfind(path.as_ptr(), path.len(), b'/') }, Some(10));
+/// ```
+#[inline]
+pub unsafe fn mem_rfind(ptr: *const u8, len: usize, value: u8) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+    std::slice::from_raw_parts(ptr, len)
+        .iter()
+        .rposition(|&byte| byte == value)
+}
+
+/// Find the offset of the last occurrence of the pattern (`needle_len` bytes from `needle`)
+/// within `hay_len` bytes from `hay`, searching from right to left.
+///
+/// An empty pattern is ALWAYS found at offset `hay_len`.
+///
+/// # Safety
+///
+/// `hay` MUST be valid for reads of `hay_len` bytes, and
+/// `needle` MUST be valid for reads of `needle_len` bytes.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::mem_rfind_pattern;
+///
+/// let list = b"a, b, c, ";
+/// let pos = unsafe { mem_rfind_pattern(list.as_ptr(), list.len(), b", ".as_ptr(), 2) };
+/// assert_eq!(pos, Some(7));
+/// ```
+pub unsafe fn mem_rfind_pattern(
+    hay: *const u8,
+    hay_len: usize,
+    needle: *const u8,
+    needle_len: usize,
+) -> Option<usize> {
+    match needle_len {
+        0 => Some(hay_len),
+        1 => mem_rfind(hay, hay_len, *needle),
+        _ if needle_len > hay_len => None,
+        _ => two_way_find(
+            &Reversed(std::slice::from_raw_parts(hay, hay_len)),
+            &Reversed(std::slice::from_raw_parts(needle, needle_len)),
+        )
+        .map(|pos| hay_len - pos - needle_len),
     }
 }
 
@@ -43,16 +408,159 @@ pub unsafe fn mem_find(ptr: *const u8, len: usize, value: u8) -> Option<usize> {
 // Memory (Object-Leveled) Operations
 ////////////////////////////////////////////////////////////////////////////////
 
+/// Copy `count` elements of type `T` from `src` to `dst`.
+///
+/// # Safety
+///
+/// The same as `mem_copy`, with `count` measured in elements rather than bytes.
+///
+/// # Panics
+///
+/// The same as `mem_copy`.
 #[inline]
 pub unsafe fn mem_copy_for<T>(src: *const T, dst: *mut T, count: usize) {
     mem_copy(src as _, dst as _, size_of::<T>() * count);
 }
 
+/// Move `count` elements of type `T` from `src` to `dst`, the two regions MAY overlap.
+///
+/// # Safety
+///
+/// The same as `mem_move`, with `count` measured in elements rather than bytes.
 #[inline]
 pub unsafe fn mem_move_for<T>(src: *const T, dst: *mut T, count: usize) {
     mem_move(src as _, dst as _, size_of::<T>() * count);
 }
 
+/// Swap `count` elements of type `T` between two memory regions.
+///
+/// # Safety
+///
+/// The same as `mem_swap`, with `count` measured in elements rather than bytes.
+#[inline]
+pub unsafe fn mem_swap_for<T>(ptr1: *mut T, ptr2: *mut T, count: usize) {
+    std::ptr::swap_nonoverlapping(ptr1, ptr2, count);
+}
+
+/// Reverse the order of `count` elements of type `T` starting from `ptr` in place.
+///
+/// # Safety
+///
+/// The same as `mem_reverse`, with `count` measured in elements rather than bytes.
+#[inline]
+pub unsafe fn mem_reverse_for<T>(ptr: *mut T, count: usize) {
+    if count > 1 {
+        std::slice::from_raw_parts_mut(ptr, count).reverse();
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Memory Primitives
+////////////////////////////////////////////////////////////////////////////////
+
+/// Memory primitives directing to libc (`memcpy`, `memcmp`, `memchr` and so on).
+#[cfg(not(any(feature = "no-libc", not(unix), miri)))]
+mod sys {
+    use std::cmp::Ordering;
+
+    #[inline]
+    pub(super) unsafe fn copy(src: *const u8, dst: *mut u8, count: usize) {
+        libc::memcpy(dst as _, src as _, count);
+    }
+
+    #[inline]
+    pub(super) unsafe fn move_(src: *const u8, dst: *mut u8, count: usize) {
+        libc::memmove(dst as _, src as _, count);
+    }
+
+    #[inline]
+    pub(super) unsafe fn set(ptr: *mut u8, value: u8, count: usize) {
+        libc::memset(ptr as _, value as _, count);
+    }
+
+    #[inline]
+    pub(super) unsafe fn cmp(ptr1: *const u8, ptr2: *const u8, count: usize) -> Ordering {
+        match libc::memcmp(ptr1 as _, ptr2 as _, count) {
+            v if v < 0 => Ordering::Less,
+            v if v > 0 => Ordering::Greater,
+            _ => Ordering::Equal,
+        }
+    }
+
+    #[inline]
+    pub(super) unsafe fn find(ptr: *const u8, len: usize, value: u8) -> Option<usize> {
+        let pch = libc::memchr(ptr as _, value as _, len) as *const u8;
+        match pch.is_null() {
+            true => None,
+            false => Some(pch as usize - ptr as usize),
+        }
+    }
+}
+
+/// Memory primitives implemented in pure Rust, which are adopted with the `no-libc` feature
+/// enabled, on non-Unix targets (such as Windows MSVC) and under Miri.
+#[cfg(any(feature = "no-libc", not(unix), miri))]
+mod sys {
+    use std::cmp::Ordering;
+
+    #[inline]
+    pub(super) unsafe fn copy(src: *const u8, dst: *mut u8, count: usize) {
+        std::ptr::copy_nonoverlapping(src, dst, count);
+    }
+
+    #[inline]
+    pub(super) unsafe fn move_(src: *const u8, dst: *mut u8, count: usize) {
+        std::ptr::copy(src, dst, count);
+    }
+
+    #[inline]
+    pub(super) unsafe fn set(ptr: *mut u8, value: u8, count: usize) {
+        std::ptr::write_bytes(ptr, value, count);
+    }
+
+    #[inline]
+    pub(super) unsafe fn cmp(ptr1: *const u8, ptr2: *const u8, count: usize) -> Ordering {
+        if count == 0 {
+            return Ordering::Equal;
+        }
+        let bytes1 = std::slice::from_raw_parts(ptr1, count);
+        let bytes2 = std::slice::from_raw_parts(ptr2, count);
+        bytes1.cmp(bytes2)
+    }
+
+    const LO_BYTES: usize = usize::from_ne_bytes([0x01; std::mem::size_of::<usize>()]);
+    const HI_BYTES: usize = usize::from_ne_bytes([0x80; std::mem::size_of::<usize>()]);
+
+    /// Find a byte word by word (SWAR), rather than byte by byte.
+    pub(super) unsafe fn find(ptr: *const u8, len: usize, value: u8) -> Option<usize> {
+        if len == 0 {
+            return None;
+        }
+        let bytes = std::slice::from_raw_parts(ptr, len);
+        let (prefix, words, _) = bytes.align_to::<usize>();
+
+        if let Some(pos) = prefix.iter().position(|&byte| byte == value) {
+            return Some(pos);
+        }
+
+        let pattern = LO_BYTES * value as usize;
+        let mut offset = prefix.len();
+        for word in words.iter() {
+            let x = word ^ pattern;
+            // Any byte of `x` is ZERO iff the word contains the byte.
+            if x.wrapping_sub(LO_BYTES) & !x & HI_BYTES != 0 {
+                break;
+            }
+            offset += std::mem::size_of::<usize>();
+        }
+
+        bytes[offset..]
+            .iter()
+            .position(|&byte| byte == value)
+            .map(|pos| offset + pos)
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // Unit Tests
 ////////////////////////////////////////////////////////////////////////////////
@@ -61,6 +569,14 @@ pub unsafe fn mem_move_for<T>(src: *const T, dst: *mut T, count: usize) {
 mod mem_ops_tests {
     use super::*;
 
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "mem_copy on overlapping regions")]
+    fn copy_overlapping_data() {
+        let mut data = [1u64, 2, 3, 4];
+        unsafe { mem_copy_for(data.as_ptr(), data.as_mut_ptr().add(1), 2) };
+    }
+
     #[test]
     fn copy_data() {
         let (src, mut dst) = (vec![1, 2, 3, 4], vec![0; 4]);
@@ -74,11 +590,7 @@ mod mem_ops_tests {
     fn move_data() {
         let mut elems = vec![1, 2, 3, 4, 5, 6, 7, 8];
         unsafe {
-            mem_move(
-                elems.as_ptr(),
-                (&mut elems[2..]).as_mut_ptr(),
-                size_of::<u8>() * 4,
-            );
+            mem_move(elems.as_ptr(), elems[2..].as_mut_ptr(), size_of::<u8>() * 4);
         }
         assert_eq!(elems, vec![1, 2, 1, 2, 3, 4, 7, 8]);
     }
@@ -92,6 +604,13 @@ mod mem_ops_tests {
         assert_eq!(elems, vec![0; 4]);
     }
 
+    #[test]
+    fn wipe_data_securely() {
+        let mut elems = [1, 4, 2, 5];
+        unsafe { mem_secure_zero(elems.as_mut_ptr(), 3) };
+        assert_eq!(elems, [0, 0, 0, 5]);
+    }
+
     #[test]
     fn cmp_data() {
         let (src, dst) = (vec![1, 2, 3, 4], vec![1, 2, 3, 4]);
@@ -107,9 +626,22 @@ mod mem_ops_tests {
         assert_eq!(ord, Ordering::Less);
     }
 
+    #[test]
+    fn cmp_data_in_constant_time() {
+        let (src, mut dst) = ([1, 2, 3, 4], [1, 2, 3, 4]);
+        assert!(unsafe { mem_cmp_ct(src.as_ptr(), dst.as_ptr(), 4) });
+        assert!(unsafe { mem_cmp_ct(src.as_ptr(), dst.as_ptr(), 0) });
+
+        for pos in 0..4 {
+            dst[pos] ^= 0x80;
+            assert!(!unsafe { mem_cmp_ct(src.as_ptr(), dst.as_ptr(), 4) });
+            dst[pos] ^= 0x80;
+        }
+    }
+
     #[test]
     fn find_byte_from_data() {
-        let elems = vec![1, 2, 3, 4];
+        let elems = [1, 2, 3, 4];
         assert_eq!(
             unsafe { mem_find(elems.as_ptr(), size_of::<u8>() * 4, 3) },
             Some(2)
@@ -120,6 +652,78 @@ mod mem_ops_tests {
         );
     }
 
+    #[test]
+    fn find_pattern_from_data() {
+        let find = |hay: &[u8], needle: &[u8]| unsafe {
+            mem_find_pattern(hay.as_ptr(), hay.len(), needle.as_ptr(), needle.len())
+        };
+        assert_eq!(find(b"hello world", b""), Some(0));
+        assert_eq!(find(b"hello world", b"o"), Some(4));
+        assert_eq!(find(b"hello world", b"world"), Some(6));
+        assert_eq!(find(b"hello world", b"worlds"), None);
+        assert_eq!(find(b"aaaaaaaaab", b"aaab"), Some(6));
+        assert_eq!(find(b"abababac", b"ababac"), Some(2));
+        assert_eq!(find(b"$3\r\nGET\r\n", b"\r\n"), Some(2));
+        assert_eq!(find(b"ab", b"abc"), None);
+    }
+
+    #[test]
+    fn find_pattern_as_naive_search() {
+        // Pseudo-random haystacks & needles over a tiny alphabet, to hit periodic patterns.
+        let mut seed = 0x2545F491u32;
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            b"ab"[(seed % 2) as usize] + seed.is_multiple_of(7) as u8
+        };
+        for _ in 0..2000 {
+            let hay: Vec<u8> = (0..80).map(|_| next()).collect();
+            let nlen = 2 + (next() as usize % 6);
+            let needle: Vec<u8> = (0..nlen).map(|_| next()).collect();
+
+            let naive = hay.windows(nlen).position(|w| w == &needle[..]);
+            let found = unsafe { mem_find_pattern(hay.as_ptr(), hay.len(), needle.as_ptr(), nlen) };
+            assert_eq!(found, naive, "{:?} in {:?}", needle, hay);
+
+            let naive = hay.windows(nlen).rposition(|w| w == &needle[..]);
+            let found =
+                unsafe { mem_rfind_pattern(hay.as_ptr(), hay.len(), needle.as_ptr(), nlen) };
+            assert_eq!(found, naive, "{:?} in {:?} reversely", needle, hay);
+        }
+    }
+
+    #[test]
+    fn find_byte_at_any_offset() {
+        let mut elems = [0u8; 100];
+        for start in 0..8 {
+            for pos in start..elems.len() {
+                elems[pos] = 0xAB;
+                let len = elems.len() - start;
+                let found = unsafe { mem_find(elems[start..].as_ptr(), len, 0xAB) };
+                assert_eq!(found, Some(pos - start));
+                elems[pos] = 0;
+            }
+        }
+    }
+
+    #[test]
+    fn rfind_from_data() {
+        let elems = [1, 2, 3, 2, 1];
+        assert_eq!(unsafe { mem_rfind(elems.as_ptr(), 5, 2) }, Some(3));
+        assert_eq!(unsafe { mem_rfind(elems.as_ptr(), 5, 4) }, None);
+        assert_eq!(unsafe { mem_rfind(elems.as_ptr(), 0, 1) }, None);
+
+        let rfind = |hay: &[u8], needle: &[u8]| unsafe {
+            mem_rfind_pattern(hay.as_ptr(), hay.len(), needle.as_ptr(), needle.len())
+        };
+        assert_eq!(rfind(b"hello world", b""), Some(11));
+        assert_eq!(rfind(b"hello world", b"o"), Some(7));
+        assert_eq!(rfind(b"abcabcab", b"abc"), Some(3));
+        assert_eq!(rfind(b"baaaaaaaaa", b"baaa"), Some(0));
+        assert_eq!(rfind(b"ab", b"abc"), None);
+    }
+
     #[test]
     fn copy_elems() {
         let (src, mut dst) = (vec![1, 2, 3, 4], vec![0; 4]);
@@ -133,8 +737,33 @@ mod mem_ops_tests {
     fn move_elems() {
         let mut elems = vec![1, 2, 3, 4, 5, 6, 7, 8];
         unsafe {
-            mem_move_for::<u32>(elems.as_ptr(), (&mut elems[2..]).as_mut_ptr(), 4);
+            mem_move_for::<u32>(elems.as_ptr(), elems[2..].as_mut_ptr(), 4);
         }
         assert_eq!(elems, vec![1, 2, 1, 2, 3, 4, 7, 8]);
     }
+
+    #[test]
+    fn swap_and_reverse_data() {
+        let (mut a, mut b) = ([1, 2, 3, 4], [5, 6, 7, 8]);
+        unsafe { mem_swap(a.as_mut_ptr(), b.as_mut_ptr(), 3) };
+        assert_eq!(a, [5, 6, 7, 4]);
+        assert_eq!(b, [1, 2, 3, 8]);
+
+        let mut elems = [1, 2, 3, 4, 5];
+        unsafe { mem_reverse(elems.as_mut_ptr(), 5) };
+        assert_eq!(elems, [5, 4, 3, 2, 1]);
+        unsafe { mem_reverse(elems.as_mut_ptr(), 0) };
+        assert_eq!(elems, [5, 4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn swap_and_reverse_elems() {
+        let (mut a, mut b) = ([1u32, 2], [3u32, 4]);
+        unsafe { mem_swap_for(a.as_mut_ptr(), b.as_mut_ptr(), 2) };
+        assert_eq!((a, b), ([3, 4], [1, 2]));
+
+        let mut elems = [0x0102u16, 0x0304, 0x0506];
+        unsafe { mem_reverse_for(elems.as_mut_ptr(), 3) };
+        assert_eq!(elems, [0x0506, 0x0304, 0x0102]);
+    }
 }
@@ -0,0 +1,299 @@
+use std::fmt;
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+use crate::poison::{poison, unpoison};
+use crate::{free_for, malloc_for, size_of};
+
+////////////////////////////////////////////////////////////////////////////////
+// Object Pool
+////////////////////////////////////////////////////////////////////////////////
+
+/// DEFAULT number of free slots kept by `ObjectPool::trim`.
+pub const OBJECT_POOL_LOW_WATERMARK: usize = 64;
+/// DEFAULT MAX number of free slots cached by an `ObjectPool`.
+pub const OBJECT_POOL_HIGH_WATERMARK: usize = 1024;
+
+/// ObjectPool recycles memory slots of objects with a certain type (such as dict entries
+/// and list nodes created & destroyed frequently), that objects are allocated from
+/// free slots cached rather than directing to the allocator each time.
+///
+/// Free slots are cached up to the high watermark (the others are deallocated at once),
+/// and SHOULD be trimmed down to the low watermark periodically (such as from a cron task)
+/// with `trim`, thus memory is returned back once the burst of objects passes.
+///
+/// # Notes
+///
+/// Objects live MUST be released (with `release`) to the pool they're allocated from,
+/// before the pool dropped, otherwise they're leaked.
+///
+/// No memory will be allocated for objects of ZERO-sized types.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::ObjectPool;
+///
+/// let mut pool = ObjectPool::new();
+///
+/// let node = pool.alloc((1u64, 2u64));
+/// assert_eq!(unsafe { *node.as_ref() }, (1, 2));
+/// unsafe { pool.release(node) };
+/// assert_eq!(pool.cached(), 1);
+///
+/// // The slot is reused.
+/// let other = pool.alloc((3, 4));
+/// assert_eq!(other, node);
+/// unsafe { pool.release(other) };
+/// ```
+pub struct ObjectPool<T> {
+    free_slots: Vec<NonNull<T>>,
+    live: usize,
+
+    low_watermark: usize,
+    high_watermark: usize,
+
+    hits: u64,
+    misses: u64,
+
+    _marker: PhantomData<T>,
+}
+
+// Free slots are owned by the pool exclusively, while objects live are owned by the callers.
+unsafe impl<T: Send> Send for ObjectPool<T> {}
+
+impl<T> ObjectPool<T> {
+    /// Create an empty pool with DEFAULT watermarks.
+    #[inline]
+    pub const fn new() -> Self {
+        Self::with_watermarks(OBJECT_POOL_LOW_WATERMARK, OBJECT_POOL_HIGH_WATERMARK)
+    }
+
+    /// Create an empty pool with the low & high watermarks of free slots cached.
+    ///
+    /// The low watermark is capped at the high one.
+    #[inline]
+    pub const fn with_watermarks(low: usize, high: usize) -> Self {
+        ObjectPool {
+            free_slots: Vec::new(),
+            live: 0,
+            low_watermark: if low < high { low } else { high },
+            high_watermark: high,
+            hits: 0,
+            misses: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Get the low watermark of free slots cached.
+    #[inline]
+    pub fn low_watermark(&self) -> usize {
+        self.low_watermark
+    }
+
+    /// Get the high watermark of free slots cached.
+    #[inline]
+    pub fn high_watermark(&self) -> usize {
+        self.high_watermark
+    }
+
+    /// Get number of free slots currently cached.
+    #[inline]
+    pub fn cached(&self) -> usize {
+        self.free_slots.len()
+    }
+
+    /// Get number of objects allocated from the pool & NOT released yet.
+    #[inline]
+    pub fn live(&self) -> usize {
+        self.live
+    }
+
+    /// Get the ratio of allocations served with free slots cached, in range [0, 1].
+    #[inline]
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0f64,
+            total => self.hits as f64 / total as f64,
+        }
+    }
+
+    /// Allocate an object holding `value`, from a free slot cached if available.
+    ///
+    /// # Aborts
+    ///
+    /// It will abort while memory allocation errors/failures occur (such as OOM).
+    #[cfg_attr(feature = "leak-track", track_caller)]
+    pub fn alloc(&mut self, value: T) -> NonNull<T> {
+        let slot = match self.free_slots.pop() {
+            Some(slot) => {
+                self.hits += 1;
+                unpoison(slot.as_ptr() as _, size_of::<T>());
+                slot
+            }
+            None => {
+                self.misses += 1;
+                Self::alloc_slot()
+            }
+        };
+
+        unsafe { slot.as_ptr().write(value) };
+        self.live += 1;
+        slot
+    }
+
+    /// Drop an object & release its slot back to the pool, which is cached (or deallocated
+    /// if the high watermark is reached).
+    ///
+    /// # Safety
+    ///
+    /// `ptr` MUST be allocated from the same pool (with `alloc`), and MUST NOT be used
+    /// any more once released.
+    pub unsafe fn release(&mut self, ptr: NonNull<T>) {
+        std::ptr::drop_in_place(ptr.as_ptr());
+        self.recycle(ptr);
+    }
+
+    /// Move an object out of the pool & release its slot, just like `release` does
+    /// (except that the object is returned rather than dropped).
+    ///
+    /// # Safety
+    ///
+    /// The same as `release`.
+    pub unsafe fn take(&mut self, ptr: NonNull<T>) -> T {
+        let value = ptr.as_ptr().read();
+        self.recycle(ptr);
+        value
+    }
+
+    /// Deallocate free slots cached down to the low watermark, and return number of slots
+    /// deallocated.
+    pub fn trim(&mut self) -> usize {
+        self.trim_to(self.low_watermark)
+    }
+
+    /// Deallocate ALL free slots cached, and return number of slots deallocated.
+    #[inline]
+    pub fn purge(&mut self) -> usize {
+        self.trim_to(0)
+    }
+
+    fn trim_to(&mut self, count: usize) -> usize {
+        let count = std::cmp::min(count, self.free_slots.len());
+        let trimmed = self.free_slots.len() - count;
+        for slot in self.free_slots.drain(count..) {
+            unpoison(slot.as_ptr() as _, size_of::<T>());
+            Self::free_slot(slot);
+        }
+        self.free_slots.shrink_to(count);
+        trimmed
+    }
+
+    /// Cache the slot of an object dropped (or moved out), unless the high watermark
+    /// is reached.
+    #[inline]
+    fn recycle(&mut self, slot: NonNull<T>) {
+        self.live -= 1;
+        if self.free_slots.len() < self.high_watermark {
+            poison(slot.as_ptr() as _, size_of::<T>());
+            self.free_slots.push(slot);
+        } else {
+            Self::free_slot(slot);
+        }
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "leak-track", track_caller)]
+    fn alloc_slot() -> NonNull<T> {
+        match size_of::<T>() {
+            0 => NonNull::dangling(),
+            _ => NonNull::new(malloc_for::<T>().0).expect("NULL pointer allocated"),
+        }
+    }
+
+    #[inline]
+    fn free_slot(slot: NonNull<T>) {
+        if size_of::<T>() != 0 {
+            free_for::<T>(slot.as_ptr());
+        }
+    }
+}
+
+impl<T> Drop for ObjectPool<T> {
+    fn drop(&mut self) {
+        self.purge();
+    }
+}
+
+impl<T> Default for ObjectPool<T> {
+    #[inline]
+    fn default() -> ObjectPool<T> {
+        ObjectPool::new()
+    }
+}
+
+impl<T> fmt::Debug for ObjectPool<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ObjectPool")
+            .field("live", &self.live)
+            .field("cached", &self.free_slots.len())
+            .field("low_watermark", &self.low_watermark)
+            .field("high_watermark", &self.high_watermark)
+            .finish()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Unit Tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod object_tests {
+    use super::*;
+
+    use std::rc::Rc;
+
+    #[test]
+    fn recycle_slots() {
+        let mut pool = ObjectPool::with_watermarks(2, 4);
+        let value = Rc::new(0);
+
+        let objects: Vec<_> = (0..6).map(|_| pool.alloc(Rc::clone(&value))).collect();
+        assert_eq!(pool.live(), 6);
+        assert_eq!(Rc::strong_count(&value), 7);
+
+        for &object in objects.iter() {
+            unsafe { pool.release(object) };
+        }
+        assert_eq!(Rc::strong_count(&value), 1);
+        assert_eq!(pool.live(), 0);
+        assert_eq!(pool.cached(), 4);
+
+        let object = pool.alloc(Rc::clone(&value));
+        assert!(objects.contains(&object));
+        assert_eq!(pool.hit_rate(), 1f64 / 7f64);
+        assert_eq!(*unsafe { pool.take(object) }, 0);
+
+        assert_eq!(pool.trim(), 2);
+        assert_eq!(pool.cached(), 2);
+        assert_eq!(pool.trim(), 0);
+        assert_eq!(pool.purge(), 2);
+    }
+
+    #[test]
+    fn pool_zero_sized_objects() {
+        let mut pool = ObjectPool::<()>::new();
+        let object = pool.alloc(());
+        assert_eq!(object, NonNull::dangling());
+        unsafe { pool.release(object) };
+        assert_eq!(pool.cached(), 1);
+    }
+
+    #[test]
+    fn cap_low_watermark() {
+        let pool = ObjectPool::<u64>::with_watermarks(10, 4);
+        assert_eq!(pool.low_watermark(), 4);
+        assert_eq!(pool.high_watermark(), 4);
+        assert!(format!("{:?}", pool).contains("live: 0"));
+    }
+}
@@ -0,0 +1,118 @@
+////////////////////////////////////////////////////////////////////////////////
+// Memory Poisoning
+////////////////////////////////////////////////////////////////////////////////
+
+// Memory recycled by pooling layers (the thread-local cache, the size-class pool) and
+// headroom of arena chunks are NEVER deallocated from the view of memory checkers,
+// thus they are poisoned (marked inaccessible) explicitly, that sanitizer runs still catch
+// use-after-free and buffer overruns on them:
+//
+// - AddressSanitizer, with the `asan` feature enabled (built with `-Zsanitizer=address`).
+// - Valgrind (memcheck) client requests, with the `valgrind` feature enabled (x86_64 ONLY),
+//   which are no-ops while NOT running under Valgrind.
+//
+// Otherwise, all of them are no-ops.
+
+/// Mark memory of the region `[ptr, ptr + len)` inaccessible.
+#[inline]
+pub(crate) fn poison(ptr: *const u8, len: usize) {
+    #[cfg(feature = "asan")]
+    unsafe {
+        asan::__asan_poison_memory_region(ptr as _, len)
+    };
+    #[cfg(all(feature = "valgrind", target_arch = "x86_64", not(miri)))]
+    valgrind::client_request(valgrind::MAKE_MEM_NOACCESS, ptr, len);
+    let _ = (ptr, len);
+}
+
+/// Mark memory of the region `[ptr, ptr + len)` accessible, with its content undefined
+/// (as memory newly allocated).
+#[inline]
+pub(crate) fn unpoison(ptr: *const u8, len: usize) {
+    #[cfg(feature = "asan")]
+    unsafe {
+        asan::__asan_unpoison_memory_region(ptr as _, len)
+    };
+    #[cfg(all(feature = "valgrind", target_arch = "x86_64", not(miri)))]
+    valgrind::client_request(valgrind::MAKE_MEM_UNDEFINED, ptr, len);
+    let _ = (ptr, len);
+}
+
+/// Mark memory of the region `[ptr, ptr + len)` accessible, with its content defined
+/// (such as bookkeeping data written before poisoned).
+#[inline]
+pub(crate) fn unpoison_defined(ptr: *const u8, len: usize) {
+    #[cfg(feature = "asan")]
+    unsafe {
+        asan::__asan_unpoison_memory_region(ptr as _, len)
+    };
+    #[cfg(all(feature = "valgrind", target_arch = "x86_64", not(miri)))]
+    valgrind::client_request(valgrind::MAKE_MEM_DEFINED, ptr, len);
+    let _ = (ptr, len);
+}
+
+#[cfg(feature = "asan")]
+mod asan {
+    use std::ffi::c_void;
+
+    extern "C" {
+        pub(super) fn __asan_poison_memory_region(addr: *const c_void, size: usize);
+        pub(super) fn __asan_unpoison_memory_region(addr: *const c_void, size: usize);
+        #[cfg(test)]
+        pub(super) fn __asan_address_is_poisoned(addr: *const c_void) -> i32;
+    }
+}
+
+#[cfg(all(feature = "valgrind", target_arch = "x86_64", not(miri)))]
+mod valgrind {
+    /// Memcheck client request codes, based on `VG_USERREQ_TOOL_BASE('M', 'C')`.
+    pub(super) const MAKE_MEM_NOACCESS: usize = 0x4D43_0000;
+    pub(super) const MAKE_MEM_UNDEFINED: usize = 0x4D43_0001;
+    pub(super) const MAKE_MEM_DEFINED: usize = 0x4D43_0002;
+
+    /// Issue a client request with the "magic" instruction sequence recognized by Valgrind,
+    /// which rotates `rdi` by 128 bits in total (a no-op natively).
+    #[inline(always)]
+    pub(super) fn client_request(request: usize, ptr: *const u8, len: usize) {
+        let args: [usize; 6] = [request, ptr as usize, len, 0, 0, 0];
+        unsafe {
+            std::arch::asm!(
+                "rol rdi, 3",
+                "rol rdi, 13",
+                "rol rdi, 61",
+                "rol rdi, 51",
+                "xchg rbx, rbx",
+                in("rax") args.as_ptr(),
+                inout("rdx") 0usize => _,
+                inout("rdi") 0usize => _,
+            );
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Unit Tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod poison_tests {
+    use super::*;
+
+    use crate::{free, malloc};
+
+    #[test]
+    fn poison_and_unpoison() {
+        let (ptr, size) = malloc(64);
+        poison(ptr, size);
+        #[cfg(feature = "asan")]
+        assert_eq!(unsafe { asan::__asan_address_is_poisoned(ptr as _) }, 1);
+
+        unpoison_defined(ptr, 8);
+        unpoison(ptr, size);
+        #[cfg(feature = "asan")]
+        assert_eq!(unsafe { asan::__asan_address_is_poisoned(ptr as _) }, 0);
+
+        unsafe { crate::mem_set(ptr, 0, size) };
+        free(ptr, size);
+    }
+}
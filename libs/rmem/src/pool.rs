@@ -0,0 +1,269 @@
+use std::sync::Mutex;
+
+use crate::poison::{poison, unpoison, unpoison_defined};
+use crate::{free, malloc};
+
+////////////////////////////////////////////////////////////////////////////////
+// Size-Class Memory Pool
+////////////////////////////////////////////////////////////////////////////////
+
+/// Size of the smallest size class, which MUST be able to hold a free-list link.
+pub const POOL_MIN_CLASS_SIZE: usize = 8;
+/// Size of the largest size class, larger memory is NOT pooled at all.
+pub const POOL_MAX_CLASS_SIZE: usize = 4096;
+/// MAX number of free blocks cached in each size class.
+pub const POOL_MAX_CACHED_BLOCKS: usize = 1024;
+
+/// Number of size classes -- 8/16/32/.../4096.
+const POOL_CLASS_COUNT: usize = 10;
+
+/// Free list of recycled blocks within a size class.
+///
+/// Free blocks are linked intrusively, that the first `usize` of each block
+/// stores the pointer to the next free block.
+struct FreeList {
+    head: *mut u8,
+    len: usize,
+
+    hits: u64,
+    misses: u64,
+}
+
+// Blocks in free list are owned by the pool exclusively.
+unsafe impl Send for FreeList {}
+
+impl FreeList {
+    const fn new() -> Self {
+        FreeList {
+            head: std::ptr::null_mut(),
+            len: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Pop a free block of `csize` bytes, which is unpoisoned (as memory newly allocated).
+    fn pop(&mut self, csize: usize) -> Option<*mut u8> {
+        if self.head.is_null() {
+            return None;
+        }
+
+        let ptr = self.head;
+        unpoison_defined(ptr, std::mem::size_of::<*mut u8>());
+        self.head = unsafe { *(ptr as *const *mut u8) };
+        unpoison(ptr, csize);
+        self.len -= 1;
+        Some(ptr)
+    }
+
+    /// Push a free block of `csize` bytes, which is poisoned until popped.
+    fn push(&mut self, ptr: *mut u8, csize: usize) {
+        unsafe {
+            *(ptr as *mut *mut u8) = self.head;
+        }
+        poison(ptr, csize);
+        self.head = ptr;
+        self.len += 1;
+    }
+}
+
+#[allow(clippy::declare_interior_mutable_const)]
+const EMPTY_FREE_LIST: Mutex<FreeList> = Mutex::new(FreeList::new());
+
+static POOL: [Mutex<FreeList>; POOL_CLASS_COUNT] = [EMPTY_FREE_LIST; POOL_CLASS_COUNT];
+
+/// Locate the size class (index & class size) a certain size belongs to.
+#[inline]
+fn class_of(size: usize) -> Option<(usize, usize)> {
+    if size > POOL_MAX_CLASS_SIZE {
+        return None;
+    }
+
+    let csize = std::cmp::max(size, POOL_MIN_CLASS_SIZE).next_power_of_two();
+    let idx = (csize.trailing_zeros() - POOL_MIN_CLASS_SIZE.trailing_zeros()) as usize;
+    Some((idx, csize))
+}
+
+#[inline]
+fn lock(idx: usize) -> std::sync::MutexGuard<'static, FreeList> {
+    POOL[idx].lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Allocate memory/buffer from the size-class pool.
+///
+/// The required size is rounded up to its size class (8/16/32/.../4096 bytes), and
+/// a recycled block of the class is reused if available, otherwise allocated via `malloc`.
+/// Memory larger than the largest size class is allocated via `malloc` directly.
+///
+/// A valid `pointer` with the `size` (of its size class) will be returned.
+///
+/// `pool_malloc` & `pool_free` SHOULD work as pairs for memory allocation & deallocation.
+///
+/// # Aborts
+///
+/// It will abort while memory allocation errors/failures occur (such as OOM).
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{pool_free, pool_malloc};
+///
+/// let (ptr, size) = pool_malloc(20);
+/// assert!(!ptr.is_null());
+/// assert_eq!(size, 32);
+///
+/// // The block is recycled into the pool rather than returned back to the system.
+/// pool_free(ptr, size);
+/// ```
+#[cfg_attr(feature = "leak-track", track_caller)]
+pub fn pool_malloc(size: usize) -> (*mut u8, usize) {
+    let (idx, csize) = match class_of(size) {
+        Some(class) => class,
+        None => return malloc(size),
+    };
+
+    let mut list = lock(idx);
+    match list.pop(csize) {
+        Some(ptr) => {
+            list.hits += 1;
+            (ptr, csize)
+        }
+        None => {
+            list.misses += 1;
+            drop(list);
+            malloc(csize)
+        }
+    }
+}
+
+/// Deallocate memory/buffer previously allocated with `pool_malloc`.
+///
+/// `size` can be either the required size or the one returned by `pool_malloc`.
+///
+/// The block is recycled into the free list of its size class, unless the free list
+/// is already full (with `POOL_MAX_CACHED_BLOCKS` blocks) that it will be freed directly.
+pub fn pool_free(ptr: *mut u8, size: usize) {
+    if ptr.is_null() {
+        return;
+    }
+
+    let (idx, csize) = match class_of(size) {
+        Some(class) => class,
+        None => return free(ptr, size),
+    };
+
+    let mut list = lock(idx);
+    if list.len < POOL_MAX_CACHED_BLOCKS {
+        list.push(ptr, csize);
+    } else {
+        drop(list);
+        free(ptr, csize);
+    }
+}
+
+/// Return ALL recycled blocks cached in the pool back to the allocator.
+pub fn pool_purge() {
+    for idx in 0..POOL_CLASS_COUNT {
+        let mut list = lock(idx);
+        let csize = POOL_MIN_CLASS_SIZE << idx;
+        while let Some(ptr) = list.pop(csize) {
+            free(ptr, csize);
+        }
+    }
+}
+
+/// Statistics of a size class in the pool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PoolClassStats {
+    /// Size (in bytes) of blocks in the class.
+    pub size: usize,
+    /// Number of free blocks currently cached.
+    pub cached: usize,
+    /// Number of allocations served with recycled blocks.
+    pub hits: u64,
+    /// Number of allocations directed to `malloc`.
+    pub misses: u64,
+}
+
+impl PoolClassStats {
+    /// Get the ratio of allocations served with recycled blocks, in range [0, 1].
+    #[inline]
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0f64,
+            total => self.hits as f64 / total as f64,
+        }
+    }
+}
+
+/// Get statistics of ALL size classes in the pool, in the order of class size.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{pool_free, pool_malloc, pool_stats};
+///
+/// let (ptr, size) = pool_malloc(100);
+/// pool_free(ptr, size);
+/// let (ptr, size) = pool_malloc(100);
+/// pool_free(ptr, size);
+///
+/// let stats = pool_stats();
+/// assert_eq!(stats[4].size, 128);
+/// assert_eq!(stats[4].hit_rate(), 0.5);
+/// ```
+pub fn pool_stats() -> Vec<PoolClassStats> {
+    (0..POOL_CLASS_COUNT)
+        .map(|idx| {
+            let list = lock(idx);
+            PoolClassStats {
+                size: POOL_MIN_CLASS_SIZE << idx,
+                cached: list.len,
+                hits: list.hits,
+                misses: list.misses,
+            }
+        })
+        .collect()
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Unit Tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod pool_tests {
+    use super::*;
+
+    #[test]
+    fn locate_size_class() {
+        assert_eq!(class_of(0), Some((0, 8)));
+        assert_eq!(class_of(8), Some((0, 8)));
+        assert_eq!(class_of(9), Some((1, 16)));
+        assert_eq!(class_of(1000), Some((7, 1024)));
+        assert_eq!(class_of(4096), Some((9, 4096)));
+        assert_eq!(class_of(4097), None);
+    }
+
+    #[test]
+    fn recycle_block_in_class() {
+        let (ptr, size) = pool_malloc(2000);
+        assert_eq!(size, 2048);
+        pool_free(ptr, size);
+
+        let hits = pool_stats()[8].hits;
+        let (ptr2, size) = pool_malloc(1500);
+        assert_eq!(ptr2, ptr);
+        assert_eq!(pool_stats()[8].hits, hits + 1);
+
+        pool_free(ptr2, size);
+    }
+
+    #[test]
+    fn bypass_pool_for_large_memory() {
+        let (ptr, size) = pool_malloc(10000);
+        assert!(!ptr.is_null());
+        assert_eq!(size, 10000);
+
+        pool_free(ptr, size);
+    }
+}
@@ -0,0 +1,254 @@
+use crate::backend::allocator;
+use crate::{used_memory, used_memory_peak};
+
+////////////////////////////////////////////////////////////////////////////////
+// Process Memory Reporting
+////////////////////////////////////////////////////////////////////////////////
+
+/// Get the resident set size (RSS, in bytes) of the current process.
+///
+/// It's read from `/proc` on Linux, Mach APIs on macOS and Win32 APIs on Windows
+/// (the working set size), while ZERO is returned if unavailable.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::proc_rss;
+///
+/// if cfg!(target_os = "linux") {
+///     assert!(proc_rss() > 0);
+/// }
+/// ```
+#[inline]
+pub fn proc_rss() -> usize {
+    sys::rss().unwrap_or(0)
+}
+
+/// Get the peak resident set size (RSS, in bytes) of the current process,
+/// while ZERO is returned if unavailable.
+#[inline]
+pub fn proc_peak_rss() -> usize {
+    sys::peak_rss().unwrap_or(0)
+}
+
+/// An overview of memory usage of the current process, that the gap between memory allocated
+/// via `rmem` and memory actually resident can be reported.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryOverview {
+    /// Bytes of memory currently allocated via `rmem` (as `used_memory`).
+    pub allocated: usize,
+    /// The MAX value `allocated` has ever reached (as `used_memory_peak`).
+    pub peak_allocated: usize,
+    /// Resident set size of the process (as `proc_rss`).
+    pub rss: usize,
+    /// Peak resident set size of the process (as `proc_peak_rss`).
+    pub peak_rss: usize,
+    /// Bytes of memory taken by the allocator backend for bookkeeping (ZERO if unknown).
+    pub allocator_metadata: usize,
+}
+
+impl MemoryOverview {
+    /// Get the fragmentation ratio, which is `rss / allocated` (ZERO if unknown).
+    ///
+    /// A ratio much larger than 1.0 indicates memory fragmentation (or memory NOT allocated
+    /// via `rmem`), while a ratio less than 1.0 indicates memory swapped out.
+    #[inline]
+    pub fn fragmentation(&self) -> f64 {
+        match self.allocated == 0 || self.rss == 0 {
+            true => 0.0,
+            false => self.rss as f64 / self.allocated as f64,
+        }
+    }
+
+    /// Get bytes of memory resident but NOT allocated via `rmem` (`rss - allocated`),
+    /// which goes negative while memory swapped out.
+    #[inline]
+    pub fn fragmentation_bytes(&self) -> isize {
+        self.rss as isize - self.allocated as isize
+    }
+}
+
+/// Take an overview of memory usage of the current process.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{free, malloc, memory_overview};
+///
+/// let (ptr, size) = malloc(1 << 20);
+/// let overview = memory_overview();
+/// assert!(overview.allocated >= 1 << 20);
+/// assert!(overview.peak_allocated >= overview.allocated);
+///
+/// println!(
+///     "rss: {}, fragmentation: {:.2} ({} bytes)",
+///     overview.rss,
+///     overview.fragmentation(),
+///     overview.fragmentation_bytes()
+/// );
+/// free(ptr, size);
+/// ```
+pub fn memory_overview() -> MemoryOverview {
+    MemoryOverview {
+        allocated: used_memory(),
+        peak_allocated: used_memory_peak(),
+        rss: proc_rss(),
+        peak_rss: proc_peak_rss(),
+        allocator_metadata: allocator().metadata_size(),
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod sys {
+    /// Read a field (in kB) of `/proc/self/status` in bytes.
+    fn status_field(key: &str) -> Option<usize> {
+        let status = std::fs::read_to_string("/proc/self/status").ok()?;
+        let line = status.lines().find(|line| line.starts_with(key))?;
+        let kb = line[key.len()..].trim().trim_end_matches("kB").trim();
+        kb.parse::<usize>().ok().map(|kb| kb * 1024)
+    }
+
+    #[inline]
+    pub(super) fn rss() -> Option<usize> {
+        status_field("VmRSS:")
+    }
+
+    #[inline]
+    pub(super) fn peak_rss() -> Option<usize> {
+        status_field("VmHWM:")
+    }
+}
+
+#[cfg(all(target_os = "macos", not(any(feature = "no-libc", miri))))]
+mod sys {
+    #[allow(deprecated)]
+    fn task_basic_info() -> Option<libc::mach_task_basic_info> {
+        let mut info: libc::mach_task_basic_info = unsafe { std::mem::zeroed() };
+        let mut count = libc::MACH_TASK_BASIC_INFO_COUNT;
+        let ret = unsafe {
+            libc::task_info(
+                libc::mach_task_self(),
+                libc::MACH_TASK_BASIC_INFO,
+                &mut info as *mut libc::mach_task_basic_info as libc::task_info_t,
+                &mut count,
+            )
+        };
+        match ret == libc::KERN_SUCCESS {
+            true => Some(info),
+            false => None,
+        }
+    }
+
+    #[inline]
+    pub(super) fn rss() -> Option<usize> {
+        task_basic_info().map(|info| info.resident_size as usize)
+    }
+
+    #[inline]
+    pub(super) fn peak_rss() -> Option<usize> {
+        task_basic_info().map(|info| info.resident_size_max as usize)
+    }
+}
+
+#[cfg(all(windows, not(miri)))]
+mod sys {
+    use std::ffi::c_void;
+
+    #[repr(C)]
+    #[allow(non_snake_case)]
+    struct PROCESS_MEMORY_COUNTERS {
+        cb: u32,
+        PageFaultCount: u32,
+        PeakWorkingSetSize: usize,
+        WorkingSetSize: usize,
+        QuotaPeakPagedPoolUsage: usize,
+        QuotaPagedPoolUsage: usize,
+        QuotaPeakNonPagedPoolUsage: usize,
+        QuotaNonPagedPoolUsage: usize,
+        PagefileUsage: usize,
+        PeakPagefileUsage: usize,
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetCurrentProcess() -> *mut c_void;
+        fn K32GetProcessMemoryInfo(
+            process: *mut c_void,
+            counters: *mut PROCESS_MEMORY_COUNTERS,
+            cb: u32,
+        ) -> i32;
+    }
+
+    fn memory_counters() -> Option<PROCESS_MEMORY_COUNTERS> {
+        let mut counters: PROCESS_MEMORY_COUNTERS = unsafe { std::mem::zeroed() };
+        let cb = std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
+        counters.cb = cb;
+        match unsafe { K32GetProcessMemoryInfo(GetCurrentProcess(), &mut counters, cb) } {
+            0 => None,
+            _ => Some(counters),
+        }
+    }
+
+    #[inline]
+    pub(super) fn rss() -> Option<usize> {
+        memory_counters().map(|counters| counters.WorkingSetSize)
+    }
+
+    #[inline]
+    pub(super) fn peak_rss() -> Option<usize> {
+        memory_counters().map(|counters| counters.PeakWorkingSetSize)
+    }
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    all(target_os = "macos", not(any(feature = "no-libc", miri))),
+    all(windows, not(miri))
+)))]
+mod sys {
+    #[inline]
+    pub(super) fn rss() -> Option<usize> {
+        None
+    }
+
+    #[inline]
+    pub(super) fn peak_rss() -> Option<usize> {
+        None
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Unit Tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod proc_tests {
+    use super::*;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn read_proc_rss() {
+        let rss = proc_rss();
+        assert!(rss > 0);
+        assert!(proc_peak_rss() >= rss);
+    }
+
+    #[test]
+    fn calc_fragmentation() {
+        let overview = MemoryOverview {
+            allocated: 1000,
+            rss: 1500,
+            ..Default::default()
+        };
+        assert_eq!(overview.fragmentation(), 1.5);
+        assert_eq!(overview.fragmentation_bytes(), 500);
+
+        let overview = MemoryOverview {
+            rss: 1500,
+            ..Default::default()
+        };
+        assert_eq!(overview.fragmentation(), 0.0);
+        assert_eq!(memory_overview().allocator_metadata, 0);
+    }
+}
@@ -0,0 +1,351 @@
+use std::fmt;
+
+use crate::{free, malloc, mem_copy, realloc};
+
+////////////////////////////////////////////////////////////////////////////////
+// Ring Buffer
+////////////////////////////////////////////////////////////////////////////////
+
+/// RingBuf is a growable FIFO byte buffer with a power-of-two capacity (allocated with
+/// `malloc` & grown with `realloc`), where bytes are written at the tail and read from the head.
+///
+/// Bytes stored MAYBE wrapped around the end of the memory, thus they're exposed as
+/// (at most) 2 contiguous slices for reading (`as_slices`), while free space at the tail
+/// is exposed as a contiguous slice for writing in place (`spare_slice` & `commit`).
+///
+/// # Notes
+///
+/// No memory will be allocated for an empty buffer created with `new`.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::RingBuf;
+///
+/// let mut ring = RingBuf::with_capacity(8);
+/// ring.push_slice(b"hello");
+/// ring.consume(3);
+/// ring.push_slice(b" world");
+///
+/// let (front, back) = ring.as_slices();
+/// assert_eq!([front, back].concat(), b"lo world");
+///
+/// let mut bytes = [0u8; 4];
+/// assert_eq!(ring.pop_into(&mut bytes), 4);
+/// assert_eq!(&bytes, b"lo w");
+/// assert_eq!(ring.len(), 4);
+/// ```
+pub struct RingBuf {
+    ptr: *mut u8,
+    /// Capacity of the buffer, which is ZERO or the power of 2.
+    cap: usize,
+    /// Offset of the first byte stored.
+    head: usize,
+    len: usize,
+}
+
+// RingBuf owns its memory exclusively, just like `VecDeque<u8>` does.
+unsafe impl Send for RingBuf {}
+unsafe impl Sync for RingBuf {}
+
+impl RingBuf {
+    /// Create an empty buffer, WITHOUT memory allocated.
+    #[inline]
+    pub const fn new() -> Self {
+        RingBuf {
+            ptr: std::ptr::null_mut(),
+            cap: 0,
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Create an empty buffer with (at least) `capacity` bytes allocated,
+    /// rounded up to the power of 2.
+    ///
+    /// # Aborts
+    ///
+    /// It will abort while memory allocation errors/failures occur (such as OOM).
+    #[cfg_attr(feature = "leak-track", track_caller)]
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut ring = RingBuf::new();
+        ring.reserve(capacity);
+        ring
+    }
+
+    /// Get number of bytes stored in the buffer.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if the buffer is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get capacity (in bytes) of the buffer, which is ZERO or the power of 2.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    /// Get bytes of free space in the buffer.
+    #[inline]
+    pub fn free_space(&self) -> usize {
+        self.cap - self.len
+    }
+
+    /// Get the offset of the first free byte (the tail).
+    #[inline]
+    fn tail(&self) -> usize {
+        (self.head + self.len) & (self.cap.wrapping_sub(1))
+    }
+
+    /// Reserve capacity for (at least) `extra` more bytes, where the capacity is doubled
+    /// (or more) to the power of 2.
+    ///
+    /// # Aborts
+    ///
+    /// It will abort while memory allocation errors/failures occur (such as OOM).
+    #[cfg_attr(feature = "leak-track", track_caller)]
+    pub fn reserve(&mut self, extra: usize) {
+        let required = self.len.checked_add(extra).expect("capacity overflow");
+        if required <= self.cap {
+            return;
+        }
+
+        let new_cap = required
+            .checked_next_power_of_two()
+            .expect("capacity overflow");
+        if self.ptr.is_null() {
+            self.ptr = malloc(new_cap).0;
+        } else {
+            let old_cap = self.cap;
+            self.ptr = realloc(self.ptr, old_cap, new_cap).0;
+            // Move the wrapped part right after the old end, as the new capacity is
+            // at least doubled that it always fits.
+            if self.head + self.len > old_cap {
+                let wrapped = self.head + self.len - old_cap;
+                unsafe { mem_copy(self.ptr, self.ptr.add(old_cap), wrapped) };
+            }
+        }
+        self.cap = new_cap;
+    }
+
+    /// Append ALL bytes of `bytes` to the tail of the buffer, growing it if required.
+    ///
+    /// # Aborts
+    ///
+    /// It will abort while memory allocation errors/failures occur (such as OOM).
+    #[cfg_attr(feature = "leak-track", track_caller)]
+    pub fn push_slice(&mut self, bytes: &[u8]) {
+        if bytes.is_empty() {
+            return;
+        }
+
+        self.reserve(bytes.len());
+        let tail = self.tail();
+        let first = std::cmp::min(bytes.len(), self.cap - tail);
+        unsafe {
+            mem_copy(bytes.as_ptr(), self.ptr.add(tail), first);
+            mem_copy(bytes.as_ptr().add(first), self.ptr, bytes.len() - first);
+        }
+        self.len += bytes.len();
+    }
+
+    /// Get bytes stored as (at most) 2 contiguous slices, in the order of reading.
+    #[inline]
+    pub fn as_slices(&self) -> (&[u8], &[u8]) {
+        if self.len == 0 {
+            return (&[], &[]);
+        }
+
+        let first = std::cmp::min(self.len, self.cap - self.head);
+        unsafe {
+            (
+                std::slice::from_raw_parts(self.ptr.add(self.head), first),
+                std::slice::from_raw_parts(self.ptr, self.len - first),
+            )
+        }
+    }
+
+    /// Get the contiguous free space at the tail, which can be written in place and then
+    /// appended with `commit`.
+    ///
+    /// It MAYBE shorter than `free_space` while the free space wraps around,
+    /// and it's empty if the buffer is full.
+    #[inline]
+    pub fn spare_slice(&mut self) -> &mut [u8] {
+        if self.len == self.cap {
+            return &mut [];
+        }
+
+        let tail = self.tail();
+        let contiguous = match tail >= self.head {
+            true => self.cap - tail,
+            false => self.head - tail,
+        };
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.add(tail), contiguous) }
+    }
+
+    /// Append `count` bytes written in place to `spare_slice`.
+    ///
+    /// # Panics
+    ///
+    /// `count` MUST NOT exceed length of `spare_slice`.
+    #[inline]
+    pub fn commit(&mut self, count: usize) {
+        let spare = self.spare_slice().len();
+        assert!(
+            count <= spare,
+            "commit {} bytes with {} spare",
+            count,
+            spare
+        );
+        self.len += count;
+    }
+
+    /// Drop `count` bytes from the head, which has the buffer cleared if `count` exceeds
+    /// the length.
+    #[inline]
+    pub fn consume(&mut self, count: usize) {
+        let count = std::cmp::min(count, self.len);
+        self.head = (self.head + count) & (self.cap.wrapping_sub(1));
+        self.len -= count;
+        if self.len == 0 {
+            self.head = 0;
+        }
+    }
+
+    /// Copy bytes from `offset` (relative to the head) into `dst` WITHOUT consuming them,
+    /// and return number of bytes copied (wrap-aware).
+    pub fn copy_to(&self, offset: usize, dst: &mut [u8]) -> usize {
+        if offset >= self.len {
+            return 0;
+        }
+
+        let count = std::cmp::min(dst.len(), self.len - offset);
+        let start = (self.head + offset) & (self.cap - 1);
+        let first = std::cmp::min(count, self.cap - start);
+        unsafe {
+            mem_copy(self.ptr.add(start), dst.as_mut_ptr(), first);
+            mem_copy(self.ptr, dst.as_mut_ptr().add(first), count - first);
+        }
+        count
+    }
+
+    /// Move bytes from the head into `dst`, and return number of bytes moved.
+    #[inline]
+    pub fn pop_into(&mut self, dst: &mut [u8]) -> usize {
+        let count = self.copy_to(0, dst);
+        self.consume(count);
+        count
+    }
+
+    /// Clear the buffer, with the capacity kept as is.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+}
+
+impl Drop for RingBuf {
+    #[inline]
+    fn drop(&mut self) {
+        free(self.ptr, self.cap);
+    }
+}
+
+impl Default for RingBuf {
+    #[inline]
+    fn default() -> RingBuf {
+        RingBuf::new()
+    }
+}
+
+impl fmt::Debug for RingBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RingBuf")
+            .field("len", &self.len)
+            .field("capacity", &self.cap)
+            .field("head", &self.head)
+            .finish()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Unit Tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod ring_tests {
+    use super::*;
+
+    fn contents(ring: &RingBuf) -> Vec<u8> {
+        let (front, back) = ring.as_slices();
+        [front, back].concat()
+    }
+
+    #[test]
+    fn empty_ring_without_memory() {
+        let mut ring = RingBuf::new();
+        assert_eq!(ring.capacity(), 0);
+        assert_eq!(ring.as_slices(), (&b""[..], &b""[..]));
+        assert!(ring.spare_slice().is_empty());
+        assert_eq!(ring.copy_to(0, &mut [0; 4]), 0);
+        ring.consume(10);
+
+        assert_eq!(RingBuf::with_capacity(100).capacity(), 128);
+    }
+
+    #[test]
+    fn grow_wrapped_ring() {
+        let mut ring = RingBuf::with_capacity(8);
+        ring.push_slice(b"abcdef");
+        ring.consume(4);
+        ring.push_slice(b"ghijk");
+        assert_eq!(ring.capacity(), 8);
+        assert_eq!(ring.as_slices(), (&b"efgh"[..], &b"ijk"[..]));
+
+        ring.push_slice(b"lmnop");
+        assert_eq!(ring.capacity(), 16);
+        assert_eq!(contents(&ring), b"efghijklmnop");
+
+        let mut bytes = [0u8; 5];
+        assert_eq!(ring.copy_to(2, &mut bytes), 5);
+        assert_eq!(&bytes, b"ghijk");
+        assert_eq!(ring.copy_to(10, &mut bytes), 2);
+        assert_eq!(&bytes[..2], b"op");
+    }
+
+    #[test]
+    fn write_in_place() {
+        let mut ring = RingBuf::with_capacity(8);
+        ring.push_slice(b"0123456");
+        ring.consume(6);
+
+        assert_eq!(ring.spare_slice().len(), 1);
+        ring.spare_slice()[0] = b'7';
+        ring.commit(1);
+
+        let spare = ring.spare_slice();
+        assert_eq!(spare.len(), 6);
+        spare[..3].copy_from_slice(b"abc");
+        ring.commit(3);
+        assert_eq!(contents(&ring), b"67abc");
+
+        ring.clear();
+        assert!(ring.is_empty());
+        assert_eq!(ring.free_space(), 8);
+    }
+
+    #[test]
+    #[should_panic(expected = "commit 9 bytes with 8 spare")]
+    fn commit_over_spare() {
+        let mut ring = RingBuf::with_capacity(8);
+        ring.commit(9);
+    }
+}
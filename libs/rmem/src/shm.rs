@@ -0,0 +1,352 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::os::unix::io::{AsRawFd, RawFd};
+
+use crate::{mem_set, page_size, size_of_aligned, size_of_sys_aligned, AllocError};
+
+////////////////////////////////////////////////////////////////////////////////
+// Shared Memory Region
+////////////////////////////////////////////////////////////////////////////////
+
+/// SharedRegion is a fixed-size memory region backed by a shared memory object
+/// (`memfd_create` on Linux, `shm_open` elsewhere), which memory can be allocated from
+/// and deallocated to (as `malloc` & `free` do).
+///
+/// The region is mapped as SHARED, thus it's visible to child processes forked (as is),
+/// or mapped by other processes with the file descriptor (see `from_raw_fd`), that a helper
+/// process can read the dataset WITHOUT a full fork copy.
+///
+/// # Notes
+///
+/// Memory is located by `pointer` in the creating process, while the region MAYBE mapped
+/// at another address in other processes, thus `offset_of` & `ptr_at` SHOULD be used
+/// to pass locations across processes.
+///
+/// Memory allocated from a region is NOT accounted in `used_memory`, and MUST NOT be
+/// deallocated with `free`/`zfree`.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::SharedRegion;
+///
+/// let mut region = SharedRegion::new(1 << 20).unwrap();
+///
+/// let (ptr, size) = region.malloc(100).unwrap();
+/// assert!(!ptr.is_null());
+/// assert_eq!(region.ptr_at(region.offset_of(ptr)), ptr);
+///
+/// region.free(ptr, size);
+/// assert_eq!(region.free_bytes(), region.size());
+/// ```
+pub struct SharedRegion {
+    fd: RawFd,
+    ptr: *mut u8,
+    size: usize,
+    /// Free blocks (offset => size) of the region, which are coalesced once deallocated.
+    free_blocks: BTreeMap<usize, usize>,
+}
+
+// SharedRegion owns its mapping exclusively, while mutation requires `&mut`.
+unsafe impl Send for SharedRegion {}
+unsafe impl Sync for SharedRegion {}
+
+impl SharedRegion {
+    /// Create a shared region of (at least) `size` bytes, rounded up to the page size,
+    /// with ALL memory zero-initialized & free.
+    ///
+    /// The file descriptor is inherited by child processes (NOT close-on-exec).
+    ///
+    /// # Errors
+    ///
+    /// The OS error is returned if the shared memory object fails to be created or mapped.
+    pub fn new(size: usize) -> io::Result<Self> {
+        let size = size_of_aligned(std::cmp::max(size, 1), page_size());
+        let fd = sys::create()?;
+        let mut region = SharedRegion {
+            fd,
+            ptr: std::ptr::null_mut(),
+            size,
+            free_blocks: BTreeMap::new(),
+        };
+
+        if unsafe { libc::ftruncate(fd, size as libc::off_t) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        region.ptr = map(fd, size)?;
+        region.free_blocks.insert(0, size);
+        Ok(region)
+    }
+
+    /// Map an existing shared region (created by `new`, possibly in another process)
+    /// with its file descriptor, which is owned (and closed on drop) by the region returned.
+    ///
+    /// NO memory is free in the region returned, since allocation is owned by the creator.
+    ///
+    /// # Errors
+    ///
+    /// The OS error is returned if the file descriptor fails to be inspected or mapped.
+    ///
+    /// # Safety
+    ///
+    /// `fd` MUST be a VALID file descriptor of a shared region, owned by the caller.
+    pub unsafe fn from_raw_fd(fd: RawFd) -> io::Result<Self> {
+        let mut region = SharedRegion {
+            fd,
+            ptr: std::ptr::null_mut(),
+            size: 0,
+            free_blocks: BTreeMap::new(),
+        };
+
+        let mut stat: libc::stat = std::mem::zeroed();
+        if libc::fstat(fd, &mut stat) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        region.size = stat.st_size as usize;
+        region.ptr = map(fd, region.size)?;
+        Ok(region)
+    }
+
+    /// Get the size (in bytes) of the region.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Get bytes of memory free in the region.
+    #[inline]
+    pub fn free_bytes(&self) -> usize {
+        self.free_blocks.values().sum()
+    }
+
+    /// Get the raw `pointer` of the region (in the current process).
+    #[inline]
+    pub fn as_ptr(&self) -> *mut u8 {
+        self.ptr
+    }
+
+    /// Get the offset (from the start of the region) of memory within the region.
+    ///
+    /// # Panics
+    ///
+    /// `ptr` MUST be within the region.
+    #[inline]
+    pub fn offset_of(&self, ptr: *const u8) -> usize {
+        let offset = (ptr as usize).wrapping_sub(self.ptr as usize);
+        assert!(offset < self.size, "pointer {:p} out of the region", ptr);
+        offset
+    }
+
+    /// Get the `pointer` (in the current process) of an offset within the region.
+    ///
+    /// # Panics
+    ///
+    /// `offset` MUST be within the region.
+    #[inline]
+    pub fn ptr_at(&self, offset: usize) -> *mut u8 {
+        assert!(offset < self.size, "offset {} out of the region", offset);
+        unsafe { self.ptr.add(offset) }
+    }
+
+    /// Allocate memory with a certain size from the region (first-fit).
+    ///
+    /// A valid `pointer` as well as the `size` of the allocated memory will be returned.
+    ///
+    /// # Panics
+    ///
+    /// ZERO size is NOT supported/permitted.
+    ///
+    /// # Errors
+    ///
+    /// An `AllocError` is returned if NO free block in the region is large enough.
+    pub fn malloc(&mut self, size: usize) -> Result<(*mut u8, usize), AllocError> {
+        assert!(size != 0, "ZERO size is NOT supported");
+
+        let bsize = size_of_sys_aligned(size);
+        let (offset, free_size) = self
+            .free_blocks
+            .iter()
+            .find(|(_, &free_size)| free_size >= bsize)
+            .map(|(&offset, &free_size)| (offset, free_size))
+            .ok_or_else(|| AllocError::new(size))?;
+
+        self.free_blocks.remove(&offset);
+        if free_size > bsize {
+            self.free_blocks.insert(offset + bsize, free_size - bsize);
+        }
+        Ok((unsafe { self.ptr.add(offset) }, size))
+    }
+
+    /// Allocate memory with zero-initialized with a certain size from the region.
+    ///
+    /// # Panics
+    ///
+    /// ZERO size is NOT supported/permitted.
+    ///
+    /// # Errors
+    ///
+    /// An `AllocError` is returned if NO free block in the region is large enough.
+    pub fn calloc(&mut self, size: usize) -> Result<(*mut u8, usize), AllocError> {
+        let (ptr, size) = self.malloc(size)?;
+        unsafe { mem_set(ptr, 0, size) };
+        Ok((ptr, size))
+    }
+
+    /// Deallocate memory (allocated from the region) with the same size previously provided,
+    /// which is coalesced with adjacent free blocks.
+    ///
+    /// # Panics
+    ///
+    /// `ptr` MUST be allocated from the region.
+    pub fn free(&mut self, ptr: *mut u8, size: usize) {
+        if ptr.is_null() {
+            return;
+        }
+
+        let mut offset = self.offset_of(ptr);
+        let mut bsize = size_of_sys_aligned(size);
+        if let Some((&next, &next_size)) = self.free_blocks.range(offset..).next() {
+            if offset + bsize == next {
+                self.free_blocks.remove(&next);
+                bsize += next_size;
+            }
+        }
+        if let Some((&prev, &prev_size)) = self.free_blocks.range(..offset).next_back() {
+            if prev + prev_size == offset {
+                offset = prev;
+                bsize += prev_size;
+            }
+        }
+        self.free_blocks.insert(offset, bsize);
+    }
+}
+
+impl AsRawFd for SharedRegion {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl Drop for SharedRegion {
+    fn drop(&mut self) {
+        unsafe {
+            if !self.ptr.is_null() {
+                libc::munmap(self.ptr as *mut libc::c_void, self.size);
+            }
+            libc::close(self.fd);
+        }
+    }
+}
+
+/// Map a shared memory object (as SHARED & read-write).
+fn map(fd: RawFd, size: usize) -> io::Result<*mut u8> {
+    let ptr = unsafe {
+        libc::mmap(
+            std::ptr::null_mut(),
+            size,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_SHARED,
+            fd,
+            0,
+        )
+    };
+    match ptr == libc::MAP_FAILED {
+        true => Err(io::Error::last_os_error()),
+        false => Ok(ptr as *mut u8),
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod sys {
+    use std::io;
+    use std::os::unix::io::RawFd;
+
+    /// Create an anonymous shared memory object.
+    pub(super) fn create() -> io::Result<RawFd> {
+        match unsafe { libc::memfd_create(b"rmem-shared\0".as_ptr() as _, 0) } {
+            -1 => Err(io::Error::last_os_error()),
+            fd => Ok(fd),
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+mod sys {
+    use std::io;
+    use std::os::unix::io::RawFd;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static SEQUENCE: AtomicUsize = AtomicUsize::new(0);
+
+    /// Create an anonymous shared memory object, which is unlinked right after created.
+    pub(super) fn create() -> io::Result<RawFd> {
+        let name = format!(
+            "/rmem-{}-{}\0",
+            std::process::id(),
+            SEQUENCE.fetch_add(1, Ordering::Relaxed)
+        );
+        let flags = libc::O_RDWR | libc::O_CREAT | libc::O_EXCL;
+        #[cfg(any(target_os = "macos", target_os = "ios"))]
+        let fd = unsafe { libc::shm_open(name.as_ptr() as _, flags, 0o600 as libc::c_uint) };
+        #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+        let fd = unsafe { libc::shm_open(name.as_ptr() as _, flags, 0o600 as libc::mode_t) };
+        if fd == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        unsafe { libc::shm_unlink(name.as_ptr() as _) };
+        Ok(fd)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Unit Tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod shm_tests {
+    use super::*;
+
+    #[test]
+    fn alloc_and_coalesce() {
+        let mut region = SharedRegion::new(4096).unwrap();
+        assert_eq!(region.size(), size_of_aligned(4096, page_size()));
+
+        let (a, asize) = region.calloc(100).unwrap();
+        let (b, bsize) = region.malloc(200).unwrap();
+        let (c, csize) = region.malloc(300).unwrap();
+        assert_eq!(region.offset_of(a), 0);
+        assert_eq!(region.offset_of(b), size_of_sys_aligned(100));
+        assert_eq!(unsafe { *a.add(99) }, 0);
+
+        region.free(b, bsize);
+        let (d, dsize) = region.malloc(50).unwrap();
+        assert_eq!(d, b);
+
+        region.free(a, asize);
+        region.free(c, csize);
+        region.free(d, dsize);
+        assert_eq!(region.free_blocks.len(), 1);
+        assert_eq!(region.free_bytes(), region.size());
+
+        assert!(region.malloc(region.size() + 1).is_err());
+    }
+
+    #[test]
+    fn map_region_twice() {
+        let mut region = SharedRegion::new(100).unwrap();
+        let (ptr, _) = region.malloc(5).unwrap();
+        unsafe { crate::mem_copy(b"hello".as_ptr(), ptr, 5) };
+
+        let other = unsafe { SharedRegion::from_raw_fd(libc::dup(region.as_raw_fd())) }.unwrap();
+        assert_eq!(other.size(), region.size());
+        assert_eq!(other.free_bytes(), 0);
+        assert_ne!(other.as_ptr(), region.as_ptr());
+
+        let other_ptr = other.ptr_at(region.offset_of(ptr));
+        assert_eq!(
+            unsafe { std::slice::from_raw_parts(other_ptr, 5) },
+            b"hello"
+        );
+    }
+}
@@ -0,0 +1,276 @@
+use std::cmp::Ordering;
+
+////////////////////////////////////////////////////////////////////////////////
+// SIMD-Accelerated Memory Operations
+////////////////////////////////////////////////////////////////////////////////
+
+/// Memory SHORTER than the SIMD threshold is NOT worth the SIMD fast paths at all.
+const SIMD_THRESHOLD: usize = 32;
+/// Patterns LONGER than the MAX SIMD pattern size are searched with the Two-Way algorithm,
+/// that the candidates verification stays cheap enough.
+pub(crate) const SIMD_MAX_PATTERN_SIZE: usize = 32;
+
+/// Compare memory with the SIMD fast path, if available (runtime-detected) & worthwhile.
+#[inline]
+pub(crate) unsafe fn simd_cmp(ptr1: *const u8, ptr2: *const u8, count: usize) -> Option<Ordering> {
+    if count < SIMD_THRESHOLD {
+        return None;
+    }
+    #[cfg(all(target_arch = "x86_64", not(miri)))]
+    if avx2::detected() {
+        return Some(avx2::cmp(ptr1, ptr2, count));
+    }
+    #[cfg(all(target_arch = "aarch64", not(miri)))]
+    if neon::detected() {
+        return Some(neon::cmp(ptr1, ptr2, count));
+    }
+    let _ = (ptr1, ptr2);
+    None
+}
+
+/// Find a byte with the SIMD fast path, if available (runtime-detected) & worthwhile.
+#[inline]
+pub(crate) unsafe fn simd_find(ptr: *const u8, len: usize, value: u8) -> Option<Option<usize>> {
+    if len < SIMD_THRESHOLD {
+        return None;
+    }
+    #[cfg(all(target_arch = "x86_64", not(miri)))]
+    if avx2::detected() {
+        return Some(avx2::find(ptr, len, value));
+    }
+    #[cfg(all(target_arch = "aarch64", not(miri)))]
+    if neon::detected() {
+        return Some(neon::find(ptr, len, value));
+    }
+    let _ = (ptr, value);
+    None
+}
+
+/// Find a pattern (with 2 ~ `SIMD_MAX_PATTERN_SIZE` bytes) with the SIMD fast path,
+/// if available (runtime-detected) & worthwhile.
+///
+/// Candidates are located by matching the first & last bytes of the pattern in parallel,
+/// and then verified one by one.
+#[inline]
+pub(crate) unsafe fn simd_find_pattern(hay: &[u8], needle: &[u8]) -> Option<Option<usize>> {
+    if hay.len() < SIMD_THRESHOLD + needle.len() {
+        return None;
+    }
+    #[cfg(all(target_arch = "x86_64", not(miri)))]
+    if avx2::detected() {
+        return Some(avx2::find_pattern(hay, needle));
+    }
+    #[cfg(all(target_arch = "aarch64", not(miri)))]
+    if neon::detected() {
+        return Some(neon::find_pattern(hay, needle));
+    }
+    let _ = needle;
+    None
+}
+
+/// Search the rest positions (from `offset`) of a pattern one by one.
+#[cfg(all(any(target_arch = "x86_64", target_arch = "aarch64"), not(miri)))]
+#[inline]
+fn find_pattern_from(hay: &[u8], needle: &[u8], offset: usize) -> Option<usize> {
+    (offset..=hay.len() - needle.len()).find(|&pos| &hay[pos..pos + needle.len()] == needle)
+}
+
+#[cfg(all(target_arch = "x86_64", not(miri)))]
+mod avx2 {
+    use std::arch::x86_64::*;
+    use std::cmp::Ordering;
+
+    const LANES: usize = 32;
+
+    #[inline]
+    pub(super) fn detected() -> bool {
+        is_x86_feature_detected!("avx2")
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn cmp(ptr1: *const u8, ptr2: *const u8, count: usize) -> Ordering {
+        let mut offset = 0;
+        while offset + LANES <= count {
+            let a = _mm256_loadu_si256(ptr1.add(offset) as *const __m256i);
+            let b = _mm256_loadu_si256(ptr2.add(offset) as *const __m256i);
+            let mask = _mm256_movemask_epi8(_mm256_cmpeq_epi8(a, b)) as u32;
+            if mask != u32::MAX {
+                let idx = offset + (!mask).trailing_zeros() as usize;
+                return (*ptr1.add(idx)).cmp(&*ptr2.add(idx));
+            }
+            offset += LANES;
+        }
+
+        let rest1 = std::slice::from_raw_parts(ptr1.add(offset), count - offset);
+        let rest2 = std::slice::from_raw_parts(ptr2.add(offset), count - offset);
+        rest1.cmp(rest2)
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn find(ptr: *const u8, len: usize, value: u8) -> Option<usize> {
+        let pattern = _mm256_set1_epi8(value as i8);
+        let mut offset = 0;
+        while offset + LANES <= len {
+            let chunk = _mm256_loadu_si256(ptr.add(offset) as *const __m256i);
+            let mask = _mm256_movemask_epi8(_mm256_cmpeq_epi8(chunk, pattern)) as u32;
+            if mask != 0 {
+                return Some(offset + mask.trailing_zeros() as usize);
+            }
+            offset += LANES;
+        }
+
+        std::slice::from_raw_parts(ptr.add(offset), len - offset)
+            .iter()
+            .position(|&byte| byte == value)
+            .map(|pos| offset + pos)
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn find_pattern(hay: &[u8], needle: &[u8]) -> Option<usize> {
+        let last = needle.len() - 1;
+        let first_bytes = _mm256_set1_epi8(needle[0] as i8);
+        let last_bytes = _mm256_set1_epi8(needle[last] as i8);
+
+        let mut offset = 0;
+        while offset + last + LANES <= hay.len() {
+            let a = _mm256_loadu_si256(hay.as_ptr().add(offset) as *const __m256i);
+            let b = _mm256_loadu_si256(hay.as_ptr().add(offset + last) as *const __m256i);
+            let matched = _mm256_and_si256(
+                _mm256_cmpeq_epi8(a, first_bytes),
+                _mm256_cmpeq_epi8(b, last_bytes),
+            );
+            let mut mask = _mm256_movemask_epi8(matched) as u32;
+            while mask != 0 {
+                let pos = offset + mask.trailing_zeros() as usize;
+                if hay[pos + 1..pos + last] == needle[1..last] {
+                    return Some(pos);
+                }
+                mask &= mask - 1;
+            }
+            offset += LANES;
+        }
+
+        super::find_pattern_from(hay, needle, offset)
+    }
+}
+
+#[cfg(all(target_arch = "aarch64", not(miri)))]
+mod neon {
+    use std::arch::aarch64::*;
+    use std::cmp::Ordering;
+
+    const LANES: usize = 16;
+
+    #[inline]
+    pub(super) fn detected() -> bool {
+        std::arch::is_aarch64_feature_detected!("neon")
+    }
+
+    #[target_feature(enable = "neon")]
+    pub(super) unsafe fn cmp(ptr1: *const u8, ptr2: *const u8, count: usize) -> Ordering {
+        let mut offset = 0;
+        while offset + LANES <= count {
+            let eq = vceqq_u8(vld1q_u8(ptr1.add(offset)), vld1q_u8(ptr2.add(offset)));
+            if vminvq_u8(eq) != u8::MAX {
+                break;
+            }
+            offset += LANES;
+        }
+
+        let rest1 = std::slice::from_raw_parts(ptr1.add(offset), count - offset);
+        let rest2 = std::slice::from_raw_parts(ptr2.add(offset), count - offset);
+        rest1.cmp(rest2)
+    }
+
+    #[target_feature(enable = "neon")]
+    pub(super) unsafe fn find(ptr: *const u8, len: usize, value: u8) -> Option<usize> {
+        let pattern = vdupq_n_u8(value);
+        let mut offset = 0;
+        while offset + LANES <= len {
+            let eq = vceqq_u8(vld1q_u8(ptr.add(offset)), pattern);
+            if vmaxvq_u8(eq) != 0 {
+                break;
+            }
+            offset += LANES;
+        }
+
+        std::slice::from_raw_parts(ptr.add(offset), len - offset)
+            .iter()
+            .position(|&byte| byte == value)
+            .map(|pos| offset + pos)
+    }
+
+    #[target_feature(enable = "neon")]
+    pub(super) unsafe fn find_pattern(hay: &[u8], needle: &[u8]) -> Option<usize> {
+        let last = needle.len() - 1;
+        let first_bytes = vdupq_n_u8(needle[0]);
+        let last_bytes = vdupq_n_u8(needle[last]);
+
+        let mut offset = 0;
+        while offset + last + LANES <= hay.len() {
+            let a = vceqq_u8(vld1q_u8(hay.as_ptr().add(offset)), first_bytes);
+            let b = vceqq_u8(vld1q_u8(hay.as_ptr().add(offset + last)), last_bytes);
+            if vmaxvq_u8(vandq_u8(a, b)) != 0 {
+                for pos in offset..offset + LANES {
+                    if hay[pos..pos + needle.len()] == *needle {
+                        return Some(pos);
+                    }
+                }
+            }
+            offset += LANES;
+        }
+
+        super::find_pattern_from(hay, needle, offset)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Unit Tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod simd_tests {
+    use super::*;
+
+    #[test]
+    fn simd_cmp_at_any_offset() {
+        let a = [7u8; 100];
+        let mut b = a;
+        assert_eq!(unsafe { simd_cmp(a.as_ptr(), b.as_ptr(), 10) }, None);
+
+        for pos in 0..b.len() {
+            b[pos] = 8;
+            let ord = unsafe { simd_cmp(a.as_ptr(), b.as_ptr(), 100) };
+            assert!(ord.is_none() || ord == Some(Ordering::Less));
+            b[pos] = 7;
+        }
+        let ord = unsafe { simd_cmp(a.as_ptr(), b.as_ptr(), 100) };
+        assert!(ord.is_none() || ord == Some(Ordering::Equal));
+    }
+
+    #[test]
+    fn simd_find_at_any_offset() {
+        let mut bytes = [0u8; 100];
+        for pos in 0..bytes.len() {
+            bytes[pos] = b'\n';
+            let found = unsafe { simd_find(bytes.as_ptr(), bytes.len(), b'\n') };
+            assert!(found.is_none() || found == Some(Some(pos)));
+            bytes[pos] = 0;
+        }
+        let found = unsafe { simd_find(bytes.as_ptr(), bytes.len(), b'\n') };
+        assert!(found.is_none() || found == Some(None));
+    }
+
+    #[test]
+    fn simd_find_pattern_at_any_offset() {
+        let mut bytes = [b'\r'; 100];
+        for pos in 0..bytes.len() - 1 {
+            bytes[pos + 1] = b'\n';
+            let found = unsafe { simd_find_pattern(&bytes, b"\r\n") };
+            assert!(found.is_none() || found == Some(Some(pos)));
+            bytes[pos + 1] = b'\r';
+        }
+        let found = unsafe { simd_find_pattern(&bytes, b"\r\n") };
+        assert!(found.is_none() || found == Some(None));
+    }
+}
@@ -0,0 +1,208 @@
+use std::fmt;
+use std::mem::{ManuallyDrop, MaybeUninit};
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+
+use crate::{calloc_array_for, free_array_for, malloc_array_for, size_of};
+
+////////////////////////////////////////////////////////////////////////////////
+// Owned Typed Slice
+////////////////////////////////////////////////////////////////////////////////
+
+/// OwnedSlice is a fixed-length slice of elements owning its memory (allocated with
+/// `malloc_array_for`), where ALL elements are dropped & the memory is deallocated on drop.
+///
+/// It's created uninitialized (as `OwnedSlice<MaybeUninit<T>>`) with `alloc_slice`,
+/// and turned into `OwnedSlice<T>` with `assume_init` once ALL elements are written,
+/// thus hash table buckets and such can be built WITHOUT raw pointer arithmetic.
+///
+/// # Notes
+///
+/// No memory will be allocated for an empty slice (or a slice of ZERO-sized elements).
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::alloc_slice;
+///
+/// let mut slice = alloc_slice::<String>(3);
+/// for (idx, elem) in slice.iter_mut().enumerate() {
+///     elem.write(idx.to_string());
+/// }
+/// let slice = unsafe { slice.assume_init() };
+/// assert_eq!(&slice[..], &["0", "1", "2"]);
+/// ```
+pub struct OwnedSlice<T> {
+    ptr: NonNull<T>,
+    len: usize,
+}
+
+// OwnedSlice owns its elements exclusively, just like `Box<[T]>` does.
+unsafe impl<T: Send> Send for OwnedSlice<T> {}
+unsafe impl<T: Sync> Sync for OwnedSlice<T> {}
+
+/// Allocate an uninitialized slice of `n` elements with a certain type.
+///
+/// # Panics
+///
+/// The total size (`n * size_of::<T>()`) MUST NOT overflow.
+///
+/// # Aborts
+///
+/// It will abort while memory allocation errors/failures occur (such as OOM).
+#[cfg_attr(feature = "leak-track", track_caller)]
+pub fn alloc_slice<T>(n: usize) -> OwnedSlice<MaybeUninit<T>> {
+    OwnedSlice::alloc_with(n, malloc_array_for::<MaybeUninit<T>>)
+}
+
+/// Allocate a slice of `n` elements with a certain type, with ALL bytes zero-initialized.
+///
+/// It's sound to `assume_init` right away for types where ALL-ZERO bytes is a valid value
+/// (such as integers or `Option<NonNull<T>>` being `None`).
+///
+/// # Panics
+///
+/// The total size (`n * size_of::<T>()`) MUST NOT overflow.
+///
+/// # Aborts
+///
+/// It will abort while memory allocation errors/failures occur (such as OOM).
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::alloc_slice_zeroed;
+///
+/// let buckets = alloc_slice_zeroed::<usize>(8);
+/// let buckets = unsafe { buckets.assume_init() };
+/// assert_eq!(&buckets[..], &[0; 8]);
+/// ```
+#[cfg_attr(feature = "leak-track", track_caller)]
+pub fn alloc_slice_zeroed<T>(n: usize) -> OwnedSlice<MaybeUninit<T>> {
+    OwnedSlice::alloc_with(n, calloc_array_for::<MaybeUninit<T>>)
+}
+
+impl<T> OwnedSlice<T> {
+    /// Whether memory is allocated (NOT empty nor ZERO-sized) for the slice.
+    #[inline]
+    fn allocated(len: usize) -> bool {
+        len != 0 && size_of::<T>() != 0
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "leak-track", track_caller)]
+    fn alloc_with<F: FnOnce(usize) -> (*mut T, usize)>(len: usize, alloc: F) -> Self {
+        let ptr = match Self::allocated(len) {
+            true => NonNull::new(alloc(len).0).expect("NULL pointer allocated"),
+            false => NonNull::dangling(),
+        };
+        OwnedSlice { ptr, len }
+    }
+
+    /// Get the raw `pointer` of the slice, which is dangling (but aligned) if no memory
+    /// allocated.
+    #[inline]
+    pub fn as_ptr(&self) -> *const T {
+        self.ptr.as_ptr()
+    }
+
+    /// Get the raw mutable `pointer` of the slice, which is dangling (but aligned) if no memory
+    /// allocated.
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.ptr.as_ptr()
+    }
+}
+
+impl<T> OwnedSlice<MaybeUninit<T>> {
+    /// Convert into a slice of initialized elements.
+    ///
+    /// # Safety
+    ///
+    /// ALL elements MUST be initialized, otherwise it's undefined behavior to read or drop them.
+    #[inline]
+    pub unsafe fn assume_init(self) -> OwnedSlice<T> {
+        let slice = ManuallyDrop::new(self);
+        OwnedSlice {
+            ptr: slice.ptr.cast(),
+            len: slice.len,
+        }
+    }
+}
+
+impl<T> Drop for OwnedSlice<T> {
+    fn drop(&mut self) {
+        unsafe { std::ptr::drop_in_place(&mut self[..] as *mut [T]) };
+        if Self::allocated(self.len) {
+            free_array_for(self.ptr.as_ptr(), self.len);
+        }
+    }
+}
+
+impl<T> Deref for OwnedSlice<T> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &[T] {
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T> DerefMut for OwnedSlice<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for OwnedSlice<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Unit Tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod slice_tests {
+    use super::*;
+
+    use std::rc::Rc;
+
+    #[test]
+    fn drop_elements_once() {
+        let value = Rc::new(0);
+        let mut slice = alloc_slice::<Rc<i32>>(10);
+        for elem in slice.iter_mut() {
+            elem.write(Rc::clone(&value));
+        }
+        assert_eq!(Rc::strong_count(&value), 11);
+
+        let slice = unsafe { slice.assume_init() };
+        assert_eq!(Rc::strong_count(&value), 11);
+        drop(slice);
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+
+    #[test]
+    fn empty_slice_without_memory() {
+        let slice = unsafe { alloc_slice::<u64>(0).assume_init() };
+        assert!(slice.is_empty());
+        assert_eq!(slice.as_ptr(), NonNull::dangling().as_ptr());
+
+        let mut units = alloc_slice::<()>(100);
+        units.iter_mut().for_each(|unit| {
+            unit.write(());
+        });
+        assert_eq!(unsafe { units.assume_init() }.len(), 100);
+    }
+
+    #[test]
+    fn alloc_zeroed_slice() {
+        let slice = unsafe { alloc_slice_zeroed::<Option<NonNull<u8>>>(16).assume_init() };
+        assert!(slice.iter().all(Option::is_none));
+        assert_eq!(format!("{:?}", &slice[..2]), "[None, None]");
+    }
+}
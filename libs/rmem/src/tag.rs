@@ -0,0 +1,240 @@
+use std::cell::Cell;
+use std::fmt;
+use std::sync::atomic::{AtomicIsize, Ordering};
+
+use crate::{free, malloc};
+
+////////////////////////////////////////////////////////////////////////////////
+// Allocation Tagging
+////////////////////////////////////////////////////////////////////////////////
+
+/// Tag of the subsystem which memory is allocated for, that memory usage can be reported
+/// separately (like MEMORY STATS) for each subsystem.
+///
+/// Memory allocated out of any tag scope is accounted as `Untagged`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Tag {
+    /// Memory NOT tagged by any subsystem.
+    Untagged,
+    /// Keys of the keyspace.
+    Keys,
+    /// Values of the keyspace.
+    Values,
+    /// Hash tables (buckets & entries) of dictionaries.
+    Dict,
+    /// Expiration times of keys.
+    Expires,
+    /// Query & reply buffers of clients.
+    ClientBuffers,
+    /// The replication backlog.
+    ReplBacklog,
+}
+
+impl Tag {
+    /// ALL tags, in the order of declaration.
+    pub const ALL: [Tag; 7] = [
+        Tag::Untagged,
+        Tag::Keys,
+        Tag::Values,
+        Tag::Dict,
+        Tag::Expires,
+        Tag::ClientBuffers,
+        Tag::ReplBacklog,
+    ];
+
+    /// Get the name of the tag, in the form used by MEMORY STATS.
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tag::Untagged => "untagged",
+            Tag::Keys => "keys",
+            Tag::Values => "values",
+            Tag::Dict => "dict",
+            Tag::Expires => "expires",
+            Tag::ClientBuffers => "clients",
+            Tag::ReplBacklog => "repl-backlog",
+        }
+    }
+}
+
+impl Default for Tag {
+    #[inline]
+    fn default() -> Tag {
+        Tag::Untagged
+    }
+}
+
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Bytes of memory currently allocated for each tag.
+///
+/// Memory MAYBE deallocated under a tag other than the one it was allocated with,
+/// thus counters are signed and saturated at ZERO once read.
+#[allow(clippy::declare_interior_mutable_const)]
+const TAG_USED_INIT: AtomicIsize = AtomicIsize::new(0);
+static TAG_USED: [AtomicIsize; Tag::ALL.len()] = [TAG_USED_INIT; Tag::ALL.len()];
+
+thread_local! {
+    static CURRENT_TAG: Cell<Tag> = const { Cell::new(Tag::Untagged) };
+}
+
+/// Get the tag memory allocated by the current thread is accounted for.
+#[inline]
+pub fn current_tag() -> Tag {
+    CURRENT_TAG.try_with(Cell::get).unwrap_or_default()
+}
+
+/// Account memory resized by `delta` bytes for the tag of the current thread.
+#[inline]
+pub(crate) fn tag_stat_add(delta: isize) {
+    TAG_USED[current_tag() as usize].fetch_add(delta, Ordering::Relaxed);
+}
+
+/// Get bytes of memory currently allocated for a tag.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{free_tagged, malloc_tagged, tagged_memory, Tag};
+///
+/// let (ptr, size) = malloc_tagged(1024, Tag::ReplBacklog);
+/// assert!(tagged_memory(Tag::ReplBacklog) >= 1024);
+///
+/// free_tagged(ptr, size, Tag::ReplBacklog);
+/// ```
+#[inline]
+pub fn tagged_memory(tag: Tag) -> usize {
+    std::cmp::max(TAG_USED[tag as usize].load(Ordering::Relaxed), 0) as usize
+}
+
+/// Get bytes of memory currently allocated for ALL tags, in the order of `Tag::ALL`.
+pub fn tag_stats() -> Vec<(Tag, usize)> {
+    Tag::ALL
+        .iter()
+        .map(|&tag| (tag, tagged_memory(tag)))
+        .collect()
+}
+
+/// A scope within which ALL memory (de)allocated by the current thread is accounted
+/// for a tag, created by `tag_scope`.
+///
+/// The previous tag is restored once the scope is dropped, thus scopes can be nested.
+#[must_use = "the tag is restored once the scope is dropped"]
+#[derive(Debug)]
+pub struct TagScope {
+    prev: Tag,
+}
+
+impl Drop for TagScope {
+    #[inline]
+    fn drop(&mut self) {
+        let _ = CURRENT_TAG.try_with(|tag| tag.set(self.prev));
+    }
+}
+
+/// Account ALL memory (de)allocated by the current thread for `tag`, until the returned
+/// scope is dropped.
+///
+/// Memory SHOULD be deallocated under the same tag it was allocated with, otherwise
+/// it's moved from one tag to another in the statistics.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{current_tag, tag_scope, tagged_memory, zfree, zmalloc, Tag};
+///
+/// let ptr = {
+///     let _scope = tag_scope(Tag::Expires);
+///     assert_eq!(current_tag(), Tag::Expires);
+///
+///     zmalloc(100).0
+/// };
+/// assert_eq!(current_tag(), Tag::Untagged);
+/// assert!(tagged_memory(Tag::Expires) >= 100);
+///
+/// let _scope = tag_scope(Tag::Expires);
+/// zfree(ptr);
+/// ```
+#[inline]
+pub fn tag_scope(tag: Tag) -> TagScope {
+    let prev = CURRENT_TAG
+        .try_with(|current| current.replace(tag))
+        .unwrap_or_default();
+    TagScope { prev }
+}
+
+/// Allocate memory/buffer (as `malloc` does) accounted for `tag`.
+///
+/// `malloc_tagged` & `free_tagged` SHOULD work as pairs (with the same tag) for memory
+/// allocation & deallocation separately.
+///
+/// # Aborts
+///
+/// It will abort while memory allocation errors/failures occur (such as OOM).
+#[inline]
+#[cfg_attr(feature = "leak-track", track_caller)]
+pub fn malloc_tagged(size: usize, tag: Tag) -> (*mut u8, usize) {
+    let _scope = tag_scope(tag);
+    malloc(size)
+}
+
+/// Deallocate memory (as `free` does) accounted for `tag`.
+#[inline]
+pub fn free_tagged(ptr: *mut u8, size: usize, tag: Tag) {
+    let _scope = tag_scope(tag);
+    free(ptr, size)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Unit Tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tag_tests {
+    use super::*;
+
+    use crate::{zfree, zmalloc};
+
+    #[test]
+    fn nest_tag_scopes() {
+        std::thread::spawn(|| {
+            assert_eq!(current_tag(), Tag::Untagged);
+            {
+                let _keys = tag_scope(Tag::Keys);
+                {
+                    let _dict = tag_scope(Tag::Dict);
+                    assert_eq!(current_tag(), Tag::Dict);
+                }
+                assert_eq!(current_tag(), Tag::Keys);
+            }
+            assert_eq!(current_tag(), Tag::Untagged);
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn account_per_tag() {
+        let (ptr, size) = malloc_tagged(4000, Tag::ClientBuffers);
+        assert!(tagged_memory(Tag::ClientBuffers) >= 4000);
+
+        let ptr2 = {
+            let _scope = tag_scope(Tag::ClientBuffers);
+            zmalloc(6000).0
+        };
+        assert!(tagged_memory(Tag::ClientBuffers) >= 10000);
+
+        free_tagged(ptr, size, Tag::ClientBuffers);
+        let _scope = tag_scope(Tag::ClientBuffers);
+        zfree(ptr2);
+
+        let stats = tag_stats();
+        assert_eq!(stats.len(), Tag::ALL.len());
+        assert_eq!(stats[0].0, Tag::Untagged);
+        assert_eq!(Tag::ClientBuffers.to_string(), "clients");
+    }
+}
@@ -0,0 +1,298 @@
+use std::alloc::Layout;
+use std::cell::{Cell, RefCell};
+
+use crate::guard::{guarded_check, guarded_dealloc};
+use crate::poison::{poison, unpoison};
+
+////////////////////////////////////////////////////////////////////////////////
+// Per-Thread Memory Statistics
+////////////////////////////////////////////////////////////////////////////////
+
+/// Memory allocation statistics of the current thread.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ThreadStats {
+    /// Number of memory allocations made by the thread.
+    pub allocs: u64,
+    /// Number of memory deallocations made by the thread.
+    pub frees: u64,
+    /// Bytes of memory allocated minus bytes deallocated by the thread.
+    ///
+    /// Memory allocated by one thread MAYBE deallocated by another one,
+    /// thus it's saturated at ZERO rather than going negative.
+    pub used: usize,
+    /// The MAX value `used` has ever reached.
+    pub peak: usize,
+    /// Number of memory allocations served by the thread-local cache.
+    pub cache_hits: u64,
+    /// Bytes of free memory currently kept in the thread-local cache.
+    pub cached: usize,
+}
+
+thread_local! {
+    static THREAD_STATS: Cell<ThreadStats> = const {
+        Cell::new(ThreadStats {
+            allocs: 0,
+            frees: 0,
+            used: 0,
+            peak: 0,
+            cache_hits: 0,
+            cached: 0,
+        })
+    };
+}
+
+/// Update statistics of the current thread (if still accessible).
+#[inline]
+fn update_stats<F: FnOnce(&mut ThreadStats)>(f: F) {
+    let _ = THREAD_STATS.try_with(|cell| {
+        let mut stats = cell.get();
+        f(&mut stats);
+        cell.set(stats);
+    });
+}
+
+/// Account a memory allocation with `size` bytes made by the current thread.
+#[inline]
+pub(crate) fn thread_stat_alloc(size: usize) {
+    update_stats(|stats| {
+        stats.allocs += 1;
+        stats.used += size;
+        stats.peak = std::cmp::max(stats.peak, stats.used);
+    });
+}
+
+/// Account a memory deallocation with `size` bytes made by the current thread.
+#[inline]
+pub(crate) fn thread_stat_free(size: usize) {
+    update_stats(|stats| {
+        stats.frees += 1;
+        stats.used = stats.used.saturating_sub(size);
+    });
+}
+
+/// Account memory resized from `old_size` bytes to `new_size` bytes by the current thread.
+#[inline]
+pub(crate) fn thread_stat_realloc(old_size: usize, new_size: usize) {
+    update_stats(|stats| {
+        stats.used = (stats.used + new_size).saturating_sub(old_size);
+        stats.peak = std::cmp::max(stats.peak, stats.used);
+    });
+}
+
+/// Get memory allocation statistics of the current thread.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{free, malloc, thread_stats};
+///
+/// let (ptr, size) = malloc(1024);
+/// let stats = thread_stats();
+/// assert!(stats.allocs >= 1);
+/// assert!(stats.used >= 1024);
+///
+/// free(ptr, size);
+/// assert!(thread_stats().frees >= 1);
+/// ```
+#[inline]
+pub fn thread_stats() -> ThreadStats {
+    THREAD_STATS.try_with(Cell::get).unwrap_or_default()
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Thread-Local Memory Cache
+////////////////////////////////////////////////////////////////////////////////
+
+/// Memory blocks of sizes up to `THREAD_CACHE_MAX_SIZE` (multiples of `THREAD_CACHE_STEP`)
+/// are cached (up to `THREAD_CACHE_MAX_BLOCKS` blocks for each size) once deallocated,
+/// and reused by further allocations of exactly the same size in the same thread,
+/// without directing to the (contended) allocator backend.
+const THREAD_CACHE_STEP: usize = 8;
+const THREAD_CACHE_MAX_SIZE: usize = 256;
+const THREAD_CACHE_MAX_BLOCKS: usize = 16;
+
+const THREAD_CACHE_BINS: usize = THREAD_CACHE_MAX_SIZE / THREAD_CACHE_STEP;
+
+struct ThreadCache {
+    bins: [Vec<*mut u8>; THREAD_CACHE_BINS],
+}
+
+impl ThreadCache {
+    fn new() -> Self {
+        ThreadCache {
+            bins: Default::default(),
+        }
+    }
+
+    /// Locate the bin which memory of a certain layout is cached in.
+    #[inline]
+    fn bin_of(layout: Layout) -> Option<usize> {
+        let size = layout.size();
+        match layout.align() == 1
+            && size != 0
+            && size <= THREAD_CACHE_MAX_SIZE
+            && size.is_multiple_of(THREAD_CACHE_STEP)
+        {
+            true => Some(size / THREAD_CACHE_STEP - 1),
+            false => None,
+        }
+    }
+
+    /// Return ALL cached memory blocks back to the allocator backend.
+    fn flush(&mut self) {
+        for (idx, bin) in self.bins.iter_mut().enumerate() {
+            let layout = Layout::from_size_align((idx + 1) * THREAD_CACHE_STEP, 1).unwrap();
+            for ptr in bin.drain(..) {
+                unpoison(ptr, layout.size());
+                unsafe { guarded_dealloc(ptr, layout) };
+            }
+        }
+        update_stats(|stats| stats.cached = 0);
+    }
+}
+
+impl Drop for ThreadCache {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+thread_local! {
+    static THREAD_CACHE: RefCell<ThreadCache> = RefCell::new(ThreadCache::new());
+}
+
+/// Take a cached memory block of the layout from the thread-local cache.
+#[inline]
+pub(crate) fn cache_take(layout: Layout) -> Option<*mut u8> {
+    let idx = ThreadCache::bin_of(layout)?;
+    let ptr = THREAD_CACHE
+        .try_with(|cache| cache.try_borrow_mut().ok()?.bins[idx].pop())
+        .ok()??;
+    unpoison(ptr, layout.size());
+
+    update_stats(|stats| {
+        stats.cache_hits += 1;
+        stats.cached -= layout.size();
+    });
+    Some(ptr)
+}
+
+/// Keep a deallocated memory block of the layout in the thread-local cache,
+/// `false` will be returned if the cache is full (or inaccessible).
+#[inline]
+pub(crate) fn cache_keep(ptr: *mut u8, layout: Layout) -> bool {
+    let idx = match ThreadCache::bin_of(layout) {
+        Some(idx) => idx,
+        None => return false,
+    };
+    // Canary bytes (if any) are verified once deallocated, rather than once flushed.
+    unsafe { guarded_check(ptr, layout) };
+
+    let kept = THREAD_CACHE
+        .try_with(|cache| match cache.try_borrow_mut() {
+            Ok(mut cache) if cache.bins[idx].len() < THREAD_CACHE_MAX_BLOCKS => {
+                cache.bins[idx].push(ptr);
+                true
+            }
+            _ => false,
+        })
+        .unwrap_or(false);
+
+    if kept {
+        poison(ptr, layout.size());
+        update_stats(|stats| stats.cached += layout.size());
+    }
+    kept
+}
+
+/// Return ALL memory blocks kept in the thread-local cache of the current thread
+/// back to the allocator backend.
+///
+/// The cache is flushed automatically while the thread exits.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{flush_thread_cache, free, malloc, thread_stats};
+///
+/// let (ptr, size) = malloc(32);
+/// free(ptr, size);
+/// assert_eq!(thread_stats().cached, 32);
+///
+/// flush_thread_cache();
+/// assert_eq!(thread_stats().cached, 0);
+/// ```
+pub fn flush_thread_cache() {
+    let _ = THREAD_CACHE.try_with(|cache| {
+        if let Ok(mut cache) = cache.try_borrow_mut() {
+            cache.flush();
+        }
+    });
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Unit Tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod thread_tests {
+    use super::*;
+
+    use crate::{free, malloc, zfree, zmalloc};
+
+    #[test]
+    fn locate_cache_bin() {
+        let layout = |size| Layout::from_size_align(size, 1).unwrap();
+        assert_eq!(ThreadCache::bin_of(layout(8)), Some(0));
+        assert_eq!(ThreadCache::bin_of(layout(24)), Some(2));
+        assert_eq!(ThreadCache::bin_of(layout(256)), Some(31));
+        assert_eq!(ThreadCache::bin_of(layout(0)), None);
+        assert_eq!(ThreadCache::bin_of(layout(12)), None);
+        assert_eq!(ThreadCache::bin_of(layout(264)), None);
+        assert_eq!(
+            ThreadCache::bin_of(Layout::from_size_align(8, 8).unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn reuse_cached_memory() {
+        std::thread::spawn(|| {
+            let (ptr, size) = malloc(64);
+            free(ptr, size);
+            assert_eq!(thread_stats().cached, 64);
+
+            let (ptr2, size) = malloc(64);
+            assert_eq!(ptr2, ptr);
+            assert_eq!(thread_stats().cache_hits, 1);
+            assert_eq!(thread_stats().cached, 0);
+            free(ptr2, size);
+
+            flush_thread_cache();
+            assert_eq!(thread_stats().cached, 0);
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn account_per_thread() {
+        std::thread::spawn(|| {
+            assert_eq!(thread_stats(), ThreadStats::default());
+
+            let (ptr, _) = zmalloc(1000);
+            let stats = thread_stats();
+            assert_eq!(stats.allocs, 1);
+            assert!(stats.used >= 1000);
+            assert_eq!(stats.peak, stats.used);
+
+            zfree(ptr);
+            let stats = thread_stats();
+            assert_eq!(stats.frees, 1);
+            assert_eq!(stats.used, 0);
+            assert!(stats.peak >= 1000);
+        })
+        .join()
+        .unwrap();
+    }
+}
@@ -0,0 +1,211 @@
+use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::current_tag;
+
+////////////////////////////////////////////////////////////////////////////////
+// Allocation Tracing
+////////////////////////////////////////////////////////////////////////////////
+
+/// DEFAULT MIN size (in bytes) of memory (re)allocated/deallocated to emit tracing events for.
+pub const DEFAULT_TRACE_THRESHOLD: usize = 1024 * 1024;
+
+static TRACE_THRESHOLD: AtomicUsize = AtomicUsize::new(DEFAULT_TRACE_THRESHOLD);
+static TRACE_SAMPLE_RATE: AtomicUsize = AtomicUsize::new(1);
+
+thread_local! {
+    /// Sequence of (re)allocations/deallocations above the threshold made by the current
+    /// thread, for sampling.
+    static TRACE_SEQUENCE: Cell<usize> = const { Cell::new(0) };
+    /// Whether an event is being emitted by the current thread, that allocations made
+    /// by subscribers (via `rmem`) are NOT traced recursively.
+    static TRACING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Set the MIN size (in bytes) of memory (re)allocated/deallocated to emit tracing events for,
+/// and return the previous one.
+///
+/// # Examples
+///
+/// ```
+/// # use rmem::{set_trace_threshold, trace_threshold};
+///
+/// let prev = set_trace_threshold(64 * 1024);
+/// assert_eq!(trace_threshold(), 64 * 1024);
+/// # set_trace_threshold(prev);
+/// ```
+#[inline]
+pub fn set_trace_threshold(size: usize) -> usize {
+    TRACE_THRESHOLD.swap(size, Ordering::Relaxed)
+}
+
+/// Get the MIN size (in bytes) of memory (re)allocated/deallocated to emit tracing events for.
+#[inline]
+pub fn trace_threshold() -> usize {
+    TRACE_THRESHOLD.load(Ordering::Relaxed)
+}
+
+/// Set the sample rate of tracing events, where ONLY 1 out of every `rate`
+/// (re)allocations/deallocations above the threshold is emitted, and return the previous one.
+///
+/// The DEFAULT rate is 1 (ALL emitted), and ZERO is treated as 1. Sampling is counted
+/// per thread.
+#[inline]
+pub fn set_trace_sample_rate(rate: usize) -> usize {
+    TRACE_SAMPLE_RATE.swap(std::cmp::max(rate, 1), Ordering::Relaxed)
+}
+
+/// Get the sample rate of tracing events.
+#[inline]
+pub fn trace_sample_rate() -> usize {
+    TRACE_SAMPLE_RATE.load(Ordering::Relaxed)
+}
+
+/// Check if an event SHOULD be emitted for memory of `size` bytes.
+#[inline]
+fn sampled(size: usize) -> bool {
+    if size < trace_threshold() {
+        return false;
+    }
+    let rate = trace_sample_rate();
+    rate == 1
+        || TRACE_SEQUENCE
+            .try_with(|seq| seq.replace(seq.get().wrapping_add(1)) % rate == 0)
+            .unwrap_or(false)
+}
+
+/// Run `emit` with recursive tracing suppressed.
+#[inline]
+fn emit_once<F: FnOnce()>(emit: F) {
+    if let Ok(false) = TRACING.try_with(|tracing| tracing.replace(true)) {
+        emit();
+        TRACING.with(|tracing| tracing.set(false));
+    }
+}
+
+/// Emit a tracing event for memory (re)allocated (from NULL).
+#[inline]
+pub(crate) fn trace_alloc(ptr: *mut u8, size: usize) {
+    if sampled(size) {
+        emit_once(|| {
+            tracing::debug!(target: "rmem", size, tag = %current_tag(), ptr = ?ptr, "alloc");
+        });
+    }
+}
+
+/// Emit a tracing event for memory deallocated.
+#[inline]
+pub(crate) fn trace_free(ptr: *mut u8, size: usize) {
+    if sampled(size) {
+        emit_once(|| {
+            tracing::debug!(target: "rmem", size, tag = %current_tag(), ptr = ?ptr, "free");
+        });
+    }
+}
+
+/// Emit a tracing event for memory reallocated, where the larger size of both is
+/// checked against the threshold.
+#[inline]
+pub(crate) fn trace_realloc(old_ptr: *mut u8, new_ptr: *mut u8, old_size: usize, size: usize) {
+    if sampled(std::cmp::max(old_size, size)) {
+        emit_once(|| {
+            tracing::debug!(
+                target: "rmem",
+                size,
+                old_size,
+                tag = %current_tag(),
+                ptr = ?new_ptr,
+                old_ptr = ?old_ptr,
+                "realloc"
+            );
+        });
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Unit Tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod trace_tests {
+    use super::*;
+
+    use std::sync::atomic::AtomicU64;
+    use std::sync::Mutex;
+
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    use crate::{free, malloc, realloc};
+
+    /// Settings are global, thus tests changing them are serialized.
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    /// Subscriber counting events emitted by `rmem`.
+    #[derive(Default)]
+    struct CountEvents(AtomicU64);
+
+    impl Subscriber for CountEvents {
+        fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+            metadata.target() == "rmem"
+        }
+
+        fn new_span(&self, _: &Attributes<'_>) -> Id {
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, _: &Event<'_>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    fn count_events<F: FnOnce()>(f: F) -> u64 {
+        let subscriber = std::sync::Arc::new(CountEvents::default());
+        tracing::subscriber::with_default(subscriber.clone(), f);
+        subscriber.0.load(Ordering::Relaxed)
+    }
+
+    #[test]
+    fn trace_above_threshold() {
+        let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let prev = set_trace_threshold(4096);
+
+        let events = count_events(|| {
+            let (ptr, size) = malloc(100);
+            let (ptr, size) = realloc(ptr, size, 8192);
+            free(ptr, size);
+        });
+        assert_eq!(events, 2);
+
+        set_trace_threshold(prev);
+    }
+
+    #[test]
+    fn trace_with_sampling() {
+        let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let prev_threshold = set_trace_threshold(4096);
+        let prev_rate = set_trace_sample_rate(4);
+        assert_eq!(trace_sample_rate(), 4);
+
+        let events = count_events(|| {
+            for _ in 0..8 {
+                let (ptr, size) = malloc(8192);
+                free(ptr, size);
+            }
+        });
+        assert_eq!(events, 4);
+
+        set_trace_sample_rate(0);
+        assert_eq!(trace_sample_rate(), 1);
+        set_trace_sample_rate(prev_rate);
+        set_trace_threshold(prev_threshold);
+    }
+}
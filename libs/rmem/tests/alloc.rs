@@ -36,25 +36,55 @@ fn mem_alloc_element() {
     free_for(ptr);
 }
 
+#[test]
+fn mem_alloc_aligned_memory() {
+    let (ptr, size) = malloc_aligned(200, 64);
+    assert!(!ptr.is_null());
+    assert_eq!(ptr as usize % 64, 0);
+    assert_eq!(size, 200);
+
+    free_aligned(ptr, size, 64);
+
+    let (ptr, size) = zmalloc_aligned(200, 32);
+    assert_eq!(ptr as usize % 32, 0);
+    assert!(size >= 200);
+    assert_eq!(zmem_size_of(ptr), size);
+
+    zfree_aligned(ptr, 32);
+}
+
 #[test]
 fn zmem_alloc_memory() {
     let (ptr, size) = zmalloc(6);
     assert!(!ptr.is_null());
-    assert_eq!(size, 8);
-    assert_eq!(zmem_size_of(ptr), 8);
+    assert!(size >= 8);
+    assert_eq!(zmem_size_of(ptr), size);
 
     let (ptr, size) = zrealloc(ptr, 15);
     assert!(!ptr.is_null());
-    assert_eq!(size, 16);
-    assert_eq!(zmem_size_of(ptr), 16);
+    assert!(size >= 16);
+    assert_eq!(zmem_size_of(ptr), size);
 
     zfree(ptr);
 
     let (ptr, size) = zcalloc(6);
     assert!(!ptr.is_null());
-    assert_eq!(size, 8);
-    assert_eq!(zmem_size_of(ptr), 8);
+    assert!(size >= 8);
+    assert_eq!(zmem_size_of(ptr), size);
     assert_eq!(unsafe { *(ptr as *const u64) }, 0);
 
     zfree(ptr);
 }
+
+#[test]
+fn used_memory_stats() {
+    let (ptr, size) = zmalloc(1000);
+    assert!(used_memory() >= size);
+
+    let (ptr, size) = zrealloc(ptr, 5000);
+    assert!(used_memory() >= size);
+    assert!(used_memory_peak() >= used_memory());
+
+    zfree(ptr);
+    assert!(used_memory_peak() >= 5000);
+}
@@ -0,0 +1,29 @@
+use rmem::*;
+use std::cmp::Ordering;
+
+#[test]
+fn arena_alloc_cmd_args() {
+    let mut arena = Arena::new();
+
+    // Parse a batch of command arguments into the arena
+    let args: [&[u8]; 3] = [b"SET", b"key", b"value"];
+    let mut bufs = Vec::new();
+    for arg in args.iter() {
+        let (ptr, size) = arena.alloc(arg.len());
+        unsafe { mem_copy(arg.as_ptr(), ptr, size) };
+        bufs.push((ptr, size));
+    }
+    assert_eq!(arena.used_bytes(), 11);
+
+    for ((ptr, size), arg) in bufs.iter().zip(args.iter()) {
+        assert_eq!(
+            unsafe { mem_cmp(*ptr, arg.as_ptr(), *size) },
+            Ordering::Equal
+        );
+    }
+
+    // Release ALL arguments at once
+    arena.reset();
+    assert_eq!(arena.used_bytes(), 0);
+    assert_eq!(arena.allocated_bytes(), ARENA_CHUNK_SIZE);
+}
@@ -0,0 +1,50 @@
+use rmem::*;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct CountingAllocator {
+    allocs: AtomicUsize,
+    frees: AtomicUsize,
+}
+
+unsafe impl Allocator for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.allocs.fetch_add(1, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.frees.fetch_add(1, Ordering::SeqCst);
+        System.dealloc(ptr, layout)
+    }
+}
+
+static BACKEND: CountingAllocator = CountingAllocator {
+    allocs: AtomicUsize::new(0),
+    frees: AtomicUsize::new(0),
+};
+
+// The backend is process-wide, thus ALL checks are kept in one single test.
+#[test]
+fn route_alloc_to_installed_backend() {
+    assert!(set_allocator(&BACKEND).is_ok());
+
+    let (ptr, size) = malloc(16);
+    free(ptr, size);
+
+    let (ptr, _) = zcalloc(16);
+    assert_eq!(unsafe { *(ptr as *const u64) }, 0);
+    zfree(ptr);
+
+    // Return the memory kept in the thread-local cache back to the backend.
+    flush_thread_cache();
+
+    assert_eq!(BACKEND.allocs.load(Ordering::SeqCst), 2);
+    assert_eq!(BACKEND.frees.load(Ordering::SeqCst), 2);
+
+    assert!(set_allocator(&SystemAllocator).is_err());
+}
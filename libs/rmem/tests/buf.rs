@@ -0,0 +1,24 @@
+use rmem::*;
+
+#[test]
+fn mem_buf_build_reply() {
+    // Build a RESP bulk string reply without touching raw pointers
+    let mut buf = MemBuf::with_capacity(16);
+    let value = b"value";
+    buf.extend_from_slice(b"$");
+    buf.extend_from_slice(value.len().to_string().as_bytes());
+    buf.extend_from_slice(b"\r\n");
+    buf.extend_from_slice(value);
+    buf.extend_from_slice(b"\r\n");
+    assert_eq!(&buf[..], b"$5\r\nvalue\r\n");
+    assert_eq!(zmem_size_of(buf.as_ptr() as _), buf.capacity());
+
+    buf.resize(64, b' ');
+    assert_eq!(buf.len(), 64);
+    assert!(buf.ends_with(b"  "));
+
+    let copied = buf.clone();
+    drop(buf);
+    assert_eq!(copied.len(), 64);
+    assert!(copied.starts_with(b"$5\r\n"));
+}
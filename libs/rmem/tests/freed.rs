@@ -0,0 +1,28 @@
+#![cfg(feature = "poison-on-free")]
+
+use rmem::*;
+
+#[test]
+fn fill_freed_memory() {
+    // Memory deallocated is kept in the thread-local cache, thus stays accessible.
+    let (ptr, size) = malloc(32);
+    unsafe { mem_set(ptr, 0, size) };
+    free(ptr, size);
+
+    let (new_ptr, _) = malloc(32);
+    assert_eq!(new_ptr, ptr);
+    assert!(unsafe { std::slice::from_raw_parts(ptr, size) }
+        .iter()
+        .all(|&byte| byte == FREED_BYTE));
+    free(new_ptr, size);
+}
+
+// ZMEM memory 16-byte aligned is NOT kept in the thread-local cache.
+#[cfg(not(feature = "zmem-align16"))]
+#[test]
+#[should_panic(expected = "use-after-free detected on ZMEM memory")]
+fn detect_zmem_use_after_free() {
+    let (ptr, _) = zmalloc(16);
+    zfree(ptr);
+    zmem_size_of(ptr);
+}
@@ -0,0 +1,33 @@
+#![cfg(feature = "mem-guard")]
+
+use rmem::*;
+
+#[test]
+fn guard_intact_memory() {
+    let (ptr, size) = zmalloc(10);
+    assert_eq!(size, 16);
+    assert_eq!(zmem_usable_size(ptr), size);
+    unsafe { mem_set(ptr, 0xFF, size) };
+
+    let (ptr, size) = zrealloc(ptr, 100);
+    unsafe { mem_set(ptr, 0xFF, size) };
+    zfree(ptr);
+}
+
+// Offsets are based on the DEFAULT (8-byte) ZMEM header.
+#[cfg(not(feature = "zmem-align16"))]
+#[test]
+#[should_panic(expected = "memory corruption detected at offset 24 of 24 bytes")]
+fn guard_zmem_overrun() {
+    let (ptr, size) = zmalloc(16);
+    unsafe { mem_set(ptr, 0xFF, size + 1) };
+    zfree(ptr);
+}
+
+#[test]
+#[should_panic(expected = "memory corruption detected at offset -2 of 32 bytes")]
+fn guard_cached_memory_underrun() {
+    let (ptr, size) = malloc(32);
+    unsafe { *ptr.sub(2) = 0 };
+    free(ptr, size);
+}
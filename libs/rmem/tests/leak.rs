@@ -0,0 +1,27 @@
+#![cfg(feature = "leak-track")]
+
+use rmem::*;
+
+#[test]
+fn leak_track_subsystems() {
+    let mut buf = MemBuf::new();
+    let buf_line = line!() + 1;
+    buf.extend_from_slice(b"leaked?");
+    let pool_line = line!() + 1;
+    let (ptr, size) = pool_malloc(5000);
+
+    let live = live_allocations();
+    let sites: Vec<u32> = live
+        .iter()
+        .filter(|a| a.caller.file() == file!())
+        .map(|a| a.caller.line())
+        .collect();
+    assert_eq!(sites, vec![buf_line, pool_line]);
+    assert!(dump_live_allocations() >= 2);
+
+    pool_free(ptr, size);
+    drop(buf);
+    assert!(live_allocations()
+        .iter()
+        .all(|a| a.caller.file() != file!()));
+}
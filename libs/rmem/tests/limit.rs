@@ -0,0 +1,48 @@
+use rmem::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static HOOK_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+fn on_memory_limit(size: usize, used: usize) {
+    assert!(size > 0);
+    assert!(used + size > memory_limit());
+    HOOK_CALLS.fetch_add(1, Ordering::SeqCst);
+}
+
+// The memory limit is process-wide, thus ALL checks are kept in one single test.
+#[test]
+fn enforce_memory_limit() {
+    assert_eq!(memory_limit(), 0);
+    set_memory_limit_hook(on_memory_limit);
+
+    // Reserve a buffer, then leave only 4KiB of room.
+    let (buf, bsize) = zmalloc(1024);
+    set_memory_limit(used_memory() + 4096);
+
+    // Fallible allocations within the budget succeed.
+    let (ptr, size) = try_malloc(1024).unwrap();
+    assert_eq!(HOOK_CALLS.load(Ordering::SeqCst), 0);
+
+    // Fallible allocations exceeding the budget fail.
+    let err = try_malloc(8192).unwrap_err();
+    assert!(err.is_over_limit());
+    assert_eq!(err.size(), 8192);
+    assert!(try_zmalloc(8192).unwrap_err().is_over_limit());
+    assert!(try_realloc(ptr, size, 8192).unwrap_err().is_over_limit());
+
+    // Infallible allocations run the hook and proceed.
+    let (big, big_size) = malloc(8192);
+    assert!(!big.is_null());
+    assert_eq!(HOOK_CALLS.load(Ordering::SeqCst), 1);
+    assert!(used_memory() > memory_limit());
+
+    free(big, big_size);
+    free(ptr, size);
+    zfree(buf);
+    assert!(bsize >= 1024);
+
+    assert!(take_memory_limit_hook().is_some());
+    assert!(take_memory_limit_hook().is_none());
+    set_memory_limit(0);
+    assert!(try_malloc(8192).map(|(ptr, size)| free(ptr, size)).is_ok());
+}
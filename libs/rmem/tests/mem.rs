@@ -75,12 +75,12 @@ fn mem_ops_on_elem_queue() {
         let mut len = 0usize;
 
         // A batch of job data is pushed into queue
-        let jdata = vec![1, 2, 3, 4, 3, 2, 1];
+        let jdata = [1, 2, 3, 4, 3, 2, 1];
         mem_copy_for(jdata.as_ptr(), queue.as_mut_ptr().add(len), jdata.len());
         len += jdata.len();
 
         // A batch of job data is pushed into queue
-        let jdata = vec![8, 7, 6, 5, 4, 3, 2, 1];
+        let jdata = [8, 7, 6, 5, 4, 3, 2, 1];
         mem_copy_for(jdata.as_ptr(), queue.as_mut_ptr().add(len), jdata.len());
         len += jdata.len();
 
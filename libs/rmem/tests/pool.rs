@@ -0,0 +1,36 @@
+use rmem::*;
+
+#[test]
+fn pool_alloc_churn() {
+    let mut blocks = Vec::new();
+    for size in [5, 24, 100, 600, 3000].iter().cycle().take(50) {
+        let (ptr, csize) = pool_malloc(*size);
+        assert!(!ptr.is_null());
+        assert!(csize >= *size && csize.is_power_of_two());
+        unsafe { mem_set(ptr, 0xAB, csize) };
+        blocks.push((ptr, csize));
+    }
+    for (ptr, csize) in blocks.drain(..) {
+        pool_free(ptr, csize);
+    }
+
+    // Recycled blocks are reused for the same size classes
+    for size in [5, 24, 100, 600, 3000].iter() {
+        let (ptr, csize) = pool_malloc(*size);
+        blocks.push((ptr, csize));
+    }
+    let stats = pool_stats();
+    assert_eq!(stats.len(), 10);
+    assert_eq!(stats.first().unwrap().size, POOL_MIN_CLASS_SIZE);
+    assert_eq!(stats.last().unwrap().size, POOL_MAX_CLASS_SIZE);
+    for cstats in stats.iter().filter(|cstats| cstats.hits > 0) {
+        assert!(cstats.hit_rate() > 0f64 && cstats.hit_rate() <= 1f64);
+    }
+    assert_eq!(stats.iter().filter(|cstats| cstats.hits > 0).count(), 5);
+
+    for (ptr, csize) in blocks.drain(..) {
+        pool_free(ptr, csize);
+    }
+    pool_purge();
+    assert!(pool_stats().iter().all(|cstats| cstats.cached == 0));
+}
@@ -0,0 +1,19 @@
+use rmem::*;
+
+#[test]
+fn zmem_max_prealloc_policy() {
+    assert_eq!(zmem_max_prealloc(), ZMEM_MAX_PREALLOC);
+
+    set_zmem_max_prealloc(64);
+    let (ptr, size) = zrealloc_with_headroom(std::ptr::null_mut(), 1000);
+    assert!((1064..2000).contains(&size));
+
+    // Preallocation disabled.
+    set_zmem_max_prealloc(0);
+    assert_eq!(zmem_max_prealloc(), 0);
+    let (ptr, size) = zrealloc_with_headroom(ptr, 2000);
+    assert!((2000..4000).contains(&size));
+    zfree(ptr);
+
+    set_zmem_max_prealloc(ZMEM_MAX_PREALLOC);
+}
@@ -0,0 +1,23 @@
+use rmem::*;
+use std::alloc::System;
+use std::collections::HashMap;
+
+#[global_allocator]
+static GLOBAL: TrackedAllocator<System> = TrackedAllocator::new(System);
+
+#[test]
+fn track_rust_allocations() {
+    let bytes: Vec<u8> = Vec::with_capacity(1 << 20);
+    assert!(used_memory() >= 1 << 20);
+
+    let (ptr, size) = zmalloc(1 << 20);
+    assert!(used_memory() >= bytes.capacity() + size);
+    zfree(ptr);
+
+    let mut map = HashMap::new();
+    for i in 0..1024u64 {
+        map.insert(i, i.to_string());
+    }
+    assert!(used_memory() >= bytes.capacity() + map.len() * 16);
+    assert!(used_memory_peak() >= used_memory());
+}
@@ -0,0 +1,87 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use rtypes::RList;
+
+/// Lengths (from 16 to 64K) of lists benchmarked.
+const LENGTHS: [usize; 4] = [16, 256, 4 << 10, 64 << 10];
+
+fn filled(len: usize) -> RList<u64> {
+    let mut list = RList::new();
+    for i in 0..len as u64 {
+        list.push_back(i);
+    }
+    list
+}
+
+fn bench_push_pop(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rlist_push_pop");
+    for &len in LENGTHS.iter() {
+        group.throughput(Throughput::Elements(len as u64));
+        group.bench_with_input(BenchmarkId::new("back_front", len), &len, |b, &len| {
+            b.iter(|| {
+                let mut list = RList::new();
+                for i in 0..len as u64 {
+                    list.push_back(black_box(i));
+                }
+                while let Some(value) = list.pop_front() {
+                    black_box(value);
+                }
+            })
+        });
+        let values: Vec<u64> = (0..len as u64).collect();
+        group.bench_with_input(BenchmarkId::new("bulk_front", len), &len, |b, _| {
+            b.iter(|| {
+                let mut list = RList::new();
+                list.extend_from_slice(black_box(&values));
+                while let Some(value) = list.pop_front() {
+                    black_box(value);
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("front_back", len), &len, |b, &len| {
+            b.iter(|| {
+                let mut list = RList::new();
+                for i in 0..len as u64 {
+                    list.push_front(black_box(i));
+                }
+                while let Some(value) = list.pop_back() {
+                    black_box(value);
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_traverse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rlist_traverse");
+    for &len in LENGTHS.iter() {
+        let list = filled(len);
+        group.throughput(Throughput::Elements(len as u64));
+        group.bench_with_input(BenchmarkId::new("to_vec", len), &len, |b, _| {
+            b.iter(|| black_box(list.to_vec()))
+        });
+        // The element in the middle, that half of the list is walked through.
+        group.bench_with_input(BenchmarkId::new("get_middle", len), &len, |b, &len| {
+            b.iter(|| black_box(list.get(black_box(len / 2))))
+        });
+    }
+    group.finish();
+}
+
+fn bench_insert_remove(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rlist_insert_remove");
+    for &len in LENGTHS.iter() {
+        let mut list = filled(len);
+        group.bench_with_input(BenchmarkId::new("middle", len), &len, |b, &len| {
+            b.iter(|| {
+                list.insert_after(black_box(len / 2), 0);
+                black_box(list.remove(black_box(len / 2 + 1)));
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_push_pop, bench_traverse, bench_insert_remove);
+criterion_main!(benches);
@@ -0,0 +1,30 @@
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use rtypes::SipBuildHasher;
+
+/// Lengths of keys benchmarked, from short keys to long ones.
+const LENGTHS: [usize; 4] = [8, 16, 64, 1024];
+
+fn bench_hash_bytes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("siphash_bytes");
+    let sip13 = SipBuildHasher::new();
+    let std = RandomState::new();
+    for &len in LENGTHS.iter() {
+        let key = vec![b'k'; len];
+        group.throughput(Throughput::Bytes(len as u64));
+        group.bench_with_input(BenchmarkId::new("sip13", len), &key, |b, key| {
+            b.iter(|| sip13.hash_one(black_box(key.as_slice())))
+        });
+        // The DEFAULT hasher of `HashMap`, whose algorithm is NOT specified.
+        group.bench_with_input(BenchmarkId::new("std", len), &key, |b, key| {
+            b.iter(|| std.hash_one(black_box(key.as_slice())))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_hash_bytes);
+criterion_main!(benches);
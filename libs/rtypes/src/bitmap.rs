@@ -0,0 +1,558 @@
+use std::convert::TryInto;
+
+use crate::RString;
+
+////////////////////////////////////////////////////////////////////////////////
+// Bitmap Operations
+////////////////////////////////////////////////////////////////////////////////
+
+/// Bitwise operations between strings, see `RString::bitop`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitOp {
+    And,
+    Or,
+    Xor,
+    Not,
+}
+
+// Bits are numbered from the MSB of the first byte (just like Redis does), that bit 0 is
+// the MSB of byte 0, and bit 8 is the MSB of byte 1.
+
+impl RString {
+    /// Get the bit at `offset` (introduced from Redis `GETBIT`), where bits beyond
+    /// the content are ZERO.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RString;
+    ///
+    /// let s = RString::from_bytes(b"\x40");
+    /// assert!(s.get_bit(1));
+    /// assert!(!s.get_bit(0));
+    /// assert!(!s.get_bit(100));
+    /// ```
+    #[inline]
+    pub fn get_bit(&self, offset: usize) -> bool {
+        match self.as_bytes().get(offset >> 3) {
+            Some(&byte) => byte & bit_mask(offset) != 0,
+            None => false,
+        }
+    }
+
+    /// Set the bit at `offset` to `value` (introduced from Redis `SETBIT`), and return
+    /// the previous one.
+    ///
+    /// The content is grown with ZERO bytes padded if `offset` is beyond it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RString;
+    ///
+    /// let mut s = RString::new();
+    /// assert!(!s.set_bit(9, true));
+    /// assert_eq!(s.as_bytes(), b"\x00\x40");
+    /// assert!(s.set_bit(9, false));
+    /// assert_eq!(s.as_bytes(), b"\x00\x00");
+    /// ```
+    pub fn set_bit(&mut self, offset: usize, value: bool) -> bool {
+        let index = offset >> 3;
+        if index >= self.len() {
+            self.append_padding(0, index + 1 - self.len());
+        }
+
+        let byte = &mut self.as_mut_bytes()[index];
+        let prev = *byte & bit_mask(offset) != 0;
+        match value {
+            true => *byte |= bit_mask(offset),
+            false => *byte &= !bit_mask(offset),
+        }
+        prev
+    }
+
+    /// Count set bits of bytes in the range `[start, end)` (introduced from Redis `BITCOUNT`),
+    /// where the range is clamped to the content.
+    ///
+    /// Bits are counted a word (8 bytes) at a time, with the `popcnt` instruction if
+    /// available (or SWAR otherwise).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RString;
+    ///
+    /// let s = RString::from_str("foobar");
+    /// assert_eq!(s.bit_count(0, s.len()), 26);
+    /// assert_eq!(s.bit_count(1, 2), 6);
+    /// ```
+    pub fn bit_count(&self, start: usize, end: usize) -> usize {
+        let end = std::cmp::min(self.len(), end);
+        if start >= end {
+            return 0;
+        }
+
+        let mut words = self.as_bytes()[start..end].chunks_exact(8);
+        let count: usize = (&mut words)
+            .map(|word| u64::from_ne_bytes(word.try_into().unwrap()).count_ones() as usize)
+            .sum();
+        count
+            + words
+                .remainder()
+                .iter()
+                .map(|byte| byte.count_ones() as usize)
+                .sum::<usize>()
+    }
+
+    /// Find the offset of the first bit equal to `bit`, from the bit at `start`
+    /// (introduced from Redis `BITPOS`), where bits beyond the content are NOT searched.
+    ///
+    /// Bytes are skipped a word (8 bytes) at a time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RString;
+    ///
+    /// let s = RString::from_bytes(b"\xff\xf0\x00");
+    /// assert_eq!(s.bit_pos(false, 0), Some(12));
+    /// assert_eq!(s.bit_pos(true, 3), Some(3));
+    /// assert_eq!(s.bit_pos(true, 12), None);
+    /// ```
+    pub fn bit_pos(&self, bit: bool, start: usize) -> Option<usize> {
+        let bytes = self.as_bytes();
+        let mut index = start >> 3;
+        if index >= bytes.len() {
+            return None;
+        }
+
+        // Flip bytes searched for clear bits, thus set bits are ALWAYS searched.
+        let (flip, flip_word) = match bit {
+            true => (0u8, 0u64),
+            false => (u8::MAX, u64::MAX),
+        };
+
+        // Mask bits before `start` off within the first byte.
+        let first = (bytes[index] ^ flip) & (u8::MAX >> (start & 7));
+        if first != 0 {
+            return Some((index << 3) + first.leading_zeros() as usize);
+        }
+        index += 1;
+
+        // Words are read in big endian, thus leading zeros are bits in order.
+        while index + 8 <= bytes.len() {
+            let word = u64::from_be_bytes(bytes[index..index + 8].try_into().unwrap()) ^ flip_word;
+            if word != 0 {
+                return Some((index << 3) + word.leading_zeros() as usize);
+            }
+            index += 8;
+        }
+        bytes[index..]
+            .iter()
+            .position(|&byte| byte ^ flip != 0)
+            .map(|pos| ((index + pos) << 3) + (bytes[index + pos] ^ flip).leading_zeros() as usize)
+    }
+
+    /// Perform the bitwise operation `op` between `sources` (introduced from Redis `BITOP`),
+    /// and return the result as long as the longest source, where shorter sources are
+    /// treated as padded with ZERO bytes.
+    ///
+    /// Bytes are operated a word (8 bytes) at a time, and an empty string is returned
+    /// if NO sources are given.
+    ///
+    /// # Panics
+    ///
+    /// Exactly 1 source MUST be given for `BitOp::Not`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::{BitOp, RString};
+    ///
+    /// let a = RString::from_bytes(b"\x0f\xff");
+    /// let b = RString::from_bytes(b"\x3c");
+    /// assert_eq!(RString::bitop(BitOp::And, &[&a, &b]).as_bytes(), b"\x0c\x00");
+    /// assert_eq!(RString::bitop(BitOp::Or, &[&a, &b]).as_bytes(), b"\x3f\xff");
+    /// assert_eq!(RString::bitop(BitOp::Xor, &[&a, &b]).as_bytes(), b"\x33\xff");
+    /// assert_eq!(RString::bitop(BitOp::Not, &[&b]).as_bytes(), b"\xc3");
+    /// ```
+    pub fn bitop(op: BitOp, sources: &[&RString]) -> RString {
+        if op == BitOp::Not {
+            assert_eq!(sources.len(), 1, "BITOP NOT with {} sources", sources.len());
+        }
+
+        let (first, rest) = match sources.split_first() {
+            Some(split) => split,
+            None => return RString::new(),
+        };
+        let max_len = sources.iter().map(|s| s.len()).max().unwrap_or(0);
+        let mut dest = RString::with_capacity(max_len);
+        dest.append_rstr(first);
+        dest.append_padding(0, max_len - first.len());
+
+        let bytes = dest.as_mut_bytes();
+        match op {
+            BitOp::Not => apply_bitop(bytes, first, |_, word| !word, |_, byte| !byte),
+            BitOp::And => {
+                for src in rest {
+                    apply_bitop(bytes, src, |w, s| w & s, |b, s| b & s);
+                    // Bytes beyond the source are ANDed with ZERO.
+                    bytes[src.len()..].iter_mut().for_each(|byte| *byte = 0);
+                }
+            }
+            BitOp::Or => {
+                for src in rest {
+                    apply_bitop(bytes, src, |w, s| w | s, |b, s| b | s);
+                }
+            }
+            BitOp::Xor => {
+                for src in rest {
+                    apply_bitop(bytes, src, |w, s| w ^ s, |b, s| b ^ s);
+                }
+            }
+        }
+        dest
+    }
+}
+
+/// Apply `word_op` (or `byte_op` for the tail) to `dest` with bytes of `src` a word
+/// at a time, where `src` is NOT longer than `dest`.
+#[inline]
+fn apply_bitop(
+    dest: &mut [u8],
+    src: &[u8],
+    word_op: impl Fn(u64, u64) -> u64,
+    byte_op: impl Fn(u8, u8) -> u8,
+) {
+    let mut dest_words = dest[..src.len()].chunks_exact_mut(8);
+    let mut src_words = src.chunks_exact(8);
+    for (dest, src) in (&mut dest_words).zip(&mut src_words) {
+        let word = word_op(
+            u64::from_ne_bytes((&*dest).try_into().unwrap()),
+            u64::from_ne_bytes(src.try_into().unwrap()),
+        );
+        dest.copy_from_slice(&word.to_ne_bytes());
+    }
+
+    let dest_tail = dest_words.into_remainder().iter_mut();
+    for (dest, &src) in dest_tail.zip(src_words.remainder()) {
+        *dest = byte_op(*dest, src);
+    }
+}
+
+/// Get the mask of the bit at `offset` within its byte.
+#[inline]
+fn bit_mask(offset: usize) -> u8 {
+    0x80 >> (offset & 7)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// RBitmap
+////////////////////////////////////////////////////////////////////////////////
+
+/// Type of integer fields of bitmaps (introduced from Redis BITFIELD), i.e. signed
+/// (`i1` to `i64`) or unsigned (`u1` to `u63`) integers of the width given.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BitFieldType {
+    signed: bool,
+    bits: u8,
+}
+
+/// Behavior of BITFIELD SET & INCRBY on overflows (and underflows).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BitFieldOverflow {
+    /// Wrap around, i.e. the result modulo the range.
+    #[default]
+    Wrap,
+    /// Saturate at the MIN (or MAX) value.
+    Sat,
+    /// Fail, leaving the field unchanged.
+    Fail,
+}
+
+impl BitFieldType {
+    /// Create a type of `bits` bits, or `None` if out of range.
+    #[inline]
+    pub fn new(signed: bool, bits: u8) -> Option<Self> {
+        let max = if signed { 64 } else { 63 };
+        match (1..=max).contains(&bits) {
+            true => Some(BitFieldType { signed, bits }),
+            false => None,
+        }
+    }
+
+    /// Parse a type from "i<bits>" or "u<bits>", or return `None` if invalid.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let signed = match bytes.first()? {
+            b'i' => true,
+            b'u' => false,
+            _ => return None,
+        };
+        let bits = std::str::from_utf8(&bytes[1..]).ok()?;
+        if !bits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        Self::new(signed, bits.parse().ok()?)
+    }
+
+    #[inline]
+    pub fn is_signed(self) -> bool {
+        self.signed
+    }
+
+    #[inline]
+    pub fn bits(self) -> u8 {
+        self.bits
+    }
+
+    // Gets the range of values of the type.
+    #[inline]
+    fn range(self) -> (i128, i128) {
+        match self.signed {
+            true => (-(1i128 << (self.bits - 1)), (1i128 << (self.bits - 1)) - 1),
+            false => (0, (1i128 << self.bits) - 1),
+        }
+    }
+
+    // Fits `value` into the range of the type with `overflow` (introduced from Redis
+    // `checkSignedBitfieldOverflow` & `checkUnsignedBitfieldOverflow`), or gets `None`
+    // if it fails.
+    fn fit(self, value: i128, overflow: BitFieldOverflow) -> Option<i64> {
+        let (min, max) = self.range();
+        if min <= value && value <= max {
+            return Some(value as i64);
+        }
+        match overflow {
+            BitFieldOverflow::Wrap => {
+                Some(((value - min).rem_euclid(1i128 << self.bits) + min) as i64)
+            }
+            BitFieldOverflow::Sat if value > max => Some(max as i64),
+            BitFieldOverflow::Sat => Some(min as i64),
+            BitFieldOverflow::Fail => None,
+        }
+    }
+}
+
+/// RBitmap is an array of bits held in an `RString` (introduced from Redis bitmaps), where
+/// bits are numbered from the MSB of the first byte, and bits beyond the content are ZERO.
+///
+/// Besides single bits, integer fields of 1 to 64 bits at arbitrary bit offsets are read &
+/// written (introduced from Redis BITFIELD), with overflows handled as asked, and bits are
+/// counted a word (8 bytes) at a time.
+///
+/// # Notes
+///
+/// The content is grown with ZERO bytes padded once bits beyond it are written, and NEVER
+/// shrunk.
+///
+/// # Examples
+///
+/// ```
+/// # use rtypes::{BitFieldOverflow, BitFieldType, RBitmap};
+///
+/// let mut bitmap = RBitmap::new();
+/// let u8_type = BitFieldType::from_bytes(b"u8").unwrap();
+/// assert_eq!(bitmap.set_field(4, u8_type, 255, BitFieldOverflow::Wrap), Some(0));
+/// assert_eq!(bitmap.as_bytes(), b"\x0f\xf0");
+/// assert_eq!(bitmap.incr_field(4, u8_type, 10, BitFieldOverflow::Wrap), Some(9));
+/// assert_eq!(bitmap.incr_field(4, u8_type, 300, BitFieldOverflow::Sat), Some(255));
+/// assert_eq!(bitmap.incr_field(4, u8_type, 1, BitFieldOverflow::Fail), None);
+///
+/// let i4_type = BitFieldType::from_bytes(b"i4").unwrap();
+/// assert_eq!(bitmap.get_field(4, i4_type), -1);
+/// assert_eq!(bitmap.count_ones(), 8);
+/// ```
+#[derive(Clone, Default, Debug, PartialEq, Eq, Hash)]
+pub struct RBitmap {
+    bits: RString,
+}
+
+// private methods
+impl RBitmap {
+    // Reads the 128 bits from byte `index`, where bytes beyond the content are ZERO.
+    #[inline]
+    fn read_window(&self, index: usize) -> u128 {
+        let bytes = self.bits.as_bytes();
+        let mut window = [0u8; 16];
+        if index < bytes.len() {
+            let n = std::cmp::min(bytes.len() - index, window.len());
+            window[..n].copy_from_slice(&bytes[index..index + n]);
+        }
+        u128::from_be_bytes(window)
+    }
+
+    // Reads `bits` bits at `offset` as an unsigned integer.
+    #[inline]
+    fn read_raw(&self, offset: usize, bits: u8) -> u64 {
+        // A field spans 9 bytes at most, thus is ALWAYS in the window.
+        let shift = 128 - (offset & 7) - bits as usize;
+        (self.read_window(offset >> 3) >> shift) as u64 & field_mask(bits)
+    }
+
+    // Writes the low `bits` bits of `raw` at `offset`.
+    fn write_raw(&mut self, offset: usize, bits: u8, raw: u64) {
+        let (index, end) = (offset >> 3, (offset + bits as usize).div_ceil(8));
+        if end > self.bits.len() {
+            self.bits.append_padding(0, end - self.bits.len());
+        }
+
+        let shift = 128 - (offset & 7) - bits as usize;
+        let mask = (field_mask(bits) as u128) << shift;
+        let window = (self.read_window(index) & !mask) | ((raw as u128) << shift & mask);
+        self.bits.as_mut_bytes()[index..end].copy_from_slice(&window.to_be_bytes()[..end - index]);
+    }
+}
+
+// public methods
+impl RBitmap {
+    /// Create an empty bitmap.
+    #[inline]
+    pub fn new() -> Self {
+        RBitmap {
+            bits: RString::new(),
+        }
+    }
+
+    /// Create a bitmap holding bits of `bits`.
+    #[inline]
+    pub fn from_rstring(bits: RString) -> Self {
+        RBitmap { bits }
+    }
+
+    #[inline]
+    pub fn into_rstring(self) -> RString {
+        self.bits
+    }
+
+    #[inline]
+    pub fn as_rstring(&self) -> &RString {
+        &self.bits
+    }
+
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        self.bits.as_bytes()
+    }
+
+    /// Get number of bytes of the content.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    /// Get the bit at `offset`, see `RString::get_bit`.
+    #[inline]
+    pub fn get(&self, offset: usize) -> bool {
+        self.bits.get_bit(offset)
+    }
+
+    /// Set the bit at `offset` to `value`, and return the previous one, see
+    /// `RString::set_bit`.
+    #[inline]
+    pub fn set(&mut self, offset: usize, value: bool) -> bool {
+        self.bits.set_bit(offset, value)
+    }
+
+    /// Count ALL set bits (introduced from Redis BITCOUNT).
+    #[inline]
+    pub fn count_ones(&self) -> usize {
+        self.bits.bit_count(0, self.bits.len())
+    }
+
+    /// Count set bits in the range of bits `[start, end)` (introduced from Redis BITCOUNT
+    /// with BIT), where the range is clamped to the content.
+    ///
+    /// Whole bytes in the range are counted a word (8 bytes) at a time, and partial bytes
+    /// at both ends are masked.
+    pub fn count_ones_in(&self, start: usize, end: usize) -> usize {
+        let end = std::cmp::min(end, self.bits.len() << 3);
+        if start >= end {
+            return 0;
+        }
+
+        let bytes = self.bits.as_bytes();
+        let (first, last) = (start >> 3, (end - 1) >> 3);
+        let head = u8::MAX >> (start & 7);
+        let tail = u8::MAX << (7 - ((end - 1) & 7));
+        if first == last {
+            return (bytes[first] & head & tail).count_ones() as usize;
+        }
+        (bytes[first] & head).count_ones() as usize
+            + self.bits.bit_count(first + 1, last)
+            + (bytes[last] & tail).count_ones() as usize
+    }
+
+    /// Find the offset of the first bit equal to `bit` from the bit at `start`, see
+    /// `RString::bit_pos`.
+    #[inline]
+    pub fn position(&self, bit: bool, start: usize) -> Option<usize> {
+        self.bits.bit_pos(bit, start)
+    }
+
+    /// Get the integer field of `ty` at the bit `offset` (introduced from Redis BITFIELD
+    /// GET), where unsigned fields are NOT negative.
+    pub fn get_field(&self, offset: usize, ty: BitFieldType) -> i64 {
+        let raw = self.read_raw(offset, ty.bits);
+        match ty.signed {
+            // Sign extended from the MSB of the field.
+            true => ((raw << (64 - ty.bits)) as i64) >> (64 - ty.bits),
+            false => raw as i64,
+        }
+    }
+
+    /// Set the integer field of `ty` at the bit `offset` to `value` (introduced from Redis
+    /// BITFIELD SET), and return the previous value, or `None` if `value` is out of range
+    /// with `BitFieldOverflow::Fail`, where the field is NOT changed.
+    ///
+    /// Just like Redis, `value` is taken as `u64` for unsigned fields, thus negative values
+    /// overflow, rather than underflow.
+    pub fn set_field(
+        &mut self,
+        offset: usize,
+        ty: BitFieldType,
+        value: i64,
+        overflow: BitFieldOverflow,
+    ) -> Option<i64> {
+        let value = match ty.signed {
+            true => ty.fit(value as i128, overflow)?,
+            false => ty.fit(value as u64 as i128, overflow)?,
+        };
+        let prev = self.get_field(offset, ty);
+        self.write_raw(offset, ty.bits, value as u64);
+        Some(prev)
+    }
+
+    /// Increment the integer field of `ty` at the bit `offset` by `incr` (introduced from
+    /// Redis BITFIELD INCRBY), and return the new value, or `None` if it's out of range
+    /// with `BitFieldOverflow::Fail`, where the field is NOT changed.
+    pub fn incr_field(
+        &mut self,
+        offset: usize,
+        ty: BitFieldType,
+        incr: i64,
+        overflow: BitFieldOverflow,
+    ) -> Option<i64> {
+        let value = self.get_field(offset, ty) as i128 + incr as i128;
+        let value = ty.fit(value, overflow)?;
+        self.write_raw(offset, ty.bits, value as u64);
+        Some(value)
+    }
+}
+
+impl From<RString> for RBitmap {
+    #[inline]
+    fn from(bits: RString) -> Self {
+        Self::from_rstring(bits)
+    }
+}
+
+/// Get the mask of the low `bits` bits.
+#[inline]
+fn field_mask(bits: u8) -> u64 {
+    u64::MAX >> (64 - bits)
+}
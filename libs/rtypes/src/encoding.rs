@@ -0,0 +1,123 @@
+use std::fmt;
+
+use crate::QUICKLIST_DEFAULT_FILL;
+
+////////////////////////////////////////////////////////////////////////////////
+// Encodings
+////////////////////////////////////////////////////////////////////////////////
+
+/// Encoding of values (introduced from Redis `OBJ_ENCODING_*`), i.e. how they're held in
+/// memory, where small values are held compactly & converted once they grow.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    /// Integer held as `i64`, for strings.
+    Int,
+    /// Short string allocated exactly, for strings.
+    EmbStr,
+    /// String allocated with spare capacity, see `RString`.
+    Raw,
+    /// Sorted integers, see `RIntSet`.
+    IntSet,
+    /// Entries packed in a single memory block, see `RListPack`.
+    ListPack,
+    /// Hash table, see `RDict`.
+    HashTable,
+    /// Linked list of listpacks, see `RQuickList`.
+    QuickList,
+    /// Skip list with a hash table, see `RZSet`.
+    SkipList,
+    /// Radix tree of listpacks, see `RStream`.
+    Stream,
+}
+
+impl Encoding {
+    /// Get the name reported by OBJECT ENCODING.
+    #[inline]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Encoding::Int => "int",
+            Encoding::EmbStr => "embstr",
+            Encoding::Raw => "raw",
+            Encoding::IntSet => "intset",
+            Encoding::ListPack => "listpack",
+            Encoding::HashTable => "hashtable",
+            Encoding::QuickList => "quicklist",
+            Encoding::SkipList => "skiplist",
+            Encoding::Stream => "stream",
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Encoding Limits
+////////////////////////////////////////////////////////////////////////////////
+
+/// Limits of compact encodings (introduced from Redis `*-max-listpack-*` & co. configs),
+/// beyond which values are converted on insertion, shared by ALL types of values.
+///
+/// # Examples
+///
+/// ```
+/// # use rtypes::{Encoding, EncodingLimits, RSet};
+///
+/// let limits = EncodingLimits {
+///     set_max_intset_entries: 2,
+///     ..EncodingLimits::default()
+/// };
+/// let mut set = RSet::with_limits(&limits);
+/// set.add(b"1");
+/// set.add(b"2");
+/// assert_eq!(set.encoding(), Encoding::IntSet);
+/// set.add(b"3");
+/// assert_eq!(set.encoding(), Encoding::HashTable);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EncodingLimits {
+    /// MAX number of fields of hashes in `listpack` (Redis `hash-max-listpack-entries`).
+    pub hash_max_listpack_entries: usize,
+    /// MAX length of fields & values of hashes in `listpack` (Redis
+    /// `hash-max-listpack-value`).
+    pub hash_max_listpack_value: usize,
+    /// MAX number of members of sets in `intset` (Redis `set-max-intset-entries`).
+    pub set_max_intset_entries: usize,
+    /// MAX number of members of sets in `listpack` (Redis `set-max-listpack-entries`).
+    pub set_max_listpack_entries: usize,
+    /// MAX length of members of sets in `listpack` (Redis `set-max-listpack-value`).
+    pub set_max_listpack_value: usize,
+    /// MAX number of members of sorted sets in `listpack` (Redis
+    /// `zset-max-listpack-entries`).
+    pub zset_max_listpack_entries: usize,
+    /// MAX length of members of sorted sets in `listpack` (Redis
+    /// `zset-max-listpack-value`).
+    pub zset_max_listpack_value: usize,
+    /// Fill of quicklist nodes (Redis `list-max-listpack-size`), see `RQuickList`.
+    pub list_max_listpack_size: isize,
+}
+
+impl EncodingLimits {
+    /// DEFAULT limits, the same as Redis.
+    pub const DEFAULT: EncodingLimits = EncodingLimits {
+        hash_max_listpack_entries: 128,
+        hash_max_listpack_value: 64,
+        set_max_intset_entries: 512,
+        set_max_listpack_entries: 128,
+        set_max_listpack_value: 64,
+        zset_max_listpack_entries: 128,
+        zset_max_listpack_value: 64,
+        list_max_listpack_size: QUICKLIST_DEFAULT_FILL,
+    };
+}
+
+impl Default for EncodingLimits {
+    #[inline]
+    fn default() -> Self {
+        EncodingLimits::DEFAULT
+    }
+}
@@ -0,0 +1,229 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::{
+    geo_distance, GeoHash, GeoShape, RString, RZSet, ZAddFlags, ZAddOutcome, GEO_STEP_MAX,
+};
+
+////////////////////////////////////////////////////////////////////////////////
+// RGeo
+////////////////////////////////////////////////////////////////////////////////
+
+/// Unit of distances (introduced from Redis `extractUnitOrReply`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GeoUnit {
+    Meters,
+    Kilometers,
+    Feet,
+    Miles,
+}
+
+impl GeoUnit {
+    /// Parse a unit from "m", "km", "ft" or "mi" (case-insensitively), or return `None`
+    /// if invalid.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let unit = match bytes.to_ascii_lowercase().as_slice() {
+            b"m" => GeoUnit::Meters,
+            b"km" => GeoUnit::Kilometers,
+            b"ft" => GeoUnit::Feet,
+            b"mi" => GeoUnit::Miles,
+            _ => return None,
+        };
+        Some(unit)
+    }
+
+    /// Get number of meters of 1 unit.
+    #[inline]
+    pub fn to_meters(self) -> f64 {
+        match self {
+            GeoUnit::Meters => 1.0,
+            GeoUnit::Kilometers => 1000.0,
+            GeoUnit::Feet => 0.3048,
+            GeoUnit::Miles => 1609.34,
+        }
+    }
+}
+
+/// Error of points NOT indexable, i.e. out of ranges of longitudes & latitudes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GeoError {
+    pub lon: f64,
+    pub lat: f64,
+}
+
+impl fmt::Display for GeoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid longitude,latitude pair {:.6},{:.6}",
+            self.lon, self.lat
+        )
+    }
+}
+
+impl Error for GeoError {}
+
+/// Member found by `RGeo::search`, with its distance (in meters) from the center & its
+/// position decoded.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeoPoint {
+    pub member: RString,
+    pub distance: f64,
+    pub lon: f64,
+    pub lat: f64,
+    pub hash: u64,
+}
+
+/// RGeo is a set of members with positions (introduced from Redis `geo`), layered on an
+/// `RZSet`, where positions are encoded into 52-bit geohashes as scores, thus members
+/// nearby have scores nearby.
+///
+/// Members in a shape are searched in the cells (of geohashes) covering the shape, i.e.
+/// in ranges of scores, then filtered by distances.
+///
+/// # Notes
+///
+/// Positions decoded are the centers of cells of geohashes, which are within 1 meter of
+/// the positions added.
+///
+/// # Examples
+///
+/// ```
+/// # use rtypes::{GeoShape, RGeo, ZAddFlags};
+///
+/// let mut geo = RGeo::new();
+/// geo.add(13.361389, 38.115556, b"Palermo", ZAddFlags::NONE).unwrap();
+/// geo.add(15.087269, 37.502669, b"Catania", ZAddFlags::NONE).unwrap();
+/// assert!(geo.add(200.0, 0.0, b"Nowhere", ZAddFlags::NONE).is_err());
+///
+/// let distance = geo.distance(b"Palermo", b"Catania").unwrap();
+/// assert!((distance - 166274.1516).abs() < 0.01);
+///
+/// let found = geo.search((15.0, 37.0), GeoShape::Radius(100_000.0)).unwrap();
+/// assert_eq!(found.len(), 1);
+/// assert_eq!(found[0].member.as_bytes(), b"Catania");
+/// ```
+#[derive(Default)]
+pub struct RGeo {
+    zset: RZSet,
+}
+
+impl RGeo {
+    /// Create an empty set.
+    #[inline]
+    pub fn new() -> Self {
+        RGeo { zset: RZSet::new() }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.zset.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.zset.is_empty()
+    }
+
+    /// Get the underlying sorted set, whose scores are geohashes.
+    #[inline]
+    pub fn as_zset(&self) -> &RZSet {
+        &self.zset
+    }
+
+    /// Add the member at the position, or update its position (introduced from Redis
+    /// GEOADD), under conditions of `flags` (ONLY `NX` & `XX`), and return the outcome.
+    ///
+    /// # Panics
+    ///
+    /// Panics if flags other than `NX` & `XX` are given.
+    pub fn add(
+        &mut self,
+        lon: f64,
+        lat: f64,
+        member: &[u8],
+        flags: ZAddFlags,
+    ) -> Result<ZAddOutcome, GeoError> {
+        let others = [ZAddFlags::INCR, ZAddFlags::GT, ZAddFlags::LT];
+        assert!(
+            !others.iter().any(|&other| flags.contains(other)),
+            "geo flags {:?}",
+            flags
+        );
+        let hash = GeoHash::encode(lon, lat, GEO_STEP_MAX).ok_or(GeoError { lon, lat })?;
+        Ok(self.zset.add(hash.to_score(), member, flags))
+    }
+
+    /// Remove the member, and return `false` if NOT found.
+    #[inline]
+    pub fn remove(&mut self, member: &[u8]) -> bool {
+        self.zset.remove(member).is_some()
+    }
+
+    /// Get the position of the member as `(longitude, latitude)` (introduced from Redis
+    /// GEOPOS), or `None` if NOT found.
+    #[inline]
+    pub fn position(&self, member: &[u8]) -> Option<(f64, f64)> {
+        self.zset
+            .score(member)
+            .map(|score| GeoHash::from_score(score).to_lon_lat())
+    }
+
+    /// Get the 52-bit geohash of the member, or `None` if NOT found.
+    #[inline]
+    pub fn hash(&self, member: &[u8]) -> Option<GeoHash> {
+        self.zset.score(member).map(GeoHash::from_score)
+    }
+
+    /// Get the standard geohash string of the member (introduced from Redis GEOHASH), or
+    /// `None` if NOT found, see `GeoHash::to_base32`.
+    #[inline]
+    pub fn hash_string(&self, member: &[u8]) -> Option<String> {
+        let (lon, lat) = self.position(member)?;
+        GeoHash::to_base32(lon, lat)
+    }
+
+    /// Get the distance in meters between 2 members (introduced from Redis GEODIST), or
+    /// `None` if either is NOT found.
+    pub fn distance(&self, member1: &[u8], member2: &[u8]) -> Option<f64> {
+        let (lon1, lat1) = self.position(member1)?;
+        let (lon2, lat2) = self.position(member2)?;
+        Some(geo_distance(lon1, lat1, lon2, lat2))
+    }
+
+    /// Search members in the shape around the center `(longitude, latitude)` (introduced
+    /// from Redis GEOSEARCH), in an arbitrary order, or return an error if the center is
+    /// NOT indexable.
+    pub fn search(&self, center: (f64, f64), shape: GeoShape) -> Result<Vec<GeoPoint>, GeoError> {
+        let (lon, lat) = center;
+        let areas = GeoHash::search_areas(lon, lat, shape).ok_or(GeoError { lon, lat })?;
+        let mut found = Vec::new();
+        for area in areas {
+            let (min, max) = area.score_range();
+            for (score, member) in self.zset.range_by_score(min..max) {
+                let hash = GeoHash::from_score(score);
+                let (point_lon, point_lat) = hash.to_lon_lat();
+                if let Some(distance) = shape.distance_within(center, (point_lon, point_lat)) {
+                    found.push(GeoPoint {
+                        member: member.to_rstring(),
+                        distance,
+                        lon: point_lon,
+                        lat: point_lat,
+                        hash: hash.bits,
+                    });
+                }
+            }
+        }
+        Ok(found)
+    }
+}
+
+impl fmt::Debug for RGeo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let positions = self
+            .zset
+            .iter()
+            .map(|(score, member)| (member, GeoHash::from_score(score).to_lon_lat()));
+        f.debug_map().entries(positions).finish()
+    }
+}
@@ -0,0 +1,379 @@
+////////////////////////////////////////////////////////////////////////////////
+// Geohash
+////////////////////////////////////////////////////////////////////////////////
+
+/// MIN & MAX longitudes indexed (introduced from Redis `GEO_LONG_*`).
+pub const GEO_LONG_MIN: f64 = -180.0;
+pub const GEO_LONG_MAX: f64 = 180.0;
+/// MIN & MAX latitudes indexed (introduced from Redis `GEO_LAT_*`), i.e. the limits of
+/// the Web Mercator projection (EPSG:3857).
+pub const GEO_LAT_MIN: f64 = -85.05112878;
+pub const GEO_LAT_MAX: f64 = 85.05112878;
+/// MAX precision of geohashes, i.e. 26 bits for each of longitudes & latitudes.
+pub const GEO_STEP_MAX: u8 = 26;
+
+/// Radius of the earth in meters, the same as Redis uses.
+const EARTH_RADIUS_IN_METERS: f64 = 6372797.560856;
+/// Half of the circumference of the earth in the Mercator projection.
+const MERCATOR_MAX: f64 = 20037726.37;
+
+/// Geohash of `step` bits for each of longitudes & latitudes (introduced from Redis
+/// `GeoHashBits`), which are interleaved into `bits`, where bits of longitudes are at odd
+/// positions, and bits of latitudes at even positions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct GeoHash {
+    pub bits: u64,
+    pub step: u8,
+}
+
+/// Area of longitudes & latitudes, which is a cell of a geohash, or a bounding box.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GeoArea {
+    pub min_lon: f64,
+    pub max_lon: f64,
+    pub min_lat: f64,
+    pub max_lat: f64,
+}
+
+/// Shape searched around a center (introduced from Redis `GeoShape`), in meters.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GeoShape {
+    /// Circle of the radius (introduced from Redis GEOSEARCH BYRADIUS).
+    Radius(f64),
+    /// Box of the width & the height (introduced from Redis GEOSEARCH BYBOX).
+    Box(f64, f64),
+}
+
+// Spreads the low 32 bits of `x` to even positions.
+#[inline]
+fn spread(x: u32) -> u64 {
+    let mut x = x as u64;
+    x = (x | (x << 16)) & 0x0000ffff0000ffff;
+    x = (x | (x << 8)) & 0x00ff00ff00ff00ff;
+    x = (x | (x << 4)) & 0x0f0f0f0f0f0f0f0f;
+    x = (x | (x << 2)) & 0x3333333333333333;
+    (x | (x << 1)) & 0x5555555555555555
+}
+
+// Squashes bits at even positions into the low 32 bits.
+#[inline]
+fn squash(x: u64) -> u32 {
+    let mut x = x & 0x5555555555555555;
+    x = (x | (x >> 1)) & 0x3333333333333333;
+    x = (x | (x >> 2)) & 0x0f0f0f0f0f0f0f0f;
+    x = (x | (x >> 4)) & 0x00ff00ff00ff00ff;
+    x = (x | (x >> 8)) & 0x0000ffff0000ffff;
+    ((x | (x >> 16)) & 0x00000000ffffffff) as u32
+}
+
+#[inline]
+fn deg_rad(deg: f64) -> f64 {
+    deg * (std::f64::consts::PI / 180.0)
+}
+
+#[inline]
+fn rad_deg(rad: f64) -> f64 {
+    rad / (std::f64::consts::PI / 180.0)
+}
+
+/// Get the great-circle distance in meters between 2 points (introduced from Redis
+/// `geohashGetDistance`), with the haversine formula.
+pub fn geo_distance(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> f64 {
+    let (lat1r, lat2r) = (deg_rad(lat1), deg_rad(lat2));
+    let u = ((lat2r - lat1r) / 2.0).sin();
+    let v = (deg_rad(lon2 - lon1) / 2.0).sin();
+    2.0 * EARTH_RADIUS_IN_METERS * (u * u + lat1r.cos() * lat2r.cos() * v * v).sqrt().asin()
+}
+
+impl GeoHash {
+    /// Encode the point into a geohash of `step` bits for each coordinate (introduced
+    /// from Redis `geohashEncodeWGS84`), or return `None` if it's NOT indexable.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is NOT in `1..=GEO_STEP_MAX`.
+    pub fn encode(lon: f64, lat: f64, step: u8) -> Option<Self> {
+        Self::encode_in(lon, lat, (GEO_LAT_MIN, GEO_LAT_MAX), step)
+    }
+
+    // Encodes the point with latitudes ranging in `lat_range`.
+    fn encode_in(lon: f64, lat: f64, lat_range: (f64, f64), step: u8) -> Option<Self> {
+        assert!(
+            (1..=GEO_STEP_MAX).contains(&step),
+            "geohash of {} steps",
+            step
+        );
+        let (lat_min, lat_max) = lat_range;
+        if !(GEO_LONG_MIN..=GEO_LONG_MAX).contains(&lon) || !(lat_min..=lat_max).contains(&lat) {
+            return None;
+        }
+
+        let scale = (1u64 << step) as f64;
+        let lon_offset = (lon - GEO_LONG_MIN) / (GEO_LONG_MAX - GEO_LONG_MIN) * scale;
+        let lat_offset = (lat - lat_min) / (lat_max - lat_min) * scale;
+        // The MAX coordinates belong to the last cells.
+        let max = (1u64 << step) - 1;
+        let lon_cell = std::cmp::min(lon_offset as u64, max) as u32;
+        let lat_cell = std::cmp::min(lat_offset as u64, max) as u32;
+        Some(GeoHash {
+            bits: spread(lat_cell) | (spread(lon_cell) << 1),
+            step,
+        })
+    }
+
+    /// Get the standard geohash string of 11 characters of the point (introduced from
+    /// Redis GEOHASH), where latitudes range in `[-90, 90]` rather than the Mercator
+    /// limits, or `None` if it's NOT indexable.
+    pub fn to_base32(lon: f64, lat: f64) -> Option<String> {
+        const ALPHABET: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+        let hash = Self::encode_in(lon, lat, (-90.0, 90.0), GEO_STEP_MAX)?;
+        // The last character is padded with ZERO bits, just like Redis does.
+        let chars = (0..11).map(|i| match i {
+            10 => '0',
+            _ => ALPHABET[(hash.bits >> (52 - (i + 1) * 5)) as usize & 0x1f] as char,
+        });
+        Some(chars.collect())
+    }
+
+    /// Decode the cell of the geohash (introduced from Redis `geohashDecodeWGS84`).
+    pub fn decode(self) -> GeoArea {
+        let lat_cell = squash(self.bits) as f64;
+        let lon_cell = squash(self.bits >> 1) as f64;
+        let scale = (1u64 << self.step) as f64;
+        let (lon_span, lat_span) = (GEO_LONG_MAX - GEO_LONG_MIN, GEO_LAT_MAX - GEO_LAT_MIN);
+        GeoArea {
+            min_lon: GEO_LONG_MIN + lon_cell / scale * lon_span,
+            max_lon: GEO_LONG_MIN + (lon_cell + 1.0) / scale * lon_span,
+            min_lat: GEO_LAT_MIN + lat_cell / scale * lat_span,
+            max_lat: GEO_LAT_MIN + (lat_cell + 1.0) / scale * lat_span,
+        }
+    }
+
+    /// Decode the center of the cell (introduced from Redis `geohashDecodeToLongLatWGS84`),
+    /// as `(longitude, latitude)`.
+    #[inline]
+    pub fn to_lon_lat(self) -> (f64, f64) {
+        let area = self.decode();
+        let lon = ((area.min_lon + area.max_lon) / 2.0).clamp(GEO_LONG_MIN, GEO_LONG_MAX);
+        let lat = ((area.min_lat + area.max_lat) / 2.0).clamp(GEO_LAT_MIN, GEO_LAT_MAX);
+        (lon, lat)
+    }
+
+    /// Create a geohash of `GEO_STEP_MAX` steps from the score of a member of `RGeo`.
+    #[inline]
+    pub fn from_score(score: f64) -> Self {
+        GeoHash {
+            bits: score as u64,
+            step: GEO_STEP_MAX,
+        }
+    }
+
+    /// Get the 52-bit score (introduced from Redis `geohashAlign52Bits`), i.e. bits aligned
+    /// to `GEO_STEP_MAX` steps, which is exactly representable in `f64`.
+    #[inline]
+    pub fn to_score(self) -> f64 {
+        (self.bits << (2 * (GEO_STEP_MAX - self.step))) as f64
+    }
+
+    /// Get the range of scores `[min, max)` of ALL points in the cell.
+    #[inline]
+    pub fn score_range(self) -> (f64, f64) {
+        let next = GeoHash {
+            bits: self.bits + 1,
+            step: self.step,
+        };
+        (self.to_score(), next.to_score())
+    }
+
+    // Moves the cell by `d` cells along longitudes, wrapped around.
+    fn move_x(self, d: i8) -> Self {
+        let zz = 0x5555555555555555u64 >> (64 - 2 * self.step as u32);
+        let x = self.bits & 0xaaaaaaaaaaaaaaaa;
+        let y = self.bits & 0x5555555555555555;
+        let x = match d > 0 {
+            true => x.wrapping_add(zz + 1),
+            false => (x | zz).wrapping_sub(zz + 1),
+        };
+        GeoHash {
+            bits: (x & (0xaaaaaaaaaaaaaaaa >> (64 - 2 * self.step as u32))) | y,
+            step: self.step,
+        }
+    }
+
+    // Moves the cell by `d` cells along latitudes, wrapped around.
+    fn move_y(self, d: i8) -> Self {
+        let zz = 0xaaaaaaaaaaaaaaaau64 >> (64 - 2 * self.step as u32);
+        let x = self.bits & 0xaaaaaaaaaaaaaaaa;
+        let y = self.bits & 0x5555555555555555;
+        let y = match d > 0 {
+            true => y.wrapping_add(zz + 1),
+            false => (y | zz).wrapping_sub(zz + 1),
+        };
+        GeoHash {
+            bits: x | (y & (0x5555555555555555 >> (64 - 2 * self.step as u32))),
+            step: self.step,
+        }
+    }
+
+    /// Get the 8 neighbor cells (introduced from Redis `geohashNeighbors`), in order of
+    /// north, south, east, west, north-east, south-east, north-west & south-west.
+    pub fn neighbors(self) -> [GeoHash; 8] {
+        [
+            self.move_y(1),
+            self.move_y(-1),
+            self.move_x(1),
+            self.move_x(-1),
+            self.move_x(1).move_y(1),
+            self.move_x(1).move_y(-1),
+            self.move_x(-1).move_y(1),
+            self.move_x(-1).move_y(-1),
+        ]
+    }
+
+    /// Estimate steps of cells covering the range in meters around the latitude
+    /// (introduced from Redis `geohashEstimateStepsByRadius`).
+    pub fn estimate_steps(range: f64, lat: f64) -> u8 {
+        if range == 0.0 {
+            return GEO_STEP_MAX;
+        }
+        let mut range = range;
+        let mut step = 1i32;
+        while range < MERCATOR_MAX {
+            range *= 2.0;
+            step += 1;
+        }
+        // Cells are narrower near poles, where more are needed.
+        step -= 2;
+        if !(-66.0..=66.0).contains(&lat) {
+            step -= 1;
+            if !(-80.0..=80.0).contains(&lat) {
+                step -= 1;
+            }
+        }
+        step.clamp(1, GEO_STEP_MAX as i32) as u8
+    }
+
+    /// Get cells covering the shape around the center (introduced from Redis
+    /// `geohashCalculateAreasByShapeWGS84`), i.e. the cell of the center & its neighbors,
+    /// with ones NOT intersecting the bounding box excluded, or `None` if the center is
+    /// NOT indexable.
+    pub fn search_areas(lon: f64, lat: f64, shape: GeoShape) -> Option<Vec<GeoHash>> {
+        let bbox = GeoArea::bounding_box(lon, lat, shape);
+        let radius = match shape {
+            GeoShape::Radius(radius) => radius,
+            GeoShape::Box(width, height) => (width / 2.0).hypot(height / 2.0),
+        };
+        let mut step = Self::estimate_steps(radius, lat);
+        let mut center = Self::encode(lon, lat, step)?;
+        let mut neighbors = center.neighbors();
+
+        // The cells are too small to cover the bounding box.
+        if step >= 2 {
+            let [north, south, east, west, ..] = neighbors.map(GeoHash::decode);
+            if north.max_lat < bbox.max_lat
+                || south.min_lat > bbox.min_lat
+                || east.max_lon < bbox.max_lon
+                || west.min_lon > bbox.min_lon
+            {
+                step -= 1;
+                center = Self::encode(lon, lat, step)?;
+                neighbors = center.neighbors();
+            }
+        }
+
+        // Excludes neighbors beyond the bounding box.
+        let area = center.decode();
+        let mut useless = [false; 8];
+        if step >= 2 {
+            let (n, s, e, w, ne, se, nw, sw) = (0, 1, 2, 3, 4, 5, 6, 7);
+            if area.min_lat < bbox.min_lat {
+                useless[s] = true;
+                useless[se] = true;
+                useless[sw] = true;
+            }
+            if area.max_lat > bbox.max_lat {
+                useless[n] = true;
+                useless[ne] = true;
+                useless[nw] = true;
+            }
+            if area.min_lon < bbox.min_lon {
+                useless[w] = true;
+                useless[nw] = true;
+                useless[sw] = true;
+            }
+            if area.max_lon > bbox.max_lon {
+                useless[e] = true;
+                useless[ne] = true;
+                useless[se] = true;
+            }
+        }
+
+        let mut areas = vec![center];
+        for (neighbor, &useless) in neighbors.iter().zip(useless.iter()) {
+            // Neighbors MAYBE the same, with large cells wrapped around.
+            if !useless && !areas.contains(neighbor) {
+                areas.push(*neighbor);
+            }
+        }
+        Some(areas)
+    }
+}
+
+impl GeoArea {
+    /// Get the bounding box of the shape around the center (introduced from Redis
+    /// `geohashBoundingBox`).
+    pub fn bounding_box(lon: f64, lat: f64, shape: GeoShape) -> Self {
+        let (half_width, half_height) = match shape {
+            GeoShape::Radius(radius) => (radius, radius),
+            GeoShape::Box(width, height) => (width / 2.0, height / 2.0),
+        };
+        let lat_delta = rad_deg(half_height / EARTH_RADIUS_IN_METERS);
+        let lon_delta_top =
+            rad_deg(half_width / EARTH_RADIUS_IN_METERS / deg_rad(lat + lat_delta).cos());
+        let lon_delta_bottom =
+            rad_deg(half_width / EARTH_RADIUS_IN_METERS / deg_rad(lat - lat_delta).cos());
+        // The edge nearer the pole is wider in degrees.
+        let lon_delta = match lat < 0.0 {
+            true => lon_delta_bottom,
+            false => lon_delta_top,
+        };
+        GeoArea {
+            min_lon: lon - lon_delta,
+            max_lon: lon + lon_delta,
+            min_lat: lat - lat_delta,
+            max_lat: lat + lat_delta,
+        }
+    }
+
+    /// Check if the point is in the area.
+    #[inline]
+    pub fn contains(&self, lon: f64, lat: f64) -> bool {
+        (self.min_lon..=self.max_lon).contains(&lon) && (self.min_lat..=self.max_lat).contains(&lat)
+    }
+}
+
+impl GeoShape {
+    /// Get the distance in meters from the center to the point, or `None` if the point is
+    /// NOT in the shape (introduced from Redis `geohashGetDistanceIfInRadiusWGS84` &
+    /// `geohashGetDistanceIfInRectangle`).
+    pub fn distance_within(self, center: (f64, f64), point: (f64, f64)) -> Option<f64> {
+        let ((lon1, lat1), (lon2, lat2)) = (center, point);
+        match self {
+            GeoShape::Radius(radius) => {
+                let distance = geo_distance(lon1, lat1, lon2, lat2);
+                (distance <= radius).then_some(distance)
+            }
+            GeoShape::Box(width, height) => {
+                let lat_distance = EARTH_RADIUS_IN_METERS * (deg_rad(lat2) - deg_rad(lat1)).abs();
+                if lat_distance > height / 2.0 {
+                    return None;
+                }
+                // Measured along the latitude of the point.
+                if geo_distance(lon1, lat2, lon2, lat2) > width / 2.0 {
+                    return None;
+                }
+                Some(geo_distance(lon1, lat1, lon2, lat2))
+            }
+        }
+    }
+}
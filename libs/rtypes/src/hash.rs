@@ -0,0 +1,351 @@
+use std::fmt;
+use std::iter::FusedIterator;
+
+use crate::rand::random_below;
+use crate::{
+    Encoding, EncodingLimits, ListPackValue, RDict, RDictIter, RListPack, RListPackIter, RString,
+};
+
+////////////////////////////////////////////////////////////////////////////////
+// RHash
+////////////////////////////////////////////////////////////////////////////////
+
+/// Fields & values of a hash, in one of the encodings.
+enum Fields {
+    // Each field is followed by its value.
+    ListPack(RListPack),
+    Dict(RDict<RString, RString>),
+}
+
+/// RHash is a map of fields to values (introduced from Redis `hash`), which is encoded in
+/// a listpack while small, and converted to a hash table once it grows:
+///
+/// - `listpack`: NO more than `hash_max_listpack_entries` fields, each of which (as well
+///   as its value) is NO longer than `hash_max_listpack_value` bytes, see
+///   `EncodingLimits`.
+/// - `hashtable`: otherwise.
+///
+/// Fields MAYBE given expiration times (for HEXPIRE & co.), which are held aside ONLY
+/// once any is given, and cleared once fields are set again or removed.
+///
+/// # Notes
+///
+/// Hashes are NEVER converted back to listpacks, even if fields are removed. Fields
+/// expired are NOT removed until `remove_expired` is called.
+///
+/// # Examples
+///
+/// ```
+/// # use rtypes::{Encoding, ListPackValue, RHash};
+///
+/// let mut hash = RHash::new();
+/// assert!(hash.set(b"name", b"ruch"));
+/// assert!(hash.set(b"age", b"3"));
+/// assert!(!hash.set(b"age", b"4"));
+/// assert_eq!(hash.get(b"age"), Some(ListPackValue::Int(4)));
+/// assert_eq!(hash.encoding(), Encoding::ListPack);
+///
+/// assert!(hash.set(b"bio", &[b'x'; 100]));
+/// assert_eq!(hash.encoding(), Encoding::HashTable);
+/// assert_eq!(hash.get(b"name"), Some(ListPackValue::Str(b"ruch")));
+/// assert!(hash.remove(b"name"));
+/// assert_eq!(hash.len(), 2);
+/// ```
+pub struct RHash {
+    fields: Fields,
+    // Expiration times of fields, in milliseconds of the UNIX time.
+    expires: Option<Box<RDict<RString, u64>>>,
+    max_listpack_entries: usize,
+    max_listpack_value: usize,
+}
+
+/// Iterator over fields & values of an `RHash`, in an arbitrary order.
+pub struct RHashIter<'a> {
+    inner: HashIterInner<'a>,
+}
+
+enum HashIterInner<'a> {
+    ListPack(RListPackIter<'a>),
+    Dict(RDictIter<'a, RString, RString>),
+}
+
+// private methods
+impl RHash {
+    // Finds the index of `field` in the listpack, i.e. the one before its value.
+    #[inline]
+    fn find_in_listpack(lp: &RListPack, field: &[u8]) -> Option<usize> {
+        lp.iter()
+            .step_by(2)
+            .position(|value| value.eq_bytes(field))
+            .map(|idx| 2 * idx)
+    }
+
+    // Converts fields into a hash table (introduced from Redis `hashTypeConvert`).
+    fn convert_to_dict(&mut self) {
+        let lp = match &self.fields {
+            Fields::ListPack(lp) => lp,
+            Fields::Dict(_) => return,
+        };
+        let mut dict = RDict::new();
+        dict.expand(lp.len() / 2);
+        let mut iter = lp.iter();
+        while let (Some(field), Some(value)) = (iter.next(), iter.next()) {
+            dict.insert(field.to_rstring(), value.to_rstring());
+        }
+        self.fields = Fields::Dict(dict);
+    }
+
+    // Clears the expiration time of `field`.
+    #[inline]
+    fn clear_expire(&mut self, field: &[u8]) {
+        if let Some(expires) = &mut self.expires {
+            expires.remove(field);
+            if expires.is_empty() {
+                self.expires = None;
+            }
+        }
+    }
+}
+
+// public methods
+impl RHash {
+    /// Create an empty hash in `listpack`, with DEFAULT limits.
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_limits(&EncodingLimits::DEFAULT)
+    }
+
+    /// Create an empty hash in `listpack`, which is converted to a hash table once it grows
+    /// out of `limits`.
+    #[inline]
+    pub fn with_limits(limits: &EncodingLimits) -> Self {
+        RHash {
+            fields: Fields::ListPack(RListPack::new()),
+            expires: None,
+            max_listpack_entries: limits.hash_max_listpack_entries,
+            max_listpack_value: limits.hash_max_listpack_value,
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        match &self.fields {
+            Fields::ListPack(lp) => lp.len() / 2,
+            Fields::Dict(dict) => dict.len(),
+        }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the encoding of fields.
+    #[inline]
+    pub fn encoding(&self) -> Encoding {
+        match &self.fields {
+            Fields::ListPack(_) => Encoding::ListPack,
+            Fields::Dict(_) => Encoding::HashTable,
+        }
+    }
+
+    /// Set the value of `field` (introduced from Redis `hashTypeSet`), and return `true`
+    /// if it's added, where its expiration time is cleared (if any).
+    pub fn set(&mut self, field: &[u8], value: &[u8]) -> bool {
+        self.clear_expire(field);
+        if field.len() > self.max_listpack_value || value.len() > self.max_listpack_value {
+            self.convert_to_dict();
+        }
+
+        if let Fields::ListPack(lp) = &mut self.fields {
+            if let Some(idx) = Self::find_in_listpack(lp, field) {
+                lp.replace(idx + 1, value);
+                return false;
+            }
+            lp.push_back(field);
+            lp.push_back(value);
+            if lp.len() / 2 > self.max_listpack_entries {
+                self.convert_to_dict();
+            }
+            return true;
+        }
+
+        match &mut self.fields {
+            Fields::Dict(dict) => match dict.get_mut(field) {
+                Some(cur) => {
+                    *cur = RString::from_bytes(value);
+                    false
+                }
+                None => {
+                    dict.insert(RString::from_bytes(field), RString::from_bytes(value));
+                    true
+                }
+            },
+            Fields::ListPack(_) => unreachable!("hash NOT converted to hashtable"),
+        }
+    }
+
+    /// Get the value of `field`, or `None` if NOT found.
+    pub fn get(&self, field: &[u8]) -> Option<ListPackValue<'_>> {
+        match &self.fields {
+            Fields::ListPack(lp) => lp.get(Self::find_in_listpack(lp, field)? + 1),
+            Fields::Dict(dict) => dict
+                .get(field)
+                .map(|value| ListPackValue::Str(value.as_bytes())),
+        }
+    }
+
+    #[inline]
+    pub fn contains(&self, field: &[u8]) -> bool {
+        match &self.fields {
+            Fields::ListPack(lp) => Self::find_in_listpack(lp, field).is_some(),
+            Fields::Dict(dict) => dict.contains_key(field),
+        }
+    }
+
+    /// Remove `field` (introduced from Redis `hashTypeDelete`), and return `false` if it's
+    /// NOT found.
+    pub fn remove(&mut self, field: &[u8]) -> bool {
+        let removed = match &mut self.fields {
+            Fields::ListPack(lp) => match Self::find_in_listpack(lp, field) {
+                Some(idx) => lp.remove_range(idx, 2) == 2,
+                None => false,
+            },
+            Fields::Dict(dict) => dict.remove(field).is_some(),
+        };
+        if removed {
+            self.clear_expire(field);
+        }
+        removed
+    }
+
+    /// Get a random field & its value (introduced from Redis HRANDFIELD), or `None` if
+    /// empty.
+    pub fn random_field(&self) -> Option<(ListPackValue<'_>, ListPackValue<'_>)> {
+        match &self.fields {
+            Fields::ListPack(lp) if !lp.is_empty() => {
+                let mut iter = lp.iter().skip(2 * random_below(lp.len() / 2));
+                Some((iter.next()?, iter.next()?))
+            }
+            Fields::ListPack(_) => None,
+            Fields::Dict(dict) => dict.random_entry().map(|(field, value)| {
+                (
+                    ListPackValue::Str(field.as_bytes()),
+                    ListPackValue::Str(value.as_bytes()),
+                )
+            }),
+        }
+    }
+
+    /// Get an iterator over fields & values, in an arbitrary order.
+    #[inline]
+    pub fn iter(&self) -> RHashIter<'_> {
+        let inner = match &self.fields {
+            Fields::ListPack(lp) => HashIterInner::ListPack(lp.iter()),
+            Fields::Dict(dict) => HashIterInner::Dict(dict.iter()),
+        };
+        RHashIter { inner }
+    }
+
+    /// Set the expiration time (in milliseconds of the UNIX time) of `field`, and return
+    /// `false` if it's NOT found.
+    pub fn set_expire_at(&mut self, field: &[u8], at: u64) -> bool {
+        if !self.contains(field) {
+            return false;
+        }
+        self.expires
+            .get_or_insert_with(Default::default)
+            .insert(RString::from_bytes(field), at);
+        true
+    }
+
+    /// Get the expiration time (in milliseconds of the UNIX time) of `field`, or `None`
+    /// if it's NOT found or has NO expiration time.
+    #[inline]
+    pub fn expire_at(&self, field: &[u8]) -> Option<u64> {
+        self.expires.as_ref()?.get(field).copied()
+    }
+
+    /// Clear the expiration time of `field` (introduced from Redis HPERSIST), and return
+    /// `false` if it has NO expiration time.
+    #[inline]
+    pub fn persist(&mut self, field: &[u8]) -> bool {
+        let found = self.expire_at(field).is_some();
+        self.clear_expire(field);
+        found
+    }
+
+    /// Remove fields expired at `now` (in milliseconds of the UNIX time), and return
+    /// number of fields removed.
+    pub fn remove_expired(&mut self, now: u64) -> usize {
+        let expired: Vec<RString> = match &self.expires {
+            Some(expires) => expires
+                .iter()
+                .filter(|(_, &at)| at <= now)
+                .map(|(field, _)| field.clone())
+                .collect(),
+            None => return 0,
+        };
+        for field in expired.iter() {
+            self.remove(field.as_bytes());
+        }
+        expired.len()
+    }
+}
+
+impl Default for RHash {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for RHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Iterators
+////////////////////////////////////////////////////////////////////////////////
+
+impl<'a> IntoIterator for &'a RHash {
+    type Item = (ListPackValue<'a>, ListPackValue<'a>);
+    type IntoIter = RHashIter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> Iterator for RHashIter<'a> {
+    type Item = (ListPackValue<'a>, ListPackValue<'a>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.inner {
+            HashIterInner::ListPack(iter) => Some((iter.next()?, iter.next()?)),
+            HashIterInner::Dict(iter) => iter.next().map(|(field, value)| {
+                (
+                    ListPackValue::Str(field.as_bytes()),
+                    ListPackValue::Str(value.as_bytes()),
+                )
+            }),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = match &self.inner {
+            HashIterInner::ListPack(iter) => iter.len() / 2,
+            HashIterInner::Dict(iter) => iter.len(),
+        };
+        (len, Some(len))
+    }
+}
+
+impl<'a> ExactSizeIterator for RHashIter<'a> {}
+
+impl<'a> FusedIterator for RHashIter<'a> {}
@@ -0,0 +1,177 @@
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::sync::OnceLock;
+
+use crate::{RString, SharedRStr};
+
+////////////////////////////////////////////////////////////////////////////////
+// Shared Objects
+////////////////////////////////////////////////////////////////////////////////
+
+/// Number of integers (from ZERO) shared, see `shared_integer`.
+pub const SHARED_INTEGERS: usize = 10000;
+
+/// SharedObjects holds strings used frequently (introduced from Redis `sharedObjects`),
+/// created once & shared by ALL threads, that NO allocations are made for them.
+#[derive(Debug)]
+pub struct SharedObjects {
+    pub crlf: SharedRStr,
+    pub ok: SharedRStr,
+    pub err: SharedRStr,
+    pub pong: SharedRStr,
+    pub queued: SharedRStr,
+    /// Integer reply of ZERO (`:0\r\n`).
+    pub czero: SharedRStr,
+    /// Integer reply of 1 (`:1\r\n`).
+    pub cone: SharedRStr,
+    /// Null bulk reply (`$-1\r\n`).
+    pub null_bulk: SharedRStr,
+    /// Empty bulk reply (`$0\r\n\r\n`).
+    pub empty_bulk: SharedRStr,
+    /// Empty multi-bulk reply (`*0\r\n`).
+    pub empty_array: SharedRStr,
+    /// Integers from ZERO to `SHARED_INTEGERS - 1` formatted in decimal.
+    integers: Vec<SharedRStr>,
+}
+
+impl SharedObjects {
+    fn create() -> Self {
+        SharedObjects {
+            crlf: SharedRStr::from("\r\n"),
+            ok: SharedRStr::from("+OK\r\n"),
+            err: SharedRStr::from("-ERR\r\n"),
+            pong: SharedRStr::from("+PONG\r\n"),
+            queued: SharedRStr::from("+QUEUED\r\n"),
+            czero: SharedRStr::from(":0\r\n"),
+            cone: SharedRStr::from(":1\r\n"),
+            null_bulk: SharedRStr::from("$-1\r\n"),
+            empty_bulk: SharedRStr::from("$0\r\n\r\n"),
+            empty_array: SharedRStr::from("*0\r\n"),
+            integers: (0..SHARED_INTEGERS as i64)
+                .map(|value| SharedRStr::from(RString::from_i64(value)))
+                .collect(),
+        }
+    }
+
+    /// Get the integer `value` shared if it's in range `[0, SHARED_INTEGERS)`.
+    #[inline]
+    pub fn integer(&self, value: i64) -> Option<&SharedRStr> {
+        usize::try_from(value)
+            .ok()
+            .and_then(|value| self.integers.get(value))
+    }
+}
+
+/// Get the shared objects, which are created on the first call.
+///
+/// # Examples
+///
+/// ```
+/// # use rtypes::{shared_objects, SharedRStr};
+///
+/// let ok = shared_objects().ok.clone();
+/// assert_eq!(ok.as_bytes(), b"+OK\r\n");
+/// assert!(SharedRStr::ptr_eq(&ok, &shared_objects().ok));
+/// ```
+#[inline]
+pub fn shared_objects() -> &'static SharedObjects {
+    static SHARED_OBJECTS: OnceLock<SharedObjects> = OnceLock::new();
+    SHARED_OBJECTS.get_or_init(SharedObjects::create)
+}
+
+/// Get the integer `value` shared if it's in range `[0, SHARED_INTEGERS)`, see
+/// `shared_objects`.
+#[inline]
+pub fn shared_integer(value: i64) -> Option<SharedRStr> {
+    shared_objects().integer(value).cloned()
+}
+
+impl SharedRStr {
+    /// Create a string holding `value` formatted in decimal, which is shared WITHOUT
+    /// allocations if it's in range `[0, SHARED_INTEGERS)`.
+    #[inline]
+    pub fn from_i64(value: i64) -> Self {
+        shared_integer(value).unwrap_or_else(|| SharedRStr::from(RString::from_i64(value)))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Intern Pool
+////////////////////////////////////////////////////////////////////////////////
+
+/// InternPool interns strings (such as field names & values repeated frequently), that
+/// strings with the same content are ALL handled with the same `SharedRStr`.
+///
+/// Strings interned are held by the pool, and SHOULD be purged (with `purge`) periodically
+/// once NOT referenced elsewhere.
+///
+/// # Examples
+///
+/// ```
+/// # use rtypes::{InternPool, SharedRStr};
+///
+/// let mut pool = InternPool::new();
+/// let a = pool.intern(b"field");
+/// let b = pool.intern(b"field");
+/// assert!(SharedRStr::ptr_eq(&a, &b));
+/// assert_eq!(pool.len(), 1);
+///
+/// drop((a, b));
+/// assert_eq!(pool.purge(), 1);
+/// assert!(pool.is_empty());
+/// ```
+#[derive(Debug, Default)]
+pub struct InternPool {
+    strings: HashSet<SharedRStr>,
+}
+
+impl InternPool {
+    /// Create an empty pool.
+    #[inline]
+    pub fn new() -> Self {
+        InternPool::default()
+    }
+
+    /// Get number of strings interned.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Check if the pool is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    /// Get the string interned with the content `bytes`, which is interned first if absent.
+    pub fn intern(&mut self, bytes: &[u8]) -> SharedRStr {
+        if let Some(interned) = self.strings.get(bytes) {
+            return interned.clone();
+        }
+
+        let interned = SharedRStr::from(bytes);
+        self.strings.insert(interned.clone());
+        interned
+    }
+
+    /// Get the string interned with the content `bytes` if present.
+    #[inline]
+    pub fn get(&self, bytes: &[u8]) -> Option<SharedRStr> {
+        self.strings.get(bytes).cloned()
+    }
+
+    /// Remove strings interned NOT referenced elsewhere (held by the pool ONLY), and return
+    /// number of strings removed.
+    pub fn purge(&mut self) -> usize {
+        let len = self.strings.len();
+        self.strings.retain(|interned| !interned.is_unique());
+        len - self.strings.len()
+    }
+
+    /// Remove ALL strings interned.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.strings.clear();
+    }
+}
@@ -0,0 +1,359 @@
+use std::convert::TryInto;
+use std::fmt;
+use std::iter::FusedIterator;
+
+use crate::RString;
+
+////////////////////////////////////////////////////////////////////////////////
+// RIntSet
+////////////////////////////////////////////////////////////////////////////////
+
+/// Size of the header, i.e. the encoding (the width of integers) & the length, in `u32` each.
+const HEADER_SIZE: usize = 8;
+
+/// Widths (in bytes) of integers, i.e. Redis `INTSET_ENC_INT16/32/64`.
+const ENC_INT16: usize = 2;
+const ENC_INT32: usize = 4;
+const ENC_INT64: usize = 8;
+
+/// RIntSet is a set of integers (introduced from Redis `intset`), which are sorted in
+/// a single memory block (allocated with `rmem`), with the MIN width (16, 32 or 64 bits)
+/// holding ALL of them.
+///
+/// Integers are looked up (with binary searches) in O(log(n)) time, while inserted &
+/// removed in O(n) time, thus ONLY small sets should be held, in 2 to 8 bytes per integer.
+///
+/// # Notes
+///
+/// The memory block is laid out just like Redis, i.e. `[encoding | length | contents]`
+/// (in little endian), and resized exactly on EACH insertion & removal.
+///
+/// The width is upgraded once an integer NOT fitting in is inserted, and NEVER downgraded.
+///
+/// # Examples
+///
+/// ```
+/// # use rtypes::RIntSet;
+///
+/// let mut set = RIntSet::new();
+/// assert!(set.insert(5));
+/// assert!(set.insert(-3));
+/// assert!(!set.insert(5));
+/// assert_eq!(set.bytes_len(), 8 + 2 * 2);
+///
+/// assert!(set.insert(1 << 40));
+/// assert_eq!(set.bytes_len(), 8 + 3 * 8);
+/// assert_eq!(set.iter().collect::<Vec<_>>(), vec![-3, 5, 1 << 40]);
+/// ```
+#[derive(Clone)]
+pub struct RIntSet {
+    buf: RString,
+}
+
+/// Iterator over integers of an `RIntSet`, in ascending (or descending) order.
+#[derive(Clone)]
+pub struct RIntSetIter<'a> {
+    set: &'a RIntSet,
+    front: usize,
+    back: usize,
+}
+
+/// Get the MIN width holding `value` (introduced from Redis `_intsetValueEncoding`).
+#[inline]
+fn width_of(value: i64) -> usize {
+    if value < i32::MIN as i64 || value > i32::MAX as i64 {
+        ENC_INT64
+    } else if value < i16::MIN as i64 || value > i16::MAX as i64 {
+        ENC_INT32
+    } else {
+        ENC_INT16
+    }
+}
+
+/// Read the integer at `idx` of `bytes` (the memory block) in `width`.
+#[inline]
+fn read_at(bytes: &[u8], width: usize, idx: usize) -> i64 {
+    let int = &bytes[HEADER_SIZE + idx * width..HEADER_SIZE + (idx + 1) * width];
+    match width {
+        ENC_INT16 => i16::from_le_bytes(int.try_into().unwrap()) as i64,
+        ENC_INT32 => i32::from_le_bytes(int.try_into().unwrap()) as i64,
+        _ => i64::from_le_bytes(int.try_into().unwrap()),
+    }
+}
+
+/// Write `value` (fitting in `width`) at `idx` of `bytes` (the memory block) in `width`.
+#[inline]
+fn write_at(bytes: &mut [u8], width: usize, idx: usize, value: i64) {
+    let int = &mut bytes[HEADER_SIZE + idx * width..HEADER_SIZE + (idx + 1) * width];
+    // Low bytes of integers in little endian are the same in ANY wider width.
+    int.copy_from_slice(&value.to_le_bytes()[..width]);
+}
+
+// private methods
+impl RIntSet {
+    // Reads the `u32` field at `offset` of the header.
+    #[inline]
+    fn header_field(&self, offset: usize) -> usize {
+        let field = &self.buf.as_bytes()[offset..offset + 4];
+        u32::from_le_bytes(field.try_into().unwrap()) as usize
+    }
+
+    #[inline]
+    fn width(&self) -> usize {
+        self.header_field(0)
+    }
+
+    // Writes the width & the length into the header.
+    fn set_header(&mut self, width: usize, len: usize) {
+        let len: u32 = len.try_into().expect("intset too large");
+        let bytes = self.buf.as_mut_bytes();
+        bytes[..4].copy_from_slice(&(width as u32).to_le_bytes());
+        bytes[4..HEADER_SIZE].copy_from_slice(&len.to_le_bytes());
+    }
+
+    // Resizes the memory block exactly for `len` integers in `width` (introduced from
+    // Redis `intsetResize`), where integers beyond are dropped.
+    fn resize(&mut self, width: usize, len: usize) {
+        let new_len = HEADER_SIZE + width * len;
+        let old_len = self.buf.len();
+        if new_len > old_len {
+            self.buf.reserve_exact(new_len - old_len);
+            self.buf.append_padding(0, new_len - old_len);
+        } else if new_len < old_len {
+            self.buf.truncate(new_len);
+            self.buf.shrink_to_fit();
+        }
+    }
+
+    // Gets the integer at `idx`, which MUST be in range.
+    #[inline]
+    fn value_at(&self, idx: usize) -> i64 {
+        read_at(self.buf.as_bytes(), self.width(), idx)
+    }
+
+    // Searches `value` (introduced from Redis `intsetSearch`), and gets its index, or
+    // where it would be inserted.
+    fn search(&self, value: i64) -> Result<usize, usize> {
+        let len = self.len();
+        if width_of(value) > self.width() {
+            return Err(if value < 0 { 0 } else { len });
+        }
+        let (mut low, mut high) = (0, len);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match self.value_at(mid).cmp(&value) {
+                std::cmp::Ordering::Less => low = mid + 1,
+                std::cmp::Ordering::Greater => high = mid,
+                std::cmp::Ordering::Equal => return Ok(mid),
+            }
+        }
+        Err(low)
+    }
+
+    // Upgrades the width for `value` NOT fitting in, and inserts it (introduced from Redis
+    // `intsetUpgradeAndAdd`), which is either the MIN or the MAX.
+    fn upgrade_and_insert(&mut self, value: i64) {
+        let (old_width, new_width, len) = (self.width(), width_of(value), self.len());
+        let prepend = (value < 0) as usize;
+        self.resize(new_width, len + 1);
+        let bytes = self.buf.as_mut_bytes();
+        // Moved from the back, thus NO integers are overwritten before moved.
+        for idx in (0..len).rev() {
+            let int = read_at(bytes, old_width, idx);
+            write_at(bytes, new_width, idx + prepend, int);
+        }
+        write_at(bytes, new_width, if prepend == 1 { 0 } else { len }, value);
+        self.set_header(new_width, len + 1);
+    }
+
+    // Removes the integer at `idx`, which MUST be in range.
+    fn remove_unchecked(&mut self, idx: usize) {
+        let (width, len) = (self.width(), self.len());
+        let offset = HEADER_SIZE + idx * width;
+        let bytes = self.buf.as_mut_bytes();
+        bytes.copy_within(offset + width.., offset);
+        self.set_header(width, len - 1);
+        self.resize(width, len - 1);
+    }
+}
+
+// public methods
+impl RIntSet {
+    /// Create an empty set, in 16 bits per integer.
+    #[inline]
+    pub fn new() -> Self {
+        let mut set = RIntSet {
+            buf: RString::with_capacity(HEADER_SIZE),
+        };
+        set.resize(ENC_INT16, 0);
+        set.set_header(ENC_INT16, 0);
+        set
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.header_field(4)
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get number of bytes of the set serialized (introduced from Redis `intsetBlobLen`),
+    /// i.e. the header (the width & the length) & ALL integers.
+    #[inline]
+    pub fn bytes_len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Get the serialized form, i.e. the memory block.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        self.buf.as_bytes()
+    }
+
+    #[inline]
+    pub(crate) fn as_rstr(&self) -> &RString {
+        &self.buf
+    }
+
+    /// Insert `value` (introduced from Redis `intsetAdd`), and return `false` if it's in
+    /// the set already.
+    pub fn insert(&mut self, value: i64) -> bool {
+        let (width, len) = (self.width(), self.len());
+        if width_of(value) > width {
+            self.upgrade_and_insert(value);
+            return true;
+        }
+        let idx = match self.search(value) {
+            Ok(_) => return false,
+            Err(idx) => idx,
+        };
+        self.resize(width, len + 1);
+        let offset = HEADER_SIZE + idx * width;
+        let bytes = self.buf.as_mut_bytes();
+        bytes.copy_within(offset..HEADER_SIZE + len * width, offset + width);
+        write_at(bytes, width, idx, value);
+        self.set_header(width, len + 1);
+        true
+    }
+
+    /// Remove `value` (introduced from Redis `intsetRemove`), and return `false` if it's
+    /// NOT found.
+    pub fn remove(&mut self, value: i64) -> bool {
+        match self.search(value) {
+            Ok(idx) => {
+                self.remove_unchecked(idx);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Remove the integer at `idx` (in ascending order), and return it, or `None` if out
+    /// of range.
+    #[inline]
+    pub fn remove_at(&mut self, idx: usize) -> Option<i64> {
+        let value = self.get(idx)?;
+        self.remove_unchecked(idx);
+        Some(value)
+    }
+
+    #[inline]
+    pub fn contains(&self, value: i64) -> bool {
+        self.search(value).is_ok()
+    }
+
+    /// Get the integer at `idx` (in ascending order), or `None` if out of range.
+    #[inline]
+    pub fn get(&self, idx: usize) -> Option<i64> {
+        match idx < self.len() {
+            true => Some(self.value_at(idx)),
+            false => None,
+        }
+    }
+
+    /// Get the MIN integer.
+    #[inline]
+    pub fn first(&self) -> Option<i64> {
+        self.get(0)
+    }
+
+    /// Get the MAX integer.
+    #[inline]
+    pub fn last(&self) -> Option<i64> {
+        self.get(self.len().checked_sub(1)?)
+    }
+
+    /// Get an iterator over integers, in ascending order.
+    #[inline]
+    pub fn iter(&self) -> RIntSetIter<'_> {
+        RIntSetIter {
+            set: self,
+            front: 0,
+            back: self.len(),
+        }
+    }
+}
+
+impl Default for RIntSet {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for RIntSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Iterators
+////////////////////////////////////////////////////////////////////////////////
+
+impl<'a> IntoIterator for &'a RIntSet {
+    type Item = i64;
+    type IntoIter = RIntSetIter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> Iterator for RIntSetIter<'a> {
+    type Item = i64;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.set.value_at(self.front - 1))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<'a> DoubleEndedIterator for RIntSetIter<'a> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.set.value_at(self.back))
+    }
+}
+
+impl<'a> ExactSizeIterator for RIntSetIter<'a> {}
+
+impl<'a> FusedIterator for RIntSetIter<'a> {}
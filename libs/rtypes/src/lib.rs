@@ -1,5 +1,79 @@
+mod bitmap;
+mod encoding;
+mod geo;
+mod geohash;
+mod hash;
+mod intern;
+mod intset;
+mod listpack;
+mod lzf;
+mod mem;
+mod num;
+mod object;
+mod quicklist;
+mod rand;
+mod rax;
+mod rdict;
 mod rlist;
+mod rstr;
+mod rstream;
 mod rstring;
+mod set;
+mod shared;
+mod siphash;
+mod skiplist;
+mod stream;
+mod utf8;
+mod zset;
 
-pub use rlist::RList;
+pub use bitmap::{BitFieldOverflow, BitFieldType, BitOp, RBitmap};
+pub use encoding::{Encoding, EncodingLimits};
+pub use geo::{GeoError, GeoPoint, GeoUnit, RGeo};
+pub use geohash::{geo_distance, GeoArea, GeoHash, GeoShape};
+pub use geohash::{GEO_LAT_MAX, GEO_LAT_MIN, GEO_LONG_MAX, GEO_LONG_MIN, GEO_STEP_MAX};
+pub use hash::{RHash, RHashIter};
+pub use intern::InternPool;
+pub use intern::{shared_integer, shared_objects, SharedObjects, SHARED_INTEGERS};
+pub use intset::{RIntSet, RIntSetIter};
+pub use listpack::{ListPackValue, RListPack, RListPackIter};
+pub use mem::MemSized;
+pub use num::ParseNumError;
+pub use object::{lru_clock, ObjectType, RObject};
+pub use object::{LFU_INIT_VAL, LRU_CLOCK_MAX, LRU_CLOCK_RESOLUTION, OBJ_SHARED_REFCOUNT};
+pub use quicklist::{RQuickList, RQuickListIter, QUICKLIST_DEFAULT_FILL, QUICKLIST_MAX_FILL};
+pub use rax::{RRax, RRaxIter};
+pub use rdict::{RDict, RDictIter, RDictIterMut, RDICT_INITIAL_SIZE};
+pub use rlist::{
+    CursorMut, End, MaxLen, RList, RListDrain, RListIntoIter, RListIter, ReadyNotifier,
+};
+pub use rstr::RStr;
+pub use rstream::{
+    AutoClaim, RStream, RStreamIter, StreamConsumer, StreamEntry, StreamError, StreamFieldsIter,
+    StreamGroup, StreamId, StreamNack, StreamTrim, XAddId,
+};
 pub use rstring::RString;
+pub use set::{RSet, RSetIter};
+pub use shared::SharedRStr;
+pub use siphash::{SipBuildHasher, SipHasher13};
+pub use skiplist::{RSkipList, RSkipListIter};
+pub use stream::RStringReader;
+pub use utf8::Utf8RString;
+pub use zset::{RZSet, RZSetIter, ZAddFlags, ZAddOutcome};
+
+/// Create an `RString` holding formatted content, just like `format!` does (WITHOUT
+/// an intermediate `String`), see `RString::from_fmt`.
+///
+/// # Examples
+///
+/// ```
+/// # use rtypes::rformat;
+///
+/// let reply = rformat!("${}\r\n{}\r\n", 5, "hello");
+/// assert_eq!(reply.as_bytes(), b"$5\r\nhello\r\n");
+/// ```
+#[macro_export]
+macro_rules! rformat {
+    ($($arg: tt)*) => {
+        $crate::RString::from_fmt(format_args!($($arg)*))
+    };
+}
@@ -0,0 +1,714 @@
+use std::convert::TryInto;
+use std::fmt;
+use std::iter::FusedIterator;
+
+use crate::num::{format_i64, parse_i64, I64_MAX_LEN};
+use crate::rstring::write_repr;
+use crate::RString;
+
+////////////////////////////////////////////////////////////////////////////////
+// RListPack
+////////////////////////////////////////////////////////////////////////////////
+
+// Entries are packed back to back in a single `RString` (introduced from Redis `listpack`),
+// with a header & a terminator:
+//
+//     <total-bytes> <num-elements> <entry> ... <entry> <end>
+//
+// - `total-bytes`: number of bytes of the listpack, as a 32-bit little-endian integer.
+// - `num-elements`: number of entries, as a 16-bit little-endian integer, which is
+//   `NUM_ELEMENTS_UNKNOWN` if there're too many entries to count in the header.
+// - `end`: the byte `EOF`.
+//
+// Each entry is encoded as:
+//
+//     <encoding> <data> <backlen>
+//
+// - `encoding`: type of the entry (with length of the string, or the integer itself if
+//   it's small enough), see `ENC_*`.
+// - `backlen`: length of `<encoding> <data>`, as a LEB128 varint stored in the reverse
+//   order, thus it's parsed from its last byte, that entries can be walked from the back.
+
+/// Size of the header, i.e. `<total-bytes> <num-elements>`.
+const HEADER_SIZE: usize = 6;
+/// Number of entries in the header when there're too many to count (introduced from Redis
+/// `LP_HDR_NUMELE_UNKNOWN`), thus they're counted by walking through.
+const NUM_ELEMENTS_UNKNOWN: u16 = u16::MAX;
+/// The terminator.
+const EOF: u8 = 0xff;
+
+/// `0xxxxxxx`: integer in [0, 127].
+const ENC_7BIT_UINT: u8 = 0x00;
+/// `10xxxxxx`: string of at most 63 bytes.
+const ENC_6BIT_STR: u8 = 0x80;
+/// `110xxxxx xxxxxxxx`: integer in [-4096, 4095].
+const ENC_13BIT_INT: u8 = 0xc0;
+/// `1110xxxx xxxxxxxx`: string of at most 4095 bytes.
+const ENC_12BIT_STR: u8 = 0xe0;
+/// `11110000`, then the length in 4 bytes.
+const ENC_32BIT_STR: u8 = 0xf0;
+/// `11110001` to `11110100`, then the integer in 2, 3, 4 or 8 bytes.
+const ENC_16BIT_INT: u8 = 0xf1;
+const ENC_24BIT_INT: u8 = 0xf2;
+const ENC_32BIT_INT: u8 = 0xf3;
+const ENC_64BIT_INT: u8 = 0xf4;
+
+/// Get number of bytes of `value` encoded as a varint.
+#[inline]
+fn varint_len(mut value: usize) -> usize {
+    let mut len = 1;
+    while value >= 0x80 {
+        value >>= 7;
+        len += 1;
+    }
+    len
+}
+
+/// Decode a reversed varint from the tail of `bytes`, and return the value & number of
+/// bytes read, or `None` if truncated.
+#[inline]
+fn read_backlen(bytes: &[u8]) -> Option<(usize, usize)> {
+    let mut value = 0;
+    for (pos, &byte) in bytes.iter().rev().enumerate().take(5) {
+        value |= ((byte & 0x7f) as usize) << (7 * pos);
+        if byte & 0x80 == 0 {
+            return Some((value, pos + 1));
+        }
+    }
+    None
+}
+
+/// Encode `value` as a reversed varint into the head of `buf`.
+#[inline]
+fn write_backlen(value: usize, buf: &mut [u8]) {
+    let len = varint_len(value);
+    for (pos, byte) in buf[..len].iter_mut().rev().enumerate() {
+        *byte = (value >> (7 * pos)) as u8 & 0x7f;
+        if pos + 1 < len {
+            *byte |= 0x80;
+        }
+    }
+}
+
+/// Get number of bytes of the integer encoded (WITHOUT `backlen`).
+#[inline]
+fn int_len(value: i64) -> usize {
+    match value {
+        0..=127 => 1,
+        -4096..=4095 => 2,
+        -0x8000..=0x7fff => 3,
+        -0x80_0000..=0x7f_ffff => 4,
+        -0x8000_0000..=0x7fff_ffff => 5,
+        _ => 9,
+    }
+}
+
+/// Get number of bytes of the string encoded (WITHOUT `backlen`).
+#[inline]
+fn str_len(len: usize) -> usize {
+    match len {
+        0..=63 => 1 + len,
+        64..=4095 => 2 + len,
+        _ => 5 + len,
+    }
+}
+
+/// Value of an entry to encode, where strings of integers (in the canonical form) are
+/// encoded as integers.
+#[derive(Clone, Copy)]
+enum Encoded<'a> {
+    Int(i64),
+    Str(&'a [u8]),
+}
+
+impl<'a> Encoded<'a> {
+    #[inline]
+    fn new(data: &'a [u8]) -> Self {
+        match data.len() <= I64_MAX_LEN {
+            true => parse_i64(data).map_or(Encoded::Str(data), Encoded::Int),
+            false => Encoded::Str(data),
+        }
+    }
+
+    /// Get number of bytes of `<encoding> <data>`.
+    #[inline]
+    fn body_len(self) -> usize {
+        match self {
+            Encoded::Int(value) => int_len(value),
+            Encoded::Str(data) => str_len(data.len()),
+        }
+    }
+
+    /// Get number of bytes of the entry.
+    #[inline]
+    fn entry_len(self) -> usize {
+        let body = self.body_len();
+        body + varint_len(body)
+    }
+
+    /// Encode the entry into the head of `buf`, which MUST be long enough.
+    fn write(self, buf: &mut [u8]) {
+        let body = self.body_len();
+        match self {
+            Encoded::Int(value) => {
+                let bytes = value.to_le_bytes();
+                match body {
+                    1 => buf[0] = ENC_7BIT_UINT | value as u8,
+                    2 => {
+                        let value = (value as u16) & 0x1fff;
+                        buf[0] = ENC_13BIT_INT | (value >> 8) as u8;
+                        buf[1] = value as u8;
+                    }
+                    _ => {
+                        buf[0] = match body {
+                            3 => ENC_16BIT_INT,
+                            4 => ENC_24BIT_INT,
+                            5 => ENC_32BIT_INT,
+                            _ => ENC_64BIT_INT,
+                        };
+                        buf[1..body].copy_from_slice(&bytes[..body - 1]);
+                    }
+                }
+            }
+            Encoded::Str(data) => {
+                let len = data.len();
+                let header = body - len;
+                match header {
+                    1 => buf[0] = ENC_6BIT_STR | len as u8,
+                    2 => {
+                        buf[0] = ENC_12BIT_STR | (len >> 8) as u8;
+                        buf[1] = len as u8;
+                    }
+                    _ => {
+                        buf[0] = ENC_32BIT_STR;
+                        buf[1..5].copy_from_slice(&(len as u32).to_le_bytes());
+                    }
+                }
+                buf[header..body].copy_from_slice(data);
+            }
+        }
+        write_backlen(body, &mut buf[body..]);
+    }
+}
+
+/// Decode the entry at the head of `bytes`, and return its value & number of bytes of
+/// `<encoding> <data>`, or `None` if it's the terminator, truncated or corrupted.
+fn decode_entry(bytes: &[u8]) -> Option<(ListPackValue<'_>, usize)> {
+    let encoding = *bytes.first()?;
+    let (value, body) = match encoding {
+        _ if encoding & 0x80 == ENC_7BIT_UINT => (ListPackValue::Int(encoding as i64), 1),
+        _ if encoding & 0xc0 == ENC_6BIT_STR => {
+            let len = (encoding & 0x3f) as usize;
+            (ListPackValue::Str(bytes.get(1..1 + len)?), 1 + len)
+        }
+        _ if encoding & 0xe0 == ENC_13BIT_INT => {
+            let value = ((encoding & 0x1f) as i64) << 8 | *bytes.get(1)? as i64;
+            // Sign extended from 13 bits.
+            (ListPackValue::Int((value << 51) >> 51), 2)
+        }
+        _ if encoding & 0xf0 == ENC_12BIT_STR => {
+            let len = ((encoding & 0x0f) as usize) << 8 | *bytes.get(1)? as usize;
+            (ListPackValue::Str(bytes.get(2..2 + len)?), 2 + len)
+        }
+        ENC_32BIT_STR => {
+            let len = u32::from_le_bytes(bytes.get(1..5)?.try_into().ok()?) as usize;
+            (
+                ListPackValue::Str(bytes.get(5..5usize.checked_add(len)?)?),
+                5 + len,
+            )
+        }
+        ENC_16BIT_INT..=ENC_64BIT_INT => {
+            let size = match encoding {
+                ENC_16BIT_INT => 2,
+                ENC_24BIT_INT => 3,
+                ENC_32BIT_INT => 4,
+                _ => 8,
+            };
+            let mut word = [0u8; 8];
+            word[..size].copy_from_slice(bytes.get(1..1 + size)?);
+            // Sign extended from `size` bytes.
+            let shift = 64 - 8 * size;
+            let value = (i64::from_le_bytes(word) << shift) >> shift;
+            (ListPackValue::Int(value), 1 + size)
+        }
+        _ => return None,
+    };
+    Some((value, body))
+}
+
+/// Value of an entry of an `RListPack`, which is an integer if encoded as an integer,
+/// or a string borrowed from the listpack otherwise.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ListPackValue<'a> {
+    Int(i64),
+    Str(&'a [u8]),
+}
+
+impl<'a> ListPackValue<'a> {
+    /// Call `f` with bytes of the value, where integers are formatted in decimal.
+    #[inline]
+    pub fn with_bytes<R, F>(self, f: F) -> R
+    where
+        F: FnOnce(&[u8]) -> R,
+    {
+        match self {
+            ListPackValue::Int(value) => {
+                let mut buf = [0u8; I64_MAX_LEN];
+                let start = format_i64(value, &mut buf);
+                f(&buf[start..])
+            }
+            ListPackValue::Str(data) => f(data),
+        }
+    }
+
+    /// Copy the value out as a string, where integers are formatted in decimal.
+    #[inline]
+    pub fn to_rstring(self) -> RString {
+        match self {
+            ListPackValue::Int(value) => RString::from_i64(value),
+            ListPackValue::Str(data) => RString::from_bytes(data),
+        }
+    }
+
+    /// Get the integer value, parsing strings strictly, or `None` if NOT an integer.
+    #[inline]
+    pub fn as_i64(self) -> Option<i64> {
+        match self {
+            ListPackValue::Int(value) => Some(value),
+            ListPackValue::Str(data) => parse_i64(data).ok(),
+        }
+    }
+
+    /// Check if the value equals `data` (introduced from Redis `lpCompare`), where integers
+    /// are compared in decimal.
+    #[inline]
+    pub fn eq_bytes(self, data: &[u8]) -> bool {
+        match self {
+            ListPackValue::Int(value) => parse_i64(data) == Ok(value),
+            ListPackValue::Str(bytes) => bytes == data,
+        }
+    }
+}
+
+impl<'a> fmt::Debug for ListPackValue<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ListPackValue::Int(value) => write!(f, "{}", value),
+            ListPackValue::Str(data) => write_repr(f, data),
+        }
+    }
+}
+
+/// RListPack is a list of entries packed in a single memory block (introduced from Redis
+/// `listpack`), which is the compact encoding of small hashes, sets & sorted sets, and
+/// nodes of `RQuickList`.
+///
+/// Entries are encoded in variable lengths, where strings of integers take 1 to 9 bytes,
+/// and short strings take 2 bytes of overhead (rather than pointers of list nodes), while
+/// they're walked from either end. The memory block is the serialized form as is.
+///
+/// # Notes
+///
+/// Entries are inserted & removed in O(n) time (as the memory block is moved), thus ONLY
+/// small lists should be packed.
+///
+/// # Examples
+///
+/// ```
+/// # use rtypes::{ListPackValue, RListPack, RString};
+///
+/// let mut lp = RListPack::new();
+/// lp.push_back(b"hello");
+/// lp.push_back(b"1024");
+/// lp.push_front(b"-1");
+/// assert_eq!(lp.len(), 3);
+/// assert_eq!(lp.get(1), Some(ListPackValue::Str(b"hello")));
+/// assert_eq!(lp.get(2), Some(ListPackValue::Int(1024)));
+///
+/// lp.replace(0, b"world");
+/// let entries: Vec<_> = lp.iter().rev().map(|value| value.to_rstring()).collect();
+/// assert_eq!(entries[..2], [RString::from_str("1024"), RString::from_str("hello")]);
+/// assert_eq!(lp.get(0), Some(ListPackValue::Str(b"world")));
+/// ```
+pub struct RListPack {
+    buf: RString,
+}
+
+/// Iterator over entries of an `RListPack`, from the front or the back.
+#[derive(Clone)]
+pub struct RListPackIter<'a> {
+    /// Bytes of entries NOT iterated over yet.
+    bytes: &'a [u8],
+    remaining: usize,
+}
+
+// private methods
+impl RListPack {
+    #[inline]
+    fn num_elements(&self) -> u16 {
+        let bytes = self.buf.as_bytes();
+        u16::from_le_bytes([bytes[4], bytes[5]])
+    }
+
+    // Updates `num-elements` of the header, which is unknown if there're too many.
+    #[inline]
+    fn set_len(&mut self, len: usize) {
+        let count = std::cmp::min(len, NUM_ELEMENTS_UNKNOWN as usize) as u16;
+        self.buf.as_mut_bytes()[4..HEADER_SIZE].copy_from_slice(&count.to_le_bytes());
+    }
+
+    // Updates `total-bytes` of the header after the memory block is resized.
+    #[inline]
+    fn update_total_bytes(&mut self) {
+        let total: u32 = self.buf.len().try_into().expect("listpack too large");
+        self.buf.as_mut_bytes()[..4].copy_from_slice(&total.to_le_bytes());
+    }
+
+    // Gets bytes of ALL entries, i.e. WITHOUT the header & the terminator.
+    #[inline]
+    fn entries(&self) -> &[u8] {
+        let bytes = self.buf.as_bytes();
+        &bytes[HEADER_SIZE..bytes.len() - 1]
+    }
+
+    // Gets the offset of the entry at `idx` (or the terminator if `idx` is the length),
+    // walking from the nearer end, which MUST be in range.
+    fn offset_of(&self, idx: usize, len: usize) -> usize {
+        let mut iter = RListPackIter {
+            bytes: self.entries(),
+            remaining: len,
+        };
+        if idx <= len / 2 {
+            for _ in 0..idx {
+                iter.next_entry();
+            }
+            self.buf.len() - 1 - iter.bytes.len()
+        } else {
+            for _ in idx..len {
+                iter.next_back_entry();
+            }
+            HEADER_SIZE + iter.bytes.len()
+        }
+    }
+
+    // Replaces `old_len` bytes of the entry at `offset` with `entry`.
+    fn splice(&mut self, offset: usize, old_len: usize, entry: Option<Encoded<'_>>) {
+        let new_len = entry.map_or(0, Encoded::entry_len);
+        let total = self.buf.len();
+        if new_len > old_len {
+            self.buf.append_padding(0, new_len - old_len);
+        }
+        let bytes = self.buf.as_mut_bytes();
+        bytes.copy_within(offset + old_len..total, offset + new_len);
+        if let Some(entry) = entry {
+            entry.write(&mut bytes[offset..]);
+        }
+        if new_len < old_len {
+            self.buf.truncate(total - (old_len - new_len));
+        }
+        self.update_total_bytes();
+    }
+
+    // Decodes the entry at `offset`, and gets its value & number of bytes.
+    #[inline]
+    fn entry_at(&self, offset: usize) -> (ListPackValue<'_>, usize) {
+        let (value, body) =
+            decode_entry(&self.buf.as_bytes()[offset..]).expect("listpack corrupted");
+        (value, body + varint_len(body))
+    }
+}
+
+// public methods
+impl RListPack {
+    /// Create an empty listpack.
+    pub fn new() -> Self {
+        let mut buf = RString::with_capacity(HEADER_SIZE + 1);
+        buf.append_padding(0, HEADER_SIZE);
+        buf.push(EOF);
+        let mut lp = RListPack { buf };
+        lp.update_total_bytes();
+        lp
+    }
+
+    /// Create a listpack from its serialized form, which is validated (introduced from
+    /// Redis `lpValidateIntegrity`), or `None` if it's corrupted.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < HEADER_SIZE + 1 || bytes.last() != Some(&EOF) {
+            return None;
+        }
+        let total = u32::from_le_bytes(bytes[..4].try_into().ok()?);
+        if total as usize != bytes.len() {
+            return None;
+        }
+
+        let mut entries = &bytes[HEADER_SIZE..bytes.len() - 1];
+        let mut count = 0usize;
+        while !entries.is_empty() {
+            let (_, body) = decode_entry(entries)?;
+            let backlen = varint_len(body);
+            let size = body.checked_add(backlen)?;
+            if read_backlen(entries.get(..size)?) != Some((body, backlen)) {
+                return None;
+            }
+            entries = &entries[size..];
+            count += 1;
+        }
+        let num_elements = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if num_elements != NUM_ELEMENTS_UNKNOWN && num_elements as usize != count {
+            return None;
+        }
+        Some(RListPack {
+            buf: RString::from_bytes(bytes),
+        })
+    }
+
+    /// Create a listpack from its serialized form trusted (such as decompressed).
+    #[inline]
+    pub(crate) fn from_raw(buf: RString) -> Self {
+        RListPack { buf }
+    }
+
+    /// Get the serialized form, i.e. the memory block.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        self.buf.as_bytes()
+    }
+
+    #[inline]
+    pub(crate) fn as_rstr(&self) -> &RString {
+        &self.buf
+    }
+
+    /// Get number of entries (introduced from Redis `lpLength`), where entries are walked
+    /// through if there're too many to count in the header.
+    #[inline]
+    pub fn len(&self) -> usize {
+        match self.num_elements() {
+            NUM_ELEMENTS_UNKNOWN => RListPackIter {
+                bytes: self.entries(),
+                remaining: usize::MAX,
+            }
+            .count(),
+            count => count as usize,
+        }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.buf.len() == HEADER_SIZE + 1
+    }
+
+    /// Get number of bytes of the memory block (introduced from Redis `lpBytes`).
+    #[inline]
+    pub fn bytes_len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Insert `data` at `idx` (introduced from Redis `lpInsertString`), where integers (in
+    /// the canonical form) are encoded as integers.
+    ///
+    /// # Panics
+    ///
+    /// It will panic if `idx` is greater than the length.
+    pub fn insert(&mut self, idx: usize, data: &[u8]) {
+        let len = self.len();
+        assert!(idx <= len, "listpack index {} out of range", idx);
+        let offset = self.offset_of(idx, len);
+        self.splice(offset, 0, Some(Encoded::new(data)));
+        self.set_len(len + 1);
+    }
+
+    #[inline]
+    pub fn push_front(&mut self, data: &[u8]) {
+        self.insert(0, data);
+    }
+
+    #[inline]
+    pub fn push_back(&mut self, data: &[u8]) {
+        let len = self.len();
+        self.insert(len, data);
+    }
+
+    /// Remove the entry at `idx` (introduced from Redis `lpDelete`), and return it, or
+    /// `None` if out of range.
+    pub fn remove(&mut self, idx: usize) -> Option<RString> {
+        let len = self.len();
+        if idx >= len {
+            return None;
+        }
+        let offset = self.offset_of(idx, len);
+        let (value, old_len) = self.entry_at(offset);
+        let data = value.to_rstring();
+        self.splice(offset, old_len, None);
+        self.set_len(len - 1);
+        Some(data)
+    }
+
+    /// Remove (at most) `count` entries from `idx` at once (introduced from Redis
+    /// `lpDeleteRange`), and return number of entries removed.
+    pub fn remove_range(&mut self, idx: usize, count: usize) -> usize {
+        let len = self.len();
+        if idx >= len {
+            return 0;
+        }
+        let count = std::cmp::min(count, len - idx);
+        let offset = self.offset_of(idx, len);
+        let mut end = offset;
+        for _ in 0..count {
+            end += self.entry_at(end).1;
+        }
+        self.splice(offset, end - offset, None);
+        self.set_len(len - count);
+        count
+    }
+
+    /// Replace the entry at `idx` with `data` (introduced from Redis `lpReplace`), and return
+    /// `false` if out of range.
+    pub fn replace(&mut self, idx: usize, data: &[u8]) -> bool {
+        let len = self.len();
+        if idx >= len {
+            return false;
+        }
+        let offset = self.offset_of(idx, len);
+        let (_, old_len) = self.entry_at(offset);
+        self.splice(offset, old_len, Some(Encoded::new(data)));
+        true
+    }
+
+    #[inline]
+    pub fn pop_front(&mut self) -> Option<RString> {
+        self.remove(0)
+    }
+
+    #[inline]
+    pub fn pop_back(&mut self) -> Option<RString> {
+        self.remove(self.len().checked_sub(1)?)
+    }
+
+    /// Remove ALL entries.
+    #[inline]
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Get the entry at `idx`, walking from the nearer end, or `None` if out of range.
+    pub fn get(&self, idx: usize) -> Option<ListPackValue<'_>> {
+        let len = self.len();
+        if idx >= len {
+            None
+        } else if idx <= len / 2 {
+            self.iter().nth(idx)
+        } else {
+            self.iter().nth_back(len - 1 - idx)
+        }
+    }
+
+    /// Get the index of the first entry equal to `data` (introduced from Redis `lpFind`),
+    /// or `None` if NOT found.
+    #[inline]
+    pub fn find(&self, data: &[u8]) -> Option<usize> {
+        self.iter().position(|value| value.eq_bytes(data))
+    }
+
+    #[inline]
+    pub fn iter(&self) -> RListPackIter<'_> {
+        RListPackIter {
+            bytes: self.entries(),
+            remaining: self.len(),
+        }
+    }
+}
+
+/// Get number of bytes of an entry holding `data` encoded.
+#[inline]
+pub(crate) fn entry_size(data: &[u8]) -> usize {
+    Encoded::new(data).entry_len()
+}
+
+impl Default for RListPack {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clone for RListPack {
+    #[inline]
+    fn clone(&self) -> Self {
+        RListPack {
+            buf: self.buf.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for RListPack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Iterators
+////////////////////////////////////////////////////////////////////////////////
+
+impl<'a> RListPackIter<'a> {
+    /// Get the next entry & its size encoded.
+    fn next_entry(&mut self) -> Option<(ListPackValue<'a>, usize)> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let (value, body) = decode_entry(self.bytes)?;
+        let size = body + varint_len(body);
+        self.bytes = &self.bytes[size..];
+        self.remaining -= 1;
+        Some((value, size))
+    }
+
+    /// Get the next entry from the back & its size encoded.
+    fn next_back_entry(&mut self) -> Option<(ListPackValue<'a>, usize)> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let (body, backlen) = read_backlen(self.bytes)?;
+        let start = self.bytes.len() - backlen - body;
+        let (value, _) = decode_entry(&self.bytes[start..])?;
+        self.bytes = &self.bytes[..start];
+        self.remaining -= 1;
+        Some((value, body + backlen))
+    }
+}
+
+impl<'a> IntoIterator for &'a RListPack {
+    type Item = ListPackValue<'a>;
+    type IntoIter = RListPackIter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> Iterator for RListPackIter<'a> {
+    type Item = ListPackValue<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_entry().map(|(value, _)| value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a> DoubleEndedIterator for RListPackIter<'a> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.next_back_entry().map(|(value, _)| value)
+    }
+}
+
+impl<'a> ExactSizeIterator for RListPackIter<'a> {}
+
+impl<'a> FusedIterator for RListPackIter<'a> {}
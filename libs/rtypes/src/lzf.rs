@@ -0,0 +1,196 @@
+use crate::RString;
+
+////////////////////////////////////////////////////////////////////////////////
+// LZF Compression
+////////////////////////////////////////////////////////////////////////////////
+
+// LZF (introduced from liblzf, as Redis adopts for RDB strings & quicklist nodes) encodes
+// data as a sequence of:
+//
+// - Literal runs: `000LLLLL` followed by `L + 1` literal bytes (1 to 32).
+// - Back references: `LLLOOOOO [LLLLLLLL] OOOOOOOO`, that `len + 2` bytes are copied from
+//   `offset + 1` bytes back, where the length is extended by the optional byte if `LLL` is 7.
+
+/// Log2 of entries in the hash table of compression.
+const HASH_LOG: usize = 14;
+const HASH_SIZE: usize = 1 << HASH_LOG;
+
+const MAX_LIT: usize = 1 << 5;
+const MAX_OFF: usize = 1 << 13;
+const MAX_REF: usize = (1 << 8) + (1 << 3);
+
+impl RString {
+    /// Compress the content with LZF, where `None` is returned if the result is NOT
+    /// smaller than the content (thus it SHOULD be stored as is).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RString;
+    ///
+    /// let s = RString::from_str(&"abc".repeat(100));
+    /// let compressed = s.compress_lzf().unwrap();
+    /// assert!(compressed.len() < s.len());
+    /// assert_eq!(compressed.decompress_lzf(s.len()), Some(s));
+    ///
+    /// assert_eq!(RString::from_str("abc").compress_lzf(), None);
+    /// ```
+    pub fn compress_lzf(&self) -> Option<RString> {
+        if self.len() <= 1 {
+            return None;
+        }
+
+        let mut compressed = RString::with_capacity(self.len() - 1);
+        compressed.append_padding(0, self.len() - 1);
+        let len = lzf_compress(self.as_bytes(), compressed.as_mut_bytes())?;
+        compressed.truncate(len);
+        Some(compressed)
+    }
+
+    /// Decompress the content compressed with LZF, which MUST be decompressed to exactly
+    /// `expected_len` bytes, otherwise (or if it's corrupted) `None` is returned.
+    pub fn decompress_lzf(&self, expected_len: usize) -> Option<RString> {
+        let mut decompressed = RString::with_capacity(expected_len);
+        decompressed.append_padding(0, expected_len);
+        match lzf_decompress(self.as_bytes(), decompressed.as_mut_bytes()) {
+            Some(len) if len == expected_len => Some(decompressed),
+            _ => None,
+        }
+    }
+}
+
+#[inline]
+fn hash_at(input: &[u8], pos: usize) -> usize {
+    let value =
+        (input[pos] as usize) << 16 | (input[pos + 1] as usize) << 8 | input[pos + 2] as usize;
+    ((value >> (24 - HASH_LOG)).wrapping_sub(value.wrapping_mul(5))) & (HASH_SIZE - 1)
+}
+
+/// Compress `input` into `output`, and return number of bytes compressed, or `None`
+/// if `input` is empty or `output` is too small.
+pub(crate) fn lzf_compress(input: &[u8], output: &mut [u8]) -> Option<usize> {
+    let (in_len, out_len) = (input.len(), output.len());
+    if in_len == 0 || out_len == 0 {
+        return None;
+    }
+
+    // Positions (plus 1, that ZERO is empty) of 3-byte sequences last seen.
+    let mut table = vec![0usize; HASH_SIZE];
+    // The first byte is reserved for the length of the literal run.
+    let (mut ip, mut op, mut lit) = (0usize, 1usize, 0usize);
+
+    while ip < in_len {
+        // References are looked up ONLY if 3 bytes are left at least.
+        let reference = match ip + 2 < in_len {
+            true => std::mem::replace(&mut table[hash_at(input, ip)], ip + 1),
+            false => 0,
+        };
+
+        if reference != 0 {
+            let reference = reference - 1;
+            let off = ip - reference - 1;
+            if off < MAX_OFF
+                && ip + 4 < in_len
+                && input[reference..reference + 3] == input[ip..ip + 3]
+            {
+                // At most 3 bytes of the reference & 1 byte of the next literal run.
+                if op - (lit == 0) as usize + 3 + 1 >= out_len {
+                    return None;
+                }
+                // Close the literal run (or drop it if empty).
+                match lit {
+                    0 => op -= 1,
+                    _ => output[op - lit - 1] = (lit - 1) as u8,
+                }
+
+                let max_len = std::cmp::min(in_len - ip - 2, MAX_REF);
+                let mut len = 3;
+                while len < max_len && input[reference + len] == input[ip + len] {
+                    len += 1;
+                }
+
+                let code = len - 2;
+                if code < 7 {
+                    output[op] = ((off >> 8) + (code << 5)) as u8;
+                    op += 1;
+                } else {
+                    output[op] = ((off >> 8) + (7 << 5)) as u8;
+                    output[op + 1] = (code - 7) as u8;
+                    op += 2;
+                }
+                output[op] = off as u8;
+                // Reserve the length of the next literal run.
+                op += 2;
+                lit = 0;
+
+                ip += len;
+                // Hash the last 2 positions matched for further references.
+                if ip + 2 < in_len {
+                    for pos in ip - 2..ip {
+                        table[hash_at(input, pos)] = pos + 1;
+                    }
+                }
+                continue;
+            }
+        }
+
+        if op >= out_len {
+            return None;
+        }
+        output[op] = input[ip];
+        op += 1;
+        ip += 1;
+        lit += 1;
+        if lit == MAX_LIT {
+            output[op - lit - 1] = (MAX_LIT - 1) as u8;
+            op += 1;
+            lit = 0;
+        }
+    }
+
+    match lit {
+        0 => op -= 1,
+        _ => output[op - lit - 1] = (lit - 1) as u8,
+    }
+    Some(op)
+}
+
+/// Decompress `input` into `output`, and return number of bytes decompressed, or `None`
+/// if `input` is corrupted or `output` is too small.
+pub(crate) fn lzf_decompress(input: &[u8], output: &mut [u8]) -> Option<usize> {
+    let (mut ip, mut op) = (0usize, 0usize);
+
+    while ip < input.len() {
+        let ctrl = input[ip] as usize;
+        ip += 1;
+
+        if ctrl < MAX_LIT {
+            let len = ctrl + 1;
+            if ip + len > input.len() || op + len > output.len() {
+                return None;
+            }
+            output[op..op + len].copy_from_slice(&input[ip..ip + len]);
+            ip += len;
+            op += len;
+        } else {
+            let mut len = ctrl >> 5;
+            if len == 7 {
+                len += *input.get(ip)? as usize;
+                ip += 1;
+            }
+            len += 2;
+
+            let back = ((ctrl & 0x1f) << 8) + *input.get(ip)? as usize + 1;
+            ip += 1;
+            if back > op || op + len > output.len() {
+                return None;
+            }
+            // References MAYBE overlap with bytes being copied (such as repeated bytes).
+            for pos in op..op + len {
+                output[pos] = output[pos - back];
+            }
+            op += len;
+        }
+    }
+    Some(op)
+}
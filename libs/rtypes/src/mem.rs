@@ -0,0 +1,106 @@
+use rmem::{size_of, size_of_aligned, SYS_ALIGN_SIZE, ZMEM_BODY_ALIGN};
+
+use crate::{RIntSet, RStr, RString, SharedRStr};
+
+////////////////////////////////////////////////////////////////////////////////
+// Memory Usage
+////////////////////////////////////////////////////////////////////////////////
+
+/// MIN granularity of memory blocks allocated (by ALL common allocators on 64-bit OS),
+/// that sizes are rounded up to it.
+const MALLOC_ALIGN_SIZE: usize = 2 * SYS_ALIGN_SIZE;
+
+/// Size of the ZMEM header (with length) of `RString`, i.e. the length & the capacity.
+const RSTRING_HEADER_SIZE: usize = size_of_aligned(2 * size_of::<usize>(), ZMEM_BODY_ALIGN);
+
+/// Estimate number of bytes of a memory block allocated for `size` bytes, including
+/// the allocator overhead (rounded up to the allocation granularity).
+#[inline]
+pub(crate) const fn alloc_size(size: usize) -> usize {
+    size_of_aligned(size, MALLOC_ALIGN_SIZE)
+}
+
+/// MemSized estimates memory owned by values on the heap (introduced from Redis
+/// `objectComputeSize`), such as payloads of elements held by collections, for memory
+/// introspection (MEMORY USAGE) & eviction cost estimates.
+///
+/// # Examples
+///
+/// ```
+/// # use rtypes::{MemSized, RString};
+///
+/// assert_eq!(42u64.heap_size(), 0);
+/// assert!(RString::from_str("hello").heap_size() > 5);
+/// ```
+pub trait MemSized {
+    /// Get number of bytes of memory owned on the heap, EXCLUDING the value itself
+    /// (i.e. `size_of::<Self>()`, counted by its owner).
+    fn heap_size(&self) -> usize;
+}
+
+macro_rules! impl_mem_sized_inline {
+    ($($ty: ty),*) => {
+        $(
+            impl MemSized for $ty {
+                #[inline]
+                fn heap_size(&self) -> usize {
+                    0
+                }
+            }
+        )*
+    };
+}
+
+impl_mem_sized_inline!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+impl_mem_sized_inline!(f32, f64, bool, char, ());
+
+// Bytes viewed are owned by others.
+impl<'a> MemSized for RStr<'a> {
+    #[inline]
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl MemSized for RString {
+    #[inline]
+    fn heap_size(&self) -> usize {
+        alloc_size(RSTRING_HEADER_SIZE + self.capacity())
+    }
+}
+
+impl MemSized for RIntSet {
+    #[inline]
+    fn heap_size(&self) -> usize {
+        alloc_size(RSTRING_HEADER_SIZE + self.as_rstr().capacity())
+    }
+}
+
+// The string shared is counted by EACH reference, as it's NOT freed until ALL are dropped.
+impl MemSized for SharedRStr {
+    #[inline]
+    fn heap_size(&self) -> usize {
+        alloc_size(2 * size_of::<usize>() + size_of::<RString>()) + self.as_rstr().heap_size()
+    }
+}
+
+impl<T: MemSized> MemSized for Option<T> {
+    #[inline]
+    fn heap_size(&self) -> usize {
+        self.as_ref().map_or(0, MemSized::heap_size)
+    }
+}
+
+impl<A: MemSized, B: MemSized> MemSized for (A, B) {
+    #[inline]
+    fn heap_size(&self) -> usize {
+        self.0.heap_size() + self.1.heap_size()
+    }
+}
+
+impl<T: MemSized, const N: usize> MemSized for [T; N] {
+    #[inline]
+    fn heap_size(&self) -> usize {
+        self.iter().map(MemSized::heap_size).sum()
+    }
+}
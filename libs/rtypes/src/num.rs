@@ -0,0 +1,125 @@
+use std::error::Error;
+use std::fmt;
+
+/// Error of parsing numbers from strings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseNumError {
+    /// NOT an integer, or out of range of `i64`.
+    Integer,
+    /// NOT a valid float (or NaN).
+    Float,
+    /// Integer overflowed by increment or decrement.
+    Overflow,
+    /// NaN or infinity produced by increment of a float.
+    NotFinite,
+}
+
+impl fmt::Display for ParseNumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseNumError::Integer => write!(f, "value is not an integer or out of range"),
+            ParseNumError::Float => write!(f, "value is not a valid float"),
+            ParseNumError::Overflow => write!(f, "increment or decrement would overflow"),
+            ParseNumError::NotFinite => write!(f, "increment would produce NaN or Infinity"),
+        }
+    }
+}
+
+impl Error for ParseNumError {}
+
+/// MAX length of `i64` formatted, i.e. "-9223372036854775808".
+pub(crate) const I64_MAX_LEN: usize = 20;
+
+/// Parse `bytes` strictly into `i64` (introduced from Redis `string2ll`), where NO spaces,
+/// `+` signs or leading zeros are allowed, thus ONLY the canonical form is accepted.
+pub(crate) fn parse_i64(bytes: &[u8]) -> Result<i64, ParseNumError> {
+    if bytes.is_empty() || bytes.len() > I64_MAX_LEN {
+        return Err(ParseNumError::Integer);
+    }
+    if bytes == b"0" {
+        return Ok(0);
+    }
+
+    let (negative, digits) = match bytes[0] {
+        b'-' => (true, &bytes[1..]),
+        _ => (false, bytes),
+    };
+    match digits.first() {
+        Some(b'1'..=b'9') => {}
+        _ => return Err(ParseNumError::Integer),
+    }
+
+    let mut value = 0u64;
+    for &digit in digits {
+        if !digit.is_ascii_digit() {
+            return Err(ParseNumError::Integer);
+        }
+        value = value
+            .checked_mul(10)
+            .and_then(|value| value.checked_add((digit - b'0') as u64))
+            .ok_or(ParseNumError::Integer)?;
+    }
+
+    match negative {
+        true if value <= i64::MIN.unsigned_abs() => Ok((value as i64).wrapping_neg()),
+        false if value <= i64::MAX as u64 => Ok(value as i64),
+        _ => Err(ParseNumError::Integer),
+    }
+}
+
+/// Parse `bytes` into `f64` (introduced from Redis `string2d`), where NO spaces are allowed
+/// and NaN is rejected.
+pub(crate) fn parse_f64(bytes: &[u8]) -> Result<f64, ParseNumError> {
+    // Fast path for integers exactly representable.
+    if let Ok(value) = parse_i64(bytes) {
+        if value.unsigned_abs() <= 1 << f64::MANTISSA_DIGITS {
+            return Ok(value as f64);
+        }
+    }
+
+    if bytes.is_empty() || !bytes.is_ascii() || bytes[0].is_ascii_whitespace() {
+        return Err(ParseNumError::Float);
+    }
+    // ASCII bytes are valid UTF-8 already.
+    let s = unsafe { std::str::from_utf8_unchecked(bytes) };
+    match s.parse::<f64>() {
+        Ok(value) if !value.is_nan() => Ok(value),
+        _ => Err(ParseNumError::Float),
+    }
+}
+
+/// Digit pairs "00" to "99", for formatting 2 digits at a time.
+const DIGIT_PAIRS: &[u8; 200] = b"\
+    0001020304050607080910111213141516171819\
+    2021222324252627282930313233343536373839\
+    4041424344454647484950515253545556575859\
+    6061626364656667686970717273747576777879\
+    8081828384858687888990919293949596979899";
+
+/// Format `value` into the tail of `buf` (introduced from Redis `ll2string`), and return
+/// the offset where the formatted bytes start.
+pub(crate) fn format_i64(value: i64, buf: &mut [u8; I64_MAX_LEN]) -> usize {
+    let mut n = value.unsigned_abs();
+    let mut pos = I64_MAX_LEN;
+
+    while n >= 100 {
+        let pair = (n % 100) as usize * 2;
+        n /= 100;
+        pos -= 2;
+        buf[pos..pos + 2].copy_from_slice(&DIGIT_PAIRS[pair..pair + 2]);
+    }
+    if n >= 10 {
+        let pair = n as usize * 2;
+        pos -= 2;
+        buf[pos..pos + 2].copy_from_slice(&DIGIT_PAIRS[pair..pair + 2]);
+    } else {
+        pos -= 1;
+        buf[pos] = b'0' + n as u8;
+    }
+
+    if value < 0 {
+        pos -= 1;
+        buf[pos] = b'-';
+    }
+    pos
+}
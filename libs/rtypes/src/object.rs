@@ -0,0 +1,499 @@
+use std::fmt;
+
+use crate::num::{parse_i64, I64_MAX_LEN};
+use crate::rand::random_u64;
+use crate::{Encoding, RHash, RQuickList, RSet, RStream, RString, RZSet};
+
+////////////////////////////////////////////////////////////////////////////////
+// RObject
+////////////////////////////////////////////////////////////////////////////////
+
+/// MAX length of strings in `embstr` (Redis `OBJ_ENCODING_EMBSTR_SIZE_LIMIT`).
+const OBJ_EMBSTR_SIZE_LIMIT: usize = 44;
+
+/// Reference count of objects shared, which are NEVER freed (Redis `OBJ_SHARED_REFCOUNT`).
+pub const OBJ_SHARED_REFCOUNT: u32 = u32::MAX;
+
+/// MAX value of the LRU clock, which is 24 bits (Redis `LRU_CLOCK_MAX`).
+pub const LRU_CLOCK_MAX: u32 = (1 << 24) - 1;
+/// Milliseconds per tick of the LRU clock (Redis `LRU_CLOCK_RESOLUTION`).
+pub const LRU_CLOCK_RESOLUTION: u64 = 1000;
+/// Initial LFU counter of objects, that new objects are NOT evicted at once (Redis
+/// `LFU_INIT_VAL`).
+pub const LFU_INIT_VAL: u8 = 5;
+
+/// Get the LRU clock at `now` (in milliseconds of the UNIX time), which wraps around
+/// every 194 days (introduced from Redis `getLRUClock`).
+#[inline]
+pub fn lru_clock(now: u64) -> u32 {
+    ((now / LRU_CLOCK_RESOLUTION) & LRU_CLOCK_MAX as u64) as u32
+}
+
+// Gets minutes at `now` (in milliseconds), in 16 bits (introduced from Redis
+// `LFUGetTimeInMinutes`).
+#[inline]
+fn lfu_minutes(now: u64) -> u16 {
+    (now / 60_000) as u16
+}
+
+/// Type of values held by objects (introduced from Redis `OBJ_*`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ObjectType {
+    String,
+    List,
+    Set,
+    ZSet,
+    Hash,
+    Stream,
+}
+
+impl ObjectType {
+    /// Get the name reported by TYPE.
+    #[inline]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ObjectType::String => "string",
+            ObjectType::List => "list",
+            ObjectType::Set => "set",
+            ObjectType::ZSet => "zset",
+            ObjectType::Hash => "hash",
+            ObjectType::Stream => "stream",
+        }
+    }
+}
+
+impl fmt::Display for ObjectType {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Value of an object, where strings are tagged with their encodings, and encodings of
+/// others are got from themselves.
+#[derive(Debug)]
+enum Value {
+    Int(i64),
+    EmbStr(RString),
+    Raw(RString),
+    List(RQuickList),
+    Set(RSet),
+    ZSet(RZSet),
+    Hash(RHash),
+    Stream(RStream),
+}
+
+/// RObject is a value of any type held in the keyspace (introduced from Redis `robj`),
+/// with its encoding, reference count & 24 bits of LRU/LFU metadata for eviction:
+///
+/// - LRU: the LRU clock of the last access, see `lru_clock`.
+/// - LFU: minutes (in 16 bits) of the last decrement, and a logarithmic counter (in 8
+///   bits) of accesses.
+///
+/// Strings are encoded as:
+///
+/// - `int`: integers in the canonical form, held as `i64`.
+/// - `embstr`: NO longer than 44 bytes, allocated with NO spare capacity (but for alignment).
+/// - `raw`: otherwise, or once modified (see `string_mut`).
+///
+/// # Notes
+///
+/// The reference count is metadata ONLY (such as for OBJECT REFCOUNT), where objects are
+/// still owned as usual, and objects shared are NEVER modified in place.
+///
+/// # Examples
+///
+/// ```
+/// # use rtypes::{Encoding, ObjectType, RObject, RSet};
+///
+/// let mut object = RObject::from_bytes(b"12345");
+/// assert_eq!(object.object_type(), ObjectType::String);
+/// assert_eq!(object.encoding(), Encoding::EmbStr);
+/// assert!(object.try_encoding_downgrade());
+/// assert_eq!(object.encoding(), Encoding::Int);
+/// assert_eq!(object.as_int(), Some(12345));
+///
+/// object.string_mut().unwrap().append_str("6");
+/// assert_eq!(object.encoding(), Encoding::Raw);
+/// assert_eq!(object.to_rstring().unwrap().as_bytes(), b"123456");
+///
+/// let object = RObject::from(RSet::new());
+/// assert_eq!(object.object_type().as_str(), "set");
+/// assert_eq!(object.encoding().as_str(), "intset");
+/// ```
+#[derive(Debug)]
+pub struct RObject {
+    value: Value,
+    refcount: u32,
+    // LRU clock, or LFU minutes & counter, in the lowest 24 bits.
+    lru: u32,
+}
+
+// private methods
+impl RObject {
+    #[inline]
+    fn with_value(value: Value) -> Self {
+        RObject {
+            value,
+            refcount: 1,
+            lru: 0,
+        }
+    }
+
+    // Gets the LFU counter decremented by periods (of `decay_time` minutes) elapsed since
+    // the last decrement (introduced from Redis `LFUDecrAndReturn`).
+    fn lfu_decayed(&self, now: u64, decay_time: u64) -> u8 {
+        let (minutes, counter) = self.lfu();
+        let elapsed = lfu_minutes(now).wrapping_sub(minutes) as u64;
+        let periods = match decay_time {
+            0 => 0,
+            _ => elapsed / decay_time,
+        };
+        counter.saturating_sub(periods.min(u8::MAX as u64) as u8)
+    }
+}
+
+// public methods
+impl RObject {
+    /// Create a string object, in `embstr` if short enough, otherwise in `raw`.
+    #[inline]
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        match bytes.len() <= OBJ_EMBSTR_SIZE_LIMIT {
+            true => RObject::with_value(Value::EmbStr(RString::from_bytes(bytes))),
+            false => RObject::with_value(Value::Raw(RString::from_bytes(bytes))),
+        }
+    }
+
+    /// Create a string object of the integer, in `int`.
+    #[inline]
+    pub fn from_i64(value: i64) -> Self {
+        RObject::with_value(Value::Int(value))
+    }
+
+    #[inline]
+    pub fn object_type(&self) -> ObjectType {
+        match &self.value {
+            Value::Int(_) | Value::EmbStr(_) | Value::Raw(_) => ObjectType::String,
+            Value::List(_) => ObjectType::List,
+            Value::Set(_) => ObjectType::Set,
+            Value::ZSet(_) => ObjectType::ZSet,
+            Value::Hash(_) => ObjectType::Hash,
+            Value::Stream(_) => ObjectType::Stream,
+        }
+    }
+
+    /// Get the encoding reported by OBJECT ENCODING.
+    #[inline]
+    pub fn encoding(&self) -> Encoding {
+        match &self.value {
+            Value::Int(_) => Encoding::Int,
+            Value::EmbStr(_) => Encoding::EmbStr,
+            Value::Raw(_) => Encoding::Raw,
+            Value::List(_) => Encoding::QuickList,
+            Value::Set(set) => set.encoding(),
+            Value::ZSet(zset) => zset.encoding(),
+            Value::Hash(hash) => hash.encoding(),
+            Value::Stream(_) => Encoding::Stream,
+        }
+    }
+
+    /// Try to convert the string into a more compact encoding (introduced from Redis
+    /// `tryObjectEncoding`), and return `true` if converted:
+    ///
+    /// - into `int` if it's an integer in the canonical form.
+    /// - into `embstr` if it's `raw` but short enough.
+    ///
+    /// Strings left in `raw` are shrunk to fit if more than 10% of capacity is spare.
+    ///
+    /// # Notes
+    ///
+    /// ONLY strings are converted, and NEVER if the object is referenced elsewhere (with a
+    /// reference count greater than 1).
+    pub fn try_encoding_downgrade(&mut self) -> bool {
+        if self.refcount > 1 {
+            return false;
+        }
+
+        let s = match &mut self.value {
+            Value::EmbStr(s) | Value::Raw(s) => s,
+            _ => return false,
+        };
+        if s.len() <= I64_MAX_LEN {
+            if let Ok(value) = parse_i64(s.as_bytes()) {
+                self.value = Value::Int(value);
+                return true;
+            }
+        }
+        match &mut self.value {
+            Value::Raw(s) if s.len() <= OBJ_EMBSTR_SIZE_LIMIT => {
+                self.value = Value::EmbStr(RString::from_bytes(s.as_bytes()));
+                true
+            }
+            // Just like Redis `trimStringObjectIfNeeded`.
+            Value::Raw(s) if s.avail() > s.len() / 10 => {
+                s.shrink_to_fit();
+                false
+            }
+            _ => false,
+        }
+    }
+
+    /// Get the integer of the string in `int`.
+    #[inline]
+    pub fn as_int(&self) -> Option<i64> {
+        match self.value {
+            Value::Int(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Get the string in `embstr` or `raw`.
+    #[inline]
+    pub fn as_rstring(&self) -> Option<&RString> {
+        match &self.value {
+            Value::EmbStr(s) | Value::Raw(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Get the content of the string in any encoding (introduced from Redis
+    /// `getDecodedObject`).
+    pub fn to_rstring(&self) -> Option<RString> {
+        match &self.value {
+            Value::Int(value) => Some(RString::from_i64(*value)),
+            Value::EmbStr(s) | Value::Raw(s) => Some(s.clone()),
+            _ => None,
+        }
+    }
+
+    /// Get the string to be modified in place (introduced from Redis
+    /// `dbUnshareStringValue`), which is converted into `raw` first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the object is shared (see `make_shared`).
+    pub fn string_mut(&mut self) -> Option<&mut RString> {
+        assert!(!self.is_shared(), "shared object modified");
+        match &mut self.value {
+            Value::Int(value) => self.value = Value::Raw(RString::from_i64(*value)),
+            Value::EmbStr(s) => self.value = Value::Raw(std::mem::take(s)),
+            _ => {}
+        }
+        match &mut self.value {
+            Value::Raw(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn ref_count(&self) -> u32 {
+        self.refcount
+    }
+
+    /// Increase the reference count, where objects shared are NOT changed.
+    #[inline]
+    pub fn incr_ref_count(&mut self) {
+        if self.refcount != OBJ_SHARED_REFCOUNT {
+            self.refcount += 1;
+        }
+    }
+
+    /// Decrease the reference count, and return `true` if it drops to ZERO, i.e. the
+    /// object SHOULD be freed, where objects shared are NOT changed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reference count is ZERO already.
+    #[inline]
+    pub fn decr_ref_count(&mut self) -> bool {
+        assert!(self.refcount > 0, "reference count of ZERO decreased");
+        if self.refcount != OBJ_SHARED_REFCOUNT {
+            self.refcount -= 1;
+        }
+        self.refcount == 0
+    }
+
+    /// Make the object shared (introduced from Redis `makeObjectShared`), that its
+    /// reference count is NEVER changed.
+    #[inline]
+    pub fn make_shared(&mut self) {
+        self.refcount = OBJ_SHARED_REFCOUNT;
+    }
+
+    #[inline]
+    pub fn is_shared(&self) -> bool {
+        self.refcount == OBJ_SHARED_REFCOUNT
+    }
+
+    /// Get the raw 24 bits of LRU/LFU metadata.
+    #[inline]
+    pub fn lru(&self) -> u32 {
+        self.lru
+    }
+
+    /// Set the raw 24 bits of LRU/LFU metadata, where higher bits are ignored.
+    #[inline]
+    pub fn set_lru(&mut self, lru: u32) {
+        self.lru = lru & LRU_CLOCK_MAX;
+    }
+
+    /// Record an access at `now` (in milliseconds) for LRU.
+    #[inline]
+    pub fn touch_lru(&mut self, now: u64) {
+        self.lru = lru_clock(now);
+    }
+
+    /// Get milliseconds since the last access for LRU (introduced from Redis
+    /// `estimateObjectIdleTime`), with a resolution of `LRU_CLOCK_RESOLUTION`.
+    #[inline]
+    pub fn idle_time(&self, now: u64) -> u64 {
+        let clock = lru_clock(now);
+        let ticks = match clock >= self.lru {
+            true => clock - self.lru,
+            false => clock + (LRU_CLOCK_MAX - self.lru),
+        };
+        ticks as u64 * LRU_CLOCK_RESOLUTION
+    }
+
+    /// Get the minutes (in 16 bits) & the counter for LFU.
+    #[inline]
+    pub fn lfu(&self) -> (u16, u8) {
+        ((self.lru >> 8) as u16, self.lru as u8)
+    }
+
+    /// Initialize LFU metadata at `now` (in milliseconds) for a new object, with the
+    /// counter of `LFU_INIT_VAL`.
+    #[inline]
+    pub fn init_lfu(&mut self, now: u64) {
+        self.lru = (lfu_minutes(now) as u32) << 8 | LFU_INIT_VAL as u32;
+    }
+
+    /// Get the LFU counter at `now` (in milliseconds) WITHOUT recording an access, which
+    /// is decremented by 1 per `decay_time` minutes elapsed (Redis `lfu-decay-time`),
+    /// such as for OBJECT FREQ.
+    #[inline]
+    pub fn lfu_counter(&self, now: u64, decay_time: u64) -> u8 {
+        self.lfu_decayed(now, decay_time)
+    }
+
+    /// Record an access at `now` (in milliseconds) for LFU (introduced from Redis
+    /// `updateLFU`), where the counter is decayed first, then increased with a probability
+    /// of `1 / ((counter - LFU_INIT_VAL) * log_factor + 1)` (Redis `lfu-log-factor`).
+    pub fn touch_lfu(&mut self, now: u64, log_factor: u64, decay_time: u64) {
+        let mut counter = self.lfu_decayed(now, decay_time);
+        if counter < u8::MAX {
+            let base = counter.saturating_sub(LFU_INIT_VAL) as f64;
+            let p = 1.0 / (base * log_factor as f64 + 1.0);
+            let r = (random_u64() >> 11) as f64 / (1u64 << 53) as f64;
+            if r < p {
+                counter += 1;
+            }
+        }
+        self.lru = (lfu_minutes(now) as u32) << 8 | counter as u32;
+    }
+
+    #[inline]
+    pub fn as_list(&self) -> Option<&RQuickList> {
+        match &self.value {
+            Value::List(list) => Some(list),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_list_mut(&mut self) -> Option<&mut RQuickList> {
+        match &mut self.value {
+            Value::List(list) => Some(list),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_set(&self) -> Option<&RSet> {
+        match &self.value {
+            Value::Set(set) => Some(set),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_set_mut(&mut self) -> Option<&mut RSet> {
+        match &mut self.value {
+            Value::Set(set) => Some(set),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_zset(&self) -> Option<&RZSet> {
+        match &self.value {
+            Value::ZSet(zset) => Some(zset),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_zset_mut(&mut self) -> Option<&mut RZSet> {
+        match &mut self.value {
+            Value::ZSet(zset) => Some(zset),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_hash(&self) -> Option<&RHash> {
+        match &self.value {
+            Value::Hash(hash) => Some(hash),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_hash_mut(&mut self) -> Option<&mut RHash> {
+        match &mut self.value {
+            Value::Hash(hash) => Some(hash),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_stream(&self) -> Option<&RStream> {
+        match &self.value {
+            Value::Stream(stream) => Some(stream),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_stream_mut(&mut self) -> Option<&mut RStream> {
+        match &mut self.value {
+            Value::Stream(stream) => Some(stream),
+            _ => None,
+        }
+    }
+}
+
+/// Create a string object in `raw` (introduced from Redis `createRawStringObject`).
+impl From<RString> for RObject {
+    #[inline]
+    fn from(s: RString) -> Self {
+        RObject::with_value(Value::Raw(s))
+    }
+}
+
+macro_rules! impl_from_value {
+    ($($variant: ident($type: ty)),*) => {
+        $(
+            impl From<$type> for RObject {
+                #[inline]
+                fn from(value: $type) -> Self {
+                    RObject::with_value(Value::$variant(value))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_value! {
+    List(RQuickList), Set(RSet), ZSet(RZSet), Hash(RHash), Stream(RStream)
+}
@@ -0,0 +1,751 @@
+use std::fmt;
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+use std::vec;
+
+use rmem::{free_for, malloc_for};
+
+use crate::listpack::entry_size;
+use crate::{EncodingLimits, ListPackValue, RList, RListPack, RListPackIter, RString};
+
+////////////////////////////////////////////////////////////////////////////////
+// RQuickList
+////////////////////////////////////////////////////////////////////////////////
+
+/// DEFAULT fill of quicklist nodes, i.e. 8KB per node (Redis `list-max-listpack-size`).
+pub const QUICKLIST_DEFAULT_FILL: isize = -2;
+/// MAX fill (number of entries) of quicklist nodes.
+pub const QUICKLIST_MAX_FILL: isize = 1 << 15;
+
+/// MAX size (in bytes) of nodes with fills -1 to -5.
+const FILL_SIZES: [usize; 5] = [4 << 10, 8 << 10, 16 << 10, 32 << 10, 64 << 10];
+/// MIN size (in bytes) of nodes to compress, that smaller ones are NOT worth it.
+const MIN_COMPRESS_BYTES: usize = 48;
+/// MAX size (in bytes) of nodes with a positive fill, that nodes of huge entries are
+/// NOT packed further.
+const SIZE_SAFETY_LIMIT: usize = 8 << 10;
+
+/// RQuickList is a list of byte strings (introduced from Redis `quicklist`), where entries
+/// are packed into listpack nodes (each a single memory block) & nodes are allocated with
+/// `rmem` & doubly linked (just like `RList`), thus it costs ~2 bytes of overhead per
+/// (short) entry, rather than 3 pointers per element of `RList`.
+///
+/// Nodes are filled up to the `fill` limit (Redis `list-max-listpack-size`):
+///
+/// - positive: MAX number of entries per node (nodes are limited to 8KB as well).
+/// - -1 to -5: MAX size of nodes, i.e. 4KB, 8KB, 16KB, 32KB or 64KB.
+///
+/// Nodes in the interior MAYBE compressed with LZF, except `compress_depth` nodes at both
+/// ends (Redis `list-compress-depth`, where ZERO disables compression), which are
+/// decompressed transparently on access.
+///
+/// # Notes
+///
+/// Entries are copied out of nodes (as `RString`) on access.
+///
+/// # Examples
+///
+/// ```
+/// # use rtypes::RQuickList;
+///
+/// let mut list = RQuickList::with_fill(2);
+/// list.push_back(b"b");
+/// list.push_back(b"c");
+/// list.push_front(b"a");
+/// assert_eq!(list.len(), 3);
+/// assert_eq!(list.node_count(), 2);
+/// assert_eq!(list.get(1).unwrap().as_bytes(), b"b");
+/// assert_eq!(list.pop_back().unwrap().as_bytes(), b"c");
+/// ```
+pub struct RQuickList {
+    head: Link,
+    tail: Link,
+    node_count: usize,
+    len: usize,
+    fill: isize,
+    compress_depth: usize,
+    _marker: PhantomData<Box<Node>>,
+}
+
+// RQuickList owns its chain of nodes exclusively (NEVER shared with others), which are
+// mutated ONLY via `&mut RQuickList`, and nodes hold nothing but bytes, just like
+// `LinkedList<RListPack>` does.
+unsafe impl Send for RQuickList {}
+unsafe impl Sync for RQuickList {}
+
+/// Link to a node, which is NULL (`None`) at both ends of the list.
+type Link = Option<NonNull<Node>>;
+
+/// Node of an `RQuickList` linked with raw pointers, allocated with `rmem`.
+struct Node {
+    data: QuickNode,
+    prev: Link,
+    next: Link,
+}
+
+/// Node of an `RQuickList`, which is either a listpack as is, or compressed with LZF.
+enum QuickNode {
+    Raw(RListPack),
+    Lzf {
+        data: RString,
+        count: usize,
+        raw_len: usize,
+    },
+}
+
+impl Node {
+    // Allocates a node with some `data`, unlinked.
+    #[inline]
+    fn alloc(data: QuickNode) -> NonNull<Node> {
+        let node = NonNull::new(malloc_for::<Node>().0).expect("NULL pointer allocated");
+        unsafe {
+            node.as_ptr().write(Node {
+                data,
+                prev: None,
+                next: None,
+            })
+        };
+        node
+    }
+
+    // Deallocates a node unlinked, with its data dropped.
+    #[inline]
+    unsafe fn free(node: NonNull<Node>) {
+        std::ptr::drop_in_place(node.as_ptr());
+        free_for::<Node>(node.as_ptr());
+    }
+}
+
+impl QuickNode {
+    /// Get number of entries.
+    #[inline]
+    fn len(&self) -> usize {
+        match self {
+            QuickNode::Raw(lp) => lp.len(),
+            QuickNode::Lzf { count, .. } => *count,
+        }
+    }
+
+    #[inline]
+    fn is_compressed(&self) -> bool {
+        matches!(self, QuickNode::Lzf { .. })
+    }
+
+    /// Compress the node, unless it's too small or NOT compressible.
+    fn compress(&mut self) {
+        if let QuickNode::Raw(lp) = self {
+            if lp.bytes_len() < MIN_COMPRESS_BYTES {
+                return;
+            }
+            if let Some(data) = lp.as_rstr().compress_lzf() {
+                *self = QuickNode::Lzf {
+                    data,
+                    count: lp.len(),
+                    raw_len: lp.bytes_len(),
+                };
+            }
+        }
+    }
+
+    /// Decompress the node, and get the listpack.
+    fn decompress(&mut self) -> &mut RListPack {
+        if let QuickNode::Lzf { .. } = self {
+            *self = QuickNode::Raw(self.to_listpack());
+        }
+        match self {
+            QuickNode::Raw(lp) => lp,
+            QuickNode::Lzf { .. } => unreachable!(),
+        }
+    }
+
+    /// Decompress a compressed node into a listpack, leaving the node as is.
+    fn to_listpack(&self) -> RListPack {
+        match self {
+            QuickNode::Raw(lp) => lp.clone(),
+            QuickNode::Lzf { data, raw_len, .. } => {
+                let buf = data
+                    .decompress_lzf(*raw_len)
+                    .expect("quicklist node corrupted");
+                RListPack::from_raw(buf)
+            }
+        }
+    }
+
+    /// Get the listpack of a node NOT compressed.
+    #[inline]
+    fn raw(&self) -> Option<&RListPack> {
+        match self {
+            QuickNode::Raw(lp) => Some(lp),
+            QuickNode::Lzf { .. } => None,
+        }
+    }
+
+    /// Get entries of the node, copied out if compressed.
+    fn entries(&self) -> NodeEntries<'_> {
+        match self {
+            QuickNode::Raw(lp) => NodeEntries::Raw(lp.iter()),
+            QuickNode::Lzf { .. } => {
+                let entries: Vec<_> = self
+                    .to_listpack()
+                    .iter()
+                    .map(ListPackValue::to_rstring)
+                    .collect();
+                NodeEntries::Decompressed(entries.into_iter())
+            }
+        }
+    }
+}
+
+// private methods
+impl RQuickList {
+    fn push_front_node(&mut self, data: QuickNode) {
+        let node = Node::alloc(data);
+        unsafe {
+            (*node.as_ptr()).next = self.head;
+            match self.head {
+                Some(head) => (*head.as_ptr()).prev = Some(node),
+                None => self.tail = Some(node),
+            }
+        }
+        self.head = Some(node);
+        self.node_count += 1;
+    }
+
+    fn push_back_node(&mut self, data: QuickNode) {
+        let node = Node::alloc(data);
+        unsafe {
+            (*node.as_ptr()).prev = self.tail;
+            match self.tail {
+                Some(tail) => (*tail.as_ptr()).next = Some(node),
+                None => self.head = Some(node),
+            }
+        }
+        self.tail = Some(node);
+        self.node_count += 1;
+    }
+
+    // Unlinks & deallocates the node at the front, if any.
+    fn pop_front_node(&mut self) {
+        if let Some(node) = self.head {
+            unsafe {
+                self.head = (*node.as_ptr()).next;
+                match self.head {
+                    Some(head) => (*head.as_ptr()).prev = None,
+                    None => self.tail = None,
+                }
+                Node::free(node);
+            }
+            self.node_count -= 1;
+        }
+    }
+
+    // Unlinks & deallocates the node at the back, if any.
+    fn pop_back_node(&mut self) {
+        if let Some(node) = self.tail {
+            unsafe {
+                self.tail = (*node.as_ptr()).prev;
+                match self.tail {
+                    Some(tail) => (*tail.as_ptr()).next = None,
+                    None => self.head = None,
+                }
+                Node::free(node);
+            }
+            self.node_count -= 1;
+        }
+    }
+
+    #[inline]
+    fn front_node(&self) -> Option<&QuickNode> {
+        self.head.map(|node| unsafe { &(*node.as_ptr()).data })
+    }
+
+    #[inline]
+    fn front_node_mut(&mut self) -> Option<&mut QuickNode> {
+        self.head.map(|node| unsafe { &mut (*node.as_ptr()).data })
+    }
+
+    #[inline]
+    fn back_node(&self) -> Option<&QuickNode> {
+        self.tail.map(|node| unsafe { &(*node.as_ptr()).data })
+    }
+
+    #[inline]
+    fn back_node_mut(&mut self) -> Option<&mut QuickNode> {
+        self.tail.map(|node| unsafe { &mut (*node.as_ptr()).data })
+    }
+
+    // Finds the node at `idx`, walking from the nearer end.
+    fn find_node(&self, idx: usize) -> Link {
+        if idx >= self.node_count {
+            return None;
+        }
+        unsafe {
+            if idx <= self.node_count / 2 {
+                let mut node = self.head;
+                for _ in 0..idx {
+                    node = (*node?.as_ptr()).next;
+                }
+                node
+            } else {
+                let mut node = self.tail;
+                for _ in idx + 1..self.node_count {
+                    node = (*node?.as_ptr()).prev;
+                }
+                node
+            }
+        }
+    }
+
+    // Gets an iterator over nodes, from the front to the back.
+    #[inline]
+    fn nodes(&self) -> Nodes<'_> {
+        Nodes {
+            head: self.head,
+            tail: self.tail,
+            len: self.node_count,
+            _marker: PhantomData,
+        }
+    }
+}
+
+// public methods
+impl RQuickList {
+    /// Create an empty list with the DEFAULT fill.
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_fill(QUICKLIST_DEFAULT_FILL)
+    }
+
+    /// Create an empty list with the `fill` of nodes, which is clamped to [-5, MAX fill],
+    /// and ZERO is treated as 1 (just like Redis does).
+    #[inline]
+    pub fn with_fill(fill: isize) -> Self {
+        Self::with_options(fill, 0)
+    }
+
+    /// Create an empty list with the fill of nodes in `limits`, see `with_fill`.
+    #[inline]
+    pub fn with_limits(limits: &EncodingLimits) -> Self {
+        Self::with_fill(limits.list_max_listpack_size)
+    }
+
+    /// Create an empty list with the `fill` of nodes (see `with_fill`), where nodes
+    /// in the interior are compressed except `compress_depth` nodes at both ends.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RQuickList;
+    ///
+    /// let mut list = RQuickList::with_options(4, 1);
+    /// for i in 0..16 {
+    ///     list.push_back(format!("{:0>64}", i).as_bytes());
+    /// }
+    /// // Nodes other than the front & the back are compressed.
+    /// assert_eq!(list.node_count(), 4);
+    /// assert_eq!(list.compressed_node_count(), 2);
+    /// assert_eq!(list.get(5).unwrap().as_bytes(), format!("{:0>64}", 5).as_bytes());
+    /// ```
+    pub fn with_options(fill: isize, compress_depth: usize) -> Self {
+        let fill = match fill {
+            0 => 1,
+            n => n.clamp(-(FILL_SIZES.len() as isize), QUICKLIST_MAX_FILL),
+        };
+        RQuickList {
+            head: None,
+            tail: None,
+            node_count: 0,
+            len: 0,
+            fill,
+            compress_depth,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Get the fill of nodes.
+    #[inline]
+    pub fn fill(&self) -> isize {
+        self.fill
+    }
+
+    /// Get number of nodes at both ends NOT compressed, where ZERO means compression
+    /// is disabled.
+    #[inline]
+    pub fn compress_depth(&self) -> usize {
+        self.compress_depth
+    }
+
+    /// Get number of entries.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get number of nodes.
+    #[inline]
+    pub fn node_count(&self) -> usize {
+        self.node_count
+    }
+
+    /// Get number of nodes compressed.
+    #[inline]
+    pub fn compressed_node_count(&self) -> usize {
+        self.nodes().filter(|node| node.is_compressed()).count()
+    }
+
+    /// Compress or decompress the node at `idx` (if any) by the compress depth, since
+    /// the nodes at both ends have been changed.
+    fn update_compression(&mut self, idx: usize) {
+        let depth = self.compress_depth;
+        let count = self.node_count;
+        let node = match self.find_node(idx) {
+            Some(node) if depth > 0 => unsafe { &mut (*node.as_ptr()).data },
+            _ => return,
+        };
+        if idx < depth || idx >= count - depth {
+            node.decompress();
+        } else {
+            node.compress();
+        }
+    }
+
+    /// Check if an entry holding `data` can be packed into `node`.
+    fn allow_insert(&self, node: &QuickNode, data: &[u8]) -> bool {
+        let node = match node.raw() {
+            Some(node) => node,
+            None => return false,
+        };
+        if node.is_empty() {
+            return true;
+        }
+        let new_size = node.bytes_len() + entry_size(data);
+        match self.fill {
+            fill if fill > 0 => node.len() < fill as usize && new_size <= SIZE_SAFETY_LIMIT,
+            fill => new_size <= FILL_SIZES[(-fill - 1) as usize],
+        }
+    }
+
+    pub fn push_front(&mut self, data: &[u8]) {
+        match self.front_node() {
+            Some(node) if self.allow_insert(node, data) => {}
+            _ => {
+                self.push_front_node(QuickNode::Raw(RListPack::new()));
+                self.update_compression(self.compress_depth);
+            }
+        }
+        if let Some(node) = self.front_node_mut() {
+            node.decompress().push_front(data);
+        }
+        self.len += 1;
+    }
+
+    pub fn push_back(&mut self, data: &[u8]) {
+        match self.back_node() {
+            Some(node) if self.allow_insert(node, data) => {}
+            _ => {
+                self.push_back_node(QuickNode::Raw(RListPack::new()));
+                let count = self.node_count;
+                self.update_compression((count - 1).wrapping_sub(self.compress_depth));
+            }
+        }
+        if let Some(node) = self.back_node_mut() {
+            node.decompress().push_back(data);
+        }
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<RString> {
+        let node = self.front_node_mut()?.decompress();
+        let data = node.pop_front();
+        if node.is_empty() {
+            self.pop_front_node();
+            self.update_compression(self.compress_depth.wrapping_sub(1));
+        }
+        self.len -= 1;
+        data
+    }
+
+    pub fn pop_back(&mut self) -> Option<RString> {
+        let node = self.back_node_mut()?.decompress();
+        let data = node.pop_back();
+        if node.is_empty() {
+            self.pop_back_node();
+            let count = self.node_count;
+            self.update_compression(count.wrapping_sub(self.compress_depth));
+        }
+        self.len -= 1;
+        data
+    }
+
+    /// Get the entry at `idx`, walking nodes from the nearer end.
+    pub fn get(&self, idx: usize) -> Option<RString> {
+        if idx >= self.len {
+            return None;
+        }
+
+        let (node, idx) = if idx <= self.len / 2 {
+            let mut idx = idx;
+            self.nodes().find_map(|node| match node.len() {
+                count if idx < count => Some((node, idx)),
+                count => {
+                    idx -= count;
+                    None
+                }
+            })
+        } else {
+            let mut idx = self.len - 1 - idx;
+            self.nodes().rev().find_map(|node| match node.len() {
+                count if idx < count => Some((node, count - 1 - idx)),
+                count => {
+                    idx -= count;
+                    None
+                }
+            })
+        }?;
+        match node.raw() {
+            Some(lp) => lp.get(idx).map(ListPackValue::to_rstring),
+            None => node.to_listpack().get(idx).map(ListPackValue::to_rstring),
+        }
+    }
+
+    /// Get an iterator over entries (copied) of the list, from the front to the back.
+    #[inline]
+    pub fn iter(&self) -> RQuickListIter<'_> {
+        RQuickListIter {
+            nodes: self.nodes(),
+            front: None,
+            back: None,
+            len: self.len,
+        }
+    }
+
+    /// Clear the list, with ALL nodes deallocated.
+    #[inline]
+    pub fn clear(&mut self) {
+        while self.head.is_some() {
+            self.pop_front_node();
+        }
+        self.len = 0;
+    }
+
+    /// Convert the list into an `RList`, with each entry mapped by `f`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::{RList, RQuickList};
+    ///
+    /// let list = RQuickList::from(&vec!["1", "22"].into_iter().collect::<RList<_>>());
+    /// assert_eq!(list.to_rlist_with(|data| data.len()).to_vec(), vec![1, 2]);
+    /// ```
+    pub fn to_rlist_with<T, F>(&self, f: F) -> RList<T>
+    where
+        T: Copy + Clone,
+        F: FnMut(&[u8]) -> T,
+    {
+        let mut f = f;
+        let mut list = RList::new();
+        for node in self.nodes() {
+            match node.raw() {
+                Some(lp) => list.extend(lp.iter().map(|value| value.with_bytes(&mut f))),
+                None => {
+                    let lp = node.to_listpack();
+                    list.extend(lp.iter().map(|value| value.with_bytes(&mut f)))
+                }
+            }
+        }
+        list
+    }
+}
+
+impl Drop for RQuickList {
+    #[inline]
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl Default for RQuickList {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<&RList<T>> for RQuickList
+where
+    T: Copy + Clone + AsRef<[u8]>,
+{
+    fn from(list: &RList<T>) -> Self {
+        let mut quicklist = RQuickList::new();
+        for data in list {
+            quicklist.push_back(data.as_ref());
+        }
+        quicklist
+    }
+}
+
+impl fmt::Debug for RQuickList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RQuickList")
+            .field("len", &self.len)
+            .field("nodes", &self.node_count)
+            .field("fill", &self.fill)
+            .field("compress_depth", &self.compress_depth)
+            .finish()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Iterators
+////////////////////////////////////////////////////////////////////////////////
+
+impl<'a> IntoIterator for &'a RQuickList {
+    type Item = RString;
+    type IntoIter = RQuickListIter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over entries (copied) of an `RQuickList`, from the front or the back.
+pub struct RQuickListIter<'a> {
+    /// Nodes NOT iterated over yet.
+    nodes: Nodes<'a>,
+    /// Entries left in the node iterated over from the front.
+    front: Option<NodeEntries<'a>>,
+    /// Entries left in the node iterated over from the back.
+    back: Option<NodeEntries<'a>>,
+    len: usize,
+}
+
+/// Iterator over nodes of an `RQuickList`, from the front or the back.
+struct Nodes<'a> {
+    head: Link,
+    tail: Link,
+    len: usize,
+    _marker: PhantomData<&'a Node>,
+}
+
+// Nodes borrows nodes of the list shared, just like `&RQuickList` does.
+unsafe impl<'a> Send for Nodes<'a> {}
+unsafe impl<'a> Sync for Nodes<'a> {}
+
+impl<'a> Iterator for Nodes<'a> {
+    type Item = &'a QuickNode;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.head.map(|node| unsafe {
+            let node = &*node.as_ptr();
+            self.len -= 1;
+            self.head = node.next;
+            &node.data
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a> DoubleEndedIterator for Nodes<'a> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.tail.map(|node| unsafe {
+            let node = &*node.as_ptr();
+            self.len -= 1;
+            self.tail = node.prev;
+            &node.data
+        })
+    }
+}
+
+/// Entries of a quicklist node, borrowed from the node (as is) or copied out (compressed).
+enum NodeEntries<'a> {
+    Raw(RListPackIter<'a>),
+    Decompressed(vec::IntoIter<RString>),
+}
+
+impl<'a> Iterator for NodeEntries<'a> {
+    type Item = RString;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            NodeEntries::Raw(entries) => entries.next().map(ListPackValue::to_rstring),
+            NodeEntries::Decompressed(entries) => entries.next(),
+        }
+    }
+}
+
+impl<'a> DoubleEndedIterator for NodeEntries<'a> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self {
+            NodeEntries::Raw(entries) => entries.next_back().map(ListPackValue::to_rstring),
+            NodeEntries::Decompressed(entries) => entries.next_back(),
+        }
+    }
+}
+
+impl<'a> Iterator for RQuickListIter<'a> {
+    type Item = RString;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(data) = self.front.as_mut().and_then(|entries| entries.next()) {
+                self.len -= 1;
+                return Some(data);
+            }
+            match self.nodes.next() {
+                Some(node) => self.front = Some(node.entries()),
+                None => {
+                    let data = self.back.as_mut()?.next()?;
+                    self.len -= 1;
+                    return Some(data);
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a> DoubleEndedIterator for RQuickListIter<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(data) = self.back.as_mut().and_then(|entries| entries.next_back()) {
+                self.len -= 1;
+                return Some(data);
+            }
+            match self.nodes.next_back() {
+                Some(node) => self.back = Some(node.entries()),
+                None => {
+                    let data = self.front.as_mut()?.next_back()?;
+                    self.len -= 1;
+                    return Some(data);
+                }
+            }
+        }
+    }
+}
+
+impl<'a> ExactSizeIterator for RQuickListIter<'a> {}
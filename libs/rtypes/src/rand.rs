@@ -0,0 +1,34 @@
+use std::cell::Cell;
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+
+////////////////////////////////////////////////////////////////////////////////
+// Randomness
+////////////////////////////////////////////////////////////////////////////////
+
+thread_local! {
+    // State of the xorshift generator of the thread, seeded by the randomness of `std`,
+    // where ZERO is NOT a valid state.
+    static STATE: Cell<u64> = Cell::new(RandomState::new().hash_one(0u8) | 1);
+}
+
+/// Generate a random number (with xorshift64*), which is NOT cryptographically secure,
+/// such as for picking random elements.
+#[inline]
+pub(crate) fn random_u64() -> u64 {
+    STATE.with(|state| {
+        let mut x = state.get();
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        state.set(x);
+        x.wrapping_mul(0x2545f4914f6cdd1d)
+    })
+}
+
+/// Generate a random number in `[0, n)`, where `n` MUST NOT be ZERO.
+#[inline]
+pub(crate) fn random_below(n: usize) -> usize {
+    // Multiplied & shifted rather than modulo, which is (nearly) unbiased as well.
+    ((random_u64() as u128 * n as u128) >> 64) as usize
+}
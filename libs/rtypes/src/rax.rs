@@ -0,0 +1,573 @@
+use std::fmt;
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::ops::Bound;
+use std::ptr::{self, NonNull};
+
+use rmem::{free_array_for, free_for, malloc_array_for, malloc_for};
+
+use crate::RString;
+
+////////////////////////////////////////////////////////////////////////////////
+// RRax
+////////////////////////////////////////////////////////////////////////////////
+
+/// Node of an `RRax`, holding the bytes of the edge from its parent (NEVER empty but for
+/// the root), the value of the key ending here (if any), and its children sorted by their
+/// first bytes.
+struct Node<V> {
+    prefix: RString,
+    value: Option<V>,
+    // Array of children allocated with `rmem` exactly, which is NULL if NO children.
+    children: *mut NonNull<Node<V>>,
+    children_len: usize,
+}
+
+/// RRax is a radix tree (introduced from Redis `rax`) mapping binary-safe keys to values,
+/// ordered by keys (in bytes), where common prefixes of keys are stored ONLY once, and
+/// nodes are allocated with `rmem`.
+///
+/// Keys are inserted, removed & looked up in O(k) time (k is the length of the key), and
+/// iterated over in order of keys (from the front or the back), e.g. big-endian IDs of
+/// stream nodes (see `RStream`).
+///
+/// # Notes
+///
+/// Paths of nodes with a single child & NO value are compressed into one node, which
+/// is split once a key diverging in the middle is inserted, and nodes are merged back
+/// once keys are removed.
+///
+/// # Examples
+///
+/// ```
+/// # use rtypes::RRax;
+/// # use std::ops::Bound;
+///
+/// let mut rax = RRax::new();
+/// assert_eq!(rax.insert(b"annibale", 1), None);
+/// assert_eq!(rax.insert(b"annientare", 2), None);
+/// assert_eq!(rax.insert(b"anni", 3), None);
+/// assert_eq!(rax.insert(b"anni", 4), Some(3));
+/// assert_eq!(rax.get(b"annientare"), Some(&2));
+/// assert_eq!(rax.len(), 3);
+///
+/// let keys: Vec<_> = rax
+///     .range(Bound::Excluded(b"anni"), Bound::Unbounded)
+///     .map(|(key, _)| key.to_bytes())
+///     .collect();
+/// assert_eq!(keys, vec![b"annibale".to_vec(), b"annientare".to_vec()]);
+/// assert_eq!(rax.remove(b"anni"), Some(4));
+/// ```
+pub struct RRax<V> {
+    root: Node<V>,
+    len: usize,
+    _marker: PhantomData<Box<Node<V>>>,
+}
+
+// RRax owns its nodes exclusively (just like `Box<Node<V>>`), thus it's Send & Sync if
+// values are.
+unsafe impl<V: Send> Send for RRax<V> {}
+unsafe impl<V: Sync> Sync for RRax<V> {}
+
+/// Iterator over keys & values of an `RRax` in a range, in ascending (or descending)
+/// order of keys.
+pub struct RRaxIter<'a, V> {
+    rax: &'a RRax<V>,
+    start: Bound<RString>,
+    end: Bound<RString>,
+}
+
+// Gets the length of the common prefix of `a` & `b`.
+#[inline]
+fn common_len(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(x, y)| x == y).count()
+}
+
+// Borrows the key of a bound.
+#[inline]
+fn bound_ref(bound: &Bound<RString>) -> Bound<&[u8]> {
+    match bound {
+        Bound::Included(key) => Bound::Included(key.as_bytes()),
+        Bound::Excluded(key) => Bound::Excluded(key.as_bytes()),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+impl<V> Node<V> {
+    #[inline]
+    fn new(prefix: &[u8], value: Option<V>) -> Self {
+        Node {
+            prefix: RString::from_bytes(prefix),
+            value,
+            children: ptr::null_mut(),
+            children_len: 0,
+        }
+    }
+
+    // Allocates a node with `prefix` & `value`, and NO children.
+    #[inline]
+    fn alloc(node: Node<V>) -> NonNull<Node<V>> {
+        let ptr = NonNull::new(malloc_for::<Node<V>>().0).expect("NULL pointer allocated");
+        unsafe { ptr.as_ptr().write(node) };
+        ptr
+    }
+
+    // Deallocates a node unlinked, and gets it back (with its children).
+    #[inline]
+    unsafe fn free(ptr: NonNull<Node<V>>) -> Node<V> {
+        let node = ptr.as_ptr().read();
+        free_for::<Node<V>>(ptr.as_ptr());
+        node
+    }
+
+    #[inline]
+    fn children(&self) -> &[NonNull<Node<V>>] {
+        match self.children_len {
+            0 => &[],
+            len => unsafe { std::slice::from_raw_parts(self.children, len) },
+        }
+    }
+
+    #[inline]
+    fn children_mut(&mut self) -> &mut [NonNull<Node<V>>] {
+        match self.children_len {
+            0 => &mut [],
+            len => unsafe { std::slice::from_raw_parts_mut(self.children, len) },
+        }
+    }
+
+    // Replaces the array of children with `children` of `len` children.
+    fn replace_children(&mut self, children: *mut NonNull<Node<V>>, len: usize) {
+        if self.children_len > 0 {
+            free_array_for(self.children, self.children_len);
+        }
+        self.children = children;
+        self.children_len = len;
+    }
+
+    // Searches the child starting with `byte`, and gets its index, or where it would be
+    // inserted.
+    #[inline]
+    fn search_child(&self, byte: u8) -> Result<usize, usize> {
+        self.children()
+            .binary_search_by_key(&byte, |child| unsafe { child.as_ref().prefix[0] })
+    }
+
+    // Inserts `child` at `idx`, with the array of children reallocated exactly.
+    fn insert_child(&mut self, idx: usize, child: NonNull<Node<V>>) {
+        let len = self.children_len;
+        let (children, _) = malloc_array_for::<NonNull<Node<V>>>(len + 1);
+        unsafe {
+            let old = self.children().as_ptr();
+            ptr::copy_nonoverlapping(old, children, idx);
+            children.add(idx).write(child);
+            ptr::copy_nonoverlapping(old.add(idx), children.add(idx + 1), len - idx);
+        }
+        self.replace_children(children, len + 1);
+    }
+
+    // Removes the child at `idx`, with the array of children reallocated exactly.
+    fn remove_child(&mut self, idx: usize) -> NonNull<Node<V>> {
+        let (len, child) = (self.children_len, self.children()[idx]);
+        let children = match len - 1 {
+            0 => ptr::null_mut(),
+            n => {
+                let (children, _) = malloc_array_for::<NonNull<Node<V>>>(n);
+                unsafe {
+                    let old = self.children().as_ptr();
+                    ptr::copy_nonoverlapping(old, children, idx);
+                    ptr::copy_nonoverlapping(old.add(idx + 1), children.add(idx), n - idx);
+                }
+                children
+            }
+        };
+        self.replace_children(children, len - 1);
+        child
+    }
+
+    // Merges the ONLY child into the node WITHOUT a value, i.e. compresses the path.
+    fn merge_child(&mut self) {
+        let child = unsafe { Node::free(self.children()[0]) };
+        free_array_for(self.children, self.children_len);
+        self.prefix.append_bytes(child.prefix.as_bytes());
+        self.prefix.shrink_to_fit();
+        self.value = child.value;
+        self.children = child.children;
+        self.children_len = child.children_len;
+    }
+
+    // Finds the MIN key of the subtree, with the prefix of the node appended to `path`.
+    fn first(&self, path: &mut RString) -> Option<&V> {
+        path.append_bytes(self.prefix.as_bytes());
+        match (&self.value, self.children().first()) {
+            (Some(value), _) => Some(value),
+            (None, Some(child)) => unsafe { child.as_ref() }.first(path),
+            (None, None) => None,
+        }
+    }
+
+    // Finds the MAX key of the subtree, with the prefix of the node appended to `path`.
+    fn last(&self, path: &mut RString) -> Option<&V> {
+        path.append_bytes(self.prefix.as_bytes());
+        match self.children().last() {
+            Some(child) => unsafe { child.as_ref() }.last(path),
+            None => self.value.as_ref(),
+        }
+    }
+
+    // Finds the MIN key (appended to `path`) of the subtree greater than (or equal to, if
+    // `inclusive`) the key, where `rest` is what's left of the key after the node.
+    fn lower_bound(&self, rest: &[u8], inclusive: bool, path: &mut RString) -> Option<&V> {
+        let byte = match rest.first() {
+            Some(&byte) => byte,
+            None if inclusive && self.value.is_some() => return self.value.as_ref(),
+            // ALL keys of children are longer, thus greater.
+            None => {
+                return self
+                    .children()
+                    .first()
+                    .and_then(|c| unsafe { c.as_ref() }.first(path))
+            }
+        };
+        // The key of the node itself is a prefix of the key, thus less.
+        let from = match self.search_child(byte) {
+            Ok(idx) | Err(idx) => idx,
+        };
+        for child in self.children()[from..].iter() {
+            let child = unsafe { child.as_ref() };
+            let prefix = child.prefix.as_bytes();
+            let common = common_len(prefix, rest);
+            let len = path.len();
+            if common == prefix.len() {
+                path.append_bytes(prefix);
+                if let Some(value) = child.lower_bound(&rest[common..], inclusive, path) {
+                    return Some(value);
+                }
+                path.truncate(len);
+            } else if common == rest.len() || prefix[common] > rest[common] {
+                return child.first(path);
+            }
+        }
+        None
+    }
+
+    // Finds the MAX key (appended to `path`) of the subtree less than (or equal to, if
+    // `inclusive`) the key, where `rest` is what's left of the key after the node.
+    fn upper_bound(&self, rest: &[u8], inclusive: bool, path: &mut RString) -> Option<&V> {
+        let byte = match rest.first() {
+            Some(&byte) => byte,
+            // ALL keys of children are longer, thus greater.
+            None if inclusive => return self.value.as_ref(),
+            None => return None,
+        };
+        let to = match self.search_child(byte) {
+            Ok(idx) => idx + 1,
+            Err(idx) => idx,
+        };
+        for child in self.children()[..to].iter().rev() {
+            let child = unsafe { child.as_ref() };
+            let prefix = child.prefix.as_bytes();
+            let common = common_len(prefix, rest);
+            let len = path.len();
+            if common == prefix.len() {
+                path.append_bytes(prefix);
+                if let Some(value) = child.upper_bound(&rest[common..], inclusive, path) {
+                    return Some(value);
+                }
+                path.truncate(len);
+            } else if common < rest.len() && prefix[common] < rest[common] {
+                return child.last(path);
+            }
+        }
+        // The key of the node itself is a prefix of the key, thus less.
+        self.value.as_ref()
+    }
+}
+
+// Deallocates ALL descendants of `node`, with their values dropped.
+fn free_children<V>(node: &mut Node<V>) {
+    for &child in node.children() {
+        let mut child = unsafe { Node::free(child) };
+        free_children(&mut child);
+    }
+    if node.children_len > 0 {
+        free_array_for(node.children, node.children_len);
+    }
+    node.children = ptr::null_mut();
+    node.children_len = 0;
+}
+
+// private methods
+impl<V> RRax<V> {
+    // Finds the node of `key` for read-only lookups.
+    fn find(&self, key: &[u8]) -> Option<&Node<V>> {
+        let mut node = &self.root;
+        let mut rest = key;
+        while let Some(&byte) = rest.first() {
+            let idx = node.search_child(byte).ok()?;
+            let child = unsafe { node.children()[idx].as_ref() };
+            if !rest.starts_with(child.prefix.as_bytes()) {
+                return None;
+            }
+            rest = &rest[child.prefix.len()..];
+            node = child;
+        }
+        Some(node)
+    }
+
+    // Finds the node of `key` to be mutated, with the path of nodes (& indexes of children)
+    // to it, where ALL pointers are derived from the root borrowed mutably.
+    fn find_mut(
+        &mut self,
+        key: &[u8],
+        path: &mut Vec<(NonNull<Node<V>>, usize)>,
+    ) -> Option<NonNull<Node<V>>> {
+        let mut node = NonNull::from(&mut self.root);
+        let mut rest = key;
+        while let Some(&byte) = rest.first() {
+            let idx = unsafe { node.as_ref() }.search_child(byte).ok()?;
+            let child = unsafe { node.as_ref() }.children()[idx];
+            let prefix = unsafe { child.as_ref() }.prefix.as_bytes();
+            if !rest.starts_with(prefix) {
+                return None;
+            }
+            path.push((node, idx));
+            rest = &rest[prefix.len()..];
+            node = child;
+        }
+        Some(node)
+    }
+
+    // Gets the MIN key in the bound, or the MAX key if `rev`.
+    fn seek(&self, bound: Bound<&[u8]>, rev: bool) -> Option<(RString, &V)> {
+        let mut path = RString::new();
+        let value = match (bound, rev) {
+            (Bound::Included(key), false) => self.root.lower_bound(key, true, &mut path),
+            (Bound::Excluded(key), false) => self.root.lower_bound(key, false, &mut path),
+            (Bound::Unbounded, false) => self.root.first(&mut path),
+            (Bound::Included(key), true) => self.root.upper_bound(key, true, &mut path),
+            (Bound::Excluded(key), true) => self.root.upper_bound(key, false, &mut path),
+            (Bound::Unbounded, true) => self.root.last(&mut path),
+        }?;
+        Some((path, value))
+    }
+}
+
+// public methods
+impl<V> RRax<V> {
+    /// Create an empty tree.
+    #[inline]
+    pub fn new() -> Self {
+        RRax {
+            root: Node::new(b"", None),
+            len: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Get number of keys.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Insert `key` with `value` (introduced from Redis `raxInsert`), and return the value
+    /// replaced, or `None` if the key is new.
+    pub fn insert(&mut self, key: &[u8], value: V) -> Option<V> {
+        let mut node = NonNull::from(&mut self.root);
+        let mut rest = key;
+        while let Some(&byte) = rest.first() {
+            let node_mut = unsafe { node.as_mut() };
+            let idx = match node_mut.search_child(byte) {
+                Ok(idx) => idx,
+                Err(idx) => {
+                    node_mut.insert_child(idx, Node::alloc(Node::new(rest, Some(value))));
+                    self.len += 1;
+                    return None;
+                }
+            };
+            let mut child = node_mut.children()[idx];
+            let child_mut = unsafe { child.as_mut() };
+            let common = common_len(child_mut.prefix.as_bytes(), rest);
+            if common < child_mut.prefix.len() {
+                // Splits the child where the key diverges (or ends).
+                let mut mid = Node::new(&rest[..common], None);
+                child_mut.prefix.ltrim(common);
+                child_mut.prefix.shrink_to_fit();
+                mid.insert_child(0, child);
+                child = Node::alloc(mid);
+                node_mut.children_mut()[idx] = child;
+            }
+            node = child;
+            rest = &rest[common..];
+        }
+        let old = unsafe { node.as_mut() }.value.replace(value);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    /// Remove `key` (introduced from Redis `raxRemove`), and return its value, or `None` if
+    /// NOT found.
+    pub fn remove(&mut self, key: &[u8]) -> Option<V> {
+        let mut path = Vec::new();
+        let mut node = self.find_mut(key, &mut path)?;
+        let value = unsafe { node.as_mut() }.value.take()?;
+        self.len -= 1;
+
+        // Removes the node if it's a leaf, and compresses the path left.
+        let mut depth = path.len();
+        if let Some(&(mut parent, idx)) = path.last() {
+            if unsafe { node.as_ref() }.children_len == 0 {
+                let parent_mut = unsafe { parent.as_mut() };
+                drop(unsafe { Node::free(parent_mut.remove_child(idx)) });
+                node = parent;
+                depth -= 1;
+            }
+        }
+        // The root is NEVER merged, as its prefix MUST be empty.
+        let node = unsafe { node.as_mut() };
+        if depth > 0 && node.value.is_none() && node.children_len == 1 {
+            node.merge_child();
+        }
+        Some(value)
+    }
+
+    /// Get the value of `key`, or `None` if NOT found.
+    #[inline]
+    pub fn get(&self, key: &[u8]) -> Option<&V> {
+        self.find(key)?.value.as_ref()
+    }
+
+    /// Get the value of `key` mutably, or `None` if NOT found.
+    #[inline]
+    pub fn get_mut(&mut self, key: &[u8]) -> Option<&mut V> {
+        let mut node = self.find_mut(key, &mut Vec::new())?;
+        unsafe { node.as_mut() }.value.as_mut()
+    }
+
+    #[inline]
+    pub fn contains_key(&self, key: &[u8]) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Get the MIN key & its value.
+    #[inline]
+    pub fn first(&self) -> Option<(RString, &V)> {
+        self.seek(Bound::Unbounded, false)
+    }
+
+    /// Get the MAX key & its value.
+    #[inline]
+    pub fn last(&self) -> Option<(RString, &V)> {
+        self.seek(Bound::Unbounded, true)
+    }
+
+    /// Get an iterator over keys (copied) & values with keys in the range of `start` &
+    /// `end` (introduced from Redis `raxSeek`), in ascending order of keys.
+    #[inline]
+    pub fn range(&self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> RRaxIter<'_, V> {
+        let owned = |bound: Bound<&[u8]>| match bound {
+            Bound::Included(key) => Bound::Included(RString::from_bytes(key)),
+            Bound::Excluded(key) => Bound::Excluded(RString::from_bytes(key)),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+        RRaxIter {
+            rax: self,
+            start: owned(start),
+            end: owned(end),
+        }
+    }
+
+    /// Get an iterator over ALL keys (copied) & values, in ascending order of keys.
+    #[inline]
+    pub fn iter(&self) -> RRaxIter<'_, V> {
+        self.range(Bound::Unbounded, Bound::Unbounded)
+    }
+
+    /// Clear the tree, with ALL nodes deallocated.
+    #[inline]
+    pub fn clear(&mut self) {
+        free_children(&mut self.root);
+        self.root.value = None;
+        self.len = 0;
+    }
+}
+
+impl<V> Drop for RRax<V> {
+    #[inline]
+    fn drop(&mut self) {
+        free_children(&mut self.root);
+    }
+}
+
+impl<V> Default for RRax<V> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V: fmt::Debug> fmt::Debug for RRax<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Iterators
+////////////////////////////////////////////////////////////////////////////////
+
+impl<'a, V> IntoIterator for &'a RRax<V> {
+    type Item = (RString, &'a V);
+    type IntoIter = RRaxIter<'a, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, V> Iterator for RRaxIter<'a, V> {
+    type Item = (RString, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.rax.seek(bound_ref(&self.start), false)?;
+        let within = match &self.end {
+            Bound::Included(end) => key <= *end,
+            Bound::Excluded(end) => key < *end,
+            Bound::Unbounded => true,
+        };
+        if !within {
+            self.end = Bound::Excluded(RString::new());
+            return None;
+        }
+        self.start = Bound::Excluded(key.clone());
+        Some((key, value))
+    }
+}
+
+impl<'a, V> DoubleEndedIterator for RRaxIter<'a, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.rax.seek(bound_ref(&self.end), true)?;
+        let within = match &self.start {
+            Bound::Included(start) => key >= *start,
+            Bound::Excluded(start) => key > *start,
+            Bound::Unbounded => true,
+        };
+        if !within {
+            self.end = Bound::Excluded(RString::new());
+            return None;
+        }
+        self.end = Bound::Excluded(key.clone());
+        Some((key, value))
+    }
+}
+
+impl<'a, V> FusedIterator for RRaxIter<'a, V> {}
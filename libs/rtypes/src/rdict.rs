@@ -0,0 +1,813 @@
+use std::borrow::Borrow;
+use std::fmt::{self, Debug};
+use std::hash::{BuildHasher, Hash};
+use std::iter::{FromIterator, FusedIterator};
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+use std::time::{Duration, Instant};
+
+use rmem::{calloc_array_for, free_array_for, free_for, malloc_for};
+
+use crate::rand::{random_below, random_u64};
+use crate::SipBuildHasher;
+
+////////////////////////////////////////////////////////////////////////////////
+// RDict
+////////////////////////////////////////////////////////////////////////////////
+
+/// MIN number of buckets of a table (introduced from Redis `DICT_HT_INITIAL_SIZE`).
+pub const RDICT_INITIAL_SIZE: usize = 4;
+
+/// Number of buckets rehashed by each step piggybacked on operations.
+const REHASH_STEP_BUCKETS: usize = 1;
+/// Number of buckets rehashed by each batch of `rehash_for`, between checks of time.
+const REHASH_BATCH_BUCKETS: usize = 100;
+/// MAX number of empty buckets visited per bucket rehashed, that a single step is bounded.
+const REHASH_EMPTY_VISITS: usize = 10;
+
+type Link<K, V> = Option<NonNull<Entry<K, V>>>;
+
+struct Entry<K, V> {
+    key: K,
+    value: V,
+    // The hash is cached, that keys are NOT hashed again while rehashed.
+    hash: u64,
+    next: Link<K, V>,
+}
+
+/// Table of buckets, each of which is a chain of entries.
+struct Table<K, V> {
+    buckets: *mut Link<K, V>,
+    size: usize,
+    used: usize,
+}
+
+/// RDict is a hash table (introduced from Redis `dict`) with chained buckets, where
+/// entries & buckets are allocated with `rmem`.
+///
+/// The table is sized to powers of 2, and grown by rehashing incrementally: once it's
+/// grown, a 2nd table is allocated, and buckets of the 1st one are moved to the 2nd one
+/// step by step (with `rehash_step` or `rehash_for`, and piggybacked on operations with
+/// `&mut self`), thus NO single operation pays for rehashing ALL entries.
+///
+/// # Notes
+///
+/// Keys are hashed with SipHash-1-3 keyed by the per-process random key by DEFAULT (see
+/// `SipBuildHasher`), thus hash-flooding (with keys colliding on purpose) is prevented.
+///
+/// Entries are looked up in both tables while rehashing, and new ones are ONLY inserted
+/// into the 2nd one. Lookups with `&self` (such as `get`) do NOT rehash at all.
+///
+/// Iterators borrow the dict, thus NO rehashing (or any modifications) can occur while
+/// iterated over, where entries of both tables are iterated over ALL exactly once.
+///
+/// # Examples
+///
+/// ```
+/// # use rtypes::{RDict, RString};
+///
+/// let mut dict = RDict::new();
+/// for i in 0..5 {
+///     dict.insert(RString::from_i64(i), i);
+/// }
+/// // Grown from 4 to 8 buckets, which is rehashing.
+/// assert!(dict.is_rehashing());
+/// assert_eq!(dict.get(b"3".as_ref()), Some(&3));
+///
+/// while dict.rehash_step(1) {}
+/// assert_eq!(dict.buckets(), 8);
+/// assert_eq!(dict.remove(b"0".as_ref()), Some(0));
+/// assert_eq!(dict.len(), 4);
+/// ```
+pub struct RDict<K, V, S = SipBuildHasher> {
+    tables: [Table<K, V>; 2],
+    // Index of the next bucket of the 1st table to rehash, or `None` if NOT rehashing.
+    rehash_idx: Option<usize>,
+    hasher: S,
+    _marker: PhantomData<Box<Entry<K, V>>>,
+}
+
+/// Iterator over entries of an `RDict`, see `RDict::iter`.
+pub struct RDictIter<'a, K, V> {
+    raw: RawIter<K, V>,
+    _marker: PhantomData<&'a Entry<K, V>>,
+}
+
+/// Iterator over entries (with values mutable) of an `RDict`, see `RDict::iter_mut`.
+pub struct RDictIterMut<'a, K, V> {
+    raw: RawIter<K, V>,
+    _marker: PhantomData<&'a mut Entry<K, V>>,
+}
+
+/// Cursor over entries of both tables, bucket by bucket.
+struct RawIter<K, V> {
+    tables: [(*mut Link<K, V>, usize); 2],
+    table: usize,
+    idx: usize,
+    cur: Link<K, V>,
+    remaining: usize,
+}
+
+// RDict owns its entries exclusively, just like `HashMap<K, V>` does.
+unsafe impl<K: Send, V: Send, S: Send> Send for RDict<K, V, S> {}
+unsafe impl<K: Sync, V: Sync, S: Sync> Sync for RDict<K, V, S> {}
+// Iterators borrow entries of the dict, just like `&RDict` & `&mut RDict` do.
+unsafe impl<'a, K: Sync, V: Sync> Send for RDictIter<'a, K, V> {}
+unsafe impl<'a, K: Sync, V: Sync> Sync for RDictIter<'a, K, V> {}
+unsafe impl<'a, K: Sync, V: Send> Send for RDictIterMut<'a, K, V> {}
+unsafe impl<'a, K: Sync, V: Sync> Sync for RDictIterMut<'a, K, V> {}
+
+impl<K, V> Entry<K, V> {
+    // Allocates an entry unlinked.
+    #[inline]
+    fn alloc(key: K, value: V, hash: u64) -> NonNull<Entry<K, V>> {
+        let entry = NonNull::new(malloc_for::<Entry<K, V>>().0).expect("NULL pointer allocated");
+        unsafe {
+            entry.as_ptr().write(Entry {
+                key,
+                value,
+                hash,
+                next: None,
+            })
+        };
+        entry
+    }
+
+    // Deallocates an entry unlinked, and returns its key & value.
+    #[inline]
+    unsafe fn free(entry: NonNull<Entry<K, V>>) -> (K, V) {
+        let Entry { key, value, .. } = entry.as_ptr().read();
+        free_for::<Entry<K, V>>(entry.as_ptr());
+        (key, value)
+    }
+}
+
+impl<K, V> Table<K, V> {
+    #[inline]
+    const fn empty() -> Self {
+        Table {
+            buckets: std::ptr::null_mut(),
+            size: 0,
+            used: 0,
+        }
+    }
+
+    // Allocates a table with `size` (power of 2) buckets ALL empty.
+    #[inline]
+    fn with_size(size: usize) -> Self {
+        // Zeroed links are `None`, as `Option<NonNull<_>>` is NULL-pointer optimized.
+        let (buckets, size) = calloc_array_for::<Link<K, V>>(size);
+        Table {
+            buckets,
+            size,
+            used: 0,
+        }
+    }
+
+    // Gets the link to the bucket `hash` belongs to, the table MUST be allocated.
+    #[inline]
+    unsafe fn bucket(&self, hash: u64) -> *mut Link<K, V> {
+        self.buckets.add(hash as usize & (self.size - 1))
+    }
+
+    // Deallocates ALL entries & buckets, and resets the table to empty.
+    fn clear(&mut self) {
+        for idx in 0..self.size {
+            let mut cur = unsafe { (*self.buckets.add(idx)).take() };
+            while let Some(entry) = cur {
+                unsafe {
+                    cur = (*entry.as_ptr()).next;
+                    Entry::free(entry);
+                }
+            }
+        }
+        self.free();
+    }
+
+    // Deallocates buckets (entries MUST be moved out already), and resets the table.
+    #[inline]
+    fn free(&mut self) {
+        if !self.buckets.is_null() {
+            free_array_for(self.buckets, self.size);
+        }
+        *self = Table::empty();
+    }
+}
+
+/// Increment the reversed bits of `cursor` masked by `mask`, i.e. from the highest bit
+/// of the mask to the lowest one.
+#[inline]
+fn next_cursor(cursor: u64, mask: u64) -> u64 {
+    // Unmasked bits are set, that the carry is passed through them.
+    let cursor = (cursor | !mask).reverse_bits().wrapping_add(1);
+    cursor.reverse_bits()
+}
+
+// private methods
+impl<K, V, S> RDict<K, V, S> {
+    #[inline]
+    fn raw_iter(&self) -> RawIter<K, V> {
+        RawIter {
+            tables: [
+                (self.tables[0].buckets, self.tables[0].size),
+                (self.tables[1].buckets, self.tables[1].size),
+            ],
+            table: 0,
+            idx: 0,
+            cur: None,
+            remaining: self.len(),
+        }
+    }
+
+    // Allocates a table with `size` (rounded up to power of 2) buckets, which is either
+    // the 1st table (if NOT allocated) or the 2nd one to rehash into.
+    fn resize(&mut self, size: usize) -> bool {
+        if self.is_rehashing() || self.tables[0].used > size {
+            return false;
+        }
+        let size = match size.max(RDICT_INITIAL_SIZE).checked_next_power_of_two() {
+            Some(size) if size != self.tables[0].size => size,
+            _ => return false,
+        };
+
+        if self.tables[0].buckets.is_null() {
+            self.tables[0] = Table::with_size(size);
+        } else {
+            self.tables[1] = Table::with_size(size);
+            self.rehash_idx = Some(0);
+        }
+        true
+    }
+
+    // Grows the table once it's full, i.e. the load factor reaches 1.
+    #[inline]
+    fn grow_if_needed(&mut self) {
+        if self.is_rehashing() {
+            return;
+        }
+        match self.tables[0].size {
+            0 => self.resize(RDICT_INITIAL_SIZE),
+            size if self.tables[0].used >= size => self.resize(self.tables[0].used + 1),
+            _ => false,
+        };
+    }
+
+    // Rehashes a step piggybacked on operations, if rehashing.
+    #[inline]
+    fn rehash_on_op(&mut self) {
+        if self.is_rehashing() {
+            self.rehash_step(REHASH_STEP_BUCKETS);
+        }
+    }
+
+    // Moves ALL entries of the bucket `idx` of the 1st table to the 2nd one.
+    unsafe fn rehash_bucket(&mut self, idx: usize) {
+        let [old, new] = &mut self.tables;
+        let mut cur = (*old.buckets.add(idx)).take();
+        while let Some(entry) = cur {
+            cur = (*entry.as_ptr()).next;
+            let bucket = new.bucket((*entry.as_ptr()).hash);
+            (*entry.as_ptr()).next = *bucket;
+            *bucket = Some(entry);
+            old.used -= 1;
+            new.used += 1;
+        }
+    }
+}
+
+impl<K, V, S> RDict<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    // Finds the entry of `key` in both tables (if rehashing).
+    fn find<Q>(&self, key: &Q) -> Link<K, V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if self.is_empty() {
+            return None;
+        }
+        let hash = self.hasher.hash_one(key);
+        for table in self.tables.iter().take(self.tables_in_use()) {
+            let mut cur = unsafe { *table.bucket(hash) };
+            while let Some(entry) = cur {
+                let entry = unsafe { &*entry.as_ptr() };
+                if entry.hash == hash && entry.key.borrow() == key {
+                    return cur;
+                }
+                cur = entry.next;
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn tables_in_use(&self) -> usize {
+        match self.is_rehashing() {
+            true => 2,
+            false => 1,
+        }
+    }
+}
+
+// public methods
+impl<K, V> RDict<K, V, SipBuildHasher> {
+    /// Create an empty dict hashing keys with SipHash-1-3 & the per-process random key,
+    /// where NO buckets are allocated until the first insertion.
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_hasher(SipBuildHasher::new())
+    }
+}
+
+impl<K, V, S> RDict<K, V, S> {
+    /// Create an empty dict hashing keys with `hasher`.
+    #[inline]
+    pub fn with_hasher(hasher: S) -> Self {
+        RDict {
+            tables: [Table::empty(), Table::empty()],
+            rehash_idx: None,
+            hasher,
+            _marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn hasher(&self) -> &S {
+        &self.hasher
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.tables[0].used + self.tables[1].used
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get number of buckets of both tables (introduced from Redis `dictSlots`).
+    #[inline]
+    pub fn buckets(&self) -> usize {
+        self.tables[0].size + self.tables[1].size
+    }
+
+    #[inline]
+    pub fn is_rehashing(&self) -> bool {
+        self.rehash_idx.is_some()
+    }
+
+    /// Grow (or shrink) the table to hold `size` entries (introduced from Redis
+    /// `dictExpand`), where the number of buckets is rounded up to power of 2, and
+    /// entries are rehashed incrementally.
+    ///
+    /// It returns `false` (and does nothing) if rehashing already, `size` is less than
+    /// the length, or the number of buckets is unchanged.
+    #[inline]
+    pub fn expand(&mut self, size: usize) -> bool {
+        self.resize(size)
+    }
+
+    /// Shrink the table to the MIN number of buckets holding ALL entries (introduced from
+    /// Redis `dictResize`), see `expand`.
+    #[inline]
+    pub fn shrink_to_fit(&mut self) -> bool {
+        self.resize(self.len())
+    }
+
+    /// Rehash (at most) `n` buckets (introduced from Redis `dictRehash`), and return `true`
+    /// if there are still buckets to rehash.
+    ///
+    /// Visits of empty buckets are bounded to `10 * n`, that a step is NEVER blocked
+    /// for long by sparse tables.
+    pub fn rehash_step(&mut self, n: usize) -> bool {
+        let mut idx = match self.rehash_idx {
+            Some(idx) => idx,
+            None => return false,
+        };
+        let mut empty_visits = n.saturating_mul(REHASH_EMPTY_VISITS);
+
+        for _ in 0..n {
+            if self.tables[0].used == 0 {
+                break;
+            }
+            // The 1st table is NOT empty, thus there MUST be buckets NOT rehashed yet.
+            while unsafe { (*self.tables[0].buckets.add(idx)).is_none() } {
+                idx += 1;
+                empty_visits -= 1;
+                if empty_visits == 0 {
+                    self.rehash_idx = Some(idx);
+                    return true;
+                }
+            }
+            unsafe { self.rehash_bucket(idx) };
+            idx += 1;
+        }
+
+        if self.tables[0].used == 0 {
+            self.tables[0].free();
+            self.tables.swap(0, 1);
+            self.rehash_idx = None;
+            false
+        } else {
+            self.rehash_idx = Some(idx);
+            true
+        }
+    }
+
+    /// Rehash buckets in batches for about `duration` (introduced from Redis
+    /// `dictRehashMilliseconds`, such as from a cron task), and return number of buckets
+    /// rehashed (approximately, counted by batches).
+    pub fn rehash_for(&mut self, duration: Duration) -> usize {
+        let start = Instant::now();
+        let mut rehashed = 0;
+        while self.rehash_step(REHASH_BATCH_BUCKETS) {
+            rehashed += REHASH_BATCH_BUCKETS;
+            if start.elapsed() >= duration {
+                break;
+            }
+        }
+        rehashed
+    }
+
+    /// Scan entries of buckets pointed to by `cursor` (introduced from Redis `dictScan`),
+    /// calling `f` on each of them, and return the cursor to scan next, where the scan
+    /// starts with the cursor 0 & ends once 0 is returned.
+    ///
+    /// Cursors are incremented on reversed bits (from the highest bit of the mask), thus
+    /// ALL entries present during the whole scan are returned (at least once), even if
+    /// the table is grown, shrunk or rehashing between calls, while entries MAYBE
+    /// returned more than once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RDict;
+    ///
+    /// let mut dict: RDict<u32, u32> = (0..100).map(|i| (i, i)).collect();
+    /// let mut keys = Vec::new();
+    /// let mut cursor = dict.scan(0, |&key, _| keys.push(key));
+    /// // Grown while scanning.
+    /// dict.extend((100..200).map(|i| (i, i)));
+    /// while cursor != 0 {
+    ///     cursor = dict.scan(cursor, |&key, _| keys.push(key));
+    /// }
+    /// keys.sort_unstable();
+    /// keys.dedup();
+    /// assert!((0..100).all(|key| keys.binary_search(&key).is_ok()));
+    /// ```
+    pub fn scan<F>(&self, cursor: u64, mut f: F) -> u64
+    where
+        F: FnMut(&K, &V),
+    {
+        if self.is_empty() {
+            return 0;
+        }
+        let mut cursor = cursor;
+        let mut scan_bucket = |table: &Table<K, V>, cursor: u64| {
+            let mut cur = unsafe { *table.bucket(cursor) };
+            while let Some(entry) = cur {
+                let entry = unsafe { &*entry.as_ptr() };
+                f(&entry.key, &entry.value);
+                cur = entry.next;
+            }
+        };
+
+        if !self.is_rehashing() {
+            let mask = self.tables[0].size as u64 - 1;
+            scan_bucket(&self.tables[0], cursor);
+            return next_cursor(cursor, mask);
+        }
+
+        // Scan the bucket of the smaller table, then ALL buckets of the larger table
+        // expanded from it, i.e. with the same lower bits.
+        let (small, large) = match self.tables[0].size <= self.tables[1].size {
+            true => (&self.tables[0], &self.tables[1]),
+            false => (&self.tables[1], &self.tables[0]),
+        };
+        let (small_mask, large_mask) = (small.size as u64 - 1, large.size as u64 - 1);
+        scan_bucket(small, cursor);
+        loop {
+            scan_bucket(large, cursor);
+            cursor = next_cursor(cursor, large_mask);
+            if cursor & (small_mask ^ large_mask) == 0 {
+                return cursor;
+            }
+        }
+    }
+
+    /// Get a random entry (introduced from Redis `dictGetRandomKey`), or `None` if empty.
+    ///
+    /// A random bucket NOT empty is picked, then a random entry in its chain, thus
+    /// entries in shorter chains are more likely to be picked.
+    pub fn random_entry(&self) -> Option<(&K, &V)> {
+        if self.is_empty() {
+            return None;
+        }
+        let mut cur = None;
+        while cur.is_none() {
+            cur = unsafe {
+                match self.rehash_idx {
+                    // Buckets of the 1st table before `rehash_idx` are ALL empty.
+                    Some(rehash_idx) => {
+                        let (size0, size1) = (self.tables[0].size, self.tables[1].size);
+                        match rehash_idx + random_below(size0 + size1 - rehash_idx) {
+                            idx if idx >= size0 => *self.tables[1].buckets.add(idx - size0),
+                            idx => *self.tables[0].buckets.add(idx),
+                        }
+                    }
+                    None => *self.tables[0].bucket(random_u64()),
+                }
+            };
+        }
+
+        let mut len = 0;
+        let mut entry = cur;
+        while let Some(next) = entry {
+            len += 1;
+            entry = unsafe { (*next.as_ptr()).next };
+        }
+        let mut entry = cur?;
+        for _ in 0..random_below(len) {
+            entry = unsafe { (*entry.as_ptr()).next? };
+        }
+        let entry = unsafe { &*entry.as_ptr() };
+        Some((&entry.key, &entry.value))
+    }
+
+    /// Remove ALL entries, where ALL buckets are deallocated as well.
+    pub fn clear(&mut self) {
+        self.tables[0].clear();
+        self.tables[1].clear();
+        self.rehash_idx = None;
+    }
+
+    /// Get an iterator over entries, in an arbitrary order.
+    #[inline]
+    pub fn iter(&self) -> RDictIter<'_, K, V> {
+        RDictIter {
+            raw: self.raw_iter(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Get an iterator over entries (with values mutable), in an arbitrary order.
+    #[inline]
+    pub fn iter_mut(&mut self) -> RDictIterMut<'_, K, V> {
+        RDictIterMut {
+            raw: self.raw_iter(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<K, V, S> RDict<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Insert an entry (introduced from Redis `dictReplace`), and return the previous
+    /// value of `key` (where the key is NOT updated), or `None` if it's added.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.rehash_on_op();
+        if let Some(entry) = self.find(&key) {
+            return Some(std::mem::replace(
+                unsafe { &mut (*entry.as_ptr()).value },
+                value,
+            ));
+        }
+
+        self.grow_if_needed();
+        let hash = self.hasher.hash_one(&key);
+        let table = &mut self.tables[self.tables_in_use() - 1];
+        let entry = Entry::alloc(key, value, hash);
+        unsafe {
+            let bucket = table.bucket(hash);
+            (*entry.as_ptr()).next = *bucket;
+            *bucket = Some(entry);
+        }
+        table.used += 1;
+        None
+    }
+
+    #[inline]
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(key)
+            .map(|entry| unsafe { &(*entry.as_ptr()).value })
+    }
+
+    #[inline]
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(key).map(|entry| unsafe {
+            let entry = &*entry.as_ptr();
+            (&entry.key, &entry.value)
+        })
+    }
+
+    #[inline]
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.rehash_on_op();
+        self.find(key)
+            .map(|entry| unsafe { &mut (*entry.as_ptr()).value })
+    }
+
+    #[inline]
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(key).is_some()
+    }
+
+    #[inline]
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    /// Remove the entry of `key` (introduced from Redis `dictDelete`), and return it,
+    /// or `None` if NOT found.
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.rehash_on_op();
+        if self.is_empty() {
+            return None;
+        }
+        let hash = self.hasher.hash_one(key);
+        let tables = self.tables_in_use();
+        for table in self.tables.iter_mut().take(tables) {
+            unsafe {
+                let mut link = table.bucket(hash);
+                while let Some(entry) = *link {
+                    if (*entry.as_ptr()).hash == hash && (*entry.as_ptr()).key.borrow() == key {
+                        *link = (*entry.as_ptr()).next;
+                        table.used -= 1;
+                        return Some(Entry::free(entry));
+                    }
+                    link = &mut (*entry.as_ptr()).next;
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<K, V, S> Drop for RDict<K, V, S> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<K, V, S> Default for RDict<K, V, S>
+where
+    S: Default,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<K, V, S> FromIterator<(K, V)> for RDict<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher + Default,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut dict = RDict::default();
+        dict.extend(iter);
+        dict
+    }
+}
+
+impl<K, V, S> Extend<(K, V)> for RDict<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K, V, S> Debug for RDict<K, V, S>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Iterators
+////////////////////////////////////////////////////////////////////////////////
+
+impl<K, V> RawIter<K, V> {
+    fn next_entry(&mut self) -> Link<K, V> {
+        if self.remaining == 0 {
+            return None;
+        }
+        while self.cur.is_none() {
+            let (buckets, size) = self.tables[self.table];
+            if self.idx < size {
+                self.cur = unsafe { *buckets.add(self.idx) };
+                self.idx += 1;
+            } else {
+                self.table += 1;
+                self.idx = 0;
+            }
+        }
+        self.cur.inspect(|&entry| {
+            self.cur = unsafe { (*entry.as_ptr()).next };
+            self.remaining -= 1;
+        })
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a RDict<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = RDictIter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a mut RDict<K, V, S> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = RDictIterMut<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<'a, K, V> Iterator for RDictIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.raw.next_entry().map(|entry| unsafe {
+            let entry = &*entry.as_ptr();
+            (&entry.key, &entry.value)
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.raw.remaining, Some(self.raw.remaining))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for RDictIter<'a, K, V> {}
+
+impl<'a, K, V> FusedIterator for RDictIter<'a, K, V> {}
+
+impl<'a, K, V> Iterator for RDictIterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.raw.next_entry().map(|entry| unsafe {
+            let entry = &mut *entry.as_ptr();
+            (&entry.key, &mut entry.value)
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.raw.remaining, Some(self.raw.remaining))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for RDictIterMut<'a, K, V> {}
+
+impl<'a, K, V> FusedIterator for RDictIterMut<'a, K, V> {}
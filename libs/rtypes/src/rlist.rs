@@ -1,56 +1,152 @@
-use std::cell::RefCell;
+use std::cmp::Ordering;
 use std::fmt::{Debug, Display, Error, Formatter};
-use std::ops::Range;
-use std::rc::{Rc, Weak};
+use std::iter::{FromIterator, FusedIterator, Rev};
+use std::marker::PhantomData;
+use std::ops::{Bound, Range, RangeBounds};
+use std::ptr::NonNull;
+
+use rmem::{free_for, malloc_for, size_of};
+
+use crate::mem::alloc_size;
+use crate::MemSized;
+
+////////////////////////////////////////////////////////////////////////////////
+// RList
+////////////////////////////////////////////////////////////////////////////////
+
+/// Link to a node, which is NULL (`None`) at both ends of the list.
+type Link<T> = Option<NonNull<Node<T>>>;
 
 struct Node<T>
 where
     T: Copy + Clone,
 {
-    pub data: T,
-    pub prev: Option<Weak<RefCell<Node<T>>>>,
-    pub next: Option<Rc<RefCell<Node<T>>>>,
+    data: T,
+    prev: Link<T>,
+    next: Link<T>,
 }
 
+/// RList is a doubly linked list (introduced from Redis `adlist`), where nodes are
+/// allocated with `rmem` & linked with raw pointers.
+///
+/// # Notes
+///
+/// Nodes are owned by the list exclusively, that they're NEVER shared or linked across
+/// lists, thus NO cycles can be made (and nodes are ALL deallocated once the list dropped).
 pub struct RList<T>
 where
     T: Copy + Clone,
 {
-    head: Option<Rc<RefCell<Node<T>>>>,
-    tail: Option<Rc<RefCell<Node<T>>>>,
+    head: Link<T>,
+    tail: Link<T>,
     len: usize,
+    notifier: Option<Box<dyn ReadyNotifier>>,
+    max_len: Option<MaxLen>,
+    _marker: PhantomData<Box<Node<T>>>,
+}
+
+/// End of an `RList`, i.e. LEFT (the front) or RIGHT (the back) of Redis lists.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum End {
+    Front,
+    Back,
+}
+
+/// MAX length of a capped `RList` (see `RList::set_max_len`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MaxLen {
+    /// The length NEVER exceeds the cap.
+    Exact(usize),
+    /// The length MAYBE exceeds the cap by (at most) 1/8, and it's trimmed down to the cap
+    /// once exceeded, that evictions are batched.
+    Approx(usize),
+}
+
+/// Ratio of the cap a list capped approximately MAYBE exceeds by, i.e. 1/8.
+const APPROX_MAX_LEN_SLACK: usize = 8;
+
+/// ReadyNotifier is notified once elements are pushed into an `RList` (see `RList::on_push`),
+/// e.g. for blocking pops (BLPOP) to wake clients parked on the list, WITHOUT polling.
+///
+/// # Notes
+///
+/// It's called while the list is borrowed mutably (by the push), thus it MUST NOT access
+/// the list, but SHOULD defer the pop (such as scheduling the client woken).
+pub trait ReadyNotifier: Send + Sync {
+    /// Notify that an element has been pushed, where `len` is the length after pushed.
+    fn notify_ready(&self, len: usize);
+}
+
+impl<F> ReadyNotifier for F
+where
+    F: Fn(usize) + Send + Sync,
+{
+    #[inline]
+    fn notify_ready(&self, len: usize) {
+        self(len)
+    }
 }
 
-pub struct Iter<T>
+/// Iterator over elements (copied) of an `RList`, from the front or the back.
+pub struct RListIter<'a, T>
 where
     T: Copy + Clone,
 {
-    head: Option<Rc<RefCell<Node<T>>>>,
-    tail: Option<Rc<RefCell<Node<T>>>>,
+    head: Link<T>,
+    tail: Link<T>,
     len: usize,
+    _marker: PhantomData<&'a Node<T>>,
+}
+
+/// Iterator removing elements in a range from an `RList`, see `RList::drain`.
+pub struct RListDrain<'a, T>
+where
+    T: Copy + Clone,
+{
+    list: &'a mut RList<T>,
+    cur: Link<T>,
+    remaining: usize,
 }
 
+/// Iterator moving elements out of an `RList`, from the front or the back.
+pub struct RListIntoIter<T>
+where
+    T: Copy + Clone,
+{
+    list: RList<T>,
+}
+
+// RList owns its nodes exclusively, just like `LinkedList<T>` does.
+unsafe impl<T: Copy + Clone + Send> Send for RList<T> {}
+unsafe impl<T: Copy + Clone + Sync> Sync for RList<T> {}
+// RListIter borrows nodes of the list shared, just like `&RList<T>` does.
+unsafe impl<'a, T: Copy + Clone + Sync> Send for RListIter<'a, T> {}
+unsafe impl<'a, T: Copy + Clone + Sync> Sync for RListIter<'a, T> {}
+
 impl<T> Node<T>
 where
     T: Copy + Clone,
 {
-    // Constructs a node with some `data` initializing prev and next to null.
-    pub fn new(data: T) -> Self {
-        Self {
-            data,
-            prev: None,
-            next: None,
-        }
+    // Allocates a node with some `data` initializing prev and next to null.
+    #[inline]
+    fn alloc(data: T) -> NonNull<Node<T>> {
+        let node = NonNull::new(malloc_for::<Node<T>>().0).expect("NULL pointer allocated");
+        unsafe {
+            node.as_ptr().write(Node {
+                data,
+                prev: None,
+                next: None,
+            })
+        };
+        node
     }
 
-    pub fn append(cur: &mut Rc<RefCell<Node<T>>>, node: Node<T>) {
-        let wrap = Rc::new(RefCell::new(node));
-        if let Some(next) = cur.clone().borrow().next.clone() {
-            wrap.borrow_mut().next = Some(next.clone());
-            next.borrow_mut().prev = Some(Rc::downgrade(&wrap));
-        }
-        cur.borrow_mut().next = Some(wrap.clone());
-        wrap.borrow_mut().prev = Some(Rc::downgrade(&cur));
+    // Deallocates a node unlinked, and returns its data.
+    #[inline]
+    unsafe fn free(node: NonNull<Node<T>>) -> T {
+        let data = (*node.as_ptr()).data;
+        free_for::<Node<T>>(node.as_ptr());
+        data
     }
 }
 
@@ -59,115 +155,144 @@ impl<T> RList<T>
 where
     T: Copy + Clone,
 {
-    fn push_front_node(&mut self, node: Node<T>) {
-        let wrap = Rc::new(RefCell::new(node));
-        if let Some(ref mut head) = self.head {
-            wrap.borrow_mut().next = Some(head.clone());
-            head.borrow_mut().prev = Some(Rc::downgrade(&wrap));
-        } else {
-            self.tail = Some(wrap.clone());
+    fn push_front_node(&mut self, node: NonNull<Node<T>>) {
+        unsafe {
+            (*node.as_ptr()).next = self.head;
+            (*node.as_ptr()).prev = None;
+            match self.head {
+                Some(head) => (*head.as_ptr()).prev = Some(node),
+                None => self.tail = Some(node),
+            }
         }
-        self.head = Some(wrap);
+        self.head = Some(node);
         self.len += 1;
+        self.notify_ready();
     }
 
-    fn pop_front_node(&mut self) -> Option<T> {
-        self.head.take().map(|head| {
-            self.len -= 1;
-            if let Some(next) = head.borrow_mut().next.clone() {
-                next.borrow_mut().prev = None;
-                self.head = Some(next);
-            } else {
-                self.tail = None;
+    fn pop_front_node(&mut self) -> Option<NonNull<Node<T>>> {
+        self.head.inspect(|&head| unsafe {
+            self.head = (*head.as_ptr()).next;
+            match self.head {
+                Some(next) => (*next.as_ptr()).prev = None,
+                None => self.tail = None,
             }
-            head.borrow_mut().next = None;
-            head.borrow().data
+            self.len -= 1;
         })
     }
 
-    fn push_back_node(&mut self, node: Node<T>) {
-        let wrap = Rc::new(RefCell::new(node));
-        if let Some(ref mut tail) = self.tail {
-            tail.borrow_mut().next = Some(wrap.clone());
-            wrap.borrow_mut().prev = Some(Rc::downgrade(&tail));
-        } else {
-            self.head = Some(wrap.clone());
+    fn push_back_node(&mut self, node: NonNull<Node<T>>) {
+        unsafe {
+            (*node.as_ptr()).prev = self.tail;
+            (*node.as_ptr()).next = None;
+            match self.tail {
+                Some(tail) => (*tail.as_ptr()).next = Some(node),
+                None => self.head = Some(node),
+            }
         }
-        self.tail = Some(wrap);
+        self.tail = Some(node);
         self.len += 1;
+        self.notify_ready();
     }
 
-    fn pop_back_node(&mut self) -> Option<T> {
-        self.tail.take().map(|tail| {
-            self.len -= 1;
-            if let Some(prev) = tail
-                .borrow_mut()
-                .prev
-                .clone()
-                .and_then(|weak| weak.upgrade())
-            {
-                prev.borrow_mut().next = None;
-                self.tail = Some(prev);
-            } else {
-                self.head = None;
+    fn pop_back_node(&mut self) -> Option<NonNull<Node<T>>> {
+        self.tail.inspect(|&tail| unsafe {
+            self.tail = (*tail.as_ptr()).prev;
+            match self.tail {
+                Some(prev) => (*prev.as_ptr()).next = None,
+                None => self.head = None,
             }
-            tail.borrow_mut().prev = None;
-            tail.borrow().data
+            self.len -= 1;
         })
     }
 
-    fn find_node(&self, idx: usize) -> Option<Rc<RefCell<Node<T>>>> {
-        let full = self.len;
-        let half = full / 2;
-        match idx {
-            n if n <= half => {
-                let mut cur = self.head.clone();
+    // Finds the node at `idx`, walking from the nearer end.
+    fn find_node(&self, idx: usize) -> Link<T> {
+        if idx >= self.len {
+            return None;
+        }
+        unsafe {
+            if idx <= self.len / 2 {
+                let mut cur = self.head;
                 for _ in 0..idx {
-                    if let Some(node) = cur.clone() {
-                        cur = node.borrow().next.clone();
-                    } else {
-                        return None;
-                    }
+                    cur = cur.and_then(|node| (*node.as_ptr()).next);
                 }
                 cur
-            }
-
-            _ => {
-                let mut cur = self.tail.clone();
-                for _ in idx..full {
-                    if let Some(node) = cur.clone() {
-                        cur = node.borrow().prev.clone().and_then(|weak| weak.upgrade());
-                    } else {
-                        return None;
-                    }
+            } else {
+                let mut cur = self.tail;
+                for _ in idx + 1..self.len {
+                    cur = cur.and_then(|node| (*node.as_ptr()).prev);
                 }
                 cur
             }
         }
     }
 
-    fn insert(&mut self, idx: usize, node: Node<T>) {
+    // Links `node` right after `cur`, which MUST be linked to the list.
+    unsafe fn link_after(&mut self, cur: NonNull<Node<T>>, node: NonNull<Node<T>>) {
+        let next = (*cur.as_ptr()).next;
+        (*node.as_ptr()).prev = Some(cur);
+        (*node.as_ptr()).next = next;
+        match next {
+            Some(next) => (*next.as_ptr()).prev = Some(node),
+            None => self.tail = Some(node),
+        }
+        (*cur.as_ptr()).next = Some(node);
+        self.len += 1;
+        self.notify_ready();
+    }
+
+    // Links a chain of `count` nodes from `first` to `last` (linked with each other already)
+    // to the back, and notifies ONLY once.
+    unsafe fn push_back_chain(
+        &mut self,
+        first: NonNull<Node<T>>,
+        last: NonNull<Node<T>>,
+        count: usize,
+    ) {
+        (*first.as_ptr()).prev = self.tail;
+        (*last.as_ptr()).next = None;
+        match self.tail {
+            Some(tail) => (*tail.as_ptr()).next = Some(first),
+            None => self.head = Some(first),
+        }
+        self.tail = Some(last);
+        self.len += count;
+        self.notify_ready();
+    }
+
+    #[inline]
+    fn notify_ready(&self) {
+        if let Some(notifier) = &self.notifier {
+            notifier.notify_ready(self.len);
+        }
+    }
+
+    // Unlinks `node` from the list, which MUST be linked to the list.
+    unsafe fn unlink_node(&mut self, node: NonNull<Node<T>>) {
+        let Node { prev, next, .. } = *node.as_ptr();
+        match prev {
+            Some(prev) => (*prev.as_ptr()).next = next,
+            None => self.head = next,
+        }
+        match next {
+            Some(next) => (*next.as_ptr()).prev = prev,
+            None => self.tail = prev,
+        }
+        self.len -= 1;
+    }
+
+    fn insert(&mut self, idx: usize, node: NonNull<Node<T>>) {
         let full = self.len;
         match idx {
             0 => self.push_front_node(node),
-            n if n > 0 && n < full => {
-                let cur = self.find_node(idx - 1);
-                if let Some(mut cur) = cur {
-                    Node::append(&mut cur, node);
-                    self.len += 1
+            n if n < full => {
+                if let Some(cur) = self.find_node(idx - 1) {
+                    unsafe { self.link_after(cur, node) };
                 }
             }
             _ => self.push_back_node(node),
         }
     }
-
-    fn iter(&self) -> Iter<T> {
-        Iter {
-            head: self.head.clone(),
-            tail: self.tail.clone(),
-            len: self.len,
-        }
-    }
 }
 
 // public methods
@@ -181,110 +306,319 @@ where
             head: None,
             tail: None,
             len: 0,
+            notifier: None,
+            max_len: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Register `notifier` to be notified once elements are pushed (by ANY pushes &
+    /// insertions, including elements moved in with `move_element`), which replaces
+    /// the one registered before (if any).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RList;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// let ready = Arc::new(AtomicUsize::new(0));
+    /// let mut list = RList::new();
+    /// let notified = Arc::clone(&ready);
+    /// list.on_push(move |len| notified.store(len, Ordering::Release));
+    ///
+    /// list.push_back(1);
+    /// list.push_front(0);
+    /// assert_eq!(ready.load(Ordering::Acquire), 2);
+    /// ```
+    pub fn on_push<N>(&mut self, notifier: N)
+    where
+        N: ReadyNotifier + 'static,
+    {
+        self.notifier = Some(Box::new(notifier));
+    }
+
+    /// Cap the length of the list (or uncap it with `None`), where pushes beyond the cap
+    /// evict elements from the opposite end automatically (just like LPUSH + LTRIM), and
+    /// insertions (in the middle) evict elements from the back.
+    ///
+    /// Elements beyond the cap (if any) are evicted from the back at once.
+    ///
+    /// # Notes
+    ///
+    /// Insertions with a `CursorMut` evict elements as well, where the cursor is moved to
+    /// the ghost position if the element pointed to is evicted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::{MaxLen, RList};
+    ///
+    /// let mut list = RList::new();
+    /// list.set_max_len(Some(MaxLen::Exact(3)));
+    /// for i in 0..5 {
+    ///     list.push_front(i);
+    /// }
+    /// assert_eq!(list.to_vec(), vec![4, 3, 2]);
+    /// list.push_back(5);
+    /// assert_eq!(list.to_vec(), vec![3, 2, 5]);
+    /// ```
+    pub fn set_max_len(&mut self, max_len: Option<MaxLen>) {
+        self.max_len = max_len;
+        self.evict(End::Back);
+    }
+
+    /// Get the cap of the length, or `None` if uncapped.
+    #[inline]
+    pub fn max_len(&self) -> Option<MaxLen> {
+        self.max_len
+    }
+
+    /// Get number of elements to evict, since the length exceeds the cap.
+    #[inline]
+    fn eviction(&self) -> Option<usize> {
+        let (limit, keep) = match self.max_len? {
+            MaxLen::Exact(cap) => (cap, cap),
+            MaxLen::Approx(cap) => (cap.saturating_add(cap / APPROX_MAX_LEN_SLACK), cap),
+        };
+        match self.len > limit {
+            true => Some(self.len - keep),
+            false => None,
+        }
+    }
+
+    /// Evict elements from `end` if the length exceeds the cap.
+    #[inline]
+    fn evict(&mut self, end: End) {
+        if let Some(count) = self.eviction() {
+            match end {
+                End::Front => self.trim(count..self.len),
+                End::Back => self.trim(0..self.len - count),
+            }
         }
     }
 
+    /// Unregister & return the notifier registered (if any).
+    #[inline]
+    pub fn take_notifier(&mut self) -> Option<Box<dyn ReadyNotifier>> {
+        self.notifier.take()
+    }
+
     pub fn push_front(&mut self, data: T) {
-        let node = Node::new(data);
+        let node = Node::alloc(data);
         self.push_front_node(node);
+        self.evict(End::Back);
     }
 
     pub fn pop_front(&mut self) -> Option<T> {
         self.pop_front_node()
+            .map(|node| unsafe { Node::free(node) })
     }
 
     pub fn push_back(&mut self, data: T) {
-        let node = Node::new(data);
+        let node = Node::alloc(data);
         self.push_back_node(node);
+        self.evict(End::Front);
     }
 
     pub fn pop_back(&mut self) -> Option<T> {
-        self.pop_back_node()
+        self.pop_back_node().map(|node| unsafe { Node::free(node) })
     }
 
     pub fn front(&self) -> Option<T> {
-        self.head.clone().map(|node| node.borrow().data)
+        self.head.map(|node| unsafe { (*node.as_ptr()).data })
     }
 
     pub fn back(&self) -> Option<T> {
-        self.tail.clone().map(|node| node.borrow().data)
+        self.tail.map(|node| unsafe { (*node.as_ptr()).data })
     }
 
     pub fn insert_before(&mut self, idx: usize, data: T) {
-        let node = Node::new(data);
+        let node = Node::alloc(data);
         self.insert(idx, node);
+        self.evict(End::Back);
     }
 
     pub fn insert_after(&mut self, idx: usize, data: T) {
-        let node = Node::new(data);
+        let node = Node::alloc(data);
         self.insert(idx + 1, node);
+        self.evict(End::Back);
     }
 
-    pub fn range(&self, r: Range<usize>) -> Vec<T> {
-        let len = self.len;
-        let Range { start, mut end } = r;
-        if len == 0 {
-            return Vec::<T>::new();
+    /// Push ALL elements of `slice` to the back in order (introduced from Redis RPUSH with
+    /// multiple elements), where the chain of new nodes is linked to the list at once, thus
+    /// the notifier (see `on_push`) is notified ONLY once & the cap (see `set_max_len`) is
+    /// applied ONLY once.
+    ///
+    /// # Notes
+    ///
+    /// Nodes are STILL allocated one by one (just like `push_back`), rather than from a slab
+    /// (or an `ObjectPool` of the list), as they're freed one by one once popped or removed,
+    /// and moved to other lists (see `move_element` & `split_at`), thus it's NOT cheaper
+    /// than pushing elements in a loop.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RList;
+    ///
+    /// let mut list = RList::from(vec![1, 2]);
+    /// list.extend_from_slice(&[3, 4, 5]);
+    /// assert_eq!(list.to_vec(), vec![1, 2, 3, 4, 5]);
+    /// ```
+    pub fn extend_from_slice(&mut self, slice: &[T]) {
+        let (first, rest) = match slice.split_first() {
+            Some((&first, rest)) => (Node::alloc(first), rest),
+            None => return,
+        };
+        let mut last = first;
+        for &data in rest {
+            let node = Node::alloc(data);
+            unsafe {
+                (*node.as_ptr()).prev = Some(last);
+                (*last.as_ptr()).next = Some(node);
+            }
+            last = node;
         }
-        if end >= len {
-            end = len;
+        unsafe { self.push_back_chain(first, last, slice.len()) };
+        self.evict(End::Front);
+    }
+
+    /// Get an iterator over elements (copied) of the list, from the front to the back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RList;
+    ///
+    /// let list: RList<i32> = (1..=3).collect();
+    /// assert_eq!(list.iter().rev().collect::<Vec<_>>(), vec![3, 2, 1]);
+    /// assert_eq!(list.iter().len(), 3);
+    /// ```
+    pub fn iter(&self) -> RListIter<'_, T> {
+        RListIter {
+            head: self.head,
+            tail: self.tail,
+            len: self.len,
+            _marker: PhantomData,
         }
-        self.iter().skip(start).take(end - start).collect()
+    }
+
+    /// Get an iterator over elements (copied) of the list, from the back to the front.
+    #[inline]
+    pub fn iter_rev(&self) -> Rev<RListIter<'_, T>> {
+        self.iter().rev()
+    }
+
+    /// Reverse the list in place, in O(n) time WITHOUT any (re)allocations, where links
+    /// of each node are swapped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RList;
+    ///
+    /// let mut list: RList<i32> = (1..=4).collect();
+    /// list.reverse();
+    /// assert_eq!(list.to_vec(), vec![4, 3, 2, 1]);
+    /// assert_eq!(list.iter_rev().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+    /// ```
+    pub fn reverse(&mut self) {
+        let mut cur = self.head;
+        while let Some(node) = cur {
+            unsafe {
+                let node = &mut *node.as_ptr();
+                std::mem::swap(&mut node.prev, &mut node.next);
+                cur = node.prev;
+            }
+        }
+        std::mem::swap(&mut self.head, &mut self.tail);
+    }
+
+    /// Get elements in the range `r` (clamped to the length), where the first element
+    /// is found walking from the nearer end.
+    pub fn range(&self, r: Range<usize>) -> Vec<T> {
+        let end = std::cmp::min(r.end, self.len);
+        let count = end.saturating_sub(r.start);
+        let mut vec = Vec::with_capacity(count);
+        let mut cur = match count {
+            0 => None,
+            _ => self.find_node(r.start),
+        };
+        for _ in 0..count {
+            if let Some(node) = cur {
+                unsafe {
+                    vec.push((*node.as_ptr()).data);
+                    cur = (*node.as_ptr()).next;
+                }
+            }
+        }
+        vec
     }
 
     pub fn to_vec(&self) -> Vec<T> {
-        self.iter().collect()
+        let mut vec = Vec::with_capacity(self.len);
+        vec.extend(self.iter());
+        vec
     }
 
     pub fn get(&self, idx: usize) -> Option<T> {
-        if idx >= self.len {
-            None
-        } else {
-            self.iter().nth(idx)
-        }
+        self.find_node(idx)
+            .map(|node| unsafe { (*node.as_ptr()).data })
     }
 
     pub fn set(&mut self, idx: usize, val: T) -> Option<T> {
-        if idx >= self.len {
-            None
-        } else {
-            let cur = self.find_node(idx);
-            cur.map(|node| {
-                node.replace_with(|old_node| {
-                    let mut new_node = Node::new(val);
-                    new_node.prev = old_node.prev.take();
-                    new_node.next = old_node.next.take();
-                    new_node
-                })
-            })
-            .map(|node| node.data)
-        }
+        self.find_node(idx)
+            .map(|node| unsafe { std::mem::replace(&mut (*node.as_ptr()).data, val) })
     }
 
     pub fn remove(&mut self, idx: usize) -> Option<T> {
-        let full = self.len - 1;
-        match idx {
-            0 => self.pop_front(),
-            n if n > full => None,
-            n if n == full => self.pop_back(),
-            _ => {
-                let cur = self.find_node(idx);
-                match cur {
-                    Some(cur) => {
-                        let next = cur.borrow().next.clone();
-                        let prev = cur.borrow().prev.clone().and_then(|weak| weak.upgrade());
-                        match (prev, next) {
-                            (Some(prev), Some(next)) => {
-                                prev.borrow_mut().next = Some(next.clone());
-                                next.borrow_mut().prev = Some(Rc::downgrade(&prev));
-                                Some(cur.borrow().data)
-                            }
-                            _ => None,
-                        }
-                    }
-                    None => None,
+        self.find_node(idx).map(|node| unsafe {
+            self.unlink_node(node);
+            Node::free(node)
+        })
+    }
+
+    /// Swap elements at `i` & `j` (payloads swapped, nodes NOT relinked), and return
+    /// `false` if either is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RList;
+    ///
+    /// let mut list: RList<i32> = (0..5).collect();
+    /// assert!(list.swap(0, 3));
+    /// assert!(!list.swap(1, 5));
+    /// assert_eq!(list.to_vec(), vec![3, 1, 2, 0, 4]);
+    /// assert_eq!(list.swap_remove_back(1), Some(1));
+    /// assert_eq!(list.to_vec(), vec![3, 4, 2, 0]);
+    /// ```
+    pub fn swap(&mut self, i: usize, j: usize) -> bool {
+        match (self.find_node(i), self.find_node(j)) {
+            (Some(a), Some(b)) => {
+                if a != b {
+                    unsafe { std::ptr::swap(&mut (*a.as_ptr()).data, &mut (*b.as_ptr()).data) };
                 }
+                true
             }
+            _ => false,
+        }
+    }
+
+    /// Remove the element at `idx` & replace it with the back one, and return the element
+    /// removed, or `None` if out of range.
+    ///
+    /// It does NOT preserve the order, but ONLY one node at the back is unlinked, that it's
+    /// O(1) for elements at either end.
+    pub fn swap_remove_back(&mut self, idx: usize) -> Option<T> {
+        let node = self.find_node(idx)?;
+        let back = self.pop_back_node()?;
+        let data = unsafe { Node::free(back) };
+        match node == back {
+            true => Some(data),
+            false => Some(unsafe { std::mem::replace(&mut (*node.as_ptr()).data, data) }),
         }
     }
 
@@ -302,6 +636,103 @@ where
         }
     }
 
+    /// Get the element at the signed `idx` (introduced from Redis LINDEX), where negative
+    /// indexes count from the back, i.e. -1 is the last element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RList;
+    ///
+    /// let mut list: RList<i32> = (0..5).collect();
+    /// assert_eq!(list.get_signed(-1), Some(4));
+    /// assert_eq!(list.get_signed(-6), None);
+    /// assert_eq!(list.range_signed(-3, -1), vec![2, 3, 4]);
+    /// list.trim_signed(1, -2);
+    /// assert_eq!(list.to_vec(), vec![1, 2, 3]);
+    /// ```
+    #[inline]
+    pub fn get_signed(&self, idx: isize) -> Option<T> {
+        self.index_of(idx).and_then(|idx| self.get(idx))
+    }
+
+    /// Set the element at the signed `idx` (introduced from Redis LSET), and return
+    /// the previous one, or `None` if out of range.
+    #[inline]
+    pub fn set_signed(&mut self, idx: isize, val: T) -> Option<T> {
+        self.index_of(idx).and_then(|idx| self.set(idx, val))
+    }
+
+    /// Insert an element before the one at the signed `idx`, where `idx` out of range
+    /// is clamped to the front or the back (just like `insert_before` does).
+    #[inline]
+    pub fn insert_before_signed(&mut self, idx: isize, data: T) {
+        self.insert_before(self.clamp_index(idx), data);
+    }
+
+    /// Insert an element after the one at the signed `idx`, where `idx` out of range
+    /// is clamped to the front or the back (just like `insert_after` does).
+    #[inline]
+    pub fn insert_after_signed(&mut self, idx: isize, data: T) {
+        match idx {
+            idx if idx < 0 && idx.unsigned_abs() > self.len => self.push_front(data),
+            idx => self.insert_after(self.clamp_index(idx), data),
+        }
+    }
+
+    /// Get elements in the inclusive range `[start, stop]` of signed indexes (introduced
+    /// from Redis LRANGE), where the range is clamped to the list.
+    pub fn range_signed(&self, start: isize, stop: isize) -> Vec<T> {
+        match self.inclusive_range(start, stop) {
+            Some(Range { start, end }) => self.range(start..end),
+            None => Vec::new(),
+        }
+    }
+
+    /// Trim the list to the inclusive range `[start, stop]` of signed indexes (introduced
+    /// from Redis LTRIM), where ALL elements are removed if the range is empty.
+    pub fn trim_signed(&mut self, start: isize, stop: isize) {
+        match self.inclusive_range(start, stop) {
+            Some(r) => self.trim(r),
+            None => self.drain(..).for_each(drop),
+        }
+    }
+
+    /// Convert the signed `idx` into the index counted from the front, or `None` if it's
+    /// out of range.
+    #[inline]
+    fn index_of(&self, idx: isize) -> Option<usize> {
+        match idx {
+            idx if idx >= 0 => Some(idx as usize),
+            idx => self.len.checked_sub(idx.unsigned_abs()),
+        }
+    }
+
+    /// Convert the signed `idx` into the index counted from the front, clamped to
+    /// [0, len].
+    #[inline]
+    fn clamp_index(&self, idx: isize) -> usize {
+        match idx {
+            idx if idx >= 0 => std::cmp::min(idx as usize, self.len),
+            idx => self.len.saturating_sub(idx.unsigned_abs()),
+        }
+    }
+
+    /// Convert the inclusive range `[start, stop]` of signed indexes into the range
+    /// counted from the front (clamped to the list), or `None` if it's empty.
+    fn inclusive_range(&self, start: isize, stop: isize) -> Option<Range<usize>> {
+        let start = self.clamp_index(start);
+        let end = match stop {
+            stop if stop >= 0 => (stop as usize).checked_add(1)?,
+            stop => self.len.checked_sub(stop.unsigned_abs())? + 1,
+        };
+        let end = std::cmp::min(end, self.len);
+        match start < end {
+            true => Some(start..end),
+            false => None,
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         self.head.is_none()
     }
@@ -309,6 +740,718 @@ where
     pub fn len(&self) -> usize {
         self.len
     }
+
+    /// Move the element at `from_end` of the list to `to_end` of `dst` (introduced from
+    /// Redis LMOVE & RPOPLPUSH), and return the element moved, or `None` if the list
+    /// is empty.
+    ///
+    /// The node is relinked into `dst` as is, WITHOUT any (re)allocations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::{End, RList};
+    ///
+    /// let mut src: RList<i32> = (1..=3).collect();
+    /// let mut dst: RList<i32> = RList::new();
+    /// assert_eq!(src.move_element(&mut dst, End::Back, End::Front), Some(3));
+    /// assert_eq!(src.move_element(&mut dst, End::Back, End::Front), Some(2));
+    /// assert_eq!(dst.to_vec(), vec![2, 3]);
+    /// ```
+    pub fn move_element(&mut self, dst: &mut RList<T>, from_end: End, to_end: End) -> Option<T> {
+        let node = match from_end {
+            End::Front => self.pop_front_node(),
+            End::Back => self.pop_back_node(),
+        }?;
+        let data = unsafe { (*node.as_ptr()).data };
+        match to_end {
+            End::Front => {
+                dst.push_front_node(node);
+                dst.evict(End::Back);
+            }
+            End::Back => {
+                dst.push_back_node(node);
+                dst.evict(End::Front);
+            }
+        }
+        Some(data)
+    }
+
+    /// Split the list into two at `idx` (clamped to the length), i.e. elements in [0, idx)
+    /// & [idx, len), in O(min(idx, len - idx)) time WITHOUT any (re)allocations.
+    ///
+    /// # Notes
+    ///
+    /// Neither the cap (see `set_max_len`) nor the notifier (see `on_push`) is kept
+    /// by the lists split.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RList;
+    ///
+    /// let list: RList<i32> = (0..5).collect();
+    /// let (front, back) = list.split_at(2);
+    /// assert_eq!(front.to_vec(), vec![0, 1]);
+    /// assert_eq!(back.to_vec(), vec![2, 3, 4]);
+    /// ```
+    pub fn split_at(mut self, idx: usize) -> (RList<T>, RList<T>) {
+        let mut front = RList::new();
+        let mut back = RList::new();
+        let idx = std::cmp::min(idx, self.len);
+
+        match self.find_node(idx) {
+            Some(node) => unsafe {
+                let prev = (*node.as_ptr()).prev.take();
+                if let Some(prev) = prev {
+                    (*prev.as_ptr()).next = None;
+                }
+                front.head = prev.and(self.head);
+                front.tail = prev;
+                front.len = idx;
+                back.head = Some(node);
+                back.tail = self.tail;
+                back.len = self.len - idx;
+            },
+            None => {
+                front.head = self.head;
+                front.tail = self.tail;
+                front.len = self.len;
+            }
+        }
+        // ALL nodes are owned by the lists split.
+        self.head = None;
+        self.tail = None;
+        self.len = 0;
+        (front, back)
+    }
+
+    /// Retain ONLY elements which `f` returns `true` for, where ALL others are removed
+    /// in a single pass from the front to the back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RList;
+    ///
+    /// let mut list: RList<i32> = (1..=6).collect();
+    /// list.retain(|&data| data % 3 != 0);
+    /// assert_eq!(list.to_vec(), vec![1, 2, 4, 5]);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut cur = self.head;
+        while let Some(node) = cur {
+            unsafe {
+                cur = (*node.as_ptr()).next;
+                if !f(&(*node.as_ptr()).data) {
+                    self.unlink_node(node);
+                    Node::free(node);
+                }
+            }
+        }
+    }
+
+    /// Remove elements in the range `r` (clamped to the length), and return an iterator
+    /// over them, where the elements are removed as iterated over, and ALL the rest
+    /// are removed once the iterator dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RList;
+    ///
+    /// let mut list: RList<i32> = (0..6).collect();
+    /// assert_eq!(list.drain(1..3).collect::<Vec<_>>(), vec![1, 2]);
+    /// assert_eq!(list.drain(2..).count(), 2);
+    /// assert_eq!(list.to_vec(), vec![0, 3]);
+    /// ```
+    pub fn drain<R>(&mut self, r: R) -> RListDrain<'_, T>
+    where
+        R: RangeBounds<usize>,
+    {
+        let len = self.len;
+        let start = match r.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match r.end_bound() {
+            Bound::Included(&end) => end.saturating_add(1),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => len,
+        };
+        let end = std::cmp::min(end, len);
+        let remaining = end.saturating_sub(start);
+
+        RListDrain {
+            cur: if remaining > 0 {
+                self.find_node(start)
+            } else {
+                None
+            },
+            remaining,
+            list: self,
+        }
+    }
+
+    /// Sort the list with the comparator `cmp`, which is stable (i.e. elements equal
+    /// are NOT reordered), in O(n * log(n)) time & O(1) space.
+    ///
+    /// It's a bottom-up merge sort over links, that nodes are relinked in order
+    /// WITHOUT elements copied or moved.
+    ///
+    /// # Notes
+    ///
+    /// The list is kept valid (with ALL elements in some order) if `cmp` panics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RList;
+    ///
+    /// let mut list: RList<(i32, char)> = vec![(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd')]
+    ///     .into_iter()
+    ///     .collect();
+    /// list.sort_by(|a, b| a.0.cmp(&b.0));
+    /// assert_eq!(list.to_vec(), vec![(1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]);
+    /// ```
+    pub fn sort_by<F>(&mut self, mut cmp: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        if self.len < 2 {
+            return;
+        }
+
+        let mut sort = MergeSort {
+            q: self.head,
+            list: self,
+            merged: None,
+            tail: None,
+            p: None,
+            psize: 0,
+        };
+        sort.run(&mut cmp);
+    }
+
+    /// Get a cursor pointing to the front element, or the "ghost" position if the list
+    /// is empty.
+    #[inline]
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            cur: self.head,
+            index: 0,
+            list: self,
+        }
+    }
+
+    /// Get a cursor pointing to the back element, or the "ghost" position if the list
+    /// is empty.
+    #[inline]
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            cur: self.tail,
+            index: self.len.saturating_sub(1),
+            list: self,
+        }
+    }
+}
+
+// public methods of elements sized
+impl<T> RList<T>
+where
+    T: Copy + Clone + MemSized,
+{
+    /// Estimate number of bytes of memory used by the list (introduced from Redis MEMORY
+    /// USAGE), including the list itself, nodes (with the allocator overhead) & payloads
+    /// of elements on the heap (see `MemSized`), in O(n) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RList;
+    ///
+    /// let mut list: RList<u64> = RList::new();
+    /// let empty = list.mem_usage();
+    /// list.extend(0..10);
+    /// assert!(list.mem_usage() >= empty + 10 * 3 * 8);
+    /// ```
+    pub fn mem_usage(&self) -> usize {
+        let nodes = self.len * alloc_size(size_of::<Node<T>>());
+        let payloads: usize = self.iter().map(|data| data.heap_size()).sum();
+        let notifier = self
+            .notifier
+            .as_ref()
+            .map_or(0, |notifier| alloc_size(std::mem::size_of_val(&**notifier)));
+        size_of::<Self>() + nodes + payloads + notifier
+    }
+}
+
+// public methods of elements ordered
+impl<T> RList<T>
+where
+    T: Copy + Clone + Ord,
+{
+    /// Sort the list in the ascending order, which is stable, see `sort_by`.
+    #[inline]
+    pub fn sort(&mut self) {
+        self.sort_by(Ord::cmp)
+    }
+}
+
+// public methods of elements comparable
+impl<T> RList<T>
+where
+    T: Copy + Clone + PartialEq,
+{
+    /// Remove elements equal to `value` (introduced from Redis LREM), and return number of
+    /// elements removed, where:
+    ///
+    /// - `count > 0`: remove (at most) `count` elements, from the front to the back.
+    /// - `count < 0`: remove (at most) `-count` elements, from the back to the front.
+    /// - `count = 0`: remove ALL elements equal to `value`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RList;
+    ///
+    /// let mut list: RList<i32> = vec![1, 2, 1, 3, 1].into_iter().collect();
+    /// assert_eq!(list.remove_matches(&1, -2), 2);
+    /// assert_eq!(list.to_vec(), vec![1, 2, 3]);
+    /// assert_eq!(list.remove_matches(&1, 0), 1);
+    /// assert_eq!(list.to_vec(), vec![2, 3]);
+    /// ```
+    pub fn remove_matches(&mut self, value: &T, count: isize) -> usize {
+        let limit = match count {
+            0 => usize::MAX,
+            n => n.unsigned_abs(),
+        };
+        let mut removed = 0;
+        let mut cur = if count < 0 { self.tail } else { self.head };
+        while let Some(node) = cur {
+            if removed == limit {
+                break;
+            }
+            unsafe {
+                cur = if count < 0 {
+                    (*node.as_ptr()).prev
+                } else {
+                    (*node.as_ptr()).next
+                };
+                if (*node.as_ptr()).data == *value {
+                    self.unlink_node(node);
+                    Node::free(node);
+                    removed += 1;
+                }
+            }
+        }
+        removed
+    }
+
+    /// Get indexes of elements equal to `value` (introduced from Redis LPOS), where:
+    ///
+    /// - `rank`: skip the first `rank - 1` matches from the front, or the first
+    ///   `-rank - 1` matches from the back if negative (that indexes are in the reverse order).
+    /// - `count`: MAX number of indexes returned, or ALL of them if ZERO.
+    /// - `max_scan`: MAX number of elements compared, or ALL of them if ZERO.
+    ///
+    /// Indexes are ALWAYS counted from the front, even if searching from the back.
+    ///
+    /// # Panics
+    ///
+    /// `rank` MUST NOT be ZERO.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RList;
+    ///
+    /// let list: RList<char> = "abcdcc".chars().collect();
+    /// assert_eq!(list.positions(&'c', 1, 1, 0), vec![2]);
+    /// assert_eq!(list.positions(&'c', 2, 0, 0), vec![4, 5]);
+    /// assert_eq!(list.positions(&'c', -1, 2, 0), vec![5, 4]);
+    /// assert_eq!(list.positions(&'c', 1, 0, 3), vec![2]);
+    /// ```
+    pub fn positions(&self, value: &T, rank: isize, count: usize, max_scan: usize) -> Vec<usize> {
+        assert!(rank != 0, "LPOS rank can't be zero");
+        let count = if count == 0 { usize::MAX } else { count };
+        let max_scan = if max_scan == 0 { usize::MAX } else { max_scan };
+        let mut skip = rank.unsigned_abs() - 1;
+
+        let mut found = Vec::new();
+        let mut cur = if rank > 0 { self.head } else { self.tail };
+        let mut idx = if rank > 0 {
+            0
+        } else {
+            self.len.wrapping_sub(1)
+        };
+        for _ in 0..max_scan {
+            let node = match cur {
+                Some(node) => node,
+                None => break,
+            };
+            unsafe {
+                if (*node.as_ptr()).data == *value {
+                    if skip > 0 {
+                        skip -= 1;
+                    } else {
+                        found.push(idx);
+                        if found.len() == count {
+                            break;
+                        }
+                    }
+                }
+                if rank > 0 {
+                    cur = (*node.as_ptr()).next;
+                    idx += 1;
+                } else {
+                    cur = (*node.as_ptr()).prev;
+                    idx = idx.wrapping_sub(1);
+                }
+            }
+        }
+        found
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Merge Sort
+////////////////////////////////////////////////////////////////////////////////
+
+/// State of the bottom-up merge sort over links (introduced from Simon Tatham's
+/// algorithm), where nodes are linked by `next` ONLY while sorting, and `prev` links are
+/// fixed up once done (or the comparator panics) on drop.
+///
+/// Nodes NOT merged yet in the current pass are (in order) the `p` run of `psize` nodes,
+/// and ALL nodes linked from `q`, while nodes merged are linked from `merged` to `tail`
+/// (with `next` of `tail` NOT updated yet).
+struct MergeSort<'a, T>
+where
+    T: Copy + Clone,
+{
+    list: &'a mut RList<T>,
+    merged: Link<T>,
+    tail: Link<T>,
+    p: Link<T>,
+    psize: usize,
+    q: Link<T>,
+}
+
+impl<'a, T> MergeSort<'a, T>
+where
+    T: Copy + Clone,
+{
+    #[inline]
+    unsafe fn next(node: Link<T>) -> Link<T> {
+        node.and_then(|node| (*node.as_ptr()).next)
+    }
+
+    fn run<F>(&mut self, cmp: &mut F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut run_size = 1;
+        loop {
+            let mut merges = 0;
+            while self.q.is_some() {
+                merges += 1;
+                // Split the p run & the q run, each of `run_size` nodes (at most).
+                self.p = self.q;
+                self.psize = 0;
+                while self.psize < run_size && self.q.is_some() {
+                    self.psize += 1;
+                    self.q = unsafe { Self::next(self.q) };
+                }
+
+                let mut qsize = run_size;
+                while self.psize > 0 || (qsize > 0 && self.q.is_some()) {
+                    let take_p = match (self.p, self.q) {
+                        _ if self.psize == 0 => false,
+                        (_, None) => true,
+                        _ if qsize == 0 => true,
+                        (Some(p), Some(q)) => unsafe {
+                            cmp(&(*p.as_ptr()).data, &(*q.as_ptr()).data) != Ordering::Greater
+                        },
+                        _ => unreachable!(),
+                    };
+                    let node = if take_p {
+                        let node = self.p;
+                        self.p = unsafe { Self::next(node) };
+                        self.psize -= 1;
+                        node
+                    } else {
+                        let node = self.q;
+                        self.q = unsafe { Self::next(node) };
+                        qsize -= 1;
+                        node
+                    };
+                    self.append(node);
+                }
+            }
+
+            unsafe { self.close() };
+            if merges <= 1 {
+                return;
+            }
+            // Start the next pass with runs doubled.
+            self.q = self.merged.take();
+            self.tail = None;
+            run_size *= 2;
+        }
+    }
+
+    #[inline]
+    fn append(&mut self, node: Link<T>) {
+        match self.tail {
+            Some(tail) => unsafe { (*tail.as_ptr()).next = node },
+            None => self.merged = node,
+        }
+        self.tail = node;
+    }
+
+    // Links nodes NOT merged yet after `tail`, that ALL nodes are linked from `merged`.
+    unsafe fn close(&mut self) {
+        let mut rest = self.q;
+        if let Some(p) = self.p.filter(|_| self.psize > 0) {
+            let mut last = p;
+            for _ in 1..self.psize {
+                last = (*last.as_ptr()).next.expect("p run shorter than psize");
+            }
+            (*last.as_ptr()).next = rest;
+            rest = Some(p);
+        }
+        self.p = None;
+        self.psize = 0;
+        self.q = None;
+        match self.tail {
+            Some(tail) => (*tail.as_ptr()).next = rest,
+            None => self.merged = rest,
+        }
+    }
+}
+
+impl<'a, T> Drop for MergeSort<'a, T>
+where
+    T: Copy + Clone,
+{
+    fn drop(&mut self) {
+        unsafe {
+            self.close();
+            let mut prev = None;
+            let mut cur = self.merged;
+            while let Some(node) = cur {
+                (*node.as_ptr()).prev = prev;
+                prev = cur;
+                cur = (*node.as_ptr()).next;
+            }
+            self.list.head = self.merged;
+            self.list.tail = prev;
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Cursor
+////////////////////////////////////////////////////////////////////////////////
+
+/// CursorMut points to an element of an `RList` (or the "ghost" position between
+/// the back and the front), which is able to walk the list in both directions, and
+/// insert or remove elements at the position in O(1), e.g. for LINSERT & LREM walking
+/// the list once rather than scanning it per operation.
+///
+/// # Notes
+///
+/// Moving past the back (or the front) reaches the ghost position, and moving once
+/// more wraps around to the other end.
+///
+/// # Examples
+///
+/// ```
+/// # use rtypes::RList;
+///
+/// let mut list: RList<i32> = (1..=5).collect();
+/// let mut cursor = list.cursor_front_mut();
+/// while let Some(data) = cursor.current() {
+///     if data % 2 == 0 {
+///         cursor.remove_current();
+///     } else {
+///         cursor.insert_after(data * 10);
+///         cursor.seek(cursor.index().unwrap() + 2);
+///     }
+/// }
+/// assert_eq!(list.to_vec(), vec![1, 10, 3, 30, 5, 50]);
+/// ```
+pub struct CursorMut<'a, T>
+where
+    T: Copy + Clone,
+{
+    list: &'a mut RList<T>,
+    /// Node pointed to, which is `None` at the ghost position.
+    cur: Link<T>,
+    /// Index of the node pointed to, which is the length of the list at the ghost position.
+    index: usize,
+}
+
+impl<'a, T> CursorMut<'a, T>
+where
+    T: Copy + Clone,
+{
+    /// Get the index of the element pointed to, or `None` at the ghost position.
+    #[inline]
+    pub fn index(&self) -> Option<usize> {
+        self.cur.map(|_| self.index)
+    }
+
+    /// Get the element (copied) pointed to, or `None` at the ghost position.
+    #[inline]
+    pub fn current(&self) -> Option<T> {
+        self.cur.map(|node| unsafe { (*node.as_ptr()).data })
+    }
+
+    /// Get the element pointed to mutably, or `None` at the ghost position.
+    #[inline]
+    pub fn current_mut(&mut self) -> Option<&mut T> {
+        self.cur.map(|node| unsafe { &mut (*node.as_ptr()).data })
+    }
+
+    /// Move to the next element, or the ghost position from the back, or the front
+    /// from the ghost position.
+    pub fn move_next(&mut self) {
+        match self.cur {
+            Some(node) => {
+                self.cur = unsafe { (*node.as_ptr()).next };
+                self.index += 1;
+            }
+            None => {
+                self.cur = self.list.head;
+                self.index = 0;
+            }
+        }
+    }
+
+    /// Move to the previous element, or the ghost position from the front, or the back
+    /// from the ghost position.
+    pub fn move_prev(&mut self) {
+        match self.cur {
+            Some(node) => {
+                self.cur = unsafe { (*node.as_ptr()).prev };
+                self.index = match self.cur {
+                    Some(_) => self.index - 1,
+                    None => self.list.len,
+                };
+            }
+            None => {
+                self.cur = self.list.tail;
+                self.index = self.list.len.saturating_sub(1);
+            }
+        }
+    }
+
+    /// Move to the element at `idx`, or the ghost position if `idx` is out of range,
+    /// walking from the nearest of the cursor, the front & the back.
+    pub fn seek(&mut self, idx: usize) {
+        let len = self.list.len;
+        if idx >= len {
+            self.cur = None;
+            self.index = len;
+            return;
+        }
+
+        let from_ends = std::cmp::min(idx, len - 1 - idx);
+        match self.cur {
+            Some(_) if self.index.abs_diff(idx) < from_ends => {
+                while self.index < idx {
+                    self.move_next();
+                }
+                while self.index > idx {
+                    self.move_prev();
+                }
+            }
+            _ => {
+                self.cur = self.list.find_node(idx);
+                self.index = idx;
+            }
+        }
+    }
+
+    /// Insert an element before the one pointed to, or at the back at the ghost position.
+    pub fn insert_before(&mut self, data: T) {
+        let node = Node::alloc(data);
+        self.index += 1;
+        match self.cur.and_then(|cur| unsafe { (*cur.as_ptr()).prev }) {
+            Some(prev) => unsafe { self.list.link_after(prev, node) },
+            None if self.cur.is_some() => self.list.push_front_node(node),
+            None => {
+                self.list.push_back_node(node);
+                return self.evict(End::Front);
+            }
+        }
+        self.evict(End::Back);
+    }
+
+    /// Insert an element after the one pointed to, or at the front at the ghost position.
+    pub fn insert_after(&mut self, data: T) {
+        let node = Node::alloc(data);
+        match self.cur {
+            Some(cur) => unsafe { self.list.link_after(cur, node) },
+            None => {
+                self.list.push_front_node(node);
+                self.index += 1;
+            }
+        }
+        self.evict(End::Back);
+    }
+
+    /// Evict elements from `end` if the length exceeds the cap of the list, where
+    /// the cursor is moved to the ghost position if the element pointed to is evicted.
+    fn evict(&mut self, end: End) {
+        let count = match self.list.eviction() {
+            Some(count) => count,
+            None => return,
+        };
+        for _ in 0..count {
+            let node = match end {
+                End::Front => self.list.pop_front_node(),
+                End::Back => self.list.pop_back_node(),
+            };
+            if let Some(node) = node {
+                if self.cur == Some(node) {
+                    self.cur = None;
+                } else if end == End::Front && self.cur.is_some() {
+                    self.index -= 1;
+                }
+                unsafe { Node::free(node) };
+            }
+        }
+        if self.cur.is_none() {
+            self.index = self.list.len;
+        }
+    }
+
+    /// Remove the element pointed to & move to the next one (or the ghost position),
+    /// and return the element removed, or `None` at the ghost position.
+    pub fn remove_current(&mut self) -> Option<T> {
+        self.cur.map(|node| unsafe {
+            self.cur = (*node.as_ptr()).next;
+            self.list.unlink_node(node);
+            Node::free(node)
+        })
+    }
+}
+
+impl<T> Drop for RList<T>
+where
+    T: Copy + Clone,
+{
+    fn drop(&mut self) {
+        while let Some(node) = self.pop_front_node() {
+            unsafe { Node::free(node) };
+        }
+    }
 }
 
 impl<T> Default for RList<T>
@@ -321,7 +1464,106 @@ where
     }
 }
 
-impl<T> Iterator for Iter<T>
+/// Clone elements into a new list with the same cap (see `set_max_len`), where the notifier
+/// (see `on_push`) is NOT cloned.
+impl<T> Clone for RList<T>
+where
+    T: Copy + Clone,
+{
+    fn clone(&self) -> Self {
+        let mut list: RList<T> = self.iter().collect();
+        list.max_len = self.max_len;
+        list
+    }
+}
+
+impl<T> PartialEq for RList<T>
+where
+    T: Copy + Clone + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T> Eq for RList<T> where T: Copy + Clone + Eq {}
+
+impl<T> From<Vec<T>> for RList<T>
+where
+    T: Copy + Clone,
+{
+    /// Create a list of ALL elements of `vec` in order, with a node allocated for each (see
+    /// `RList::extend_from_slice`).
+    #[inline]
+    fn from(vec: Vec<T>) -> Self {
+        let mut list = RList::new();
+        list.extend_from_slice(&vec);
+        list
+    }
+}
+
+impl<T> FromIterator<T> for RList<T>
+where
+    T: Copy + Clone,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = RList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> Extend<T> for RList<T>
+where
+    T: Copy + Clone,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for data in iter {
+            self.push_back(data);
+        }
+    }
+}
+
+impl<'a, T> Extend<&'a T> for RList<T>
+where
+    T: 'a + Copy + Clone,
+{
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Iterators
+////////////////////////////////////////////////////////////////////////////////
+
+impl<T> IntoIterator for RList<T>
+where
+    T: Copy + Clone,
+{
+    type Item = T;
+    type IntoIter = RListIntoIter<T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        RListIntoIter { list: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RList<T>
+where
+    T: Copy + Clone,
+{
+    type Item = T;
+    type IntoIter = RListIter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> Iterator for RListIter<'a, T>
 where
     T: Copy + Clone,
 {
@@ -331,19 +1573,119 @@ where
         if self.len == 0 {
             None
         } else {
-            self.head.take().map(|head| {
+            self.head.map(|head| unsafe {
                 self.len -= 1;
-                if let Some(next) = head.borrow().next.clone() {
-                    self.head = Some(next);
-                } else {
-                    self.tail = None;
-                }
-                head.borrow().data
+                self.head = (*head.as_ptr()).next;
+                (*head.as_ptr()).data
             })
         }
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
 }
 
+impl<'a, T> DoubleEndedIterator for RListIter<'a, T>
+where
+    T: Copy + Clone,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            None
+        } else {
+            self.tail.map(|tail| unsafe {
+                self.len -= 1;
+                self.tail = (*tail.as_ptr()).prev;
+                (*tail.as_ptr()).data
+            })
+        }
+    }
+}
+
+impl<'a, T> ExactSizeIterator for RListIter<'a, T> where T: Copy + Clone {}
+
+impl<'a, T> FusedIterator for RListIter<'a, T> where T: Copy + Clone {}
+
+impl<'a, T> Clone for RListIter<'a, T>
+where
+    T: Copy + Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        RListIter { ..*self }
+    }
+}
+
+impl<'a, T> Iterator for RListDrain<'a, T>
+where
+    T: Copy + Clone,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.cur.map(|node| unsafe {
+            self.remaining -= 1;
+            self.cur = (*node.as_ptr()).next;
+            self.list.unlink_node(node);
+            Node::free(node)
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for RListDrain<'a, T> where T: Copy + Clone {}
+
+impl<'a, T> FusedIterator for RListDrain<'a, T> where T: Copy + Clone {}
+
+impl<'a, T> Drop for RListDrain<'a, T>
+where
+    T: Copy + Clone,
+{
+    fn drop(&mut self) {
+        self.for_each(drop);
+    }
+}
+
+impl<T> Iterator for RListIntoIter<T>
+where
+    T: Copy + Clone,
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.pop_front()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len, Some(self.list.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for RListIntoIter<T>
+where
+    T: Copy + Clone,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.list.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for RListIntoIter<T> where T: Copy + Clone {}
+
+impl<T> FusedIterator for RListIntoIter<T> where T: Copy + Clone {}
+
 // Pretty-printing
 impl<T> Display for RList<T>
 where
@@ -351,13 +1693,11 @@ where
 {
     fn fmt(&self, w: &mut Formatter) -> Result<(), Error> {
         write!(w, "[")?;
-        let mut node = self.head.clone();
-        while let Some(n) = node {
-            write!(w, "{}", n.borrow().data)?;
-            node = n.borrow().next.clone();
-            if node.is_some() {
+        for (idx, data) in self.iter().enumerate() {
+            if idx > 0 {
                 write!(w, ", ")?;
             }
+            write!(w, "{}", data)?;
         }
         write!(w, "]")
     }
@@ -365,9 +1705,9 @@ where
 
 impl<T> Debug for RList<T>
 where
-    T: Copy + Clone + Debug + Display,
+    T: Copy + Clone + Debug,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
-        Display::fmt(self, f)
+        f.debug_list().entries(self.iter()).finish()
     }
 }
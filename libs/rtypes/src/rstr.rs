@@ -0,0 +1,266 @@
+use rmem::{mem_cmp, mem_find_pattern, mem_rfind_pattern};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+
+use crate::num::{parse_f64, parse_i64, ParseNumError};
+use crate::rstring::write_repr;
+use crate::RString;
+
+/// RStr is a borrowed (non-owning) view of binary-safe bytes, represented by a `pointer`
+/// & a length, such as a token within a network buffer or a part of an `RString`.
+///
+/// It shares the search, compare & sub-slice API of `RString`, while sub-views are created
+/// WITHOUT allocation or copies.
+///
+/// # Examples
+///
+/// ```
+/// # use rtypes::{RStr, RString};
+///
+/// let buf = RString::from_str("GET key:1\r\n");
+/// let line = buf.as_rstr_view().lsub_rstr(buf.find(b"\r\n").unwrap());
+/// let tokens = line.split(b" ");
+/// assert_eq!(tokens, [RStr::from("GET"), RStr::from("key:1")]);
+/// assert_eq!(tokens[1].to_rstr(), RString::from_str("key:1"));
+/// ```
+#[derive(Clone, Copy, Default)]
+pub struct RStr<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> RStr<'a> {
+    /// Create a view of `bytes`.
+    #[inline]
+    pub const fn new(bytes: &'a [u8]) -> Self {
+        RStr { bytes }
+    }
+
+    #[inline]
+    pub const fn as_ptr(&self) -> *const u8 {
+        self.bytes.as_ptr()
+    }
+
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Get bytes viewed, with the lifetime of the underlying bytes (rather than the view).
+    #[inline]
+    pub const fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Copy bytes viewed into a new `RString`.
+    #[inline]
+    pub fn to_rstr(&self) -> RString {
+        RString::from_bytes(self.bytes)
+    }
+
+    /// Get the sub-view of bytes in the range `[start, end)`, where the range is clamped
+    /// to the view (an empty view is returned if `start` is NOT less than `end`).
+    #[inline]
+    pub fn sub_rstr(&self, start: usize, end: usize) -> RStr<'a> {
+        let end = std::cmp::min(self.len(), end);
+        match start < end {
+            true => RStr::new(&self.bytes[start..end]),
+            false => RStr::default(),
+        }
+    }
+
+    #[inline]
+    pub fn lsub_rstr(&self, end: usize) -> RStr<'a> {
+        self.sub_rstr(0, end)
+    }
+
+    #[inline]
+    pub fn rsub_rstr(&self, start: usize) -> RStr<'a> {
+        self.sub_rstr(start, self.len())
+    }
+
+    /// Find the offset of the first occurrence of `needle` within the view.
+    ///
+    /// An empty `needle` is ALWAYS found at offset ZERO.
+    #[inline]
+    pub fn find(&self, needle: &[u8]) -> Option<usize> {
+        unsafe { mem_find_pattern(self.as_ptr(), self.len(), needle.as_ptr(), needle.len()) }
+    }
+
+    /// Find the offset of the last occurrence of `needle` within the view.
+    ///
+    /// An empty `needle` is ALWAYS found at the end (offset of the length).
+    #[inline]
+    pub fn rfind(&self, needle: &[u8]) -> Option<usize> {
+        unsafe { mem_rfind_pattern(self.as_ptr(), self.len(), needle.as_ptr(), needle.len()) }
+    }
+
+    /// Check if `needle` occurs within the view.
+    #[inline]
+    pub fn contains(&self, needle: &[u8]) -> bool {
+        self.find(needle).is_some()
+    }
+
+    /// Split the view by the separator `sep` into sub-views, where empty tokens are kept.
+    ///
+    /// NO tokens are returned if the view or the separator is empty.
+    pub fn split(&self, sep: &[u8]) -> Vec<RStr<'a>> {
+        let mut tokens = Vec::new();
+        if self.is_empty() || sep.is_empty() {
+            return tokens;
+        }
+
+        let mut rest = *self;
+        while let Some(pos) = rest.find(sep) {
+            tokens.push(rest.lsub_rstr(pos));
+            rest = rest.rsub_rstr(pos + sep.len());
+        }
+        tokens.push(rest);
+        tokens
+    }
+
+    /// Get the escaped & quoted representation of the view, see `RString::append_repr`.
+    #[inline]
+    pub fn to_repr(&self) -> RString {
+        let mut repr = RString::new();
+        repr.append_repr(self.bytes);
+        repr
+    }
+
+    /// Parse the view strictly into `i64`, see `RString::parse_i64`.
+    #[inline]
+    pub fn parse_i64(&self) -> Result<i64, ParseNumError> {
+        parse_i64(self.bytes)
+    }
+
+    /// Parse the view into `f64`, see `RString::parse_f64`.
+    #[inline]
+    pub fn parse_f64(&self) -> Result<f64, ParseNumError> {
+        parse_f64(self.bytes)
+    }
+}
+
+impl RString {
+    /// Get a view of the content, which is just like `as_bytes`, while with the search,
+    /// compare & sub-slice API of `RString`.
+    #[inline]
+    pub fn as_rstr_view(&self) -> RStr<'_> {
+        RStr::new(self.as_bytes())
+    }
+}
+
+impl<'a> From<&'a [u8]> for RStr<'a> {
+    #[inline]
+    fn from(bytes: &'a [u8]) -> RStr<'a> {
+        RStr::new(bytes)
+    }
+}
+
+impl<'a> From<&'a str> for RStr<'a> {
+    #[inline]
+    fn from(s: &'a str) -> RStr<'a> {
+        RStr::new(s.as_bytes())
+    }
+}
+
+impl<'a> From<&'a RString> for RStr<'a> {
+    #[inline]
+    fn from(s: &'a RString) -> RStr<'a> {
+        s.as_rstr_view()
+    }
+}
+
+impl PartialEq for RStr<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        unsafe {
+            self.len() == other.len()
+                && Ordering::Equal == mem_cmp(self.as_ptr(), other.as_ptr(), self.len())
+        }
+    }
+}
+
+impl Eq for RStr<'_> {}
+
+impl PartialEq<RString> for RStr<'_> {
+    #[inline]
+    fn eq(&self, other: &RString) -> bool {
+        *self == other.as_rstr_view()
+    }
+}
+
+impl PartialEq<RStr<'_>> for RString {
+    #[inline]
+    fn eq(&self, other: &RStr<'_>) -> bool {
+        self.as_rstr_view() == *other
+    }
+}
+
+impl PartialOrd for RStr<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RStr<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let clen = std::cmp::min(self.len(), other.len());
+        match unsafe { mem_cmp(self.as_ptr(), other.as_ptr(), clen) } {
+            Ordering::Equal => Ord::cmp(&self.len(), &other.len()),
+            Ordering::Less => Ordering::Less,
+            Ordering::Greater => Ordering::Greater,
+        }
+    }
+}
+
+// Hashing MUST be consistent with `[u8]` for `Borrow<[u8]>`.
+impl Hash for RStr<'_> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bytes.hash(state);
+    }
+}
+
+impl Deref for RStr<'_> {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        self.bytes
+    }
+}
+
+impl AsRef<[u8]> for RStr<'_> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.bytes
+    }
+}
+
+impl Borrow<[u8]> for RStr<'_> {
+    #[inline]
+    fn borrow(&self) -> &[u8] {
+        self.bytes
+    }
+}
+
+impl fmt::Display for RStr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let printed = std::str::from_utf8(self.bytes).unwrap_or("<Unreadable Bytes>");
+        write!(f, "{}", printed)
+    }
+}
+
+impl fmt::Debug for RStr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{ len: {}, data: <{:p}>", self.len(), self.as_ptr())?;
+        write_repr(f, self.bytes)?;
+        f.write_str(" }")
+    }
+}
@@ -0,0 +1,1167 @@
+use std::collections::btree_map::{self, BTreeMap};
+use std::collections::btree_set::{self, BTreeSet};
+use std::convert::TryInto;
+use std::error::Error;
+use std::fmt;
+use std::iter::FusedIterator;
+use std::ops::{Bound, RangeBounds};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::num::{format_i64, I64_MAX_LEN};
+use crate::{ListPackValue, RListPack, RListPackIter, RRax, RRaxIter, RString};
+
+////////////////////////////////////////////////////////////////////////////////
+// Stream IDs
+////////////////////////////////////////////////////////////////////////////////
+
+/// ID of a stream entry (introduced from Redis `streamID`), i.e. the UNIX time in
+/// milliseconds & the sequence number of entries added in the same millisecond, which
+/// is formatted as "<ms>-<seq>".
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StreamId {
+    pub ms: u64,
+    pub seq: u64,
+}
+
+impl StreamId {
+    /// The MIN ID, i.e. "0-0", which is NEVER given to entries.
+    pub const MIN: StreamId = StreamId::new(0, 0);
+    /// The MAX ID, after which NO more entries can be added.
+    pub const MAX: StreamId = StreamId::new(u64::MAX, u64::MAX);
+
+    #[inline]
+    pub const fn new(ms: u64, seq: u64) -> Self {
+        StreamId { ms, seq }
+    }
+
+    /// Get the ID right after this one (introduced from Redis `streamIncrID`), or `None`
+    /// if it's the MAX ID.
+    #[inline]
+    pub fn next(self) -> Option<Self> {
+        match self.seq.checked_add(1) {
+            Some(seq) => Some(StreamId::new(self.ms, seq)),
+            None => Some(StreamId::new(self.ms.checked_add(1)?, 0)),
+        }
+    }
+
+    /// Get the ID right before this one (introduced from Redis `streamDecrID`), or `None`
+    /// if it's the MIN ID.
+    #[inline]
+    pub fn prev(self) -> Option<Self> {
+        match self.seq.checked_sub(1) {
+            Some(seq) => Some(StreamId::new(self.ms, seq)),
+            None => Some(StreamId::new(self.ms.checked_sub(1)?, u64::MAX)),
+        }
+    }
+
+    // Gets the key of the ID in nodes of streams, i.e. `ms` & `seq` in big endian, that
+    // keys are ordered just like IDs.
+    #[inline]
+    fn to_key(self) -> [u8; 16] {
+        let mut key = [0u8; 16];
+        key[..8].copy_from_slice(&self.ms.to_be_bytes());
+        key[8..].copy_from_slice(&self.seq.to_be_bytes());
+        key
+    }
+
+    #[inline]
+    fn from_key(key: &[u8]) -> Self {
+        let (ms, seq) = key.split_at(8);
+        StreamId::new(
+            u64::from_be_bytes(ms.try_into().expect("stream node key corrupted")),
+            u64::from_be_bytes(seq.try_into().expect("stream node key corrupted")),
+        )
+    }
+
+    /// Parse an ID from "<ms>-<seq>", or "<ms>" where the sequence number is ZERO, and
+    /// return `None` if invalid.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        fn parse_u64(digits: &[u8]) -> Option<u64> {
+            if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+                return None;
+            }
+            digits.iter().try_fold(0u64, |value, &digit| {
+                value.checked_mul(10)?.checked_add((digit - b'0') as u64)
+            })
+        }
+
+        match bytes.iter().position(|&b| b == b'-') {
+            Some(pos) => Some(StreamId::new(
+                parse_u64(&bytes[..pos])?,
+                parse_u64(&bytes[pos + 1..])?,
+            )),
+            None => Some(StreamId::new(parse_u64(bytes)?, 0)),
+        }
+    }
+}
+
+impl fmt::Display for StreamId {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.ms, self.seq)
+    }
+}
+
+impl fmt::Debug for StreamId {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// ID of the entry to add (introduced from Redis XADD), which is generated (partially)
+/// or given explicitly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum XAddId {
+    /// "*", i.e. the current time, with the next sequence number if NOT later than the
+    /// last ID.
+    Auto,
+    /// "<ms>-*", i.e. the millisecond given, with the next sequence number.
+    AutoSeq(u64),
+    /// "<ms>-<seq>", which MUST be greater than the last ID.
+    Explicit(StreamId),
+}
+
+/// Error of operations on streams.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StreamError {
+    /// ID given NOT greater than the last ID.
+    IdTooSmall,
+    /// ID given is "0-0".
+    IdZero,
+    /// The last ID is the MAX ID already.
+    IdExhausted,
+    /// Consumer group NOT found.
+    NoGroup,
+    /// Consumer group created already.
+    BusyGroup,
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::IdTooSmall => write!(
+                f,
+                "The ID specified in XADD is equal or smaller than the target stream top item"
+            ),
+            StreamError::IdZero => write!(f, "The ID specified in XADD must be greater than 0-0"),
+            StreamError::IdExhausted => write!(
+                f,
+                "The stream has exhausted the last possible ID, unable to add more items"
+            ),
+            StreamError::NoGroup => write!(f, "No such consumer group"),
+            StreamError::BusyGroup => write!(f, "Consumer Group name already exists"),
+        }
+    }
+}
+
+impl Error for StreamError {}
+
+/// Strategy of trimming streams (introduced from Redis XTRIM).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StreamTrim {
+    /// Remove the oldest entries, until NO more than the length given are left.
+    MaxLen(usize),
+    /// Remove entries with IDs less than the one given.
+    MinId(StreamId),
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// RStream
+////////////////////////////////////////////////////////////////////////////////
+
+/// MAX number of bytes of a node (Redis `stream-node-max-bytes`).
+const STREAM_NODE_MAX_BYTES: usize = 4096;
+/// MAX number of entries (including ones deleted) of a node (Redis
+/// `stream-node-max-entries`).
+const STREAM_NODE_MAX_ENTRIES: usize = 100;
+
+/// Flags of entries in nodes.
+const STREAM_ITEM_FLAG_DELETED: i64 = 1;
+const STREAM_ITEM_FLAG_SAMEFIELDS: i64 = 2;
+
+/// Number of elements of the master entry, excluding its fields.
+const MASTER_ENTRY_FIXED_LEN: usize = 4;
+
+/// RStream is an append-only log of entries (introduced from Redis `stream`), each of
+/// which has a unique ID & field-value pairs, where entries are packed in nodes of
+/// listpacks, keyed by the ID of their first entries in an `RRax`. Entries are
+/// consumed by consumers of consumer groups, see `StreamGroup`.
+///
+/// Each node starts with the master entry, where IDs & fields of the following entries
+/// are encoded against, i.e. IDs are deltas to the node ID, and fields are omitted if
+/// the same as the master ones:
+///
+/// ```text
+/// master:  count | deleted | num-fields | field_1 | ... | field_N | 0
+/// entry:   flags | ms-diff | seq-diff | num-fields | field_1 | value_1 | ... | lp-count
+/// entry:   flags(SAMEFIELDS) | ms-diff | seq-diff | value_1 | ... | value_N | lp-count
+/// ```
+///
+/// # Notes
+///
+/// Nodes are keyed by IDs in big endian (just like Redis), thus ordered by IDs.
+///
+/// # Examples
+///
+/// ```
+/// # use rtypes::{RStream, StreamId, StreamTrim, XAddId};
+///
+/// let mut stream = RStream::new();
+/// let id = stream.add(XAddId::Explicit(StreamId::new(1, 1)), &[(b"temp", b"20")]).unwrap();
+/// assert_eq!(id.to_string(), "1-1");
+/// let id = stream.add(XAddId::AutoSeq(1), &[(b"temp", b"21")]).unwrap();
+/// assert_eq!(id, StreamId::new(1, 2));
+/// assert!(stream.add(XAddId::Explicit(StreamId::new(1, 2)), &[(b"temp", b"22")]).is_err());
+/// assert_eq!(stream.len(), 2);
+///
+/// let ids: Vec<_> = stream.range(..).rev().map(|entry| entry.id()).collect();
+/// assert_eq!(ids, vec![StreamId::new(1, 2), StreamId::new(1, 1)]);
+///
+/// assert_eq!(stream.trim(StreamTrim::MaxLen(1), false), 1);
+/// assert_eq!(stream.first_id(), Some(StreamId::new(1, 2)));
+/// ```
+pub struct RStream {
+    nodes: RRax<RListPack>,
+    len: usize,
+    last_id: StreamId,
+    entries_added: u64,
+    groups: BTreeMap<RString, StreamGroup>,
+}
+
+/// Entry of an `RStream`, borrowed from the node holding it.
+#[derive(Clone)]
+pub struct StreamEntry<'a> {
+    id: StreamId,
+    // Fields of the master entry, if fields are omitted in the entry.
+    master_fields: Option<RListPackIter<'a>>,
+    // Values (or fields interleaved with values) of the entry.
+    values: RListPackIter<'a>,
+    len: usize,
+}
+
+/// Iterator over field-value pairs of a `StreamEntry`.
+#[derive(Clone)]
+pub struct StreamFieldsIter<'a> {
+    master_fields: Option<RListPackIter<'a>>,
+    values: RListPackIter<'a>,
+    remaining: usize,
+}
+
+/// Iterator over entries of an `RStream`, in ascending (or descending) order of IDs.
+pub struct RStreamIter<'a> {
+    nodes: RRaxIter<'a, RListPack>,
+    front: std::vec::IntoIter<StreamEntry<'a>>,
+    back: std::vec::IntoIter<StreamEntry<'a>>,
+    start: StreamId,
+    end: StreamId,
+}
+
+/// Entry decoded from a node, with its flags & the index of its first element.
+struct NodeEntry<'a> {
+    idx: usize,
+    flags: i64,
+    entry: StreamEntry<'a>,
+}
+
+// Gets the next element of a node as an integer.
+#[inline]
+fn next_int(iter: &mut RListPackIter<'_>) -> i64 {
+    iter.next()
+        .and_then(ListPackValue::as_i64)
+        .expect("stream node corrupted")
+}
+
+// Appends an integer to a node.
+#[inline]
+fn push_int(lp: &mut RListPack, value: i64) {
+    let mut buf = [0u8; I64_MAX_LEN];
+    let start = format_i64(value, &mut buf);
+    lp.push_back(&buf[start..]);
+}
+
+// Replaces the element at `idx` of a node with an integer.
+#[inline]
+fn replace_int(lp: &mut RListPack, idx: usize, value: i64) {
+    let mut buf = [0u8; I64_MAX_LEN];
+    let start = format_i64(value, &mut buf);
+    lp.replace(idx, &buf[start..]);
+}
+
+// Gets `count` & `deleted` of the master entry of a node.
+#[inline]
+fn node_counts(lp: &RListPack) -> (usize, usize) {
+    let mut iter = lp.iter();
+    let count = next_int(&mut iter) as usize;
+    (count, next_int(&mut iter) as usize)
+}
+
+// Decodes ALL entries (including ones deleted) of the node keyed by `node_id`.
+fn node_entries(node_id: StreamId, lp: &RListPack) -> Vec<NodeEntry<'_>> {
+    let mut iter = lp.iter();
+    let (count, deleted) = (next_int(&mut iter), next_int(&mut iter));
+    let num_master_fields = next_int(&mut iter) as usize;
+    let master_fields = iter.clone();
+    iter.nth(num_master_fields);
+
+    let mut idx = MASTER_ENTRY_FIXED_LEN + num_master_fields;
+    let mut entries = Vec::with_capacity((count + deleted) as usize);
+    while iter.len() > 0 {
+        let flags = next_int(&mut iter);
+        let ms = node_id.ms.wrapping_add(next_int(&mut iter) as u64);
+        let seq = node_id.seq.wrapping_add(next_int(&mut iter) as u64);
+        let (master_fields, len, lp_count) = match flags & STREAM_ITEM_FLAG_SAMEFIELDS {
+            0 => {
+                let len = next_int(&mut iter) as usize;
+                (None, len, 4 + 2 * len)
+            }
+            _ => (
+                Some(master_fields.clone()),
+                num_master_fields,
+                3 + num_master_fields,
+            ),
+        };
+        let values = iter.clone();
+        // Skips values (or fields interleaved with values) & `lp-count`.
+        iter.nth(if master_fields.is_some() {
+            len
+        } else {
+            2 * len
+        });
+
+        entries.push(NodeEntry {
+            idx,
+            flags,
+            entry: StreamEntry {
+                id: StreamId::new(ms, seq),
+                master_fields,
+                values,
+                len,
+            },
+        });
+        idx += lp_count + 1;
+    }
+    entries
+}
+
+// Gets an iterator over entries (of `nodes`) with IDs in the range `r`.
+fn range_of<R>(nodes: &RRax<RListPack>, r: R) -> RStreamIter<'_>
+where
+    R: RangeBounds<StreamId>,
+{
+    let start = match r.start_bound() {
+        Bound::Included(&id) => Some(id),
+        Bound::Excluded(&id) => id.next(),
+        Bound::Unbounded => Some(StreamId::MIN),
+    };
+    let end = match r.end_bound() {
+        Bound::Included(&id) => Some(id),
+        Bound::Excluded(&id) => id.prev(),
+        Bound::Unbounded => Some(StreamId::MAX),
+    };
+    let (start, end, nodes) = match (start, end) {
+        (Some(start), Some(end)) if start <= end => {
+            // The first node MAYBE keyed before `start`.
+            let first = nodes
+                .range(Bound::Unbounded, Bound::Included(&start.to_key()))
+                .next_back()
+                .map_or(start, |(key, _)| StreamId::from_key(&key));
+            let (first, end_key) = (first.to_key(), end.to_key());
+            let nodes = nodes.range(Bound::Included(&first), Bound::Included(&end_key));
+            (start, end, nodes)
+        }
+        // Nothing is less than the MIN ID.
+        _ => {
+            let nodes = nodes.range(Bound::Unbounded, Bound::Excluded(&StreamId::MIN.to_key()));
+            (StreamId::MAX, StreamId::MIN, nodes)
+        }
+    };
+    RStreamIter {
+        nodes,
+        front: Vec::new().into_iter(),
+        back: Vec::new().into_iter(),
+        start,
+        end,
+    }
+}
+
+// private methods
+impl RStream {
+    // Gets the node to append to, creating it (keyed by `id`) if the last one is full.
+    fn node_for_append<F, V>(
+        &mut self,
+        id: StreamId,
+        fields: &[(F, V)],
+    ) -> (StreamId, &mut RListPack)
+    where
+        F: AsRef<[u8]>,
+    {
+        let last = self.nodes.last().and_then(|(key, lp)| {
+            let (count, deleted) = node_counts(lp);
+            let full = lp.bytes_len() >= STREAM_NODE_MAX_BYTES
+                || count + deleted >= STREAM_NODE_MAX_ENTRIES;
+            match full {
+                true => None,
+                false => Some(StreamId::from_key(&key)),
+            }
+        });
+        let node_id = match last {
+            Some(node_id) => node_id,
+            None => {
+                let mut lp = RListPack::new();
+                push_int(&mut lp, 0);
+                push_int(&mut lp, 0);
+                push_int(&mut lp, fields.len() as i64);
+                for (field, _) in fields {
+                    lp.push_back(field.as_ref());
+                }
+                push_int(&mut lp, 0);
+                self.nodes.insert(&id.to_key(), lp);
+                id
+            }
+        };
+        let lp = self
+            .nodes
+            .get_mut(&node_id.to_key())
+            .expect("stream node missing");
+        (node_id, lp)
+    }
+}
+
+// public methods
+impl RStream {
+    /// Create an empty stream.
+    #[inline]
+    pub fn new() -> Self {
+        RStream {
+            nodes: RRax::new(),
+            len: 0,
+            last_id: StreamId::MIN,
+            entries_added: 0,
+            groups: BTreeMap::new(),
+        }
+    }
+
+    /// Get number of entries (introduced from Redis XLEN).
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the ID of the last entry ever added, which is NOT changed by trimming.
+    #[inline]
+    pub fn last_id(&self) -> StreamId {
+        self.last_id
+    }
+
+    /// Get the ID of the first entry, or `None` if empty.
+    #[inline]
+    pub fn first_id(&self) -> Option<StreamId> {
+        self.iter().next().map(|entry| entry.id())
+    }
+
+    /// Get number of entries ever added.
+    #[inline]
+    pub fn entries_added(&self) -> u64 {
+        self.entries_added
+    }
+
+    /// Add an entry with field-value pairs (introduced from Redis `streamAppendItem`),
+    /// and return its ID.
+    ///
+    /// # Panics
+    ///
+    /// Panics if NO fields are given.
+    pub fn add<F, V>(&mut self, id: XAddId, fields: &[(F, V)]) -> Result<StreamId, StreamError>
+    where
+        F: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        assert!(!fields.is_empty(), "stream entry without fields");
+        let last = self.last_id;
+        if last == StreamId::MAX {
+            return Err(StreamError::IdExhausted);
+        }
+        let id = match id {
+            XAddId::Auto => {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |d| d.as_millis() as u64);
+                match now > last.ms {
+                    true => StreamId::new(now, 0),
+                    false => last.next().ok_or(StreamError::IdExhausted)?,
+                }
+            }
+            XAddId::AutoSeq(ms) if ms == last.ms => {
+                StreamId::new(ms, last.seq.checked_add(1).ok_or(StreamError::IdTooSmall)?)
+            }
+            XAddId::AutoSeq(ms) if ms > last.ms => StreamId::new(ms, 0),
+            XAddId::AutoSeq(_) => return Err(StreamError::IdTooSmall),
+            XAddId::Explicit(StreamId::MIN) => return Err(StreamError::IdZero),
+            XAddId::Explicit(id) if id <= last => return Err(StreamError::IdTooSmall),
+            XAddId::Explicit(id) => id,
+        };
+
+        let (node_id, lp) = self.node_for_append(id, fields);
+        let mut iter = lp.iter();
+        let (count, _) = (next_int(&mut iter), next_int(&mut iter));
+        let num_master_fields = next_int(&mut iter) as usize;
+        let same_fields = num_master_fields == fields.len()
+            && fields
+                .iter()
+                .all(|(field, _)| iter.next().is_some_and(|f| f.eq_bytes(field.as_ref())));
+
+        let flags = match same_fields {
+            true => STREAM_ITEM_FLAG_SAMEFIELDS,
+            false => 0,
+        };
+        push_int(lp, flags);
+        push_int(lp, id.ms.wrapping_sub(node_id.ms) as i64);
+        push_int(lp, id.seq.wrapping_sub(node_id.seq) as i64);
+        if same_fields {
+            for (_, value) in fields {
+                lp.push_back(value.as_ref());
+            }
+            push_int(lp, 3 + fields.len() as i64);
+        } else {
+            push_int(lp, fields.len() as i64);
+            for (field, value) in fields {
+                lp.push_back(field.as_ref());
+                lp.push_back(value.as_ref());
+            }
+            push_int(lp, 4 + 2 * fields.len() as i64);
+        }
+        replace_int(lp, 0, count + 1);
+
+        self.len += 1;
+        self.last_id = id;
+        self.entries_added += 1;
+        Ok(id)
+    }
+
+    /// Get an iterator over entries with IDs in the range `r` (introduced from Redis
+    /// XRANGE), which is reversed for XREVRANGE.
+    pub fn range<R>(&self, r: R) -> RStreamIter<'_>
+    where
+        R: RangeBounds<StreamId>,
+    {
+        range_of(&self.nodes, r)
+    }
+
+    /// Get an iterator over ALL entries, in ascending order of IDs.
+    #[inline]
+    pub fn iter(&self) -> RStreamIter<'_> {
+        self.range(..)
+    }
+
+    /// Get the entry of `id`, or `None` if NOT found.
+    #[inline]
+    pub fn get(&self, id: StreamId) -> Option<StreamEntry<'_>> {
+        self.range(id..=id).next()
+    }
+
+    /// Trim the stream (introduced from Redis `streamTrim`), and return number of entries
+    /// removed, where ONLY whole nodes are removed if `approx` (i.e. "~").
+    ///
+    /// # Notes
+    ///
+    /// Entries removed are NOT removed from pending entries of consumer groups.
+    pub fn trim(&mut self, trim: StreamTrim, approx: bool) -> usize {
+        let mut removed = 0;
+        while let Some((key, _)) = self.nodes.first() {
+            let node_id = StreamId::from_key(&key);
+            let lp = self.nodes.get_mut(&key).expect("stream node missing");
+            let (count, deleted) = node_counts(lp);
+            let entries = node_entries(node_id, lp);
+            let mut live = entries
+                .iter()
+                .filter(|e| e.flags & STREAM_ITEM_FLAG_DELETED == 0);
+            let whole = match trim {
+                StreamTrim::MaxLen(max) if self.len <= max => break,
+                StreamTrim::MaxLen(max) => self.len - count >= max,
+                StreamTrim::MinId(min) => live.next_back().is_some_and(|e| e.entry.id < min),
+            };
+            if whole {
+                self.nodes.remove(&key);
+                self.len -= count;
+                removed += count;
+                continue;
+            }
+            if approx {
+                break;
+            }
+
+            // Marks the leading entries deleted, where the node is NOT emptied.
+            let len = self.len;
+            let marks: Vec<(usize, i64)> = entries
+                .iter()
+                .filter(|e| e.flags & STREAM_ITEM_FLAG_DELETED == 0)
+                .enumerate()
+                .take_while(|(n, e)| match trim {
+                    StreamTrim::MaxLen(max) => len - n > max,
+                    StreamTrim::MinId(min) => e.entry.id < min,
+                })
+                .map(|(_, e)| (e.idx, e.flags))
+                .collect();
+            for &(idx, flags) in marks.iter() {
+                replace_int(lp, idx, flags | STREAM_ITEM_FLAG_DELETED);
+            }
+            let n = marks.len();
+            replace_int(lp, 0, (count - n) as i64);
+            replace_int(lp, 1, (deleted + n) as i64);
+            self.len -= n;
+            removed += n;
+            break;
+        }
+        removed
+    }
+}
+
+impl Default for RStream {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for RStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.iter().map(|entry| (entry.id(), entry)))
+            .finish()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// StreamEntry
+////////////////////////////////////////////////////////////////////////////////
+
+impl<'a> StreamEntry<'a> {
+    #[inline]
+    pub fn id(&self) -> StreamId {
+        self.id
+    }
+
+    /// Get number of field-value pairs.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get an iterator over field-value pairs, in the order added.
+    #[inline]
+    pub fn fields(&self) -> StreamFieldsIter<'a> {
+        StreamFieldsIter {
+            master_fields: self.master_fields.clone(),
+            values: self.values.clone(),
+            remaining: self.len,
+        }
+    }
+}
+
+impl<'a> fmt::Debug for StreamEntry<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.fields()).finish()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Consumer Groups
+////////////////////////////////////////////////////////////////////////////////
+
+/// Pending entry of a consumer group (introduced from Redis `streamNACK`), i.e. an entry
+/// delivered to a consumer but NOT acknowledged yet.
+#[derive(Clone, Debug)]
+pub struct StreamNack {
+    consumer: RString,
+    delivery_time: u64,
+    delivery_count: u64,
+}
+
+/// Consumer of a consumer group (introduced from Redis `streamConsumer`), with IDs of
+/// entries pending for it.
+#[derive(Clone, Debug)]
+pub struct StreamConsumer {
+    seen_time: u64,
+    pel: BTreeSet<StreamId>,
+}
+
+/// Consumer group of an `RStream` (introduced from Redis `streamCG`), where entries after
+/// `last_id` are delivered to consumers, and kept pending (in the PEL) until acknowledged.
+///
+/// Times are given by callers, in milliseconds of the UNIX time.
+#[derive(Clone, Debug)]
+pub struct StreamGroup {
+    last_id: StreamId,
+    // Pending entries of ALL consumers.
+    pel: BTreeMap<StreamId, StreamNack>,
+    consumers: BTreeMap<RString, StreamConsumer>,
+}
+
+/// Result of `RStream::auto_claim` (introduced from Redis XAUTOCLAIM).
+#[derive(Debug)]
+pub struct AutoClaim<'a> {
+    /// The ID to scan from next time, or the MIN ID if the whole PEL is scanned.
+    pub next: StreamId,
+    /// Entries claimed.
+    pub claimed: Vec<StreamEntry<'a>>,
+    /// IDs of entries NOT found in the stream, which are removed from the PEL.
+    pub deleted: Vec<StreamId>,
+}
+
+impl StreamNack {
+    /// Get the name of the consumer the entry is delivered to.
+    #[inline]
+    pub fn consumer(&self) -> &[u8] {
+        self.consumer.as_bytes()
+    }
+
+    /// Get the time of the last delivery.
+    #[inline]
+    pub fn delivery_time(&self) -> u64 {
+        self.delivery_time
+    }
+
+    /// Get number of times the entry is delivered.
+    #[inline]
+    pub fn delivery_count(&self) -> u64 {
+        self.delivery_count
+    }
+}
+
+impl StreamConsumer {
+    /// Get the time the consumer is seen last.
+    #[inline]
+    pub fn seen_time(&self) -> u64 {
+        self.seen_time
+    }
+
+    #[inline]
+    pub fn pending_len(&self) -> usize {
+        self.pel.len()
+    }
+
+    /// Get an iterator over IDs of entries pending for the consumer, in ascending order.
+    #[inline]
+    pub fn pending_ids(&self) -> std::iter::Copied<btree_set::Iter<'_, StreamId>> {
+        self.pel.iter().copied()
+    }
+}
+
+// private methods
+impl StreamGroup {
+    // Gets the consumer of `name`, created if NOT found, which is seen at `now`.
+    fn touch_consumer(&mut self, name: &[u8], now: u64) -> &mut StreamConsumer {
+        if !self.consumers.contains_key(name) {
+            self.create_consumer(name, now);
+        }
+        let consumer = self.consumers.get_mut(name).expect("consumer missing");
+        consumer.seen_time = now;
+        consumer
+    }
+
+    // Assigns the pending entry of `id` (created if NOT found) to `consumer` (which MUST
+    // exist), delivered at `now`.
+    fn assign(&mut self, id: StreamId, consumer: &[u8], now: u64) -> &mut StreamNack {
+        let nack = self.pel.entry(id).or_insert_with(|| StreamNack {
+            consumer: RString::from_bytes(consumer),
+            delivery_time: now,
+            delivery_count: 0,
+        });
+        if nack.consumer.as_bytes() != consumer {
+            if let Some(owner) = self.consumers.get_mut(nack.consumer.as_bytes()) {
+                owner.pel.remove(&id);
+            }
+            nack.consumer = RString::from_bytes(consumer);
+        }
+        nack.delivery_time = now;
+        self.consumers
+            .get_mut(consumer)
+            .expect("consumer missing")
+            .pel
+            .insert(id);
+        nack
+    }
+
+    // Removes the pending entry of `id`, and returns `false` if NOT found.
+    fn remove_pending(&mut self, id: StreamId) -> bool {
+        match self.pel.remove(&id) {
+            Some(nack) => {
+                if let Some(owner) = self.consumers.get_mut(nack.consumer.as_bytes()) {
+                    owner.pel.remove(&id);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// public methods
+impl StreamGroup {
+    /// Get the ID of the last entry delivered.
+    #[inline]
+    pub fn last_id(&self) -> StreamId {
+        self.last_id
+    }
+
+    /// Set the ID of the last entry delivered (introduced from Redis XGROUP SETID).
+    #[inline]
+    pub fn set_last_id(&mut self, id: StreamId) {
+        self.last_id = id;
+    }
+
+    /// Get number of pending entries of ALL consumers.
+    #[inline]
+    pub fn pending_len(&self) -> usize {
+        self.pel.len()
+    }
+
+    /// Get the pending entry of `id`, or `None` if NOT pending.
+    #[inline]
+    pub fn pending(&self, id: StreamId) -> Option<&StreamNack> {
+        self.pel.get(&id)
+    }
+
+    /// Get an iterator over pending entries with IDs in the range `r` (introduced from
+    /// Redis XPENDING), in ascending order.
+    #[inline]
+    pub fn pending_range<R>(&self, r: R) -> btree_map::Range<'_, StreamId, StreamNack>
+    where
+        R: RangeBounds<StreamId>,
+    {
+        self.pel.range(r)
+    }
+
+    #[inline]
+    pub fn consumer(&self, name: &[u8]) -> Option<&StreamConsumer> {
+        self.consumers.get(name)
+    }
+
+    /// Get an iterator over consumers, in ascending order of names.
+    #[inline]
+    pub fn consumers(&self) -> btree_map::Iter<'_, RString, StreamConsumer> {
+        self.consumers.iter()
+    }
+
+    /// Create a consumer seen at `now` (introduced from Redis XGROUP CREATECONSUMER), and
+    /// return `false` if it exists already.
+    pub fn create_consumer(&mut self, name: &[u8], now: u64) -> bool {
+        if self.consumers.contains_key(name) {
+            return false;
+        }
+        let consumer = StreamConsumer {
+            seen_time: now,
+            pel: BTreeSet::new(),
+        };
+        self.consumers.insert(RString::from_bytes(name), consumer);
+        true
+    }
+
+    /// Remove a consumer & its pending entries (introduced from Redis XGROUP DELCONSUMER),
+    /// and return number of its pending entries, or `None` if NOT found.
+    pub fn remove_consumer(&mut self, name: &[u8]) -> Option<usize> {
+        let consumer = self.consumers.remove(name)?;
+        for id in consumer.pel.iter() {
+            self.pel.remove(id);
+        }
+        Some(consumer.pel.len())
+    }
+
+    /// Acknowledge entries (introduced from Redis XACK), i.e. remove them from the PEL,
+    /// and return number of entries acknowledged.
+    pub fn ack(&mut self, ids: &[StreamId]) -> usize {
+        ids.iter().filter(|&&id| self.remove_pending(id)).count()
+    }
+}
+
+// consumer groups
+impl RStream {
+    /// Create a consumer group delivering entries after `last_id` (introduced from Redis
+    /// XGROUP CREATE), where `last_id()` is given for "$".
+    pub fn create_group(
+        &mut self,
+        name: &[u8],
+        last_id: StreamId,
+    ) -> Result<&mut StreamGroup, StreamError> {
+        if self.groups.contains_key(name) {
+            return Err(StreamError::BusyGroup);
+        }
+        let group = StreamGroup {
+            last_id,
+            pel: BTreeMap::new(),
+            consumers: BTreeMap::new(),
+        };
+        Ok(self
+            .groups
+            .entry(RString::from_bytes(name))
+            .or_insert(group))
+    }
+
+    /// Remove a consumer group (introduced from Redis XGROUP DESTROY), and return `false`
+    /// if NOT found.
+    #[inline]
+    pub fn remove_group(&mut self, name: &[u8]) -> bool {
+        self.groups.remove(name).is_some()
+    }
+
+    #[inline]
+    pub fn group(&self, name: &[u8]) -> Option<&StreamGroup> {
+        self.groups.get(name)
+    }
+
+    #[inline]
+    pub fn group_mut(&mut self, name: &[u8]) -> Option<&mut StreamGroup> {
+        self.groups.get_mut(name)
+    }
+
+    /// Get an iterator over consumer groups, in ascending order of names.
+    #[inline]
+    pub fn groups(&self) -> btree_map::Iter<'_, RString, StreamGroup> {
+        self.groups.iter()
+    }
+
+    /// Deliver (at most `count`) entries NEVER delivered to the group to `consumer`
+    /// (introduced from Redis XREADGROUP with ">"), where entries are added to the PEL
+    /// unless `noack`.
+    pub fn read_group(
+        &mut self,
+        group: &[u8],
+        consumer: &[u8],
+        count: Option<usize>,
+        noack: bool,
+        now: u64,
+    ) -> Result<Vec<StreamEntry<'_>>, StreamError> {
+        let group = self.groups.get_mut(group).ok_or(StreamError::NoGroup)?;
+        group.touch_consumer(consumer, now);
+        let entries: Vec<_> = range_of(
+            &self.nodes,
+            (Bound::Excluded(group.last_id), Bound::Unbounded),
+        )
+        .take(count.unwrap_or(usize::MAX))
+        .collect();
+        for entry in entries.iter() {
+            group.last_id = entry.id();
+            if !noack {
+                group.assign(entry.id(), consumer, now).delivery_count = 1;
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Deliver again (at most `count`) entries pending for `consumer` with IDs greater than
+    /// `start` (introduced from Redis XREADGROUP with an ID), where entries NOT found in the
+    /// stream any more are given as `None`.
+    pub fn read_pending(
+        &mut self,
+        group: &[u8],
+        consumer: &[u8],
+        start: StreamId,
+        count: Option<usize>,
+        now: u64,
+    ) -> Result<Vec<(StreamId, Option<StreamEntry<'_>>)>, StreamError> {
+        let group = self.groups.get_mut(group).ok_or(StreamError::NoGroup)?;
+        let ids: Vec<StreamId> = group
+            .touch_consumer(consumer, now)
+            .pel
+            .range((Bound::Excluded(start), Bound::Unbounded))
+            .copied()
+            .take(count.unwrap_or(usize::MAX))
+            .collect();
+        let mut entries = Vec::with_capacity(ids.len());
+        for id in ids {
+            let nack = group.assign(id, consumer, now);
+            nack.delivery_count += 1;
+            entries.push((id, range_of(&self.nodes, id..=id).next()));
+        }
+        Ok(entries)
+    }
+
+    /// Claim pending entries idle for at least `min_idle` milliseconds to `consumer`
+    /// (introduced from Redis XCLAIM), and return entries claimed, where ones NOT found in
+    /// the stream any more are removed from the PEL.
+    pub fn claim(
+        &mut self,
+        group: &[u8],
+        consumer: &[u8],
+        min_idle: u64,
+        ids: &[StreamId],
+        now: u64,
+    ) -> Result<Vec<StreamEntry<'_>>, StreamError> {
+        let group = self.groups.get_mut(group).ok_or(StreamError::NoGroup)?;
+        group.touch_consumer(consumer, now);
+        let mut claimed = Vec::new();
+        for &id in ids {
+            let idle = match group.pel.get(&id) {
+                Some(nack) => now.saturating_sub(nack.delivery_time),
+                None => continue,
+            };
+            match range_of(&self.nodes, id..=id).next() {
+                Some(_) if idle < min_idle => {}
+                Some(entry) => {
+                    group.assign(id, consumer, now).delivery_count += 1;
+                    claimed.push(entry);
+                }
+                None => {
+                    group.remove_pending(id);
+                }
+            }
+        }
+        Ok(claimed)
+    }
+
+    /// Claim (at most `count`) pending entries idle for at least `min_idle` milliseconds to
+    /// `consumer`, scanning the PEL from `start` (introduced from Redis XAUTOCLAIM), where
+    /// at most `10 * count` pending entries are scanned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count` is ZERO.
+    pub fn auto_claim(
+        &mut self,
+        group: &[u8],
+        consumer: &[u8],
+        min_idle: u64,
+        start: StreamId,
+        count: usize,
+        now: u64,
+    ) -> Result<AutoClaim<'_>, StreamError> {
+        assert!(count > 0, "auto claim with ZERO count");
+        let group = self.groups.get_mut(group).ok_or(StreamError::NoGroup)?;
+        group.touch_consumer(consumer, now);
+        let mut scanned: Vec<(StreamId, u64)> = group
+            .pel
+            .range(start..)
+            .take(count.saturating_mul(10).saturating_add(1))
+            .map(|(&id, nack)| (id, now.saturating_sub(nack.delivery_time)))
+            .collect();
+        // The one more scanned is where to scan from next time, if any.
+        let next = match scanned.len() > count.saturating_mul(10) {
+            true => scanned.pop().map_or(StreamId::MIN, |(id, _)| id),
+            false => StreamId::MIN,
+        };
+
+        let mut result = AutoClaim {
+            next,
+            claimed: Vec::new(),
+            deleted: Vec::new(),
+        };
+        for &(id, idle) in scanned.iter() {
+            if result.claimed.len() == count {
+                result.next = id;
+                break;
+            }
+            match range_of(&self.nodes, id..=id).next() {
+                Some(_) if idle < min_idle => {}
+                Some(entry) => {
+                    group.assign(id, consumer, now).delivery_count += 1;
+                    result.claimed.push(entry);
+                }
+                None => {
+                    group.remove_pending(id);
+                    result.deleted.push(id);
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Iterators
+////////////////////////////////////////////////////////////////////////////////
+
+impl<'a> IntoIterator for &'a RStream {
+    type Item = StreamEntry<'a>;
+    type IntoIter = RStreamIter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> RStreamIter<'a> {
+    // Decodes entries (in the range, NOT deleted) of a node.
+    fn entries_of(
+        &self,
+        node_id: StreamId,
+        lp: &'a RListPack,
+    ) -> std::vec::IntoIter<StreamEntry<'a>> {
+        node_entries(node_id, lp)
+            .into_iter()
+            .filter(|e| e.flags & STREAM_ITEM_FLAG_DELETED == 0)
+            .map(|e| e.entry)
+            .filter(|entry| self.start <= entry.id && entry.id <= self.end)
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+impl<'a> Iterator for RStreamIter<'a> {
+    type Item = StreamEntry<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(entry) = self.front.next() {
+                return Some(entry);
+            }
+            match self.nodes.next() {
+                Some((key, lp)) => self.front = self.entries_of(StreamId::from_key(&key), lp),
+                None => return self.back.next(),
+            }
+        }
+    }
+}
+
+impl<'a> DoubleEndedIterator for RStreamIter<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(entry) = self.back.next_back() {
+                return Some(entry);
+            }
+            match self.nodes.next_back() {
+                Some((key, lp)) => self.back = self.entries_of(StreamId::from_key(&key), lp),
+                None => return self.front.next_back(),
+            }
+        }
+    }
+}
+
+impl<'a> FusedIterator for RStreamIter<'a> {}
+
+impl<'a> Iterator for StreamFieldsIter<'a> {
+    type Item = (ListPackValue<'a>, ListPackValue<'a>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let field = match &mut self.master_fields {
+            Some(fields) => fields.next()?,
+            None => self.values.next()?,
+        };
+        Some((field, self.values.next()?))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a> ExactSizeIterator for StreamFieldsIter<'a> {}
+
+impl<'a> FusedIterator for StreamFieldsIter<'a> {}
@@ -1,17 +1,39 @@
-use rmem::{mem_cmp, mem_copy, mem_move, mem_set};
-use rmem::{zfree, zmalloc, zrealloc};
+use rmem::{mem_cmp, mem_cmp_ct, mem_copy, mem_move, mem_set};
+use rmem::{zfree_with_len, zmalloc_with_len, zmem_len, zmem_set_len, zmem_size_of};
+use rmem::{zrealloc_with_len, zrealloc_with_len_headroom};
+use std::borrow::Borrow;
 use std::cmp::Ordering;
+use std::convert::TryFrom;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::{Copied, FromIterator};
 use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut, Index, IndexMut};
+use std::slice::{self, SliceIndex};
+use std::string::FromUtf8Error;
 
-pub struct RString {
-    len: usize,
-    cap: usize,
+use crate::num::{format_i64, parse_f64, parse_i64, ParseNumError, I64_MAX_LEN};
+use crate::RStr;
 
+/// RString is a binary-safe string represented by a single `pointer`, where its length
+/// & capacity are stored in the ZMEM header (with length) of its data.
+pub struct RString {
     data: *const u8,
     _marker: PhantomData<u8>,
 }
 
+// RString is Send & Sync, just like `Vec<u8>` is, as:
+//
+// - It owns its data exclusively (NEVER aliased by other strings), and the data is ONLY
+//   mutated via `&mut self`, thus `&RString` exposes read-only access.
+// - Its data is allocated/deallocated via `rmem`, whose backends are thread-safe (`Allocator`
+//   MUST be `Sync`), thus it MAYBE deallocated by a thread other than the allocating one
+//   (such as a lazy-free thread), where the thread-local cache & statistics of the
+//   deallocating thread are updated.
+// - No thread-local or interior-mutable state is kept by itself.
+unsafe impl Send for RString {}
+unsafe impl Sync for RString {}
+
 impl RString {
     #[inline]
     pub fn new() -> Self {
@@ -19,11 +41,9 @@ impl RString {
     }
 
     pub fn with_capacity(capacity: usize) -> Self {
-        let (ptr, cap) = zmalloc(capacity);
+        let (ptr, _) = zmalloc_with_len(capacity);
 
         RString {
-            len: 0,
-            cap: cap,
             data: ptr as _,
             _marker: PhantomData,
         }
@@ -40,35 +60,44 @@ impl RString {
     }
 
     #[inline]
-    pub const fn len(&self) -> usize {
-        self.len
+    pub fn len(&self) -> usize {
+        zmem_len(self.data as _)
     }
 
+    /// Get capacity (in bytes) of the string, which is the usable size of its memory block
+    /// (MAYBE larger than requested, as sizes are rounded up by the allocator), thus
+    /// the slack already paid for is taken use of by appends WITHOUT reallocation.
     #[inline]
-    pub const fn capacity(&self) -> usize {
-        self.cap
+    pub fn capacity(&self) -> usize {
+        zmem_size_of(self.data as _)
     }
 
+    /// Get bytes available for appends WITHOUT reallocation, see `capacity`.
     #[inline]
-    pub const fn avail(&self) -> usize {
-        self.cap - self.len
+    pub fn avail(&self) -> usize {
+        self.capacity() - self.len()
     }
 
     #[inline]
-    pub const fn is_empty(&self) -> bool {
+    pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
 
     #[inline]
-    pub const fn is_full(&self) -> bool {
+    pub fn is_full(&self) -> bool {
         self.avail() == 0
     }
+
+    #[inline]
+    fn set_len(&mut self, len: usize) {
+        zmem_set_len(self.as_mut_ptr(), len);
+    }
 }
 
 impl Drop for RString {
     #[inline]
     fn drop(&mut self) {
-        zfree(self.as_mut_ptr());
+        zfree_with_len(self.as_mut_ptr());
     }
 }
 
@@ -82,13 +111,13 @@ impl Default for RString {
 impl RString {
     #[inline]
     pub fn clear(&mut self) {
-        self.len = 0;
+        self.set_len(0);
     }
 
     #[inline]
     pub fn truncate(&mut self, new_len: usize) {
         if new_len < self.len() {
-            self.len = new_len;
+            self.set_len(new_len);
         }
     }
 
@@ -106,19 +135,37 @@ impl RString {
         }
     }
 
+    /// Reserve capacity for (at least) `extra` more bytes, with headroom preallocated for
+    /// further growth, thus appending repeatedly costs amortized O(1) only.
+    ///
+    /// The capacity is doubled while less than the MAX preallocation size (configured with
+    /// `rmem::set_zmem_max_prealloc`), otherwise it grows linearly by the MAX preallocation size.
     #[inline]
     pub fn reserve(&mut self, extra: usize) {
         if self.avail() < extra {
-            self.resize(self.len() + extra);
+            let (ptr, _) = zrealloc_with_len_headroom(self.as_mut_ptr(), self.required(extra));
+            self.data = ptr as _;
         }
     }
 
+    /// Reserve capacity for (at least) `extra` more bytes, WITHOUT headroom preallocated,
+    /// which SHOULD be used ONLY if the final length is known.
+    #[inline]
+    pub fn reserve_exact(&mut self, extra: usize) {
+        if self.avail() < extra {
+            self.resize(self.required(extra));
+        }
+    }
+
+    #[inline]
+    fn required(&self, extra: usize) -> usize {
+        self.len().checked_add(extra).expect("capacity overflow")
+    }
+
     fn resize(&mut self, min_capacity: usize) {
         let target_capacity = std::cmp::max(self.len(), min_capacity);
-        let (ptr, cap) = zrealloc(self.as_mut_ptr(), target_capacity);
-
+        let (ptr, _) = zrealloc_with_len(self.as_mut_ptr(), target_capacity);
         self.data = ptr as _;
-        self.cap = cap;
     }
 
     pub fn sub_rstr(&self, start: usize, end: usize) -> RString {
@@ -146,7 +193,7 @@ impl RString {
             unsafe {
                 mem_move(self.as_ptr().add(start), self.as_mut_ptr(), end - start);
             }
-            self.len = end - start;
+            self.set_len(end - start);
         }
     }
 
@@ -158,7 +205,7 @@ impl RString {
     #[inline]
     pub fn rtrim(&mut self, end: usize) {
         if end < self.len() {
-            self.len = end;
+            self.set_len(end);
         }
     }
 }
@@ -178,10 +225,40 @@ impl RString {
         let mut bytes = Vec::with_capacity(self.len());
         unsafe {
             mem_copy(self.as_ptr(), bytes.as_mut_ptr(), self.len());
+            bytes.set_len(self.len());
         }
         bytes
     }
 
+    /// Create a string from `bytes`, which are COPIED (& dropped then).
+    ///
+    /// # Notes
+    ///
+    /// The buffer of `bytes` is NEVER reused, as data of `RString` MUST be ZMEM-style
+    /// memory (with the header before) allocated via `rmem`, i.e. this costs a copy.
+    #[inline]
+    pub fn from_vec(bytes: Vec<u8>) -> Self {
+        Self::from_bytes(&bytes)
+    }
+
+    /// Create a string from `s`, which is COPIED (& dropped then), see `from_vec`.
+    #[inline]
+    pub fn from_string(s: String) -> Self {
+        Self::from_vec(s.into_bytes())
+    }
+
+    /// Convert the string into a `Vec<u8>`, where the content is COPIED (& the string
+    /// dropped then), as the buffer of `Vec` can NOT own ZMEM-style memory, see `from_vec`.
+    #[inline]
+    pub fn into_vec(self) -> Vec<u8> {
+        self.to_bytes()
+    }
+
+    /// Convert the content into a `String`.
+    ///
+    /// # Safety
+    ///
+    /// The content is expected to be valid UTF-8, otherwise an empty `String` is returned.
     pub unsafe fn to_string(&self) -> String {
         match std::str::from_utf8(self.as_bytes()) {
             Ok(s) => s.to_owned(),
@@ -211,21 +288,197 @@ impl RString {
         unsafe {
             mem_set(self.as_mut_ptr().add(self.len()), value, count);
         }
-        self.len += count;
+        self.set_len(self.len() + count);
     }
 
-    unsafe fn from_raw_data(data: *const u8, len: usize) -> Self {
-        let (ptr, cap) = zmalloc(len);
-        mem_copy(data, ptr, len);
+    /// Append a single byte.
+    #[inline]
+    pub fn push(&mut self, byte: u8) {
+        self.reserve(1);
+        unsafe { *self.as_mut_ptr().add(self.len()) = byte };
+        self.set_len(self.len() + 1);
+    }
 
-        RString {
-            len: len,
-            cap: cap,
-            data: ptr as _,
-            _marker: PhantomData,
+    /// Remove the last byte & return it, or `None` if empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<u8> {
+        let len = self.len().checked_sub(1)?;
+        let byte = self.as_bytes()[len];
+        self.set_len(len);
+        Some(byte)
+    }
+
+    /// Get an iterator over bytes of the content (by value).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RString;
+    ///
+    /// let mut s = RString::new();
+    /// s.push(b'o');
+    /// s.push(b'k');
+    /// assert_eq!(s.bytes().rev().collect::<Vec<_>>(), b"ko");
+    /// assert_eq!(s.pop(), Some(b'k'));
+    /// ```
+    #[inline]
+    pub fn bytes(&self) -> Copied<slice::Iter<'_, u8>> {
+        self.as_bytes().iter().copied()
+    }
+
+    /// Get an iterator over chunks of `size` bytes of the content, where the last chunk
+    /// MAYBE shorter.
+    ///
+    /// # Panics
+    ///
+    /// `size` MUST NOT be ZERO.
+    #[inline]
+    pub fn chunks(&self, size: usize) -> slice::Chunks<'_, u8> {
+        self.as_bytes().chunks(size)
+    }
+
+    /// Append formatted content (built with `format_args!`), WITHOUT an intermediate `String`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RString;
+    ///
+    /// let mut s = RString::from_str("used_memory:");
+    /// s.append_fmt(format_args!("{}\r\n", 1024));
+    /// assert_eq!(s.as_bytes(), b"used_memory:1024\r\n");
+    /// ```
+    #[inline]
+    pub fn append_fmt(&mut self, args: fmt::Arguments<'_>) {
+        // Writing into RString NEVER fails.
+        let _ = fmt::Write::write_fmt(self, args);
+    }
+
+    /// Create a string holding formatted content (built with `format_args!`), WITHOUT
+    /// an intermediate `String` (introduced from Redis `sdscatprintf`), see also `rformat!`.
+    ///
+    /// Content without arguments (such as static replies) is copied at once into memory
+    /// of the exact size.
+    #[inline]
+    pub fn from_fmt(args: fmt::Arguments<'_>) -> Self {
+        match args.as_str() {
+            Some(s) => Self::from_str(s),
+            None => {
+                let mut rstr = Self::new();
+                rstr.append_fmt(args);
+                rstr
+            }
         }
     }
 
+    /// Parse the content strictly into `i64`, where ONLY the canonical form is accepted
+    /// (NO spaces, `+` signs or leading zeros).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RString;
+    ///
+    /// assert_eq!(RString::from_str("-42").parse_i64(), Ok(-42));
+    /// assert!(RString::from_str("042").parse_i64().is_err());
+    /// assert!(RString::from_str("9223372036854775808").parse_i64().is_err());
+    /// ```
+    #[inline]
+    pub fn parse_i64(&self) -> Result<i64, ParseNumError> {
+        parse_i64(self.as_bytes())
+    }
+
+    /// Parse the content into `f64`, where NO spaces are allowed and NaN is rejected.
+    #[inline]
+    pub fn parse_f64(&self) -> Result<f64, ParseNumError> {
+        parse_f64(self.as_bytes())
+    }
+
+    /// Create a string holding `value` formatted in decimal.
+    #[inline]
+    pub fn from_i64(value: i64) -> Self {
+        let mut buf = [0u8; I64_MAX_LEN];
+        let start = format_i64(value, &mut buf);
+        Self::from_bytes(&buf[start..])
+    }
+
+    /// Append `value` formatted in decimal.
+    #[inline]
+    pub fn append_i64(&mut self, value: i64) {
+        let mut buf = [0u8; I64_MAX_LEN];
+        let start = format_i64(value, &mut buf);
+        self.append_bytes(&buf[start..]);
+    }
+
+    /// Increment the integer stored by `delta` (introduced from Redis `INCRBY`), and return
+    /// the result, which is rewritten in place (WITHOUT reallocation if it fits).
+    ///
+    /// # Errors
+    ///
+    /// `ParseNumError::Integer` is returned if the content is NOT an integer, and
+    /// `ParseNumError::Overflow` if the result overflows, where the content is kept as is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::{ParseNumError, RString};
+    ///
+    /// let mut s = RString::from_str("99");
+    /// assert_eq!(s.incr_by(1), Ok(100));
+    /// assert_eq!(s.as_bytes(), b"100");
+    /// assert_eq!(s.incr_by(i64::MAX), Err(ParseNumError::Overflow));
+    /// ```
+    pub fn incr_by(&mut self, delta: i64) -> Result<i64, ParseNumError> {
+        let value = self
+            .parse_i64()?
+            .checked_add(delta)
+            .ok_or(ParseNumError::Overflow)?;
+
+        let mut buf = [0u8; I64_MAX_LEN];
+        let start = format_i64(value, &mut buf);
+        self.copy_bytes(&buf[start..]);
+        Ok(value)
+    }
+
+    /// Increment the float stored by `delta` (introduced from Redis `INCRBYFLOAT`), and return
+    /// the result, which is rewritten in place (WITHOUT reallocation if it fits) in the
+    /// shortest decimal form (without exponents).
+    ///
+    /// # Errors
+    ///
+    /// `ParseNumError::Float` is returned if the content is NOT a valid float, and
+    /// `ParseNumError::NotFinite` if the result is NaN or infinity, where the content is kept
+    /// as is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RString;
+    ///
+    /// let mut s = RString::from_str("10.5");
+    /// assert_eq!(s.incr_by_float(0.1), Ok(10.6));
+    /// assert_eq!(s.as_bytes(), b"10.6");
+    /// assert_eq!(s.incr_by_float(-0.6), Ok(10.0));
+    /// assert_eq!(s.as_bytes(), b"10");
+    /// ```
+    pub fn incr_by_float(&mut self, delta: f64) -> Result<f64, ParseNumError> {
+        let value = self.parse_f64()? + delta;
+        if !value.is_finite() {
+            return Err(ParseNumError::NotFinite);
+        }
+
+        self.clear();
+        self.append_fmt(format_args!("{}", value));
+        Ok(value)
+    }
+
+    unsafe fn from_raw_data(data: *const u8, len: usize) -> Self {
+        let mut rstr = Self::with_capacity(len);
+        mem_copy(data, rstr.as_mut_ptr(), len);
+        rstr.set_len(len);
+        rstr
+    }
+
     unsafe fn copy_raw_data(&mut self, data: *const u8, len: usize) {
         self.clear();
         self.append_raw_data(data, len);
@@ -235,24 +488,325 @@ impl RString {
         self.reserve(len);
 
         mem_copy(data, self.as_mut_ptr().add(self.len()), len);
-        self.len += len;
+        self.set_len(self.len() + len);
     }
 
     unsafe fn replace_raw_data(&mut self, offset: usize, data: *const u8, len: usize) {
-        self.resize(offset + len);
+        let end = offset.checked_add(len).expect("capacity overflow");
+        if end > self.len() {
+            self.reserve(end - self.len());
+        }
 
         if self.len() < offset {
             mem_set(self.as_mut_ptr().add(self.len()), 0, offset - self.len());
         }
         mem_copy(data, self.as_mut_ptr().add(offset), len);
-        self.len = std::cmp::max(self.len(), offset + len);
+        self.set_len(std::cmp::max(self.len(), offset + len));
+    }
+}
+
+impl RString {
+    /// Convert ALL ASCII letters of the content into upper case in place
+    /// (introduced from Redis `sdstoupper`), where non-ASCII bytes are kept as is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RString;
+    ///
+    /// let mut s = RString::from_str("hScan");
+    /// s.make_uppercase();
+    /// assert_eq!(s.as_bytes(), b"HSCAN");
+    /// ```
+    #[inline]
+    pub fn make_uppercase(&mut self) {
+        self.as_mut_bytes().make_ascii_uppercase();
+    }
+
+    /// Convert ALL ASCII letters of the content into lower case in place
+    /// (introduced from Redis `sdstolower`), where non-ASCII bytes are kept as is.
+    #[inline]
+    pub fn make_lowercase(&mut self) {
+        self.as_mut_bytes().make_ascii_lowercase();
+    }
+}
+
+impl RString {
+    /// Find the offset of the first occurrence of `needle` within the content.
+    ///
+    /// An empty `needle` is ALWAYS found at offset ZERO.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RString;
+    ///
+    /// let s = RString::from_str("*2\r\n$3\r\nGET\r\n");
+    /// assert_eq!(s.find(b"\r\n"), Some(2));
+    /// assert_eq!(s.rfind(b"\r\n"), Some(11));
+    /// assert!(!s.contains(b"SET"));
+    /// ```
+    #[inline]
+    pub fn find(&self, needle: &[u8]) -> Option<usize> {
+        self.as_rstr_view().find(needle)
+    }
+
+    /// Find the offset of the last occurrence of `needle` within the content.
+    ///
+    /// An empty `needle` is ALWAYS found at the end (offset of the length).
+    #[inline]
+    pub fn rfind(&self, needle: &[u8]) -> Option<usize> {
+        self.as_rstr_view().rfind(needle)
+    }
+
+    /// Check if `needle` occurs within the content.
+    #[inline]
+    pub fn contains(&self, needle: &[u8]) -> bool {
+        self.find(needle).is_some()
+    }
+
+    /// Check if the content equals `other`, in constant time (such as comparing passwords),
+    /// which does NOT depend on the position of the first differing byte.
+    ///
+    /// # Notes
+    ///
+    /// The running time depends ONLY on the length of `other`, thus `self` SHOULD be
+    /// the secret & `other` the input, that the length of the secret is NOT leaked either.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RString;
+    ///
+    /// let password = RString::from_str("foobared");
+    /// assert!(password.eq_constant_time(&RString::from_str("foobared")));
+    /// assert!(!password.eq_constant_time(&RString::from_str("foobar")));
+    /// ```
+    pub fn eq_constant_time(&self, other: &RString) -> bool {
+        let len = other.len();
+        // Compare the input with itself on lengths mismatched, to take the same time.
+        let (secret, matched) = match self.len() == len {
+            true => (self.as_ptr(), true),
+            false => (other.as_ptr(), false),
+        };
+        let equal = unsafe { mem_cmp_ct(secret, other.as_ptr(), len) };
+        equal & matched
+    }
+}
+
+impl RString {
+    /// Split the content by the separator `sep` (introduced from Redis `sdssplitlen`),
+    /// where empty tokens are kept.
+    ///
+    /// NO tokens are returned if the content or the separator is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RString;
+    ///
+    /// let tokens = RString::from_str("a,,b").split(b",");
+    /// assert_eq!(tokens, [RString::from_str("a"), RString::new(), RString::from_str("b")]);
+    /// ```
+    pub fn split(&self, sep: &[u8]) -> Vec<RString> {
+        let tokens = self.as_rstr_view().split(sep);
+        tokens.iter().map(RStr::to_rstr).collect()
+    }
+
+    /// Split the content into arguments (introduced from Redis `sdssplitargs`), where
+    /// arguments are separated by spaces, and MAYBE quoted:
+    ///
+    /// - Double-quoted arguments support escapes (`\n`, `\r`, `\t`, `\b`, `\a`, `\xHH`,
+    ///   and `\` followed by any other byte as is).
+    /// - Single-quoted arguments support ONLY `\'` as an escape.
+    ///
+    /// `None` is returned if quotes are unbalanced, or a closing quote is NOT followed
+    /// by a space.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RString;
+    ///
+    /// let args = RString::from_str(r#"SET "key\x41" 'it\'s' "#).split_args().unwrap();
+    /// assert_eq!(args, [
+    ///     RString::from_str("SET"),
+    ///     RString::from_str("keyA"),
+    ///     RString::from_str("it's"),
+    /// ]);
+    ///
+    /// assert!(RString::from_str(r#""unbalanced"#).split_args().is_none());
+    /// ```
+    pub fn split_args(&self) -> Option<Vec<RString>> {
+        let bytes = self.as_bytes();
+        let mut args = Vec::new();
+        let mut pos = 0;
+
+        loop {
+            while pos < bytes.len() && is_space(bytes[pos]) {
+                pos += 1;
+            }
+            if pos == bytes.len() {
+                return Some(args);
+            }
+
+            let mut arg = RString::new();
+            let (mut in_dq, mut in_sq) = (false, false);
+            loop {
+                let byte = bytes.get(pos).copied();
+                if in_dq {
+                    match (byte, bytes.get(pos + 1).copied()) {
+                        // Closing quote missing.
+                        (None, _) => return None,
+                        (Some(b'\\'), Some(b'x')) if is_hex_pair(bytes.get(pos + 2..pos + 4)) => {
+                            arg.push(hex_value(bytes[pos + 2], bytes[pos + 3]));
+                            pos += 3;
+                        }
+                        (Some(b'\\'), Some(escaped)) => {
+                            let byte = match escaped {
+                                b'n' => b'\n',
+                                b'r' => b'\r',
+                                b't' => b'\t',
+                                b'b' => 0x08,
+                                b'a' => 0x07,
+                                other => other,
+                            };
+                            arg.push(byte);
+                            pos += 1;
+                        }
+                        (Some(b'"'), next) => {
+                            // Closing quote MUST be followed by a space or nothing.
+                            if matches!(next, Some(next) if !is_space(next)) {
+                                return None;
+                            }
+                            pos += 1;
+                            break;
+                        }
+                        (Some(byte), _) => arg.push(byte),
+                    }
+                } else if in_sq {
+                    match (byte, bytes.get(pos + 1).copied()) {
+                        (None, _) => return None,
+                        (Some(b'\\'), Some(b'\'')) => {
+                            arg.push(b'\'');
+                            pos += 1;
+                        }
+                        (Some(b'\''), next) => {
+                            if matches!(next, Some(next) if !is_space(next)) {
+                                return None;
+                            }
+                            pos += 1;
+                            break;
+                        }
+                        (Some(byte), _) => arg.push(byte),
+                    }
+                } else {
+                    match byte {
+                        None => break,
+                        Some(byte) if is_space(byte) => break,
+                        Some(b'"') => in_dq = true,
+                        Some(b'\'') => in_sq = true,
+                        Some(byte) => arg.push(byte),
+                    }
+                }
+                pos += 1;
+            }
+            args.push(arg);
+        }
     }
 }
 
+impl RString {
+    /// Append `bytes` in the escaped & quoted representation (introduced from Redis
+    /// `sdscatrepr`), where non-printable bytes are rendered as escapes (such as `\n` & `\xNN`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rtypes::RString;
+    ///
+    /// let mut s = RString::from_str("value: ");
+    /// s.append_repr(b"a\"b\r\n\x00\xff");
+    /// assert_eq!(s.as_bytes(), br#"value: "a\"b\r\n\x00\xff""#);
+    /// ```
+    pub fn append_repr(&mut self, bytes: &[u8]) {
+        self.reserve(bytes.len() + 2);
+        // Writing into `RString` NEVER fails.
+        let _ = write_repr(self, bytes);
+    }
+
+    /// Get the escaped & quoted representation of the content, see `append_repr`.
+    #[inline]
+    pub fn to_repr(&self) -> RString {
+        let mut repr = RString::new();
+        repr.append_repr(self.as_bytes());
+        repr
+    }
+}
+
+/// Write `bytes` in the escaped & quoted representation, where runs of printable bytes
+/// are written at a time.
+pub(crate) fn write_repr<W: fmt::Write>(out: &mut W, bytes: &[u8]) -> fmt::Result {
+    out.write_char('"')?;
+    let mut rest = bytes;
+    loop {
+        let printable = rest
+            .iter()
+            .position(|&byte| !is_printable(byte) || byte == b'\\' || byte == b'"')
+            .unwrap_or(rest.len());
+        // Printable bytes are ASCII, thus valid UTF-8.
+        out.write_str(unsafe { std::str::from_utf8_unchecked(&rest[..printable]) })?;
+
+        let byte = match rest.get(printable) {
+            Some(&byte) => byte,
+            None => break,
+        };
+        match byte {
+            b'\\' => out.write_str("\\\\")?,
+            b'"' => out.write_str("\\\"")?,
+            b'\n' => out.write_str("\\n")?,
+            b'\r' => out.write_str("\\r")?,
+            b'\t' => out.write_str("\\t")?,
+            0x07 => out.write_str("\\a")?,
+            0x08 => out.write_str("\\b")?,
+            _ => write!(out, "\\x{:02x}", byte)?,
+        }
+        rest = &rest[printable + 1..];
+    }
+    out.write_char('"')
+}
+
+/// Check if a byte is printable, just like `isprint` in C.
+#[inline]
+fn is_printable(byte: u8) -> bool {
+    (0x20..0x7f).contains(&byte)
+}
+
+/// Check if a byte is a space, just like `isspace` in C.
+#[inline]
+fn is_space(byte: u8) -> bool {
+    matches!(byte, b' ' | b'\t' | b'\n' | b'\r' | 0x0b | 0x0c)
+}
+
+#[inline]
+fn is_hex_pair(pair: Option<&[u8]>) -> bool {
+    matches!(pair, Some(&[high, low]) if high.is_ascii_hexdigit() && low.is_ascii_hexdigit())
+}
+
+#[inline]
+fn hex_value(high: u8, low: u8) -> u8 {
+    let digit = |byte: u8| match byte {
+        b'0'..=b'9' => byte - b'0',
+        _ => (byte | 0x20) - b'a' + 10,
+    };
+    (digit(high) << 4) | digit(low)
+}
+
 macro_rules! impl_str_ops {
     ([OP_FROM] $from: ident, $stype: ty) => {
         impl RString {
             #[inline]
+            #[allow(clippy::should_implement_trait)]
             pub fn $from(s: $stype) -> Self {
                 unsafe { Self::from_raw_data(s.as_ptr(), s.len()) }
             }
@@ -318,6 +872,82 @@ impl Clone for RString {
     }
 }
 
+impl From<&[u8]> for RString {
+    #[inline]
+    fn from(bytes: &[u8]) -> RString {
+        RString::from_bytes(bytes)
+    }
+}
+
+impl From<&str> for RString {
+    #[inline]
+    fn from(s: &str) -> RString {
+        RString::from_str(s)
+    }
+}
+
+impl From<Vec<u8>> for RString {
+    #[inline]
+    fn from(bytes: Vec<u8>) -> RString {
+        RString::from_vec(bytes)
+    }
+}
+
+impl From<String> for RString {
+    #[inline]
+    fn from(s: String) -> RString {
+        RString::from_string(s)
+    }
+}
+
+impl From<RString> for Vec<u8> {
+    #[inline]
+    fn from(s: RString) -> Vec<u8> {
+        s.into_vec()
+    }
+}
+
+impl TryFrom<RString> for String {
+    type Error = FromUtf8Error;
+
+    /// Convert into a `String` if the content is valid UTF-8, see `RString::into_vec`.
+    #[inline]
+    fn try_from(s: RString) -> Result<String, FromUtf8Error> {
+        String::from_utf8(s.into_vec())
+    }
+}
+
+impl Extend<u8> for RString {
+    fn extend<I: IntoIterator<Item = u8>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        iter.for_each(|byte| self.push(byte));
+    }
+}
+
+impl<'a> Extend<&'a u8> for RString {
+    #[inline]
+    fn extend<I: IntoIterator<Item = &'a u8>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl FromIterator<u8> for RString {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = u8>>(iter: I) -> RString {
+        let mut rstr = RString::new();
+        rstr.extend(iter);
+        rstr
+    }
+}
+
+impl<'a> FromIterator<&'a u8> for RString {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = &'a u8>>(iter: I) -> RString {
+        iter.into_iter().copied().collect()
+    }
+}
+
 impl PartialEq for RString {
     fn eq(&self, other: &Self) -> bool {
         unsafe {
@@ -325,10 +955,6 @@ impl PartialEq for RString {
                 && Ordering::Equal == mem_cmp(self.as_ptr(), other.as_ptr(), self.len())
         }
     }
-
-    fn ne(&self, other: &Self) -> bool {
-        !PartialEq::eq(self, other)
-    }
 }
 
 impl Eq for RString {}
@@ -350,30 +976,146 @@ impl Ord for RString {
     }
 }
 
+// Hashing MUST be consistent with `[u8]` for `Borrow<[u8]>`.
+impl Hash for RString {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_bytes().hash(state);
+    }
+}
+
+impl Deref for RString {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl DerefMut for RString {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [u8] {
+        self.as_mut_bytes()
+    }
+}
+
+impl AsRef<[u8]> for RString {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl AsMut<[u8]> for RString {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [u8] {
+        self.as_mut_bytes()
+    }
+}
+
+impl Borrow<[u8]> for RString {
+    #[inline]
+    fn borrow(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<I: SliceIndex<[u8]>> Index<I> for RString {
+    type Output = I::Output;
+
+    #[inline]
+    fn index(&self, index: I) -> &I::Output {
+        Index::index(self.as_bytes(), index)
+    }
+}
+
+impl<I: SliceIndex<[u8]>> IndexMut<I> for RString {
+    #[inline]
+    fn index_mut(&mut self, index: I) -> &mut I::Output {
+        IndexMut::index_mut(self.as_mut_bytes(), index)
+    }
+}
+
+impl fmt::Write for RString {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.append_str(s);
+        Ok(())
+    }
+
+    #[inline]
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.append_str(c.encode_utf8(&mut [0; 4]));
+        Ok(())
+    }
+}
+
 impl fmt::Display for RString {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let printed = match std::str::from_utf8(self.as_bytes()) {
-            Ok(s) => s,
-            Err(_) => "<Unreadable Bytes>",
-        };
+        let printed = std::str::from_utf8(self.as_bytes()).unwrap_or("<Unreadable Bytes>");
         write!(f, "{}", printed)
     }
 }
 
 impl fmt::Debug for RString {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let printed = match std::str::from_utf8(self.as_bytes()) {
-            Ok(s) => s,
-            Err(_) => "<Unreadable Bytes>",
-        };
-
         write!(
             f,
-            "{{ len: {}, cap: {}, data: <{:p}>[{}] }}",
+            "{{ len: {}, cap: {}, data: <{:p}> ",
             self.len(),
             self.capacity(),
-            self.as_ptr(),
-            printed
-        )
+            self.as_ptr()
+        )?;
+        write_repr(f, self.as_bytes())?;
+        f.write_str(" }")
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for RString {
+    #[inline]
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.as_bytes())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for RString {
+    #[inline]
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_bytes(RStringVisitor)
+    }
+}
+
+/// Visitor of `RString` deserialized, which accepts bytes, strings, and sequences of bytes
+/// (for formats WITHOUT a native bytes type, such as JSON).
+#[cfg(feature = "serde")]
+struct RStringVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> serde::de::Visitor<'de> for RStringVisitor {
+    type Value = RString;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("bytes or a string")
+    }
+
+    #[inline]
+    fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<RString, E> {
+        Ok(RString::from_bytes(v))
+    }
+
+    #[inline]
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<RString, E> {
+        Ok(RString::from_str(v))
+    }
+
+    fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<RString, A::Error> {
+        let mut s = RString::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element::<u8>()? {
+            s.push(byte);
+        }
+        Ok(s)
     }
 }
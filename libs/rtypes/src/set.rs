@@ -0,0 +1,296 @@
+use std::fmt;
+use std::iter::FusedIterator;
+
+use crate::num::parse_i64;
+use crate::rand::random_below;
+use crate::{
+    Encoding, EncodingLimits, RDict, RDictIter, RIntSet, RIntSetIter, RListPack, RListPackIter,
+    RString,
+};
+
+////////////////////////////////////////////////////////////////////////////////
+// RSet
+////////////////////////////////////////////////////////////////////////////////
+
+/// Members of a set, in one of the encodings.
+enum Members {
+    IntSet(RIntSet),
+    ListPack(RListPack),
+    Dict(RDict<RString, ()>),
+}
+
+/// RSet is a set of unique members (introduced from Redis `set`), which is encoded
+/// compactly while small, and converted to a hash table once it grows:
+///
+/// - `intset`: ALL members are integers (in the canonical form), and NO more than
+///   `set_max_intset_entries` (512 by DEFAULT).
+/// - `listpack`: NO more than `set_max_listpack_entries` members (128 by DEFAULT), each
+///   of which is NO longer than `set_max_listpack_value` bytes (64 by DEFAULT).
+/// - `hashtable`: otherwise.
+///
+/// Limits are given by `EncodingLimits`.
+///
+/// # Notes
+///
+/// Sets are NEVER converted back to compact encodings, even if members are removed.
+///
+/// # Examples
+///
+/// ```
+/// # use rtypes::{Encoding, RSet};
+///
+/// let mut set = RSet::new();
+/// assert!(set.add(b"1"));
+/// assert!(set.add(b"2"));
+/// assert!(!set.add(b"1"));
+/// assert_eq!(set.encoding(), Encoding::IntSet);
+///
+/// assert!(set.add(b"hello"));
+/// assert_eq!(set.encoding(), Encoding::ListPack);
+/// assert!(set.contains(b"hello"));
+/// assert!(set.add(&[b'x'; 100]));
+/// assert_eq!(set.encoding(), Encoding::HashTable);
+/// assert_eq!(set.len(), 4);
+/// ```
+pub struct RSet {
+    members: Members,
+    max_intset_entries: usize,
+    max_listpack_entries: usize,
+    max_listpack_value: usize,
+}
+
+/// Iterator over members (copied) of an `RSet`, in an arbitrary order.
+pub struct RSetIter<'a> {
+    inner: SetIterInner<'a>,
+}
+
+enum SetIterInner<'a> {
+    IntSet(RIntSetIter<'a>),
+    ListPack(RListPackIter<'a>),
+    Dict(RDictIter<'a, RString, ()>),
+}
+
+// private methods
+impl RSet {
+    // Converts members into `encoding` (introduced from Redis `setTypeConvert`).
+    fn convert(&mut self, encoding: Encoding) {
+        let members = match encoding {
+            Encoding::ListPack => {
+                let mut lp = RListPack::new();
+                for member in self.iter() {
+                    lp.push_back(member.as_bytes());
+                }
+                Members::ListPack(lp)
+            }
+            Encoding::HashTable => {
+                let mut dict = RDict::new();
+                dict.expand(self.len());
+                for member in self.iter() {
+                    dict.insert(member, ());
+                }
+                Members::Dict(dict)
+            }
+            _ => unreachable!("set converted to {}", encoding),
+        };
+        self.members = members;
+    }
+
+    // Gets the member at `idx` of the compact encodings, or a random one of the hash
+    // table, where the set MUST NOT be empty.
+    fn random_member_at(&self, idx: usize) -> Option<RString> {
+        match &self.members {
+            Members::IntSet(set) => set.get(idx).map(RString::from_i64),
+            Members::ListPack(lp) => lp.get(idx).map(|value| value.to_rstring()),
+            Members::Dict(dict) => dict.random_entry().map(|(member, _)| member.clone()),
+        }
+    }
+}
+
+// public methods
+impl RSet {
+    /// Create an empty set in `intset`, with DEFAULT limits.
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_limits(&EncodingLimits::DEFAULT)
+    }
+
+    /// Create an empty set in `intset`, which is converted once it grows out of `limits`.
+    #[inline]
+    pub fn with_limits(limits: &EncodingLimits) -> Self {
+        RSet {
+            members: Members::IntSet(RIntSet::new()),
+            max_intset_entries: limits.set_max_intset_entries,
+            max_listpack_entries: limits.set_max_listpack_entries,
+            max_listpack_value: limits.set_max_listpack_value,
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        match &self.members {
+            Members::IntSet(set) => set.len(),
+            Members::ListPack(lp) => lp.len(),
+            Members::Dict(dict) => dict.len(),
+        }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the encoding of members.
+    #[inline]
+    pub fn encoding(&self) -> Encoding {
+        match &self.members {
+            Members::IntSet(_) => Encoding::IntSet,
+            Members::ListPack(_) => Encoding::ListPack,
+            Members::Dict(_) => Encoding::HashTable,
+        }
+    }
+
+    /// Add the member (introduced from Redis `setTypeAdd`), and return `false` if it's
+    /// in the set already, where the set is converted if it grows out of the encoding.
+    pub fn add(&mut self, member: &[u8]) -> bool {
+        if let Members::IntSet(set) = &mut self.members {
+            if let Ok(value) = parse_i64(member) {
+                let added = set.insert(value);
+                if set.len() > self.max_intset_entries {
+                    self.convert(Encoding::HashTable);
+                }
+                return added;
+            }
+            match set.len() < self.max_listpack_entries && member.len() <= self.max_listpack_value {
+                true => self.convert(Encoding::ListPack),
+                false => self.convert(Encoding::HashTable),
+            }
+        }
+
+        if let Members::ListPack(lp) = &mut self.members {
+            if lp.find(member).is_some() {
+                return false;
+            }
+            if lp.len() < self.max_listpack_entries && member.len() <= self.max_listpack_value {
+                lp.push_back(member);
+                return true;
+            }
+            self.convert(Encoding::HashTable);
+        }
+
+        match &mut self.members {
+            Members::Dict(dict) => dict.insert(RString::from_bytes(member), ()).is_none(),
+            _ => unreachable!("set NOT converted to hashtable"),
+        }
+    }
+
+    /// Remove the member, and return `false` if it's NOT found.
+    pub fn remove(&mut self, member: &[u8]) -> bool {
+        match &mut self.members {
+            Members::IntSet(set) => parse_i64(member).is_ok_and(|value| set.remove(value)),
+            Members::ListPack(lp) => match lp.find(member) {
+                Some(idx) => lp.remove(idx).is_some(),
+                None => false,
+            },
+            Members::Dict(dict) => dict.remove(member).is_some(),
+        }
+    }
+
+    pub fn contains(&self, member: &[u8]) -> bool {
+        match &self.members {
+            Members::IntSet(set) => parse_i64(member).is_ok_and(|value| set.contains(value)),
+            Members::ListPack(lp) => lp.find(member).is_some(),
+            Members::Dict(dict) => dict.contains_key(member),
+        }
+    }
+
+    /// Get a random member (introduced from Redis SRANDMEMBER), or `None` if empty.
+    #[inline]
+    pub fn random_member(&self) -> Option<RString> {
+        if self.is_empty() {
+            return None;
+        }
+        self.random_member_at(random_below(self.len()))
+    }
+
+    /// Remove a random member (introduced from Redis SPOP), and return it, or `None` if
+    /// empty.
+    pub fn pop_random(&mut self) -> Option<RString> {
+        if self.is_empty() {
+            return None;
+        }
+        let idx = random_below(self.len());
+        match &mut self.members {
+            Members::IntSet(set) => set.remove_at(idx).map(RString::from_i64),
+            Members::ListPack(lp) => lp.remove(idx),
+            Members::Dict(_) => {
+                let member = self.random_member_at(idx)?;
+                self.remove(member.as_bytes());
+                Some(member)
+            }
+        }
+    }
+
+    /// Get an iterator over members (copied), in an arbitrary order.
+    #[inline]
+    pub fn iter(&self) -> RSetIter<'_> {
+        let inner = match &self.members {
+            Members::IntSet(set) => SetIterInner::IntSet(set.iter()),
+            Members::ListPack(lp) => SetIterInner::ListPack(lp.iter()),
+            Members::Dict(dict) => SetIterInner::Dict(dict.iter()),
+        };
+        RSetIter { inner }
+    }
+}
+
+impl Default for RSet {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for RSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Iterators
+////////////////////////////////////////////////////////////////////////////////
+
+impl<'a> IntoIterator for &'a RSet {
+    type Item = RString;
+    type IntoIter = RSetIter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> Iterator for RSetIter<'a> {
+    type Item = RString;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.inner {
+            SetIterInner::IntSet(iter) => iter.next().map(RString::from_i64),
+            SetIterInner::ListPack(iter) => iter.next().map(|value| value.to_rstring()),
+            SetIterInner::Dict(iter) => iter.next().map(|(member, _)| member.clone()),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.inner {
+            SetIterInner::IntSet(iter) => iter.size_hint(),
+            SetIterInner::ListPack(iter) => iter.size_hint(),
+            SetIterInner::Dict(iter) => iter.size_hint(),
+        }
+    }
+}
+
+impl<'a> ExactSizeIterator for RSetIter<'a> {}
+
+impl<'a> FusedIterator for RSetIter<'a> {}
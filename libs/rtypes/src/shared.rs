@@ -0,0 +1,158 @@
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::Arc;
+
+use crate::RString;
+
+/// SharedRStr is a reference-counted (`Arc`-backed) `RString`, that the same bytes (such as
+/// keys referenced by the keyspace, the expiration dict & the replication stream) are shared
+/// WITHOUT copies, and copied on write ONLY (with `make_mut`) while shared.
+///
+/// It's compared, ordered & hashed by its content, just like `RString` does.
+///
+/// # Examples
+///
+/// ```
+/// # use rtypes::SharedRStr;
+///
+/// let key = SharedRStr::from("key");
+/// let mut other = key.clone();
+/// assert!(SharedRStr::ptr_eq(&key, &other));
+/// assert_eq!(key.ref_count(), 2);
+///
+/// // Copied on write, as the content is shared.
+/// other.make_mut().append_str(":1");
+/// assert_eq!(key.as_bytes(), b"key");
+/// assert_eq!(other.as_bytes(), b"key:1");
+/// assert!(key.is_unique());
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SharedRStr(Arc<RString>);
+
+impl SharedRStr {
+    /// Create an empty shared string.
+    #[inline]
+    pub fn new() -> Self {
+        SharedRStr::default()
+    }
+
+    /// Get the string shared.
+    #[inline]
+    pub fn as_rstr(&self) -> &RString {
+        &self.0
+    }
+
+    /// Get a mutable reference to the string, which is copied (then referenced exclusively)
+    /// ONLY if it's shared.
+    #[inline]
+    pub fn make_mut(&mut self) -> &mut RString {
+        Arc::make_mut(&mut self.0)
+    }
+
+    /// Get number of references to the string.
+    #[inline]
+    pub fn ref_count(&self) -> usize {
+        Arc::strong_count(&self.0)
+    }
+
+    /// Check if the string is referenced exclusively (NOT shared).
+    #[inline]
+    pub fn is_unique(&self) -> bool {
+        self.ref_count() == 1
+    }
+
+    /// Check if both reference the same string (rather than the same content ONLY).
+    #[inline]
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        Arc::ptr_eq(&this.0, &other.0)
+    }
+
+    /// Convert into the string, which is copied ONLY if it's shared.
+    #[inline]
+    pub fn into_rstr(self) -> RString {
+        Arc::try_unwrap(self.0).unwrap_or_else(|shared| RString::from_rstr(&shared))
+    }
+}
+
+impl From<RString> for SharedRStr {
+    #[inline]
+    fn from(s: RString) -> SharedRStr {
+        SharedRStr(Arc::new(s))
+    }
+}
+
+impl From<&[u8]> for SharedRStr {
+    #[inline]
+    fn from(bytes: &[u8]) -> SharedRStr {
+        SharedRStr::from(RString::from_bytes(bytes))
+    }
+}
+
+impl From<&str> for SharedRStr {
+    #[inline]
+    fn from(s: &str) -> SharedRStr {
+        SharedRStr::from(RString::from_str(s))
+    }
+}
+
+// Hashing MUST be consistent with `[u8]` for `Borrow<[u8]>`.
+impl Hash for SharedRStr {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl Deref for SharedRStr {
+    type Target = RString;
+
+    #[inline]
+    fn deref(&self) -> &RString {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for SharedRStr {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
+
+impl Borrow<[u8]> for SharedRStr {
+    #[inline]
+    fn borrow(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
+
+impl fmt::Display for SharedRStr {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&*self.0, f)
+    }
+}
+
+impl fmt::Debug for SharedRStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{ refs: {}, rstr: {:?} }}", self.ref_count(), self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for SharedRStr {
+    #[inline]
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for SharedRStr {
+    #[inline]
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        RString::deserialize(deserializer).map(SharedRStr::from)
+    }
+}
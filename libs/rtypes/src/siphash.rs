@@ -0,0 +1,245 @@
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::OnceLock;
+
+////////////////////////////////////////////////////////////////////////////////
+// SipHash-1-3
+////////////////////////////////////////////////////////////////////////////////
+
+/// SipHasher13 is a keyed hasher of SipHash-1-3 (introduced from Redis `siphash`), i.e.
+/// SipHash with 1 compression round & 3 finalization rounds, which is faster than
+/// SipHash-2-4 (of the reference implementation) while still resistant to hash-flooding,
+/// as long as the key is kept secret.
+///
+/// Unlike hashers of `std` (whose algorithm is NOT specified), hashes are stable across
+/// builds for the same key.
+///
+/// # Examples
+///
+/// ```
+/// # use rtypes::SipHasher13;
+/// use std::hash::Hasher;
+///
+/// let mut hasher = SipHasher13::new_with_keys(1, 2);
+/// hasher.write(b"hello ");
+/// hasher.write(b"world");
+/// assert_eq!(hasher.finish(), SipHasher13::hash_bytes(1, 2, b"hello world"));
+/// ```
+#[derive(Clone)]
+pub struct SipHasher13 {
+    v0: u64,
+    v1: u64,
+    v2: u64,
+    v3: u64,
+    // Bytes NOT compressed yet (less than 8), in little-endian.
+    tail: u64,
+    ntail: usize,
+    // Number of bytes written, ONLY the lowest byte is used.
+    length: usize,
+}
+
+#[inline(always)]
+fn sip_round(v0: &mut u64, v1: &mut u64, v2: &mut u64, v3: &mut u64) {
+    *v0 = v0.wrapping_add(*v1);
+    *v1 = v1.rotate_left(13);
+    *v1 ^= *v0;
+    *v0 = v0.rotate_left(32);
+    *v2 = v2.wrapping_add(*v3);
+    *v3 = v3.rotate_left(16);
+    *v3 ^= *v2;
+    *v0 = v0.wrapping_add(*v3);
+    *v3 = v3.rotate_left(21);
+    *v3 ^= *v0;
+    *v2 = v2.wrapping_add(*v1);
+    *v1 = v1.rotate_left(17);
+    *v1 ^= *v2;
+    *v2 = v2.rotate_left(32);
+}
+
+/// Load (at most 8) `bytes` as a little-endian `u64`.
+#[inline(always)]
+fn load_le(bytes: &[u8]) -> u64 {
+    let mut word = [0u8; 8];
+    word[..bytes.len()].copy_from_slice(bytes);
+    u64::from_le_bytes(word)
+}
+
+impl SipHasher13 {
+    /// Create a hasher with the 128-bit key `(k0, k1)`.
+    #[inline]
+    pub const fn new_with_keys(k0: u64, k1: u64) -> Self {
+        SipHasher13 {
+            v0: k0 ^ 0x736f6d6570736575,
+            v1: k1 ^ 0x646f72616e646f6d,
+            v2: k0 ^ 0x6c7967656e657261,
+            v3: k1 ^ 0x7465646279746573,
+            tail: 0,
+            ntail: 0,
+            length: 0,
+        }
+    }
+
+    /// Hash `bytes` at once with the key `(k0, k1)`, just like `write` then `finish` does.
+    #[inline]
+    pub fn hash_bytes(k0: u64, k1: u64, bytes: &[u8]) -> u64 {
+        let mut hasher = SipHasher13::new_with_keys(k0, k1);
+        hasher.write(bytes);
+        hasher.finish()
+    }
+
+    #[inline(always)]
+    fn compress(&mut self, m: u64) {
+        self.v3 ^= m;
+        sip_round(&mut self.v0, &mut self.v1, &mut self.v2, &mut self.v3);
+        self.v0 ^= m;
+    }
+
+    /// Write an integer of `size` (at most 8) bytes in little-endian, just like `write`
+    /// does, WITHOUT going through the slice of bytes (such as length prefixes of keys).
+    #[inline(always)]
+    fn short_write(&mut self, x: u64, size: usize) {
+        self.length = self.length.wrapping_add(size);
+        // The tail is NEVER full, thus shifted by (at most) 56 bits.
+        self.tail |= x << (8 * self.ntail);
+        let needed = 8 - self.ntail;
+        if size < needed {
+            self.ntail += size;
+            return;
+        }
+
+        self.compress(self.tail);
+        self.ntail = size - needed;
+        self.tail = match needed {
+            8 => 0,
+            needed => x >> (8 * needed),
+        };
+    }
+}
+
+macro_rules! impl_short_write {
+    ($($name: ident: $ty: ty),*) => {
+        $(
+            #[inline]
+            fn $name(&mut self, value: $ty) {
+                self.short_write(value as u64, std::mem::size_of::<$ty>());
+            }
+        )*
+    };
+}
+
+impl Hasher for SipHasher13 {
+    impl_short_write!(write_u8: u8, write_u16: u16, write_u32: u32, write_u64: u64);
+    impl_short_write!(write_usize: usize);
+
+    fn write(&mut self, mut bytes: &[u8]) {
+        self.length = self.length.wrapping_add(bytes.len());
+
+        // Fill up the tail first.
+        if self.ntail > 0 {
+            let fill = std::cmp::min(8 - self.ntail, bytes.len());
+            self.tail |= load_le(&bytes[..fill]) << (8 * self.ntail);
+            self.ntail += fill;
+            bytes = &bytes[fill..];
+            if self.ntail < 8 {
+                return;
+            }
+            self.compress(self.tail);
+            self.tail = 0;
+            self.ntail = 0;
+        }
+
+        let mut words = bytes.chunks_exact(8);
+        for word in &mut words {
+            self.compress(load_le(word));
+        }
+        let rest = words.remainder();
+        self.tail = load_le(rest);
+        self.ntail = rest.len();
+    }
+
+    fn finish(&self) -> u64 {
+        let (mut v0, mut v1, mut v2, mut v3) = (self.v0, self.v1, self.v2, self.v3);
+
+        let m = ((self.length as u64 & 0xff) << 56) | self.tail;
+        v3 ^= m;
+        sip_round(&mut v0, &mut v1, &mut v2, &mut v3);
+        v0 ^= m;
+
+        v2 ^= 0xff;
+        for _ in 0..3 {
+            sip_round(&mut v0, &mut v1, &mut v2, &mut v3);
+        }
+        v0 ^ v1 ^ v2 ^ v3
+    }
+}
+
+impl fmt::Debug for SipHasher13 {
+    // The state (derived from the key) is NOT exposed.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SipHasher13").finish_non_exhaustive()
+    }
+}
+
+/// Get the per-process random key, which is generated once (from the randomness of `std`,
+/// seeded by the OS) & NEVER changed.
+fn process_key() -> (u64, u64) {
+    static PROCESS_KEY: OnceLock<(u64, u64)> = OnceLock::new();
+    *PROCESS_KEY.get_or_init(|| {
+        let random = RandomState::new();
+        (random.hash_one(0u8), random.hash_one(1u8))
+    })
+}
+
+/// SipBuildHasher creates `SipHasher13`s with the same key, which is the per-process random
+/// key by DEFAULT (thus hashes are consistent within the process, while unpredictable
+/// across processes), and the DEFAULT hasher of `RDict`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SipBuildHasher {
+    k0: u64,
+    k1: u64,
+}
+
+impl SipBuildHasher {
+    /// Create a builder with the per-process random key.
+    #[inline]
+    pub fn new() -> Self {
+        let (k0, k1) = process_key();
+        SipBuildHasher { k0, k1 }
+    }
+
+    /// Create a builder with the 128-bit key `(k0, k1)`, such as for reproducible hashes.
+    #[inline]
+    pub const fn with_keys(k0: u64, k1: u64) -> Self {
+        SipBuildHasher { k0, k1 }
+    }
+
+    /// Hash `bytes` at once, see `SipHasher13::hash_bytes`.
+    #[inline]
+    pub fn hash_bytes(&self, bytes: &[u8]) -> u64 {
+        SipHasher13::hash_bytes(self.k0, self.k1, bytes)
+    }
+}
+
+impl BuildHasher for SipBuildHasher {
+    type Hasher = SipHasher13;
+
+    #[inline]
+    fn build_hasher(&self) -> SipHasher13 {
+        SipHasher13::new_with_keys(self.k0, self.k1)
+    }
+}
+
+impl Default for SipBuildHasher {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for SipBuildHasher {
+    // The key is NOT exposed.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SipBuildHasher").finish_non_exhaustive()
+    }
+}
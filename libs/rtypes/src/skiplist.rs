@@ -0,0 +1,676 @@
+use std::cmp::Ordering;
+use std::collections::hash_map::RandomState;
+use std::fmt::{self, Debug};
+use std::hash::BuildHasher;
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::ops::{Bound, RangeBounds};
+use std::ptr::{self, NonNull};
+
+use rmem::{align_of, free_aligned, malloc_aligned, size_of};
+
+use crate::RString;
+
+////////////////////////////////////////////////////////////////////////////////
+// RSkipList
+////////////////////////////////////////////////////////////////////////////////
+
+/// MAX level of nodes (introduced from Redis `ZSKIPLIST_MAXLEVEL`), enough for 2^64
+/// elements.
+const SKIPLIST_MAX_LEVEL: usize = 32;
+/// Probability (in 1/65536) of a node promoted to the next level, i.e. 1/4 (introduced
+/// from Redis `ZSKIPLIST_P`).
+const SKIPLIST_P: u64 = 0x10000 / 4;
+
+type Link = Option<NonNull<Node>>;
+
+/// Link to the next node at a level, with number of nodes spanned (from this one).
+#[derive(Clone, Copy)]
+struct Level {
+    forward: Link,
+    span: usize,
+}
+
+/// Node holding an element, which is allocated along with its levels (as many as its
+/// height) in a single memory block.
+#[repr(C)]
+struct Node {
+    member: RString,
+    score: f64,
+    backward: Link,
+    height: usize,
+    levels: [Level; 0],
+}
+
+/// RSkipList is a skip list (introduced from Redis `zskiplist`) of elements of
+/// `(score, member)`, ordered by scores then by members (in bytes), where each node
+/// records spans of its links, thus ranks are calculated along the way of lookups.
+///
+/// Elements are inserted, deleted & looked up (by elements or by ranks) in O(log(n))
+/// time on average.
+///
+/// # Notes
+///
+/// Members are NOT checked to be unique, which is guaranteed by the caller (such as
+/// the dict of a sorted set). NaN scores are NOT permitted.
+///
+/// # Examples
+///
+/// ```
+/// # use rtypes::{RSkipList, RString};
+///
+/// let mut list = RSkipList::new();
+/// list.insert(2.0, RString::from_str("b"));
+/// list.insert(1.0, RString::from_str("c"));
+/// list.insert(2.0, RString::from_str("a"));
+///
+/// assert_eq!(list.rank(2.0, b"b"), Some(2));
+/// let (score, member) = list.get_by_rank(1).unwrap();
+/// assert_eq!((score, member.as_bytes()), (2.0, &b"a"[..]));
+/// let members: Vec<_> = list
+///     .range_by_score(2.0..)
+///     .map(|(_, member)| member.as_bytes())
+///     .collect();
+/// assert_eq!(members, vec![b"a", b"b"]);
+/// ```
+pub struct RSkipList {
+    header: NonNull<Node>,
+    tail: Link,
+    len: usize,
+    // MAX height of nodes.
+    level: usize,
+    // State of the xorshift generator of heights.
+    seed: u64,
+}
+
+/// Iterator over elements of an `RSkipList`, in ascending (or descending) order.
+pub struct RSkipListIter<'a> {
+    front: Link,
+    back: Link,
+    remaining: usize,
+    _marker: PhantomData<&'a Node>,
+}
+
+// RSkipList owns its nodes exclusively, whose members are Send & Sync.
+unsafe impl Send for RSkipList {}
+unsafe impl Sync for RSkipList {}
+unsafe impl<'a> Send for RSkipListIter<'a> {}
+unsafe impl<'a> Sync for RSkipListIter<'a> {}
+
+impl Node {
+    #[inline]
+    const fn layout_size(height: usize) -> usize {
+        size_of::<Node>() + height * size_of::<Level>()
+    }
+
+    // Allocates a node of `height` with levels ALL unlinked.
+    fn alloc(height: usize, score: f64, member: RString) -> NonNull<Node> {
+        let (ptr, _) = malloc_aligned(Self::layout_size(height), align_of::<Node>());
+        let node = NonNull::new(ptr as *mut Node).expect("NULL pointer allocated");
+        unsafe {
+            node.as_ptr().write(Node {
+                member,
+                score,
+                backward: None,
+                height,
+                levels: [],
+            });
+            for idx in 0..height {
+                Self::level(node, idx).write(Level {
+                    forward: None,
+                    span: 0,
+                });
+            }
+        }
+        node
+    }
+
+    // Deallocates a node unlinked, and returns its score & member.
+    unsafe fn free(node: NonNull<Node>) -> (f64, RString) {
+        let Node {
+            member,
+            score,
+            height,
+            ..
+        } = node.as_ptr().read();
+        free_aligned(
+            node.as_ptr() as _,
+            Self::layout_size(height),
+            align_of::<Node>(),
+        );
+        (score, member)
+    }
+
+    // Gets the level `idx` of `node`, which MUST be less than its height.
+    #[inline]
+    unsafe fn level(node: NonNull<Node>, idx: usize) -> *mut Level {
+        (ptr::addr_of_mut!((*node.as_ptr()).levels) as *mut Level).add(idx)
+    }
+
+    #[inline]
+    unsafe fn forward(node: NonNull<Node>, idx: usize) -> Link {
+        (*Self::level(node, idx)).forward
+    }
+
+    #[inline]
+    unsafe fn span(node: NonNull<Node>, idx: usize) -> usize {
+        (*Self::level(node, idx)).span
+    }
+
+    // Compares the element of `node` with `(score, member)`.
+    #[inline]
+    unsafe fn cmp(node: NonNull<Node>, score: f64, member: &[u8]) -> Ordering {
+        let node = &*node.as_ptr();
+        match node.score.partial_cmp(&score) {
+            Some(Ordering::Equal) | None => node.member.as_bytes().cmp(member),
+            Some(ordering) => ordering,
+        }
+    }
+}
+
+/// Check if `value` is NOT less than the lower bound.
+#[inline]
+pub(crate) fn gte_min<T: PartialOrd + ?Sized>(value: &T, min: Bound<&T>) -> bool {
+    match min {
+        Bound::Included(min) => value >= min,
+        Bound::Excluded(min) => value > min,
+        Bound::Unbounded => true,
+    }
+}
+
+/// Check if `value` is NOT greater than the upper bound.
+#[inline]
+pub(crate) fn lte_max<T: PartialOrd + ?Sized>(value: &T, max: Bound<&T>) -> bool {
+    match max {
+        Bound::Included(max) => value <= max,
+        Bound::Excluded(max) => value < max,
+        Bound::Unbounded => true,
+    }
+}
+
+// private methods
+impl RSkipList {
+    // Generates a random height in [1, MAX], where the height h is (1 - P) * P^(h - 1)
+    // likely.
+    fn random_height(&mut self) -> usize {
+        let mut height = 1;
+        while height < SKIPLIST_MAX_LEVEL && self.next_random() & 0xffff < SKIPLIST_P {
+            height += 1;
+        }
+        height
+    }
+
+    #[inline]
+    fn next_random(&mut self) -> u64 {
+        // xorshift64*
+        self.seed ^= self.seed >> 12;
+        self.seed ^= self.seed << 25;
+        self.seed ^= self.seed >> 27;
+        self.seed.wrapping_mul(0x2545f4914f6cdd1d) >> 32
+    }
+
+    // Finds the last node less than `(score, member)` at each level, with its rank
+    // (where the header ranks 0).
+    fn find_less(
+        &self,
+        score: f64,
+        member: &[u8],
+    ) -> (
+        [NonNull<Node>; SKIPLIST_MAX_LEVEL],
+        [usize; SKIPLIST_MAX_LEVEL],
+    ) {
+        let mut update = [self.header; SKIPLIST_MAX_LEVEL];
+        let mut rank = [0; SKIPLIST_MAX_LEVEL];
+        let mut x = self.header;
+        for idx in (0..self.level).rev() {
+            rank[idx] = rank.get(idx + 1).copied().unwrap_or(0);
+            unsafe {
+                while let Some(next) = Node::forward(x, idx) {
+                    if Node::cmp(next, score, member) != Ordering::Less {
+                        break;
+                    }
+                    rank[idx] += Node::span(x, idx);
+                    x = next;
+                }
+            }
+            update[idx] = x;
+        }
+        (update, rank)
+    }
+
+    // Unlinks `x` from the list, where `update` are the last nodes before it at each level.
+    unsafe fn unlink_node(&mut self, x: NonNull<Node>, update: &[NonNull<Node>]) {
+        for (idx, &prev) in update.iter().enumerate().take(self.level) {
+            let level = &mut *Node::level(prev, idx);
+            if level.forward == Some(x) {
+                level.span += Node::span(x, idx);
+                level.span -= 1;
+                level.forward = Node::forward(x, idx);
+            } else {
+                level.span -= 1;
+            }
+        }
+        match Node::forward(x, 0) {
+            Some(next) => (*next.as_ptr()).backward = (*x.as_ptr()).backward,
+            None => self.tail = (*x.as_ptr()).backward,
+        }
+        while self.level > 1 && Node::forward(self.header, self.level - 1).is_none() {
+            self.level -= 1;
+        }
+        self.len -= 1;
+    }
+
+    // Finds the node with `rank` (from 1), or `None` if out of range.
+    fn node_by_rank(&self, rank: usize) -> Link {
+        if rank == 0 || rank > self.len {
+            return None;
+        }
+        let mut traversed = 0;
+        let mut x = self.header;
+        for idx in (0..self.level).rev() {
+            unsafe {
+                while let Some(next) = Node::forward(x, idx) {
+                    if traversed + Node::span(x, idx) > rank {
+                        break;
+                    }
+                    traversed += Node::span(x, idx);
+                    x = next;
+                }
+            }
+            if traversed == rank {
+                return Some(x);
+            }
+        }
+        None
+    }
+
+    // Finds the first node in range (where nodes are NOT less than the lower bound, if
+    // `above_min`, and NOT greater than the upper bound, if `below_max`), and its rank
+    // (from 1).
+    fn first_in_range<F, G>(&self, above_min: F, below_max: G) -> Option<(NonNull<Node>, usize)>
+    where
+        F: Fn(&Node) -> bool,
+        G: Fn(&Node) -> bool,
+    {
+        let mut rank = 0;
+        let mut x = self.header;
+        for idx in (0..self.level).rev() {
+            unsafe {
+                while let Some(next) = Node::forward(x, idx) {
+                    if above_min(next.as_ref()) {
+                        break;
+                    }
+                    rank += Node::span(x, idx);
+                    x = next;
+                }
+            }
+        }
+        let x = unsafe { Node::forward(x, 0)? };
+        match below_max(unsafe { x.as_ref() }) {
+            true => Some((x, rank + 1)),
+            false => None,
+        }
+    }
+
+    // Finds the last node in range, and its rank (from 1), see `first_in_range`.
+    fn last_in_range<F, G>(&self, above_min: F, below_max: G) -> Option<(NonNull<Node>, usize)>
+    where
+        F: Fn(&Node) -> bool,
+        G: Fn(&Node) -> bool,
+    {
+        let mut rank = 0;
+        let mut x = self.header;
+        for idx in (0..self.level).rev() {
+            unsafe {
+                while let Some(next) = Node::forward(x, idx) {
+                    if !below_max(next.as_ref()) {
+                        break;
+                    }
+                    rank += Node::span(x, idx);
+                    x = next;
+                }
+            }
+        }
+        match x != self.header && above_min(unsafe { x.as_ref() }) {
+            true => Some((x, rank)),
+            false => None,
+        }
+    }
+
+    // Gets an iterator over nodes in range, see `first_in_range`.
+    fn iter_in_range<F, G>(&self, above_min: F, below_max: G) -> RSkipListIter<'_>
+    where
+        F: Fn(&Node) -> bool,
+        G: Fn(&Node) -> bool,
+    {
+        match (
+            self.first_in_range(&above_min, &below_max),
+            self.last_in_range(&above_min, &below_max),
+        ) {
+            (Some((first, first_rank)), Some((last, last_rank))) if first_rank <= last_rank => {
+                self.iter_between(Some(first), Some(last), last_rank - first_rank + 1)
+            }
+            _ => self.iter_between(None, None, 0),
+        }
+    }
+
+    #[inline]
+    fn iter_between(&self, first: Link, last: Link, count: usize) -> RSkipListIter<'_> {
+        RSkipListIter {
+            front: first,
+            back: last,
+            remaining: count,
+            _marker: PhantomData,
+        }
+    }
+}
+
+// public methods
+impl RSkipList {
+    /// Create an empty list.
+    pub fn new() -> Self {
+        let header = Node::alloc(SKIPLIST_MAX_LEVEL, 0f64, RString::new());
+        // Seeded by the randomness of `std`, where ZERO is NOT a valid state of xorshift.
+        let seed = RandomState::new().hash_one(0u8) | 1;
+        RSkipList {
+            header,
+            tail: None,
+            len: 0,
+            level: 1,
+            seed,
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Insert an element (introduced from Redis `zslInsert`), where `member` MUST NOT be
+    /// in the list already.
+    ///
+    /// # Panics
+    ///
+    /// It will panic if `score` is NaN.
+    pub fn insert(&mut self, score: f64, member: RString) {
+        assert!(!score.is_nan(), "skip list score is NaN");
+        let (mut update, mut rank) = self.find_less(score, member.as_bytes());
+
+        let height = self.random_height();
+        if height > self.level {
+            for idx in self.level..height {
+                rank[idx] = 0;
+                update[idx] = self.header;
+                unsafe { (*Node::level(self.header, idx)).span = self.len };
+            }
+            self.level = height;
+        }
+
+        let x = Node::alloc(height, score, member);
+        unsafe {
+            for idx in 0..height {
+                let prev = &mut *Node::level(update[idx], idx);
+                let level = &mut *Node::level(x, idx);
+                level.forward = prev.forward;
+                prev.forward = Some(x);
+                // Spans are split by the node inserted.
+                level.span = prev.span - (rank[0] - rank[idx]);
+                prev.span = rank[0] - rank[idx] + 1;
+            }
+            for (idx, &prev) in update.iter().enumerate().take(self.level).skip(height) {
+                (*Node::level(prev, idx)).span += 1;
+            }
+
+            (*x.as_ptr()).backward = Some(update[0]).filter(|&prev| prev != self.header);
+            match Node::forward(x, 0) {
+                Some(next) => (*next.as_ptr()).backward = Some(x),
+                None => self.tail = Some(x),
+            }
+        }
+        self.len += 1;
+    }
+
+    /// Delete the element `(score, member)` (introduced from Redis `zslDelete`), and return
+    /// the member deleted, or `None` if NOT found.
+    pub fn delete(&mut self, score: f64, member: &[u8]) -> Option<RString> {
+        let (update, _) = self.find_less(score, member);
+        unsafe {
+            let x = Node::forward(update[0], 0)?;
+            if Node::cmp(x, score, member) != Ordering::Equal {
+                return None;
+            }
+            self.unlink_node(x, &update);
+            Some(Node::free(x).1)
+        }
+    }
+
+    /// Update the score of the element `(score, member)` to `new_score` (introduced from
+    /// Redis `zslUpdateScore`), and return `false` if NOT found.
+    ///
+    /// The node is updated in place if it's still in order, otherwise it's moved.
+    ///
+    /// # Panics
+    ///
+    /// It will panic if `new_score` is NaN.
+    pub fn update_score(&mut self, score: f64, member: &[u8], new_score: f64) -> bool {
+        assert!(!new_score.is_nan(), "skip list score is NaN");
+        let (update, _) = self.find_less(score, member);
+        unsafe {
+            let x = match Node::forward(update[0], 0) {
+                Some(x) if Node::cmp(x, score, member) == Ordering::Equal => x,
+                _ => return false,
+            };
+            let in_order = (*x.as_ptr())
+                .backward
+                .is_none_or(|prev| (*prev.as_ptr()).score < new_score)
+                && Node::forward(x, 0).is_none_or(|next| (*next.as_ptr()).score > new_score);
+            if in_order {
+                (*x.as_ptr()).score = new_score;
+            } else {
+                self.unlink_node(x, &update);
+                let (_, member) = Node::free(x);
+                self.insert(new_score, member);
+            }
+        }
+        true
+    }
+
+    /// Get the rank (from 0) of the element `(score, member)` (introduced from Redis
+    /// `zslGetRank`), or `None` if NOT found.
+    pub fn rank(&self, score: f64, member: &[u8]) -> Option<usize> {
+        let mut rank = 0;
+        let mut x = self.header;
+        for idx in (0..self.level).rev() {
+            unsafe {
+                while let Some(next) = Node::forward(x, idx) {
+                    if Node::cmp(next, score, member) == Ordering::Greater {
+                        break;
+                    }
+                    rank += Node::span(x, idx);
+                    x = next;
+                }
+                if x != self.header && Node::cmp(x, score, member) == Ordering::Equal {
+                    return Some(rank - 1);
+                }
+            }
+        }
+        None
+    }
+
+    /// Get the element with `rank` (from 0), or `None` if out of range.
+    #[inline]
+    pub fn get_by_rank(&self, rank: usize) -> Option<(f64, &RString)> {
+        self.node_by_rank(rank.checked_add(1)?).map(|node| unsafe {
+            let node = &*node.as_ptr();
+            (node.score, &node.member)
+        })
+    }
+
+    /// Get the first element, i.e. with the MIN score.
+    #[inline]
+    pub fn first(&self) -> Option<(f64, &RString)> {
+        self.iter().next()
+    }
+
+    /// Get the last element, i.e. with the MAX score.
+    #[inline]
+    pub fn last(&self) -> Option<(f64, &RString)> {
+        self.iter().next_back()
+    }
+
+    /// Get an iterator over ALL elements, in ascending order.
+    #[inline]
+    pub fn iter(&self) -> RSkipListIter<'_> {
+        let first = unsafe { Node::forward(self.header, 0) };
+        self.iter_between(first, self.tail, self.len)
+    }
+
+    /// Get an iterator over elements with scores in the range `r` (introduced from Redis
+    /// ZRANGEBYSCORE), where ends of the range are found in O(log(n)) time.
+    pub fn range_by_score<R>(&self, r: R) -> RSkipListIter<'_>
+    where
+        R: RangeBounds<f64>,
+    {
+        let (min, max) = (r.start_bound(), r.end_bound());
+        self.iter_in_range(
+            |node| gte_min(&node.score, min),
+            |node| lte_max(&node.score, max),
+        )
+    }
+
+    /// Get an iterator over elements with members in the range `r` (introduced from Redis
+    /// ZRANGEBYLEX), where ends of the range are found in O(log(n)) time.
+    ///
+    /// # Notes
+    ///
+    /// Members are compared in bytes, thus elements MUST have the same score, otherwise
+    /// the result is unspecified.
+    pub fn range_by_lex<R>(&self, r: R) -> RSkipListIter<'_>
+    where
+        R: RangeBounds<[u8]>,
+    {
+        let (min, max) = (r.start_bound(), r.end_bound());
+        self.iter_in_range(
+            |node| gte_min(node.member.as_bytes(), min),
+            |node| lte_max(node.member.as_bytes(), max),
+        )
+    }
+
+    /// Get an iterator over elements with ranks (from 0) in the range `r` (clamped to
+    /// the length), where the first element is found in O(log(n)) time.
+    pub fn range_by_rank<R>(&self, r: R) -> RSkipListIter<'_>
+    where
+        R: RangeBounds<usize>,
+    {
+        let start = match r.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match r.end_bound() {
+            Bound::Included(&end) => end.saturating_add(1),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.len,
+        };
+        let end = std::cmp::min(end, self.len);
+        match start < end {
+            true => self.iter_between(
+                self.node_by_rank(start + 1),
+                self.node_by_rank(end),
+                end - start,
+            ),
+            false => self.iter_between(None, None, 0),
+        }
+    }
+}
+
+impl Drop for RSkipList {
+    fn drop(&mut self) {
+        unsafe {
+            let mut cur = Node::forward(self.header, 0);
+            while let Some(node) = cur {
+                cur = Node::forward(node, 0);
+                Node::free(node);
+            }
+            Node::free(self.header);
+        }
+    }
+}
+
+impl Default for RSkipList {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for RSkipList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Iterators
+////////////////////////////////////////////////////////////////////////////////
+
+impl<'a> IntoIterator for &'a RSkipList {
+    type Item = (f64, &'a RString);
+    type IntoIter = RSkipListIter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> Iterator for RSkipListIter<'a> {
+    type Item = (f64, &'a RString);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.front.map(|node| unsafe {
+            self.remaining -= 1;
+            self.front = Node::forward(node, 0);
+            let node = &*node.as_ptr();
+            (node.score, &node.member)
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a> DoubleEndedIterator for RSkipListIter<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.back.map(|node| unsafe {
+            self.remaining -= 1;
+            let node = &*node.as_ptr();
+            self.back = node.backward;
+            (node.score, &node.member)
+        })
+    }
+}
+
+impl<'a> ExactSizeIterator for RSkipListIter<'a> {}
+
+impl<'a> FusedIterator for RSkipListIter<'a> {}
+
+impl<'a> Clone for RSkipListIter<'a> {
+    #[inline]
+    fn clone(&self) -> Self {
+        RSkipListIter { ..*self }
+    }
+}
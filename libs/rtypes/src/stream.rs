@@ -0,0 +1,107 @@
+use std::io::{self, BufRead, Read};
+
+use crate::RString;
+
+////////////////////////////////////////////////////////////////////////////////
+// I/O Adapters
+////////////////////////////////////////////////////////////////////////////////
+
+/// Bytes written are appended to the string, where writes NEVER fail.
+impl io::Write for RString {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.append_bytes(buf);
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.append_bytes(buf);
+        Ok(())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// RStringReader reads (with `io::Read` & `io::BufRead`) the content of an `RString` from
+/// the beginning, WITHOUT copies into intermediate buffers.
+///
+/// # Examples
+///
+/// ```
+/// # use rtypes::RString;
+/// use std::io::{BufRead, Read};
+///
+/// let s = RString::from_str("*1\r\n$4\r\nPING\r\n");
+/// let mut reader = s.reader();
+///
+/// let mut line = String::new();
+/// reader.read_line(&mut line).unwrap();
+/// assert_eq!(line, "*1\r\n");
+///
+/// let mut rest = Vec::new();
+/// reader.read_to_end(&mut rest).unwrap();
+/// assert_eq!(rest, b"$4\r\nPING\r\n");
+/// ```
+#[derive(Clone, Debug)]
+pub struct RStringReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> RStringReader<'a> {
+    /// Get number of bytes read (consumed) so far.
+    #[inline]
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Get bytes NOT read yet.
+    #[inline]
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.bytes[self.pos..]
+    }
+}
+
+impl RString {
+    /// Create a reader of the content, see `RStringReader`.
+    #[inline]
+    pub fn reader(&self) -> RStringReader<'_> {
+        RStringReader {
+            bytes: self.as_bytes(),
+            pos: 0,
+        }
+    }
+}
+
+impl Read for RStringReader<'_> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let count = std::cmp::min(buf.len(), self.bytes.len() - self.pos);
+        buf[..count].copy_from_slice(&self.bytes[self.pos..self.pos + count]);
+        self.pos += count;
+        Ok(count)
+    }
+
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        let rest = self.remaining();
+        buf.extend_from_slice(rest);
+        self.pos = self.bytes.len();
+        Ok(rest.len())
+    }
+}
+
+impl BufRead for RStringReader<'_> {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Ok(self.remaining())
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.pos = std::cmp::min(self.pos + amt, self.bytes.len());
+    }
+}
@@ -0,0 +1,193 @@
+use std::fmt;
+use std::ops::Deref;
+
+use crate::RString;
+
+/// Utf8RString is an `RString` holding valid UTF-8 ONLY, which is validated once (on creation)
+/// rather than on every access, for applications storing text to do character-aware
+/// operations.
+///
+/// # Examples
+///
+/// ```
+/// # use rtypes::{RString, Utf8RString};
+///
+/// let mut s = Utf8RString::from_rstr(RString::from_str("héllo")).unwrap();
+/// s.push_str(", 世界");
+/// assert_eq!(s.char_len(), 9);
+/// assert_eq!(s.char_range(1, 5), "éllo");
+/// assert_eq!(s.as_str(), "héllo, 世界");
+///
+/// assert!(Utf8RString::from_rstr(RString::from_bytes(b"\xff")).is_err());
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Utf8RString(RString);
+
+impl Utf8RString {
+    /// Create an empty string.
+    #[inline]
+    pub fn new() -> Self {
+        Utf8RString::default()
+    }
+
+    /// Convert an `RString` (WITHOUT copies) if it holds valid UTF-8, otherwise it's
+    /// returned back as the error.
+    #[inline]
+    pub fn from_rstr(s: RString) -> Result<Self, RString> {
+        match s.as_str() {
+            Some(_) => Ok(Utf8RString(s)),
+            None => Err(s),
+        }
+    }
+
+    /// Convert an `RString` WITHOUT validation.
+    ///
+    /// # Safety
+    ///
+    /// `s` MUST hold valid UTF-8.
+    #[inline]
+    pub unsafe fn from_rstr_unchecked(s: RString) -> Self {
+        Utf8RString(s)
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        unsafe { std::str::from_utf8_unchecked(self.0.as_bytes()) }
+    }
+
+    #[inline]
+    pub fn as_rstr(&self) -> &RString {
+        &self.0
+    }
+
+    #[inline]
+    pub fn into_rstr(self) -> RString {
+        self.0
+    }
+
+    /// Get number of characters (rather than bytes).
+    #[inline]
+    pub fn char_len(&self) -> usize {
+        char_count(self.0.as_bytes())
+    }
+
+    /// Get characters in the range `[start, end)` (counted in characters rather than bytes),
+    /// where the range is clamped to the string.
+    pub fn char_range(&self, start: usize, end: usize) -> &str {
+        let s = self.as_str();
+        if start >= end {
+            return "";
+        }
+
+        let mut offsets = s.char_indices().map(|(offset, _)| offset).skip(start);
+        let byte_start = match offsets.next() {
+            Some(offset) => offset,
+            None => return "",
+        };
+        let byte_end = offsets.nth(end - start - 1).unwrap_or(s.len());
+        &s[byte_start..byte_end]
+    }
+
+    #[inline]
+    pub fn push(&mut self, c: char) {
+        self.0.append_str(c.encode_utf8(&mut [0; 4]));
+    }
+
+    #[inline]
+    pub fn push_str(&mut self, s: &str) {
+        self.0.append_str(s);
+    }
+
+    /// Truncate the string to `new_len` characters (rather than bytes).
+    pub fn truncate_chars(&mut self, new_len: usize) {
+        if let Some((offset, _)) = self.as_str().char_indices().nth(new_len) {
+            self.0.truncate(offset);
+        }
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+impl RString {
+    /// Get the content as `str` if it's valid UTF-8, which is validated on every call
+    /// (use `Utf8RString` to validate once).
+    #[inline]
+    pub fn as_str(&self) -> Option<&str> {
+        std::str::from_utf8(self.as_bytes()).ok()
+    }
+
+    /// Get number of characters of the content if it's valid UTF-8.
+    #[inline]
+    pub fn char_len(&self) -> Option<usize> {
+        self.as_str().map(|s| char_count(s.as_bytes()))
+    }
+}
+
+/// Count characters of valid UTF-8 `bytes`, i.e. bytes other than continuation bytes
+/// (`0b10xx_xxxx`).
+#[inline]
+fn char_count(bytes: &[u8]) -> usize {
+    bytes.iter().filter(|&&byte| (byte as i8) >= -0x40).count()
+}
+
+impl From<&str> for Utf8RString {
+    #[inline]
+    fn from(s: &str) -> Utf8RString {
+        Utf8RString(RString::from_str(s))
+    }
+}
+
+impl From<Utf8RString> for RString {
+    #[inline]
+    fn from(s: Utf8RString) -> RString {
+        s.0
+    }
+}
+
+impl Deref for Utf8RString {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for Utf8RString {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<[u8]> for Utf8RString {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
+
+impl fmt::Write for Utf8RString {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+}
+
+impl fmt::Display for Utf8RString {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Debug for Utf8RString {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
@@ -0,0 +1,610 @@
+use std::fmt::{self, Debug};
+use std::iter::FusedIterator;
+use std::ops::{BitOr, BitOrAssign, Bound, RangeBounds};
+
+use crate::num::parse_f64;
+use crate::skiplist::{gte_min, lte_max};
+use crate::{
+    Encoding, EncodingLimits, ListPackValue, RDict, RListPack, RListPackIter, RSkipList,
+    RSkipListIter, RString,
+};
+
+////////////////////////////////////////////////////////////////////////////////
+// RZSet
+////////////////////////////////////////////////////////////////////////////////
+
+/// Flags of adding elements to an `RZSet` (introduced from Redis `ZADD_IN_*`), which
+/// are combined with `|`.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub struct ZAddFlags(u8);
+
+impl ZAddFlags {
+    /// Add new members, and update scores of existing members.
+    pub const NONE: ZAddFlags = ZAddFlags(0);
+    /// Increment the score of the member (or set it if NOT found), like ZINCRBY.
+    pub const INCR: ZAddFlags = ZAddFlags(1 << 0);
+    /// ONLY add new members, i.e. NEVER update existing members.
+    pub const NX: ZAddFlags = ZAddFlags(1 << 1);
+    /// ONLY update existing members, i.e. NEVER add new members.
+    pub const XX: ZAddFlags = ZAddFlags(1 << 2);
+    /// ONLY update existing members if the new score is greater.
+    pub const GT: ZAddFlags = ZAddFlags(1 << 3);
+    /// ONLY update existing members if the new score is less.
+    pub const LT: ZAddFlags = ZAddFlags(1 << 4);
+
+    /// Check if ALL flags of `other` are set.
+    #[inline]
+    pub const fn contains(self, other: ZAddFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Check if flags are compatible, where NX & XX, GT & LT, as well as NX & GT (or LT)
+    /// are mutually exclusive.
+    #[inline]
+    pub const fn is_valid(self) -> bool {
+        let (nx, xx) = (self.contains(ZAddFlags::NX), self.contains(ZAddFlags::XX));
+        let (gt, lt) = (self.contains(ZAddFlags::GT), self.contains(ZAddFlags::LT));
+        !(nx && (xx || gt || lt)) && !(gt && lt)
+    }
+}
+
+impl BitOr for ZAddFlags {
+    type Output = ZAddFlags;
+
+    #[inline]
+    fn bitor(self, rhs: ZAddFlags) -> ZAddFlags {
+        ZAddFlags(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for ZAddFlags {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: ZAddFlags) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl Debug for ZAddFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const NAMES: [(ZAddFlags, &str); 5] = [
+            (ZAddFlags::INCR, "INCR"),
+            (ZAddFlags::NX, "NX"),
+            (ZAddFlags::XX, "XX"),
+            (ZAddFlags::GT, "GT"),
+            (ZAddFlags::LT, "LT"),
+        ];
+        let mut set = f.debug_set();
+        for (flag, name) in NAMES.iter() {
+            if self.contains(*flag) {
+                set.entry(&format_args!("{}", name));
+            }
+        }
+        set.finish()
+    }
+}
+
+/// Outcome of adding an element to an `RZSet` (introduced from Redis `ZADD_OUT_*`),
+/// with the score of the member afterwards.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ZAddOutcome {
+    /// The member is added.
+    Added(f64),
+    /// The score of the member is updated.
+    Updated(f64),
+    /// The member is found, whose score is the same.
+    Unchanged(f64),
+    /// Nothing is done, as conditions of flags are NOT met.
+    Skipped,
+    /// Nothing is done, as the score (incremented) is NaN.
+    NaN,
+}
+
+impl ZAddOutcome {
+    /// Get the score of the member afterwards, or `None` if skipped (or NaN).
+    #[inline]
+    pub fn score(self) -> Option<f64> {
+        match self {
+            ZAddOutcome::Added(score)
+            | ZAddOutcome::Updated(score)
+            | ZAddOutcome::Unchanged(score) => Some(score),
+            ZAddOutcome::Skipped | ZAddOutcome::NaN => None,
+        }
+    }
+
+    /// Check if the set is changed, i.e. added or updated (counted by ZADD CH).
+    #[inline]
+    pub fn is_changed(self) -> bool {
+        matches!(self, ZAddOutcome::Added(_) | ZAddOutcome::Updated(_))
+    }
+}
+
+/// Elements of a sorted set, in one of the encodings.
+enum Elements {
+    // Each member is followed by its score, ordered by scores (then by members).
+    ListPack(RListPack),
+    SkipList {
+        dict: RDict<RString, f64>,
+        list: RSkipList,
+    },
+}
+
+/// RZSet is a sorted set (introduced from Redis `zset`) of unique members with scores,
+/// ordered by scores (then by members), which is encoded in a listpack while small, and
+/// converted once it grows:
+///
+/// - `listpack`: NO more than `zset_max_listpack_entries` members, each of which is NO
+///   longer than `zset_max_listpack_value` bytes, see `EncodingLimits`.
+/// - `skiplist`: otherwise, where a dict maps members to scores, and a skip list orders
+///   elements, thus scores are looked up in O(1) time, while elements are added, removed
+///   & ranked in O(log(n)) time.
+///
+/// # Notes
+///
+/// Members of the `skiplist` encoding are held by both the dict & the skip list, i.e.
+/// each member is stored twice. Sorted sets are NEVER converted back to listpacks, even
+/// if members are removed.
+///
+/// # Examples
+///
+/// ```
+/// # use rtypes::{Encoding, RZSet, ZAddFlags, ZAddOutcome};
+///
+/// let mut zset = RZSet::new();
+/// zset.add(1.0, b"one", ZAddFlags::NONE);
+/// zset.add(2.0, b"two", ZAddFlags::NONE);
+/// assert_eq!(zset.add(3.0, b"one", ZAddFlags::GT), ZAddOutcome::Updated(3.0));
+/// assert_eq!(zset.add(0.0, b"one", ZAddFlags::GT), ZAddOutcome::Skipped);
+///
+/// assert_eq!(zset.score(b"one"), Some(3.0));
+/// assert_eq!(zset.rank(b"one"), Some(1));
+/// let mut members = zset.range_by_score(..3.0).map(|(_, m)| m.to_rstring().to_bytes());
+/// assert_eq!(members.next(), Some(b"two".to_vec()));
+/// assert_eq!(members.next(), None);
+/// assert_eq!(zset.encoding(), Encoding::ListPack);
+///
+/// zset.add(4.0, &[b'x'; 100], ZAddFlags::NONE);
+/// assert_eq!(zset.encoding(), Encoding::SkipList);
+/// assert_eq!(zset.rank(b"one"), Some(1));
+/// ```
+pub struct RZSet {
+    elements: Elements,
+    max_listpack_entries: usize,
+    max_listpack_value: usize,
+}
+
+/// Iterator over elements (with members borrowed) of an `RZSet`, in ascending order.
+pub struct RZSetIter<'a> {
+    inner: ZSetIterInner<'a>,
+}
+
+enum ZSetIterInner<'a> {
+    ListPack(RListPackIter<'a>),
+    SkipList(RSkipListIter<'a>),
+}
+
+// Formats `score` to be held in listpacks (introduced from Redis `d2string`), where
+// integral scores (exactly representable) are held as integers.
+fn format_score(score: f64) -> RString {
+    if score == 0.0 && score.is_sign_negative() {
+        RString::from_str("-0")
+    } else if score.fract() == 0.0 && score.abs() <= (1u64 << f64::MANTISSA_DIGITS) as f64 {
+        RString::from_i64(score as i64)
+    } else {
+        // The shortest form parsed back exactly, with exponents for huge (or tiny) ones.
+        RString::from_fmt(format_args!("{:?}", score))
+    }
+}
+
+// Parses a score held in listpacks.
+#[inline]
+fn parse_score(value: ListPackValue<'_>) -> f64 {
+    match value {
+        ListPackValue::Int(score) => score as f64,
+        ListPackValue::Str(score) => parse_f64(score).expect("invalid score in listpack"),
+    }
+}
+
+// Gets ranks in the range `r`, clamped to `len`.
+fn rank_range<R: RangeBounds<usize>>(r: R, len: usize) -> (usize, usize) {
+    let start = match r.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.saturating_add(1),
+        Bound::Unbounded => 0,
+    };
+    let end = match r.end_bound() {
+        Bound::Included(&end) => end.saturating_add(1),
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+    let end = std::cmp::min(end, len);
+    (std::cmp::min(start, end), end)
+}
+
+// private methods
+impl RZSet {
+    // Finds the index of `member` in the listpack, i.e. the one before its score, and
+    // the score.
+    fn find_in_listpack(lp: &RListPack, member: &[u8]) -> Option<(usize, f64)> {
+        let mut iter = lp.iter();
+        let mut idx = 0;
+        while let (Some(cur), Some(score)) = (iter.next(), iter.next()) {
+            if cur.eq_bytes(member) {
+                return Some((idx, parse_score(score)));
+            }
+            idx += 2;
+        }
+        None
+    }
+
+    // Inserts `member` with `score` into the listpack, before the first element greater
+    // (introduced from Redis `zzlInsert`).
+    fn insert_in_listpack(lp: &mut RListPack, score: f64, member: &[u8]) {
+        let mut iter = lp.iter();
+        let mut idx = 0;
+        while let (Some(cur), Some(cur_score)) = (iter.next(), iter.next()) {
+            let cur_score = parse_score(cur_score);
+            if cur_score > score || (cur_score == score && cur.with_bytes(|cur| cur > member)) {
+                break;
+            }
+            idx += 2;
+        }
+        lp.insert(idx, member);
+        lp.insert(idx + 1, format_score(score).as_bytes());
+    }
+
+    // Gets an iterator over elements of the listpack with ranks in `[start, end)`.
+    fn listpack_range(lp: &RListPack, start: usize, end: usize) -> RZSetIter<'_> {
+        let len = lp.len() / 2;
+        let mut iter = lp.iter();
+        if start > 0 {
+            iter.nth(2 * start - 1);
+        }
+        if end < len {
+            iter.nth_back(2 * (len - end) - 1);
+        }
+        RZSetIter {
+            inner: ZSetIterInner::ListPack(iter),
+        }
+    }
+
+    // Gets an iterator over elements of the listpack NOT `below` nor `above` the range,
+    // which are checked for ALL elements in O(n) time.
+    fn listpack_range_by<F, G>(lp: &RListPack, below: F, above: G) -> RZSetIter<'_>
+    where
+        F: Fn(f64, ListPackValue<'_>) -> bool,
+        G: Fn(f64, ListPackValue<'_>) -> bool,
+    {
+        let (mut start, mut end) = (0, 0);
+        for (score, member) in Self::listpack_range(lp, 0, lp.len() / 2) {
+            start += below(score, member) as usize;
+            end += !above(score, member) as usize;
+        }
+        Self::listpack_range(lp, start, std::cmp::max(start, end))
+    }
+
+    // Gets the new score of the member with `old_score`, or the outcome if NOT updated.
+    fn new_score(old_score: f64, score: f64, flags: ZAddFlags) -> Result<f64, ZAddOutcome> {
+        if flags.contains(ZAddFlags::NX) {
+            return Err(ZAddOutcome::Skipped);
+        }
+        let new_score = match flags.contains(ZAddFlags::INCR) {
+            true => old_score + score,
+            false => score,
+        };
+        if new_score.is_nan() {
+            return Err(ZAddOutcome::NaN);
+        }
+        if (flags.contains(ZAddFlags::GT) && new_score <= old_score)
+            || (flags.contains(ZAddFlags::LT) && new_score >= old_score)
+        {
+            return Err(ZAddOutcome::Skipped);
+        }
+        if new_score == old_score {
+            return Err(ZAddOutcome::Unchanged(old_score));
+        }
+        Ok(new_score)
+    }
+
+    // Converts elements into a skip list (introduced from Redis `zsetConvert`).
+    fn convert_to_skiplist(&mut self) {
+        let lp = match &self.elements {
+            Elements::ListPack(lp) => lp,
+            Elements::SkipList { .. } => return,
+        };
+        let mut dict = RDict::new();
+        let mut list = RSkipList::new();
+        dict.expand(lp.len() / 2);
+        for (score, member) in Self::listpack_range(lp, 0, lp.len() / 2) {
+            dict.insert(member.to_rstring(), score);
+            list.insert(score, member.to_rstring());
+        }
+        self.elements = Elements::SkipList { dict, list };
+    }
+}
+
+// public methods
+impl RZSet {
+    /// Create an empty set in `listpack`, with DEFAULT limits.
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_limits(&EncodingLimits::DEFAULT)
+    }
+
+    /// Create an empty set in `listpack`, which is converted to a skip list once it grows
+    /// out of `limits`.
+    #[inline]
+    pub fn with_limits(limits: &EncodingLimits) -> Self {
+        RZSet {
+            elements: Elements::ListPack(RListPack::new()),
+            max_listpack_entries: limits.zset_max_listpack_entries,
+            max_listpack_value: limits.zset_max_listpack_value,
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        match &self.elements {
+            Elements::ListPack(lp) => lp.len() / 2,
+            Elements::SkipList { list, .. } => list.len(),
+        }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the encoding of elements.
+    #[inline]
+    pub fn encoding(&self) -> Encoding {
+        match &self.elements {
+            Elements::ListPack(_) => Encoding::ListPack,
+            Elements::SkipList { .. } => Encoding::SkipList,
+        }
+    }
+
+    /// Add the member with `score`, or update its score (introduced from Redis `zsetAdd`),
+    /// under conditions of `flags`, and return the outcome, where the set is converted if
+    /// it grows out of the encoding.
+    ///
+    /// New members are added unless `XX`, while scores of existing members are updated
+    /// unless `NX` (or NOT greater for `GT`, or NOT less for `LT`), where `score` is
+    /// the increment for `INCR`.
+    ///
+    /// # Panics
+    ///
+    /// It will panic if `flags` are NOT compatible, see `ZAddFlags::is_valid`.
+    pub fn add(&mut self, score: f64, member: &[u8], flags: ZAddFlags) -> ZAddOutcome {
+        assert!(flags.is_valid(), "incompatible flags {:?}", flags);
+        if score.is_nan() {
+            return ZAddOutcome::NaN;
+        }
+
+        if let Elements::ListPack(lp) = &mut self.elements {
+            match Self::find_in_listpack(lp, member) {
+                Some((idx, old_score)) => {
+                    let new_score = match Self::new_score(old_score, score, flags) {
+                        Ok(new_score) => new_score,
+                        Err(outcome) => return outcome,
+                    };
+                    lp.remove_range(idx, 2);
+                    Self::insert_in_listpack(lp, new_score, member);
+                    return ZAddOutcome::Updated(new_score);
+                }
+                None if flags.contains(ZAddFlags::XX) => return ZAddOutcome::Skipped,
+                None if lp.len() / 2 < self.max_listpack_entries
+                    && member.len() <= self.max_listpack_value =>
+                {
+                    Self::insert_in_listpack(lp, score, member);
+                    return ZAddOutcome::Added(score);
+                }
+                None => self.convert_to_skiplist(),
+            }
+        }
+
+        let (dict, list) = match &mut self.elements {
+            Elements::SkipList { dict, list } => (dict, list),
+            Elements::ListPack(_) => unreachable!("zset NOT converted to skiplist"),
+        };
+        let cur = match dict.get_mut(member) {
+            Some(cur) => cur,
+            None if flags.contains(ZAddFlags::XX) => return ZAddOutcome::Skipped,
+            None => {
+                dict.insert(RString::from_bytes(member), score);
+                list.insert(score, RString::from_bytes(member));
+                return ZAddOutcome::Added(score);
+            }
+        };
+        let old_score = *cur;
+        let new_score = match Self::new_score(old_score, score, flags) {
+            Ok(new_score) => new_score,
+            Err(outcome) => return outcome,
+        };
+        *cur = new_score;
+        list.update_score(old_score, member, new_score);
+        ZAddOutcome::Updated(new_score)
+    }
+
+    /// Remove the member, and return its score, or `None` if NOT found.
+    pub fn remove(&mut self, member: &[u8]) -> Option<f64> {
+        match &mut self.elements {
+            Elements::ListPack(lp) => {
+                let (idx, score) = Self::find_in_listpack(lp, member)?;
+                lp.remove_range(idx, 2);
+                Some(score)
+            }
+            Elements::SkipList { dict, list } => {
+                let (member, score) = dict.remove_entry(member)?;
+                list.delete(score, member.as_bytes());
+                Some(score)
+            }
+        }
+    }
+
+    /// Get the score of the member, or `None` if NOT found.
+    #[inline]
+    pub fn score(&self, member: &[u8]) -> Option<f64> {
+        match &self.elements {
+            Elements::ListPack(lp) => Self::find_in_listpack(lp, member).map(|(_, score)| score),
+            Elements::SkipList { dict, .. } => dict.get(member).copied(),
+        }
+    }
+
+    #[inline]
+    pub fn contains(&self, member: &[u8]) -> bool {
+        self.score(member).is_some()
+    }
+
+    /// Get the rank (from 0, in ascending order) of the member, or `None` if NOT found.
+    #[inline]
+    pub fn rank(&self, member: &[u8]) -> Option<usize> {
+        match &self.elements {
+            Elements::ListPack(lp) => Self::find_in_listpack(lp, member).map(|(idx, _)| idx / 2),
+            Elements::SkipList { list, .. } => list.rank(self.score(member)?, member),
+        }
+    }
+
+    /// Get the rank (from 0, in descending order) of the member, or `None` if NOT found.
+    #[inline]
+    pub fn rev_rank(&self, member: &[u8]) -> Option<usize> {
+        self.rank(member).map(|rank| self.len() - 1 - rank)
+    }
+
+    /// Get an iterator over ALL elements, in ascending order.
+    #[inline]
+    pub fn iter(&self) -> RZSetIter<'_> {
+        self.range_by_rank(..)
+    }
+
+    /// Get an iterator over elements with ranks (from 0) in the range `r` (introduced from
+    /// Redis ZRANGE), see `RSkipList::range_by_rank`.
+    pub fn range_by_rank<R>(&self, r: R) -> RZSetIter<'_>
+    where
+        R: RangeBounds<usize>,
+    {
+        match &self.elements {
+            Elements::ListPack(lp) => {
+                let (start, end) = rank_range(r, lp.len() / 2);
+                Self::listpack_range(lp, start, end)
+            }
+            Elements::SkipList { list, .. } => RZSetIter {
+                inner: ZSetIterInner::SkipList(list.range_by_rank(r)),
+            },
+        }
+    }
+
+    /// Get an iterator over elements with scores in the range `r` (introduced from Redis
+    /// ZRANGEBYSCORE), see `RSkipList::range_by_score`.
+    pub fn range_by_score<R>(&self, r: R) -> RZSetIter<'_>
+    where
+        R: RangeBounds<f64>,
+    {
+        match &self.elements {
+            Elements::ListPack(lp) => {
+                let (min, max) = (r.start_bound(), r.end_bound());
+                Self::listpack_range_by(
+                    lp,
+                    |score, _| !gte_min(&score, min),
+                    |score, _| !lte_max(&score, max),
+                )
+            }
+            Elements::SkipList { list, .. } => RZSetIter {
+                inner: ZSetIterInner::SkipList(list.range_by_score(r)),
+            },
+        }
+    }
+
+    /// Get an iterator over elements with members in the range `r` (introduced from Redis
+    /// ZRANGEBYLEX), where ALL elements MUST have the same score, see
+    /// `RSkipList::range_by_lex`.
+    pub fn range_by_lex<R>(&self, r: R) -> RZSetIter<'_>
+    where
+        R: RangeBounds<[u8]>,
+    {
+        match &self.elements {
+            Elements::ListPack(lp) => {
+                let (min, max) = (r.start_bound(), r.end_bound());
+                Self::listpack_range_by(
+                    lp,
+                    |_, member| member.with_bytes(|member| !gte_min(member, min)),
+                    |_, member| member.with_bytes(|member| !lte_max(member, max)),
+                )
+            }
+            Elements::SkipList { list, .. } => RZSetIter {
+                inner: ZSetIterInner::SkipList(list.range_by_lex(r)),
+            },
+        }
+    }
+}
+
+impl Default for RZSet {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for RZSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Iterators
+////////////////////////////////////////////////////////////////////////////////
+
+impl<'a> IntoIterator for &'a RZSet {
+    type Item = (f64, ListPackValue<'a>);
+    type IntoIter = RZSetIter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> Iterator for RZSetIter<'a> {
+    type Item = (f64, ListPackValue<'a>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.inner {
+            ZSetIterInner::ListPack(iter) => {
+                let member = iter.next()?;
+                Some((parse_score(iter.next()?), member))
+            }
+            ZSetIterInner::SkipList(iter) => iter
+                .next()
+                .map(|(score, member)| (score, ListPackValue::Str(member.as_bytes()))),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = match &self.inner {
+            ZSetIterInner::ListPack(iter) => iter.len() / 2,
+            ZSetIterInner::SkipList(iter) => iter.len(),
+        };
+        (len, Some(len))
+    }
+}
+
+impl<'a> DoubleEndedIterator for RZSetIter<'a> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match &mut self.inner {
+            ZSetIterInner::ListPack(iter) => {
+                let score = parse_score(iter.next_back()?);
+                Some((score, iter.next_back()?))
+            }
+            ZSetIterInner::SkipList(iter) => iter
+                .next_back()
+                .map(|(score, member)| (score, ListPackValue::Str(member.as_bytes()))),
+        }
+    }
+}
+
+impl<'a> ExactSizeIterator for RZSetIter<'a> {}
+
+impl<'a> FusedIterator for RZSetIter<'a> {}
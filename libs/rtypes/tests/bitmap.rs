@@ -0,0 +1,122 @@
+use rtypes::{BitFieldOverflow, BitFieldType, RBitmap, RString};
+
+fn ty(name: &str) -> BitFieldType {
+    BitFieldType::from_bytes(name.as_bytes()).unwrap()
+}
+
+#[test]
+fn field_types() {
+    assert_eq!(
+        BitFieldType::from_bytes(b"i64"),
+        BitFieldType::new(true, 64)
+    );
+    assert_eq!(ty("u63").bits(), 63);
+    assert!(!ty("u1").is_signed());
+    for &bad in &[&b""[..], b"i", b"i0", b"i65", b"u64", b"x8", b"u+8", b"i 8"] {
+        assert_eq!(BitFieldType::from_bytes(bad), None);
+    }
+}
+
+#[test]
+fn get_and_set_fields() {
+    let bitmap = RBitmap::from_rstring(RString::from_bytes(b"\x81\x00\xff"));
+    assert_eq!(bitmap.get_field(0, ty("u1")), 1);
+    assert_eq!(bitmap.get_field(0, ty("i8")), -127);
+    assert_eq!(bitmap.get_field(0, ty("u8")), 129);
+    assert_eq!(bitmap.get_field(7, ty("u2")), 2);
+    assert_eq!(bitmap.get_field(20, ty("i8")), -16);
+    assert_eq!(bitmap.get_field(16, ty("u16")), 0xff00);
+    assert_eq!(bitmap.get_field(100, ty("i64")), 0);
+
+    // Fields across words, of the MAX width.
+    let mut bitmap = RBitmap::new();
+    let wrap = BitFieldOverflow::Wrap;
+    assert_eq!(bitmap.set_field(5, ty("i64"), i64::MIN + 1, wrap), Some(0));
+    assert_eq!(bitmap.len(), 9);
+    assert_eq!(bitmap.get_field(5, ty("i64")), i64::MIN + 1);
+    assert_eq!(bitmap.get_field(5, ty("u63")), 0x4000_0000_0000_0000);
+    assert_eq!(bitmap.count_ones(), 2);
+    assert_eq!(bitmap.set_field(7, ty("u63"), 0, wrap), Some(2));
+    assert_eq!(bitmap.get_field(5, ty("i64")), i64::MIN);
+    assert!(!bitmap.get(4) && !bitmap.get(69));
+
+    // Values out of range are set with overflows handled.
+    let mut bitmap = RBitmap::new();
+    assert_eq!(bitmap.set_field(0, ty("i4"), 9, wrap), Some(0));
+    assert_eq!(bitmap.get_field(0, ty("i4")), -7);
+    assert_eq!(
+        bitmap.set_field(0, ty("i4"), 9, BitFieldOverflow::Sat),
+        Some(-7)
+    );
+    assert_eq!(bitmap.get_field(0, ty("i4")), 7);
+    assert_eq!(
+        bitmap.set_field(0, ty("i4"), -9, BitFieldOverflow::Fail),
+        None
+    );
+    assert_eq!(bitmap.get_field(0, ty("i4")), 7);
+    // Negative values for unsigned fields overflow, just like Redis.
+    assert_eq!(bitmap.set_field(8, ty("u8"), -1, wrap), Some(0));
+    assert_eq!(bitmap.get_field(8, ty("u8")), 255);
+    assert_eq!(
+        bitmap.set_field(8, ty("u8"), -1, BitFieldOverflow::Sat),
+        Some(255)
+    );
+    assert_eq!(bitmap.get_field(8, ty("u8")), 255);
+    assert_eq!(bitmap.as_bytes(), b"\x70\xff");
+}
+
+#[test]
+fn incr_fields() {
+    let mut bitmap = RBitmap::new();
+    let (wrap, sat, fail) = (
+        BitFieldOverflow::Wrap,
+        BitFieldOverflow::Sat,
+        BitFieldOverflow::Fail,
+    );
+    assert_eq!(bitmap.incr_field(3, ty("u2"), 3, wrap), Some(3));
+    assert_eq!(bitmap.incr_field(3, ty("u2"), 1, wrap), Some(0));
+    assert_eq!(bitmap.incr_field(3, ty("u2"), -1, sat), Some(0));
+    assert_eq!(bitmap.incr_field(3, ty("u2"), -1, wrap), Some(3));
+    assert_eq!(bitmap.incr_field(3, ty("u2"), 1, fail), None);
+    assert_eq!(bitmap.get_field(3, ty("u2")), 3);
+
+    assert_eq!(
+        bitmap.incr_field(10, ty("i64"), i64::MAX, wrap),
+        Some(i64::MAX)
+    );
+    assert_eq!(bitmap.incr_field(10, ty("i64"), 1, wrap), Some(i64::MIN));
+    assert_eq!(bitmap.incr_field(10, ty("i64"), -1, sat), Some(i64::MIN));
+    assert_eq!(bitmap.incr_field(10, ty("i64"), i64::MIN, fail), None);
+    assert_eq!(bitmap.incr_field(10, ty("i64"), i64::MAX, fail), Some(-1));
+    assert_eq!(bitmap.incr_field(80, ty("u63"), -1, sat), Some(0));
+    assert_eq!(bitmap.incr_field(80, ty("u63"), -1, wrap), Some(i64::MAX));
+    assert_eq!(bitmap.get_field(3, ty("u2")), 3);
+    assert_eq!(bitmap.get_field(10, ty("i64")), -1);
+}
+
+#[test]
+fn count_bits() {
+    let bitmap = RBitmap::from(RString::from_str("foobar, with more than one word"));
+    let bits = bitmap.len() * 8;
+    let naive = |start: usize, end: usize| (start..end).filter(|&i| bitmap.get(i)).count();
+    assert_eq!(bitmap.count_ones(), naive(0, bits));
+    for &(start, end) in &[
+        (0, 0),
+        (1, 1),
+        (3, 6),
+        (5, 13),
+        (7, 200),
+        (9, 250),
+        (0, bits),
+        (8, 16),
+    ] {
+        assert_eq!(
+            bitmap.count_ones_in(start, end),
+            naive(start, end.min(bits))
+        );
+    }
+    assert_eq!(bitmap.count_ones_in(bits, bits + 10), 0);
+    assert_eq!(bitmap.count_ones_in(20, 10), 0);
+    assert_eq!(bitmap.position(true, 0), Some(1));
+    assert_eq!(bitmap.into_rstring().len(), 31);
+}
@@ -0,0 +1,128 @@
+use rtypes::{GeoError, GeoShape, GeoUnit, RGeo, ZAddFlags, ZAddOutcome};
+
+fn sicily() -> RGeo {
+    let mut geo = RGeo::new();
+    geo.add(13.361389, 38.115556, b"Palermo", ZAddFlags::NONE)
+        .unwrap();
+    geo.add(15.087269, 37.502669, b"Catania", ZAddFlags::NONE)
+        .unwrap();
+    geo.add(12.758489, 38.788135, b"edge1", ZAddFlags::NONE)
+        .unwrap();
+    geo.add(17.241510, 38.788135, b"edge2", ZAddFlags::NONE)
+        .unwrap();
+    geo
+}
+
+fn members(geo: &RGeo, center: (f64, f64), shape: GeoShape) -> Vec<(String, f64)> {
+    let mut found: Vec<_> = geo
+        .search(center, shape)
+        .unwrap()
+        .into_iter()
+        .map(|point| {
+            (
+                String::from_utf8(point.member.as_bytes().to_vec()).unwrap(),
+                point.distance,
+            )
+        })
+        .collect();
+    found.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+    found
+}
+
+#[test]
+fn add_members() {
+    let mut geo = sicily();
+    assert_eq!(geo.len(), 4);
+    assert_eq!(
+        geo.add(13.361389, 38.115556, b"Palermo", ZAddFlags::NX),
+        Ok(ZAddOutcome::Skipped)
+    );
+    assert_eq!(
+        geo.add(0.0, 0.0, b"Null Island", ZAddFlags::XX),
+        Ok(ZAddOutcome::Skipped)
+    );
+    let error = geo.add(0.0, 86.0, b"Pole", ZAddFlags::NONE).unwrap_err();
+    assert_eq!(
+        error,
+        GeoError {
+            lon: 0.0,
+            lat: 86.0
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "invalid longitude,latitude pair 0.000000,86.000000"
+    );
+
+    let (lon, lat) = geo.position(b"Palermo").unwrap();
+    assert!((lon - 13.361389).abs() < 1e-5 && (lat - 38.115556).abs() < 1e-5);
+    assert_eq!(geo.hash(b"Palermo").unwrap().bits, 3479099956230698);
+    assert_eq!(geo.hash_string(b"Catania").unwrap(), "sqdtr74hyu0");
+    assert_eq!(geo.position(b"Rome"), None);
+    assert_eq!(geo.distance(b"Palermo", b"Rome"), None);
+    let km = geo.distance(b"Palermo", b"Catania").unwrap() / GeoUnit::Kilometers.to_meters();
+    assert!((km - 166.2742).abs() < 1e-4);
+
+    // Positions are updated.
+    geo.add(15.0, 37.0, b"Palermo", ZAddFlags::XX).unwrap();
+    let (lon, lat) = geo.position(b"Palermo").unwrap();
+    assert!((lon - 15.0).abs() < 1e-5 && (lat - 37.0).abs() < 1e-5);
+    assert!(geo.remove(b"Palermo"));
+    assert!(!geo.remove(b"Palermo"));
+    assert_eq!(geo.as_zset().len(), 3);
+}
+
+#[test]
+fn search_members() {
+    let geo = sicily();
+    let center = (15.0, 37.0);
+
+    // The same as Redis GEOSEARCH gives.
+    let found = members(&geo, center, GeoShape::Radius(200_000.0));
+    let names: Vec<_> = found.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, vec!["Catania", "Palermo"]);
+    assert!((found[0].1 - 56441.3).abs() < 0.1 && (found[1].1 - 190442.4).abs() < 0.1);
+
+    let found = members(&geo, center, GeoShape::Box(400_000.0, 400_000.0));
+    let names: Vec<_> = found.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, vec!["Catania", "Palermo", "edge2", "edge1"]);
+
+    assert!(members(&geo, center, GeoShape::Radius(50_000.0)).is_empty());
+    assert_eq!(
+        geo.search((0.0, 89.0), GeoShape::Radius(1.0)).unwrap_err(),
+        GeoError {
+            lon: 0.0,
+            lat: 89.0
+        }
+    );
+
+    // Members are found exactly as scanning ALL of them.
+    let mut geo = RGeo::new();
+    for i in 0..400 {
+        let (lon, lat) = (-1.0 + (i % 20) as f64 * 0.1, 51.0 + (i / 20) as f64 * 0.1);
+        geo.add(lon, lat, i.to_string().as_bytes(), ZAddFlags::NONE)
+            .unwrap();
+    }
+    for &shape in &[
+        GeoShape::Radius(30_000.0),
+        GeoShape::Box(50_000.0, 40_000.0),
+    ] {
+        let center = (-0.13, 51.51);
+        let found = members(&geo, center, shape);
+        let expected = (0..400)
+            .filter(|i| {
+                let position = geo.position(i.to_string().as_bytes()).unwrap();
+                shape.distance_within(center, position).is_some()
+            })
+            .count();
+        assert!(expected > 10);
+        assert_eq!(found.len(), expected);
+    }
+
+    assert_eq!(GeoUnit::from_bytes(b"KM"), Some(GeoUnit::Kilometers));
+    assert_eq!(
+        GeoUnit::from_bytes(b"mi").map(GeoUnit::to_meters),
+        Some(1609.34)
+    );
+    assert_eq!(GeoUnit::from_bytes(b"yd"), None);
+}
@@ -0,0 +1,142 @@
+use rtypes::{geo_distance, GeoArea, GeoHash, GeoShape, GEO_LAT_MAX, GEO_LONG_MIN, GEO_STEP_MAX};
+
+#[test]
+fn encode_and_decode() {
+    // Scores the same as Redis GEOADD gives.
+    let palermo = GeoHash::encode(13.361389, 38.115556, GEO_STEP_MAX).unwrap();
+    assert_eq!(palermo.to_score(), 3479099956230698.0);
+    let catania = GeoHash::encode(15.087269, 37.502669, GEO_STEP_MAX).unwrap();
+    assert_eq!(catania.to_score(), 3479447370796909.0);
+    assert_eq!(GeoHash::from_score(palermo.to_score()), palermo);
+
+    let (lon, lat) = palermo.to_lon_lat();
+    assert!((lon - 13.361389338970184).abs() < 1e-12);
+    assert!((lat - 38.1155563954963).abs() < 1e-12);
+    let area = palermo.decode();
+    assert!(area.contains(13.361389, 38.115556));
+    assert!(area.max_lon - area.min_lon < 1e-5);
+
+    // Scores of coarse cells cover the fine ones.
+    let coarse = GeoHash::encode(13.361389, 38.115556, 10).unwrap();
+    let (min, max) = coarse.score_range();
+    assert!(min <= palermo.to_score() && palermo.to_score() < max);
+    assert_eq!(max - min, (1u64 << 32) as f64);
+
+    assert_eq!(GeoHash::encode(180.1, 0.0, GEO_STEP_MAX), None);
+    assert_eq!(GeoHash::encode(0.0, 85.06, GEO_STEP_MAX), None);
+    let corner = GeoHash::encode(GEO_LONG_MIN, GEO_LAT_MAX, 1).unwrap();
+    assert_eq!(corner.bits, 0b01);
+
+    assert_eq!(
+        GeoHash::to_base32(13.361389, 38.115556).unwrap(),
+        "sqc8b49rny0"
+    );
+    assert_eq!(
+        GeoHash::to_base32(15.087269, 37.502669).unwrap(),
+        "sqdtr74hyu0"
+    );
+}
+
+#[test]
+fn neighbors() {
+    let hash = GeoHash::encode(13.361389, 38.115556, 8).unwrap();
+    let area = hash.decode();
+    let [north, south, east, west, north_east, south_east, north_west, south_west] =
+        hash.neighbors().map(GeoHash::decode);
+    let eps = 1e-9;
+    assert!((north.min_lat - area.max_lat).abs() < eps && north.min_lon == area.min_lon);
+    assert!((south.max_lat - area.min_lat).abs() < eps && south.min_lon == area.min_lon);
+    assert!((east.min_lon - area.max_lon).abs() < eps && east.min_lat == area.min_lat);
+    assert!((west.max_lon - area.min_lon).abs() < eps && west.min_lat == area.min_lat);
+    assert!(north_east.min_lat == north.min_lat && north_east.min_lon == east.min_lon);
+    assert!(south_east.min_lat == south.min_lat && south_east.min_lon == east.min_lon);
+    assert!(north_west.min_lat == north.min_lat && north_west.min_lon == west.min_lon);
+    assert!(south_west.min_lat == south.min_lat && south_west.min_lon == west.min_lon);
+
+    // Cells are wrapped around the antimeridian.
+    let hash = GeoHash::encode(179.9, 0.0, 8).unwrap();
+    let [_, _, east, ..] = hash.neighbors().map(GeoHash::decode);
+    assert_eq!(east.min_lon, GEO_LONG_MIN);
+}
+
+#[test]
+fn search_areas() {
+    assert_eq!(GeoHash::estimate_steps(0.0, 0.0), GEO_STEP_MAX);
+    assert_eq!(GeoHash::estimate_steps(100_000.0, 0.0), 7);
+    assert_eq!(GeoHash::estimate_steps(100_000.0, 70.0), 6);
+    assert_eq!(GeoHash::estimate_steps(100_000.0, -85.0), 5);
+    assert_eq!(GeoHash::estimate_steps(1.0, 0.0), 24);
+
+    let bbox = GeoArea::bounding_box(15.0, 37.0, GeoShape::Radius(200_000.0));
+    assert!((geo_distance(15.0, 37.0, 15.0, bbox.max_lat) - 200_000.0).abs() < 1.0);
+    assert!(geo_distance(15.0, 37.0, bbox.min_lon, 37.0) > 200_000.0);
+    let bbox = GeoArea::bounding_box(15.0, 37.0, GeoShape::Box(400_000.0, 100_000.0));
+    assert!((geo_distance(15.0, 37.0, 15.0, bbox.min_lat) - 50_000.0).abs() < 1.0);
+
+    // Cells searched cover ALL points in the shape.
+    for &(lon, lat, shape) in &[
+        (15.0, 37.0, GeoShape::Radius(200_000.0)),
+        (-0.1, 51.5, GeoShape::Radius(1_000.0)),
+        (179.99, 0.0, GeoShape::Radius(50_000.0)),
+        (15.0, 37.0, GeoShape::Box(400_000.0, 100_000.0)),
+    ] {
+        let areas = GeoHash::search_areas(lon, lat, shape).unwrap();
+        assert!(areas.len() <= 9);
+        for i in 0..100 {
+            let angle = i as f64 * std::f64::consts::PI / 50.0;
+            let (dlon, dlat) = (angle.cos() * 0.99, angle.sin() * 0.99);
+            let bbox = GeoArea::bounding_box(lon, lat, shape);
+            let mut point_lon = lon + dlon * (bbox.max_lon - lon);
+            if point_lon > 180.0 {
+                point_lon -= 360.0;
+            }
+            let point_lat = lat + dlat * (bbox.max_lat - lat);
+            if shape
+                .distance_within((lon, lat), (point_lon, point_lat))
+                .is_none()
+            {
+                continue;
+            }
+            let score = GeoHash::encode(point_lon, point_lat, GEO_STEP_MAX)
+                .unwrap()
+                .to_score();
+            assert!(areas.iter().any(|area| {
+                let (min, max) = area.score_range();
+                min <= score && score < max
+            }));
+        }
+    }
+    assert_eq!(
+        GeoHash::search_areas(0.0, 89.0, GeoShape::Radius(1.0)),
+        None
+    );
+}
+
+#[test]
+fn distances_within_shapes() {
+    let palermo = (13.361389, 38.115556);
+    let catania = (15.087269, 37.502669);
+    let distance = geo_distance(palermo.0, palermo.1, catania.0, catania.1);
+    assert!((distance - 166274.15).abs() < 1.0);
+
+    let center = (15.0, 37.0);
+    let within = GeoShape::Radius(100_000.0)
+        .distance_within(center, catania)
+        .unwrap();
+    assert!((within - 56441.3).abs() < 1.0);
+    assert_eq!(
+        GeoShape::Radius(100_000.0).distance_within(center, palermo),
+        None
+    );
+    assert!(GeoShape::Box(400_000.0, 400_000.0)
+        .distance_within(center, palermo)
+        .is_some());
+    assert_eq!(
+        GeoShape::Box(400_000.0, 200_000.0).distance_within(center, palermo),
+        None
+    );
+    assert_eq!(
+        GeoShape::Box(200_000.0, 400_000.0).distance_within(center, palermo),
+        None
+    );
+}
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use rtypes::{Encoding, EncodingLimits, RHash};
+
+fn fields(hash: &RHash) -> HashMap<Vec<u8>, Vec<u8>> {
+    hash.iter()
+        .map(|(field, value)| (field.to_rstring().to_bytes(), value.to_rstring().to_bytes()))
+        .collect()
+}
+
+fn limits(max_entries: usize, max_value: usize) -> EncodingLimits {
+    EncodingLimits {
+        hash_max_listpack_entries: max_entries,
+        hash_max_listpack_value: max_value,
+        ..EncodingLimits::default()
+    }
+}
+
+#[test]
+fn convert_encodings() {
+    // listpack -> hashtable, with too many fields.
+    let mut hash = RHash::new();
+    for i in 0..128 {
+        assert!(hash.set(format!("f{}", i).as_bytes(), i.to_string().as_bytes()));
+    }
+    assert_eq!(hash.encoding(), Encoding::ListPack);
+    assert!(!hash.set(b"f0", b"zero"));
+    assert_eq!(hash.encoding(), Encoding::ListPack);
+    assert!(hash.set(b"f128", b"128"));
+    assert_eq!(hash.encoding(), Encoding::HashTable);
+    assert_eq!(hash.len(), 129);
+    assert_eq!(hash.get(b"f0").unwrap().to_rstring().to_bytes(), b"zero");
+    assert_eq!(hash.get(b"f127").unwrap().as_i64(), Some(127));
+
+    // listpack -> hashtable, with a long field or value, under limits given.
+    let mut hash = RHash::with_limits(&limits(4, 8));
+    hash.set(b"a", b"1");
+    assert!(hash.set(b"b", b"12345678"));
+    assert_eq!(hash.encoding(), Encoding::ListPack);
+    assert!(!hash.set(b"b", b"123456789"));
+    assert_eq!(hash.encoding(), Encoding::HashTable);
+    let expected: HashMap<_, _> = vec![
+        (b"a".to_vec(), b"1".to_vec()),
+        (b"b".to_vec(), b"123456789".to_vec()),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(fields(&hash), expected);
+
+    let mut hash = RHash::with_limits(&limits(4, 8));
+    hash.set(b"long-field", b"1");
+    assert_eq!(hash.encoding(), Encoding::HashTable);
+
+    // Hashes are NEVER converted back.
+    let mut hash = RHash::with_limits(&limits(1, 8));
+    hash.set(b"a", b"1");
+    hash.set(b"b", b"2");
+    assert_eq!(hash.encoding(), Encoding::HashTable);
+    assert!(hash.remove(b"b"));
+    assert_eq!(hash.encoding(), Encoding::HashTable);
+}
+
+#[test]
+fn remove_and_random() {
+    for &max_entries in &[128, 0] {
+        let mut hash = RHash::with_limits(&limits(max_entries, 64));
+        assert!(hash.random_field().is_none());
+        for i in 0..10 {
+            hash.set(format!("f{}", i).as_bytes(), format!("v{}", i).as_bytes());
+        }
+        assert!(hash.remove(b"f3"));
+        assert!(!hash.remove(b"f3"));
+        assert!(!hash.remove(b"v4"));
+        assert!(!hash.contains(b"f3") && hash.contains(b"f4"));
+        assert_eq!(hash.len(), 9);
+        assert_eq!(hash.iter().len(), 9);
+
+        let all = fields(&hash);
+        for _ in 0..50 {
+            let (field, value) = hash.random_field().unwrap();
+            let field = field.to_rstring().to_bytes();
+            assert_eq!(all.get(&field), Some(&value.to_rstring().to_bytes()));
+        }
+        for i in 0..10 {
+            hash.remove(format!("f{}", i).as_bytes());
+        }
+        assert!(hash.is_empty());
+    }
+}
+
+#[test]
+fn expire_fields() {
+    let mut hash = RHash::new();
+    hash.set(b"a", b"1");
+    hash.set(b"b", b"2");
+    hash.set(b"c", b"3");
+    assert!(!hash.set_expire_at(b"x", 100));
+    assert!(hash.set_expire_at(b"a", 100));
+    assert!(hash.set_expire_at(b"b", 200));
+    assert!(hash.set_expire_at(b"c", 300));
+    assert_eq!(hash.expire_at(b"a"), Some(100));
+
+    // Expiration times are cleared once fields are set, or persisted.
+    hash.set(b"b", b"22");
+    assert_eq!(hash.expire_at(b"b"), None);
+    assert!(hash.persist(b"c"));
+    assert!(!hash.persist(b"c"));
+
+    assert_eq!(hash.remove_expired(99), 0);
+    assert_eq!(hash.remove_expired(1000), 1);
+    assert_eq!(hash.len(), 2);
+    assert!(!hash.contains(b"a"));
+
+    // Expiration times are cleared once fields are removed.
+    hash.set_expire_at(b"b", 100);
+    hash.remove(b"b");
+    hash.set(b"b", b"2");
+    assert_eq!(hash.remove_expired(1000), 0);
+    assert_eq!(hash.len(), 2);
+}
+
+#[test]
+fn limit_boundaries() {
+    let mut hash = RHash::with_limits(&limits(3, 5));
+    for field in [&b"aaaaa"[..], b"b", b"c"].iter() {
+        assert!(hash.set(field, b"vvvvv"));
+    }
+    assert_eq!(hash.encoding(), Encoding::ListPack);
+    assert!(hash.set(b"d", b"4"));
+    assert_eq!(hash.encoding(), Encoding::HashTable);
+
+    let mut hash = RHash::with_limits(&limits(3, 5));
+    hash.set(b"a", b"vvvvvv");
+    assert_eq!(hash.encoding(), Encoding::HashTable);
+    let mut hash = RHash::with_limits(&limits(3, 5));
+    hash.set(b"aaaaaa", b"v");
+    assert_eq!(hash.encoding(), Encoding::HashTable);
+
+    let mut hash = RHash::with_limits(&limits(0, 64));
+    hash.set(b"a", b"1");
+    assert_eq!(hash.encoding(), Encoding::HashTable);
+}
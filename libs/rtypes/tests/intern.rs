@@ -0,0 +1,58 @@
+use std::thread;
+
+use rtypes::{shared_integer, shared_objects, InternPool, SharedRStr, SHARED_INTEGERS};
+
+#[test]
+fn share_integers() {
+    let zero = shared_integer(0).unwrap();
+    assert_eq!(zero.as_bytes(), b"0");
+    assert!(SharedRStr::ptr_eq(&zero, &SharedRStr::from_i64(0)));
+
+    let max = SHARED_INTEGERS as i64 - 1;
+    assert_eq!(shared_integer(max).unwrap().parse_i64(), Ok(max));
+    assert_eq!(shared_integer(max + 1), None);
+    assert_eq!(shared_integer(-1), None);
+
+    let large = SharedRStr::from_i64(max + 1);
+    assert!(large.is_unique());
+    assert_eq!(large.parse_i64(), Ok(max + 1));
+    assert_eq!(SharedRStr::from_i64(-5).as_bytes(), b"-5");
+
+    // Shared by ALL threads.
+    let ptr = shared_objects().integer(42).unwrap().as_ptr();
+    let other = thread::spawn(|| shared_objects().integer(42).unwrap().as_ptr() as usize);
+    assert_eq!(other.join().unwrap(), ptr as usize);
+}
+
+#[test]
+fn share_replies() {
+    let shared = shared_objects();
+    assert_eq!(shared.ok.as_bytes(), b"+OK\r\n");
+    assert_eq!(shared.null_bulk.as_bytes(), b"$-1\r\n");
+    assert_eq!(shared.czero.as_bytes(), b":0\r\n");
+    assert_eq!(shared.empty_array.as_bytes(), b"*0\r\n");
+    assert!(SharedRStr::ptr_eq(&shared.pong, &shared_objects().pong));
+}
+
+#[test]
+fn intern_strings() {
+    let mut pool = InternPool::new();
+    let name = pool.intern(b"name");
+    let age = pool.intern(b"age");
+    assert!(SharedRStr::ptr_eq(&pool.intern(b"name"), &name));
+    assert!(!SharedRStr::ptr_eq(&name, &age));
+    assert_eq!(pool.len(), 2);
+    assert_eq!(name.ref_count(), 2);
+
+    assert_eq!(pool.get(b"age").as_ref(), Some(&age));
+    assert_eq!(pool.get(b"city"), None);
+
+    drop(age);
+    assert_eq!(pool.purge(), 1);
+    assert_eq!(pool.get(b"age"), None);
+    assert_eq!(pool.len(), 1);
+
+    pool.clear();
+    assert!(pool.is_empty());
+    assert!(name.is_unique());
+}
@@ -0,0 +1,74 @@
+use rtypes::{MemSized, RIntSet, RString};
+
+#[test]
+fn insert_and_upgrade() {
+    let mut set = RIntSet::new();
+    for value in [3, -1, 2, 100, -100].iter() {
+        assert!(set.insert(*value));
+    }
+    assert!(!set.insert(2));
+    assert_eq!(set.iter().collect::<Vec<_>>(), vec![-100, -1, 2, 3, 100]);
+    assert_eq!(set.bytes_len(), 8 + 5 * 2);
+
+    // Values beyond the width are NEVER found before upgraded.
+    assert!(!set.contains(1 << 20) && !set.contains(-(1 << 20)));
+    assert!(set.insert(-(1 << 20)));
+    assert_eq!(set.bytes_len(), 8 + 6 * 4);
+    assert!(set.insert(i64::MAX));
+    assert!(set.insert(i64::MIN));
+    assert_eq!(set.bytes_len(), 8 + 8 * 8);
+    assert_eq!((set.first(), set.last()), (Some(i64::MIN), Some(i64::MAX)));
+    assert_eq!(
+        set.iter().rev().collect::<Vec<_>>(),
+        vec![i64::MAX, 100, 3, 2, -1, -100, -(1 << 20), i64::MIN]
+    );
+}
+
+#[test]
+fn remove_values() {
+    let mut set = RIntSet::new();
+    for value in 0..100 {
+        set.insert(value * 1000);
+    }
+    assert_eq!(set.len(), 100);
+    assert!(set.contains(99000));
+    assert!(set.remove(99000));
+    assert!(!set.remove(99000));
+    assert!(!set.remove(i64::MAX));
+    assert_eq!(set.remove_at(0), Some(0));
+    assert_eq!(set.remove_at(98), None);
+    assert_eq!(set.get(0), Some(1000));
+    assert_eq!(set.len(), 98);
+    // NEVER downgraded.
+    assert_eq!(set.bytes_len(), 8 + 98 * 4);
+
+    let set: RIntSet = {
+        let mut set = RIntSet::new();
+        set.insert(2);
+        set.insert(1);
+        set
+    };
+    assert_eq!(format!("{:?}", set), "{1, 2}");
+}
+
+#[test]
+fn resize_exactly() {
+    let mut set = RIntSet::new();
+    assert_eq!(set.as_bytes(), [2, 0, 0, 0, 0, 0, 0, 0]);
+    set.insert(1);
+    set.insert(-2);
+    assert_eq!(set.as_bytes(), [2, 0, 0, 0, 2, 0, 0, 0, 0xfe, 0xff, 1, 0]);
+
+    for value in 0..200 {
+        set.insert(value * 100);
+    }
+    let full = set.heap_size();
+    assert!(full <= RString::from_bytes(set.as_bytes()).heap_size());
+    for value in 0..150 {
+        assert!(set.remove(value * 100));
+    }
+    assert_eq!(set.bytes_len(), 8 + 52 * 2);
+    // Memory blocks shrunk a little MAYBE kept in place by the allocator.
+    assert!(set.heap_size() < full / 2);
+    assert!(set.heap_size() < RString::from_bytes(set.as_bytes()).heap_size() + 32);
+}
@@ -0,0 +1,184 @@
+use rtypes::{ListPackValue, RListPack, RString};
+
+fn entries(lp: &RListPack) -> Vec<String> {
+    lp.iter()
+        .map(|value| value.with_bytes(|bytes| String::from_utf8_lossy(bytes).into_owned()))
+        .collect()
+}
+
+#[test]
+fn encode_entries() {
+    let mut lp = RListPack::new();
+    assert_eq!(lp.as_bytes(), b"\x07\x00\x00\x00\x00\x00\xff");
+    lp.push_back(b"hello");
+    lp.push_back(b"1024");
+    // The same layout as Redis.
+    assert_eq!(
+        lp.as_bytes(),
+        &b"\x11\x00\x00\x00\x02\x00\x85hello\x06\xc4\x00\x02\xff"[..]
+    );
+    assert_eq!(lp.bytes_len(), 17);
+
+    let ints = [
+        0,
+        127,
+        128,
+        -1,
+        -4096,
+        4095,
+        4096,
+        i16::MIN as i64,
+        i16::MAX as i64 + 1,
+        -(1 << 23),
+        (1 << 23) - 1,
+        i32::MIN as i64,
+        i32::MAX as i64 + 1,
+        i64::MIN,
+        i64::MAX,
+    ];
+    let mut lp = RListPack::new();
+    for value in ints.iter() {
+        lp.push_back(value.to_string().as_bytes());
+    }
+    let decoded: Vec<_> = lp.iter().map(|value| value.as_i64().unwrap()).collect();
+    assert_eq!(decoded, ints);
+    assert!(lp
+        .iter()
+        .all(|value| matches!(value, ListPackValue::Int(_))));
+    // 2 * 2 + 4 * 3 + 2 * 4 + 3 * 5 + 6 + 3 * 10 bytes of entries.
+    assert_eq!(lp.bytes_len(), 6 + 75 + 1);
+
+    // Strings NOT in the canonical form of integers.
+    let mut lp = RListPack::new();
+    for data in ["", "007", "+1", "1 ", "9223372036854775808"].iter() {
+        lp.push_back(data.as_bytes());
+    }
+    assert!(lp
+        .iter()
+        .all(|value| matches!(value, ListPackValue::Str(_))));
+    assert_eq!(
+        entries(&lp),
+        vec!["", "007", "+1", "1 ", "9223372036854775808"]
+    );
+
+    let mut lp = RListPack::new();
+    for &len in [63, 64, 127, 128, 4095, 4096, 20000].iter() {
+        lp.push_front(&vec![b'x'; len]);
+    }
+    let lens: Vec<_> = lp
+        .iter()
+        .rev()
+        .map(|value| value.with_bytes(|bytes| bytes.len()))
+        .collect();
+    assert_eq!(lens, vec![63, 64, 127, 128, 4095, 4096, 20000]);
+
+    // Backlens of multiple bytes, with the high bit set on ALL but the first byte.
+    let mut lp = RListPack::new();
+    lp.push_back(&[b'x'; 127]);
+    let bytes = lp.as_bytes();
+    assert_eq!(bytes[6..8], [0xe0, 0x7f]);
+    assert_eq!(bytes[bytes.len() - 3..], [0x01, 0x81, 0xff]);
+}
+
+#[test]
+fn insert_remove_replace() {
+    let mut lp = RListPack::new();
+    for i in 0..100 {
+        lp.push_back(format!("v{}", i).as_bytes());
+    }
+    lp.insert(0, b"head");
+    lp.insert(50, b"50");
+    lp.insert(102, b"tail");
+    assert_eq!(lp.len(), 103);
+    assert_eq!(lp.get(0), Some(ListPackValue::Str(b"head")));
+    assert_eq!(lp.get(50), Some(ListPackValue::Int(50)));
+    assert_eq!(lp.get(51), Some(ListPackValue::Str(b"v49")));
+    assert_eq!(lp.get(102), Some(ListPackValue::Str(b"tail")));
+    assert_eq!(lp.get(103), None);
+    assert_eq!(lp.find(b"50"), Some(50));
+    assert_eq!(lp.find(b"v99"), Some(101));
+    assert_eq!(lp.find(b"v100"), None);
+
+    // Replaced with entries of other sizes.
+    assert!(lp.replace(50, &[b'y'; 300]));
+    assert!(lp.replace(0, b"-5"));
+    assert!(lp.replace(102, b""));
+    assert!(!lp.replace(103, b""));
+    assert_eq!(lp.get(0).unwrap().as_i64(), Some(-5));
+    assert_eq!(
+        lp.get(50).unwrap().to_rstring(),
+        RString::from_bytes(&[b'y'; 300])
+    );
+    assert_eq!(lp.get(101), Some(ListPackValue::Str(b"v99")));
+
+    assert_eq!(lp.remove(50).unwrap().len(), 300);
+    assert_eq!(lp.remove(101), Some(RString::new()));
+    assert_eq!(lp.remove(101), None);
+    assert_eq!(lp.pop_front(), Some(RString::from_str("-5")));
+    assert_eq!(lp.pop_back(), Some(RString::from_str("v99")));
+    let expected: Vec<_> = (0..99).map(|i| format!("v{}", i)).collect();
+    assert_eq!(entries(&lp), expected);
+    assert_eq!(RListPack::from_bytes(lp.as_bytes()).unwrap().len(), 99);
+
+    assert_eq!(lp.remove_range(10, 20), 20);
+    assert_eq!(lp.get(10), Some(ListPackValue::Str(b"v30")));
+    assert_eq!(lp.remove_range(70, 100), 9);
+    assert_eq!(lp.remove_range(70, 1), 0);
+    assert_eq!(lp.len(), 70);
+
+    while lp.pop_back().is_some() {}
+    assert!(lp.is_empty());
+    assert_eq!(lp.as_bytes(), RListPack::new().as_bytes());
+}
+
+#[test]
+#[should_panic(expected = "out of range")]
+fn insert_out_of_range() {
+    RListPack::new().insert(1, b"x");
+}
+
+#[test]
+fn validate_bytes() {
+    let mut lp = RListPack::new();
+    lp.push_back(b"hello");
+    lp.push_back(b"-100000");
+    let bytes = lp.as_bytes().to_vec();
+    assert_eq!(
+        entries(&RListPack::from_bytes(&bytes).unwrap()),
+        vec!["hello", "-100000"]
+    );
+
+    // Truncated, or with the header, an encoding or a backlen corrupted.
+    assert!(RListPack::from_bytes(&bytes[..bytes.len() - 1]).is_none());
+    for &(pos, byte) in [(0, 0x10), (4, 3), (6, 0x86), (6, 0xfe), (12, 0x07)].iter() {
+        let mut corrupted = bytes.clone();
+        corrupted[pos] = byte;
+        assert!(RListPack::from_bytes(&corrupted).is_none(), "{}", pos);
+    }
+
+    // Too many entries to count in the header.
+    let mut unknown = bytes.clone();
+    unknown[4..6].copy_from_slice(&[0xff, 0xff]);
+    let mut lp = RListPack::from_bytes(&unknown).unwrap();
+    assert_eq!(lp.len(), 2);
+    assert_eq!(lp.iter().len(), 2);
+    lp.push_front(b"x");
+    assert_eq!(lp.as_bytes()[4..6], [3, 0]);
+}
+
+#[test]
+fn debug_values() {
+    assert_eq!(format!("{:?}", ListPackValue::Int(-42)), "-42");
+    assert_eq!(
+        format!("{:?}", ListPackValue::Str(b"say \"hi\"\n\xff")),
+        r#""say \"hi\"\n\xff""#
+    );
+
+    let mut lp = RListPack::new();
+    lp.push_back(b"a");
+    lp.push_back(b"7");
+    assert_eq!(
+        format!("{:?}", lp.iter().collect::<Vec<_>>()),
+        r#"["a", 7]"#
+    );
+}
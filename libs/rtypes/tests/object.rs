@@ -0,0 +1,175 @@
+use rtypes::{
+    lru_clock, Encoding, ObjectType, RHash, RObject, RQuickList, RSet, RStream, RString, RZSet,
+};
+use rtypes::{LFU_INIT_VAL, LRU_CLOCK_MAX, OBJ_SHARED_REFCOUNT};
+
+#[test]
+fn types_and_encodings() {
+    let objects = [
+        (RObject::from_i64(-1), ObjectType::String, "int"),
+        (
+            RObject::from_bytes(&[b'x'; 44]),
+            ObjectType::String,
+            "embstr",
+        ),
+        (RObject::from_bytes(&[b'x'; 45]), ObjectType::String, "raw"),
+        (
+            RObject::from(RString::from_str("1")),
+            ObjectType::String,
+            "raw",
+        ),
+        (
+            RObject::from(RQuickList::new()),
+            ObjectType::List,
+            "quicklist",
+        ),
+        (RObject::from(RSet::new()), ObjectType::Set, "intset"),
+        (RObject::from(RZSet::new()), ObjectType::ZSet, "listpack"),
+        (RObject::from(RHash::new()), ObjectType::Hash, "listpack"),
+        (RObject::from(RStream::new()), ObjectType::Stream, "stream"),
+    ];
+    for (object, ty, encoding) in &objects {
+        assert_eq!(object.object_type(), *ty);
+        assert_eq!(object.encoding().to_string(), *encoding);
+    }
+    assert_eq!(ObjectType::ZSet.to_string(), "zset");
+
+    // Encodings of collections follow their own.
+    let mut object = RObject::from(RSet::new());
+    object.as_set_mut().unwrap().add(b"member");
+    assert_eq!(object.encoding(), Encoding::ListPack);
+    assert!(object.as_hash().is_none() && object.as_rstring().is_none());
+    assert_eq!(object.to_rstring(), None);
+    assert_eq!(object.string_mut(), None);
+    assert!(!object.try_encoding_downgrade());
+}
+
+#[test]
+fn downgrade_strings() {
+    for &(bytes, encoding) in &[
+        (&b"0"[..], Encoding::Int),
+        (b"-9223372036854775808", Encoding::Int),
+        (b"9223372036854775808", Encoding::EmbStr),
+        (b"012", Encoding::EmbStr),
+        (b"+1", Encoding::EmbStr),
+        (b"", Encoding::EmbStr),
+    ] {
+        let mut object = RObject::from(RString::from_bytes(bytes));
+        assert!(object.try_encoding_downgrade());
+        assert_eq!(object.encoding(), encoding);
+        assert_eq!(object.to_rstring().unwrap().as_bytes(), bytes);
+    }
+
+    let mut object = RObject::from_bytes(b"hello");
+    assert!(!object.try_encoding_downgrade());
+    assert_eq!(object.encoding(), Encoding::EmbStr);
+
+    // Raw strings short enough are allocated again with NO spare capacity.
+    let mut s = RString::with_capacity(1000);
+    s.append_str("hello");
+    let mut object = RObject::from(s);
+    assert!(object.try_encoding_downgrade());
+    assert_eq!(object.encoding(), Encoding::EmbStr);
+    assert!(object.as_rstring().unwrap().capacity() < 5 + 16);
+
+    // Raw strings too long are shrunk ONLY.
+    for &(len, capacity) in &[(100, 1000), (600, 1000)] {
+        let mut s = RString::with_capacity(capacity);
+        s.append_bytes(&vec![b'x'; len]);
+        let mut object = RObject::from(s);
+        assert!(!object.try_encoding_downgrade());
+        assert_eq!(object.encoding(), Encoding::Raw);
+        let s = object.as_rstring().unwrap();
+        assert_eq!(s.len(), len);
+        assert!(s.capacity() < len + 16);
+    }
+
+    // Raw strings with little spare capacity are left as is.
+    let mut s = RString::with_capacity(1000);
+    s.append_bytes(&[b'x'; 950]);
+    let capacity = s.capacity();
+    let mut object = RObject::from(s);
+    assert!(!object.try_encoding_downgrade());
+    assert_eq!(object.as_rstring().unwrap().capacity(), capacity);
+
+    // Objects referenced elsewhere are NOT converted.
+    let mut object = RObject::from(RString::from_str("1"));
+    object.incr_ref_count();
+    assert!(!object.try_encoding_downgrade());
+    assert!(!object.decr_ref_count());
+    assert!(object.try_encoding_downgrade());
+    assert_eq!(object.as_int(), Some(1));
+
+    // Strings modified are in raw.
+    let s = object.string_mut().unwrap();
+    assert_eq!(s.incr_by(41), Ok(42));
+    assert_eq!(object.encoding(), Encoding::Raw);
+    let mut object = RObject::from_bytes(b"foo");
+    object.string_mut().unwrap().append_str("bar");
+    assert_eq!(object.as_rstring().unwrap().as_bytes(), b"foobar");
+    assert_eq!(object.as_int(), None);
+}
+
+#[test]
+fn ref_counts() {
+    let mut object = RObject::from_i64(7);
+    assert_eq!(object.ref_count(), 1);
+    object.incr_ref_count();
+    assert_eq!(object.ref_count(), 2);
+    assert!(!object.decr_ref_count());
+    assert!(object.decr_ref_count());
+
+    object.make_shared();
+    assert!(object.is_shared());
+    object.incr_ref_count();
+    assert!(!object.decr_ref_count());
+    assert_eq!(object.ref_count(), OBJ_SHARED_REFCOUNT);
+    assert!(!object.try_encoding_downgrade());
+}
+
+#[test]
+#[should_panic]
+fn modify_shared() {
+    let mut object = RObject::from_bytes(b"shared");
+    object.make_shared();
+    object.string_mut();
+}
+
+#[test]
+fn lru_and_lfu() {
+    let mut object = RObject::from_bytes(b"value");
+    let now = 1_700_000_000_000;
+    object.touch_lru(now);
+    assert_eq!(object.lru(), lru_clock(now));
+    assert_eq!(object.idle_time(now + 2500), 2000);
+    object.set_lru(LRU_CLOCK_MAX);
+    assert_eq!(object.lru(), LRU_CLOCK_MAX);
+    // The clock wraps around.
+    assert_eq!(
+        object.idle_time(now - lru_clock(now) as u64 * 1000 + 3000),
+        3000
+    );
+    object.set_lru(u32::MAX);
+    assert_eq!(object.lru(), LRU_CLOCK_MAX);
+
+    object.init_lfu(now);
+    let minutes = (now / 60_000) as u16;
+    assert_eq!(object.lfu(), (minutes, LFU_INIT_VAL));
+    assert_eq!(object.lfu_counter(now + 3 * 60_000, 1), LFU_INIT_VAL - 3);
+    assert_eq!(object.lfu_counter(now + 3 * 60_000, 0), LFU_INIT_VAL);
+    assert_eq!(object.lfu_counter(now + 100 * 60_000, 1), 0);
+
+    // Counters below the initial value are ALWAYS increased.
+    object.touch_lfu(now + 3 * 60_000, 10, 1);
+    assert_eq!(object.lfu(), (minutes + 3, LFU_INIT_VAL - 2));
+    for _ in 0..100 {
+        object.touch_lfu(now + 3 * 60_000, 0, 1);
+    }
+    assert_eq!(object.lfu().1, LFU_INIT_VAL + 98);
+    // Increased with a probability of 1/99 at first, i.e. ~50 times in 10000 accesses.
+    for _ in 0..10000 {
+        object.touch_lfu(now + 3 * 60_000, 1, 1);
+    }
+    let counter = object.lfu().1;
+    assert!(counter > LFU_INIT_VAL + 98 && counter < u8::MAX);
+}
@@ -0,0 +1,235 @@
+use rtypes::{EncodingLimits, RList, RQuickList, QUICKLIST_MAX_FILL};
+
+fn entries(list: &RQuickList) -> Vec<Vec<u8>> {
+    list.iter().map(|data| data.to_bytes()).collect()
+}
+
+#[test]
+fn push_and_pop() {
+    let mut list = RQuickList::with_fill(3);
+    assert_eq!((list.pop_front(), list.pop_back()), (None, None));
+
+    for i in 0..10 {
+        list.push_back(format!("v{}", i).as_bytes());
+    }
+    list.push_front(b"");
+    assert_eq!(list.len(), 11);
+    assert_eq!(list.node_count(), 5);
+    assert_eq!(list.get(0).unwrap().as_bytes(), b"");
+    assert_eq!(list.get(10).unwrap().as_bytes(), b"v9");
+    assert!(list.get(11).is_none());
+    for i in 0..10 {
+        assert_eq!(
+            list.get(i + 1).unwrap().to_bytes(),
+            format!("v{}", i).into_bytes()
+        );
+    }
+
+    assert_eq!(list.pop_front().unwrap().as_bytes(), b"");
+    assert_eq!(list.pop_front().unwrap().as_bytes(), b"v0");
+    assert_eq!(list.pop_back().unwrap().as_bytes(), b"v9");
+    assert_eq!(list.len(), 8);
+    assert_eq!(list.iter().next_back().unwrap().as_bytes(), b"v8");
+    while list.pop_back().is_some() {}
+    assert!(list.is_empty());
+    assert_eq!(list.node_count(), 0);
+}
+
+#[test]
+fn fill_limits() {
+    assert_eq!(RQuickList::new().fill(), -2);
+    assert_eq!(RQuickList::with_fill(0).fill(), 1);
+    assert_eq!(RQuickList::with_fill(-100).fill(), -5);
+    assert_eq!(RQuickList::with_fill(isize::MAX).fill(), QUICKLIST_MAX_FILL);
+    let limits = EncodingLimits {
+        list_max_listpack_size: 3,
+        ..EncodingLimits::default()
+    };
+    assert_eq!(
+        RQuickList::with_limits(&EncodingLimits::default()).fill(),
+        -2
+    );
+
+    // Nodes hold at most 3 entries.
+    let mut list = RQuickList::with_limits(&limits);
+    for i in 0..7 {
+        list.push_back(i.to_string().as_bytes());
+    }
+    assert_eq!(list.node_count(), 3);
+    list.pop_back();
+    assert_eq!(list.node_count(), 2);
+
+    // 4KB per node, that each node holds 3 entries of 1KB.
+    let mut list = RQuickList::with_fill(-1);
+    let value = vec![b'x'; 1024];
+    for _ in 0..9 {
+        list.push_front(&value);
+    }
+    assert_eq!(list.node_count(), 3);
+
+    // Huge entries are NOT packed with a positive fill.
+    let mut list = RQuickList::with_fill(100);
+    let value = vec![b'x'; 5000];
+    list.push_back(&value);
+    list.push_back(&value);
+    list.push_back(b"small");
+    assert_eq!(list.node_count(), 2);
+    assert_eq!(list.get(1).unwrap().len(), 5000);
+}
+
+#[test]
+fn iterate_and_convert() {
+    let words: RList<&str> = vec!["a", "bb", "", "dddd", "long entry over 127 bytes"]
+        .into_iter()
+        .collect();
+    let mut list = RQuickList::from(&words);
+    list.push_back(&[b'z'; 300]);
+    assert_eq!(list.node_count(), 1);
+
+    let mut expected: Vec<Vec<u8>> = words.iter().map(|w| w.as_bytes().to_vec()).collect();
+    expected.push(vec![b'z'; 300]);
+    assert_eq!(entries(&list), expected);
+
+    let mut iter = list.iter();
+    assert_eq!(iter.len(), 6);
+    assert_eq!(iter.next_back().unwrap().len(), 300);
+    assert_eq!(iter.next().unwrap().as_bytes(), b"a");
+    assert_eq!(
+        iter.rev().map(|data| data.len()).collect::<Vec<_>>(),
+        vec![25, 4, 0, 2]
+    );
+
+    let lens = list.to_rlist_with(|data| data.len());
+    assert_eq!(lens.to_vec(), vec![1, 2, 0, 4, 25, 300]);
+
+    let mut count = 0;
+    for _ in &list {
+        count += 1;
+    }
+    assert_eq!(count, 6);
+    list.clear();
+    assert_eq!((list.len(), list.node_count()), (0, 0));
+}
+
+#[test]
+fn iterate_from_both_ends() {
+    let mut list = RQuickList::with_fill(2);
+    for i in 0..7u8 {
+        list.push_back(&[i]);
+    }
+    let mut iter = list.iter();
+    let mut seen = Vec::new();
+    while let (Some(front), back) = (iter.next(), iter.next_back()) {
+        seen.push(front.as_bytes()[0]);
+        if let Some(back) = back {
+            seen.push(back.as_bytes()[0]);
+        }
+    }
+    assert_eq!(seen, vec![0, 6, 1, 5, 2, 4, 3]);
+    assert!(format!("{:?}", list).contains("nodes: 4"));
+}
+
+fn entry(i: usize) -> Vec<u8> {
+    format!("entry-{:0>60}", i).into_bytes()
+}
+
+#[test]
+fn compress_interior_nodes() {
+    let mut list = RQuickList::with_options(4, 2);
+    assert_eq!(list.compress_depth(), 2);
+    for i in 0..40 {
+        list.push_back(&entry(i));
+    }
+    assert_eq!(list.node_count(), 10);
+    assert_eq!(list.compressed_node_count(), 6);
+
+    // Access is transparent, from both ends & the interior.
+    for i in 0..40 {
+        assert_eq!(list.get(i).unwrap().to_bytes(), entry(i));
+    }
+    assert_eq!(entries(&list), (0..40).map(entry).collect::<Vec<_>>());
+    assert_eq!(
+        list.iter()
+            .rev()
+            .map(|data| data.to_bytes())
+            .collect::<Vec<_>>(),
+        (0..40).rev().map(entry).collect::<Vec<_>>()
+    );
+    assert_eq!(list.to_rlist_with(|data| data.len()).len(), 40);
+
+    // Nodes reaching the ends are decompressed.
+    for i in 0..12 {
+        assert_eq!(list.pop_front().unwrap().to_bytes(), entry(i));
+    }
+    assert_eq!((list.node_count(), list.compressed_node_count()), (7, 3));
+    for i in (28..40).rev() {
+        assert_eq!(list.pop_back().unwrap().to_bytes(), entry(i));
+    }
+    assert_eq!((list.node_count(), list.compressed_node_count()), (4, 0));
+
+    // Nodes leaving the ends are compressed.
+    for i in (0..12).rev() {
+        list.push_front(&entry(i));
+    }
+    assert_eq!((list.node_count(), list.compressed_node_count()), (7, 3));
+    assert_eq!(entries(&list), (0..28).map(entry).collect::<Vec<_>>());
+    while list.pop_back().is_some() {}
+    assert_eq!((list.node_count(), list.compressed_node_count()), (0, 0));
+}
+
+#[test]
+fn skip_small_or_incompressible_nodes() {
+    let mut list = RQuickList::with_options(2, 1);
+    for i in 0..8u8 {
+        list.push_back(&[i]);
+    }
+    assert_eq!(list.compressed_node_count(), 0);
+
+    let mut list = RQuickList::with_options(1, 1);
+    let mut seed = 0x2545f491u32;
+    let mut random = || {
+        (0..64)
+            .map(|_| {
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+                seed as u8
+            })
+            .collect::<Vec<_>>()
+    };
+    for _ in 0..3 {
+        list.push_back(&random());
+    }
+    list.push_back(&[b'a'; 64]);
+    assert_eq!((list.node_count(), list.compressed_node_count()), (4, 0));
+    list.push_back(&random());
+    assert_eq!((list.node_count(), list.compressed_node_count()), (5, 1));
+    assert_eq!(list.get(3).unwrap().to_bytes(), vec![b'a'; 64]);
+    assert!(format!("{:?}", list).contains("compress_depth: 1"));
+}
+
+#[test]
+fn clear_and_send_across_threads() {
+    let mut list = RQuickList::with_options(2, 1);
+    for i in 0..10 {
+        list.push_front(&entry(i));
+    }
+    assert_eq!(list.node_count(), 5);
+    list.clear();
+    assert_eq!((list.len(), list.node_count()), (0, 0));
+    assert!(list.iter().next().is_none());
+
+    list.push_back(b"a");
+    list.push_back(b"b");
+    list.push_back(b"c");
+    let list = std::thread::spawn(move || {
+        assert_eq!(
+            entries(&list),
+            vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()]
+        );
+        list
+    })
+    .join()
+    .unwrap();
+    assert_eq!(list.node_count(), 2);
+}
@@ -0,0 +1,140 @@
+use std::collections::BTreeMap;
+use std::ops::Bound;
+
+use rtypes::RRax;
+
+fn keys(iter: impl Iterator<Item = (rtypes::RString, u32)>) -> Vec<Vec<u8>> {
+    iter.map(|(key, _)| key.to_bytes()).collect()
+}
+
+#[test]
+fn insert_split_and_merge() {
+    let mut rax = RRax::new();
+    for (i, key) in [
+        "romane", "romanus", "romulus", "rubens", "ruber", "rubicon", "r", "",
+    ]
+    .iter()
+    .enumerate()
+    {
+        assert_eq!(rax.insert(key.as_bytes(), i), None);
+    }
+    assert_eq!(rax.len(), 8);
+    assert_eq!(rax.get(b"rubens"), Some(&3));
+    assert_eq!(rax.get(b""), Some(&7));
+    assert_eq!(rax.get(b"rom"), None);
+    assert_eq!(rax.get(b"rubiconx"), None);
+    assert!(!rax.contains_key(b"ru"));
+
+    *rax.get_mut(b"r").unwrap() += 10;
+    assert_eq!(rax.insert(b"r", 0), Some(16));
+    assert_eq!(rax.len(), 8);
+
+    // Nodes split are merged back once keys are removed.
+    assert_eq!(rax.remove(b"rom"), None);
+    assert_eq!(rax.remove(b"romulus"), Some(2));
+    assert_eq!(rax.remove(b"romanus"), Some(1));
+    assert_eq!(rax.get(b"romane"), Some(&0));
+    assert_eq!(rax.remove(b"r"), Some(0));
+    assert_eq!(rax.remove(b""), Some(7));
+    assert_eq!(rax.remove(b""), None);
+    let all: Vec<_> = rax.iter().map(|(key, &v)| (key.to_bytes(), v)).collect();
+    assert_eq!(
+        all,
+        vec![
+            (b"romane".to_vec(), 0),
+            (b"rubens".to_vec(), 3),
+            (b"ruber".to_vec(), 4),
+            (b"rubicon".to_vec(), 5)
+        ]
+    );
+    assert_eq!(format!("{:?}", rax.first().unwrap().1), "0");
+
+    rax.clear();
+    assert!(rax.is_empty());
+    assert!(rax.first().is_none() && rax.last().is_none());
+    assert_eq!(rax.iter().count(), 0);
+}
+
+#[test]
+fn iterate_in_ranges() {
+    let mut rax = RRax::new();
+    for key in ["a", "ab", "abc", "abd", "b", "ba", "c"].iter() {
+        rax.insert(key.as_bytes(), 0u32);
+    }
+    let range = |start, end| keys(rax.range(start, end).map(|(key, &v)| (key, v)));
+
+    assert_eq!(
+        range(Bound::Included(&b"ab"[..]), Bound::Excluded(&b"b"[..])),
+        vec![b"ab".to_vec(), b"abc".to_vec(), b"abd".to_vec()]
+    );
+    assert_eq!(
+        range(Bound::Excluded(&b"abc"[..]), Bound::Included(&b"ba"[..])),
+        vec![b"abd".to_vec(), b"b".to_vec(), b"ba".to_vec()]
+    );
+    assert_eq!(
+        range(Bound::Included(&b"abca"[..]), Bound::Included(&b"az"[..])),
+        vec![b"abd".to_vec()]
+    );
+    assert!(range(Bound::Included(&b"d"[..]), Bound::Unbounded).is_empty());
+    assert!(range(Bound::Included(&b"b"[..]), Bound::Excluded(&b"b"[..])).is_empty());
+
+    // From both ends, where entries are NEVER yielded twice.
+    let mut iter = rax.iter();
+    assert_eq!(iter.next().unwrap().0.to_bytes(), b"a");
+    assert_eq!(iter.next_back().unwrap().0.to_bytes(), b"c");
+    assert_eq!(iter.next_back().unwrap().0.to_bytes(), b"ba");
+    assert_eq!(iter.count(), 4);
+
+    let mut iter = rax.range(Bound::Unbounded, Bound::Included(&b"abc"[..]));
+    assert_eq!(iter.next_back().unwrap().0.to_bytes(), b"abc");
+    assert_eq!(iter.next_back().unwrap().0.to_bytes(), b"ab");
+    assert_eq!(iter.next_back().unwrap().0.to_bytes(), b"a");
+    assert!(iter.next_back().is_none() && iter.next().is_none());
+}
+
+#[test]
+fn same_as_btree_map() {
+    let mut rax = RRax::new();
+    let mut map = BTreeMap::new();
+    let mut seed = 0x2545_f491_4f6c_dd1du64;
+    let mut random = move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed
+    };
+
+    // Fewer rounds under Miri, which is slower by orders of magnitude.
+    let rounds = if cfg!(miri) { 300 } else { 5000 };
+    for _ in 0..rounds {
+        let n = random();
+        // Short keys of few bytes, that prefixes are shared a lot.
+        let key: Vec<u8> = (0..n % 5)
+            .map(|i| b"abc"[(n >> (8 * i + 8)) as usize % 3])
+            .collect();
+        if n % 3 == 0 {
+            assert_eq!(rax.remove(&key), map.remove(&key));
+        } else {
+            assert_eq!(
+                rax.insert(&key, n as u32),
+                map.insert(key.clone(), n as u32)
+            );
+        }
+        assert_eq!(rax.len(), map.len());
+
+        let start = &key[..key.len() / 2];
+        let actual: Vec<_> = rax
+            .range(Bound::Excluded(start), Bound::Included(&key))
+            .rev()
+            .map(|(key, &v)| (key.to_bytes(), v))
+            .collect();
+        let expected: Vec<_> = map
+            .range::<[u8], _>((Bound::Excluded(start), Bound::Included(&key[..])))
+            .rev()
+            .map(|(key, &v)| (key.clone(), v))
+            .collect();
+        assert_eq!(actual, expected);
+    }
+    let all: Vec<_> = rax.iter().map(|(key, &v)| (key.to_bytes(), v)).collect();
+    assert_eq!(all, map.into_iter().collect::<Vec<_>>());
+}
@@ -0,0 +1,220 @@
+use rtypes::{RDict, RString, RDICT_INITIAL_SIZE};
+
+fn key(i: usize) -> RString {
+    RString::from_str(&format!("key:{}", i))
+}
+
+fn sorted<'a>(iter: impl Iterator<Item = (&'a RString, &'a usize)>) -> Vec<usize> {
+    let mut values: Vec<_> = iter.map(|(_, &value)| value).collect();
+    values.sort_unstable();
+    values
+}
+
+#[test]
+fn insert_get_remove() {
+    let mut dict = RDict::new();
+    assert_eq!((dict.len(), dict.buckets()), (0, 0));
+    assert_eq!(dict.get(b"key:0".as_ref()), None);
+    assert_eq!(dict.remove(b"key:0".as_ref()), None);
+
+    assert_eq!(dict.insert(key(0), 0), None);
+    assert_eq!(dict.buckets(), RDICT_INITIAL_SIZE);
+    assert_eq!(dict.insert(key(0), 10), Some(0));
+    assert_eq!(dict.insert(key(1), 1), None);
+    assert_eq!(dict.len(), 2);
+
+    assert_eq!(dict.get(b"key:0".as_ref()), Some(&10));
+    assert_eq!(dict.get(&key(1)), Some(&1));
+    assert!(dict.contains_key(b"key:1".as_ref()));
+    *dict.get_mut(b"key:1".as_ref()).unwrap() += 10;
+    assert_eq!(dict.get_key_value(b"key:1".as_ref()), Some((&key(1), &11)));
+
+    assert_eq!(dict.remove_entry(b"key:0".as_ref()), Some((key(0), 10)));
+    assert_eq!(dict.remove(b"key:0".as_ref()), None);
+    assert_eq!(dict.len(), 1);
+    let single: RDict<u8, &str> = vec![(1, "a")].into_iter().collect();
+    assert_eq!(format!("{:?}", single), r#"{1: "a"}"#);
+
+    dict.clear();
+    assert_eq!((dict.len(), dict.buckets()), (0, 0));
+}
+
+#[test]
+fn incremental_rehash() {
+    let mut dict = RDict::new();
+    for i in 0..4 {
+        dict.insert(key(i), i);
+    }
+    assert!(!dict.is_rehashing());
+
+    // Full, thus grown (into 8 buckets) & rehashing.
+    dict.insert(key(4), 4);
+    assert!(dict.is_rehashing());
+    assert_eq!(dict.buckets(), 4 + 8);
+    assert!(!dict.expand(64));
+
+    // Entries are found in both tables while rehashing.
+    for i in 0..5 {
+        assert_eq!(dict.get(&key(i)), Some(&i));
+    }
+    assert_eq!(dict.iter().len(), 5);
+    assert_eq!(sorted(dict.iter()), (0..5).collect::<Vec<_>>());
+
+    // Each operation with `&mut self` rehashes a step.
+    let mut steps = 0;
+    while dict.is_rehashing() {
+        dict.get_mut(&key(0));
+        steps += 1;
+    }
+    assert!(steps <= RDICT_INITIAL_SIZE);
+    assert_eq!(dict.buckets(), 8);
+    assert!(!dict.rehash_step(1));
+
+    for i in 5..1000 {
+        dict.insert(key(i), i);
+        dict.remove(&key(i - 5));
+    }
+    for i in 995..1000 {
+        assert_eq!(dict.get(&key(i)), Some(&i));
+    }
+    assert_eq!(dict.len(), 5);
+}
+
+#[test]
+fn resize_table() {
+    let mut dict: RDict<RString, usize> = (0..100).map(|i| (key(i), i)).collect();
+    dict.rehash_for(std::time::Duration::from_secs(1));
+    assert!(!dict.is_rehashing());
+    assert_eq!(dict.buckets(), 128);
+
+    for i in 10..100 {
+        dict.remove(&key(i));
+    }
+    assert!(dict.shrink_to_fit());
+    assert_eq!(dict.buckets(), 128 + 16);
+    while dict.rehash_step(1) {}
+    assert_eq!(dict.buckets(), 16);
+    assert!(!dict.shrink_to_fit());
+
+    // Grown ahead of insertions.
+    assert!(dict.expand(1000));
+    assert!(dict.rehash_step(1));
+    dict.rehash_for(std::time::Duration::from_secs(1));
+    assert_eq!(dict.buckets(), 1024);
+    assert!(!dict.expand(5));
+
+    for (_, value) in dict.iter_mut() {
+        *value *= 2;
+    }
+    assert_eq!(
+        sorted(dict.iter()),
+        (0..10).map(|i| i * 2).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn drop_entries() {
+    use std::rc::Rc;
+
+    let value = Rc::new(0);
+    let mut dict = RDict::new();
+    for i in 0..50 {
+        dict.insert(i, Rc::clone(&value));
+    }
+    // Dropped while rehashing.
+    while dict.rehash_step(1) {}
+    assert!(dict.expand(1000));
+    dict.rehash_step(2);
+    assert!(dict.is_rehashing());
+    dict.remove(&0);
+    assert_eq!(Rc::strong_count(&value), 50);
+    drop(dict);
+    assert_eq!(Rc::strong_count(&value), 1);
+}
+
+fn scan_all<F>(dict: &mut RDict<RString, usize>, mut between: F) -> Vec<usize>
+where
+    F: FnMut(&mut RDict<RString, usize>, usize),
+{
+    let mut values = Vec::new();
+    let mut cursor = 0;
+    let mut calls = 0;
+    loop {
+        cursor = dict.scan(cursor, |_, &value| values.push(value));
+        if cursor == 0 {
+            break;
+        }
+        between(dict, calls);
+        calls += 1;
+    }
+    values.sort_unstable();
+    values
+}
+
+#[test]
+fn scan_stable_table() {
+    let mut dict = RDict::new();
+    assert_eq!(dict.scan(0, |_, _: &usize| unreachable!()), 0);
+
+    dict.extend((0..100).map(|i| (key(i), i)));
+    while dict.rehash_step(1) {}
+    // Each entry is returned exactly once, if NOT resized while scanning.
+    assert_eq!(scan_all(&mut dict, |_, _| {}), (0..100).collect::<Vec<_>>());
+
+    // Also while rehashing (but NOT rehashed further).
+    dict.shrink_to_fit();
+    for i in 100..300 {
+        dict.insert(key(i), i);
+    }
+    assert!(dict.is_rehashing());
+    assert_eq!(scan_all(&mut dict, |_, _| {}), (0..300).collect::<Vec<_>>());
+}
+
+#[test]
+fn scan_while_resized() {
+    // Grown (& rehashed step by step) while scanning.
+    let mut dict: RDict<RString, usize> = (0..100).map(|i| (key(i), i)).collect();
+    let mut values = scan_all(&mut dict, |dict, calls| {
+        for i in (0..50).filter(|_| calls < 20) {
+            let i = 1000 + calls * 50 + i;
+            dict.insert(key(i), i);
+        }
+    });
+    values.dedup();
+    values.retain(|&value| value < 100);
+    assert_eq!(values, (0..100).collect::<Vec<_>>());
+
+    // Shrunk (& rehashed step by step) while scanning.
+    for i in 1000..2000 {
+        dict.remove(&key(i));
+    }
+    let mut values = scan_all(&mut dict, |dict, calls| {
+        if calls == 3 {
+            while dict.rehash_step(1) {}
+            assert!(dict.shrink_to_fit());
+        }
+        dict.rehash_step(1);
+    });
+    values.dedup();
+    values.retain(|&value| value < 100);
+    assert_eq!(values, (0..100).collect::<Vec<_>>());
+}
+
+#[test]
+fn random_entries() {
+    let mut dict: RDict<usize, usize> = RDict::new();
+    assert_eq!(dict.random_entry(), None);
+    dict.extend((0..64).map(|i| (i, i)));
+    // Picked from both tables while rehashing.
+    dict.expand(1024);
+    dict.rehash_step(8);
+    assert!(dict.is_rehashing());
+
+    let mut picked = [false; 64];
+    for _ in 0..10000 {
+        let (&key, &value) = dict.random_entry().unwrap();
+        assert_eq!(key, value);
+        picked[key] = true;
+    }
+    assert!(picked.iter().all(|&picked| picked));
+}
@@ -1,4 +1,4 @@
-use rtypes::RList;
+use rtypes::{End, MaxLen, RList};
 
 #[test]
 fn basic() {
@@ -71,3 +71,601 @@ fn insert() {
     assert_eq!(list.remove(2), Some(2));
     assert_eq!(list.to_vec(), vec![9, 1, 3]);
 }
+
+#[test]
+fn remove_and_set() {
+    let mut list: RList<u32> = RList::new();
+    assert_eq!(list.remove(0), None);
+    assert_eq!(list.set(0, 1), None);
+
+    list.trim(0..10);
+    for i in 0..5 {
+        list.push_back(i);
+    }
+    assert_eq!(list.set(3, 30), Some(3));
+    assert_eq!(list.remove(2), Some(2));
+    assert_eq!(list.len(), 4);
+    assert_eq!(list.remove(4), None);
+    assert_eq!(list.remove(3), Some(4));
+    assert_eq!(list.back(), Some(30));
+    assert_eq!(list.remove(0), Some(0));
+    assert_eq!(list.front(), Some(1));
+    assert_eq!(list.to_vec(), vec![1, 30]);
+    assert_eq!(list.range(2..5), vec![]);
+    assert_eq!(format!("{}", list), "[1, 30]");
+}
+
+#[test]
+fn get_from_both_ends() {
+    let mut list = RList::new();
+    for i in 0..101 {
+        list.push_front(100 - i);
+    }
+    for i in 0..101 {
+        assert_eq!(list.get(i), Some(i));
+    }
+    assert_eq!(list.get(101), None);
+}
+
+#[test]
+fn move_across_threads() {
+    let mut list = RList::new();
+    list.push_back(1u64);
+    let list = std::thread::spawn(move || {
+        list.push_back(2);
+        list
+    })
+    .join()
+    .unwrap();
+    assert_eq!(list.to_vec(), vec![1, 2]);
+}
+
+#[test]
+fn iterate_both_ends() {
+    let list: RList<u32> = (0..6).collect();
+    let mut iter = list.iter();
+    assert_eq!(iter.len(), 6);
+    assert_eq!(iter.next(), Some(0));
+    assert_eq!(iter.next_back(), Some(5));
+    assert_eq!(iter.len(), 4);
+    assert_eq!(iter.clone().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+    assert_eq!(iter.rev().collect::<Vec<_>>(), vec![4, 3, 2, 1]);
+
+    let mut sum = 0;
+    for data in &list {
+        sum += data;
+    }
+    assert_eq!(sum, 15);
+
+    let mut iter = list.into_iter();
+    assert_eq!(iter.next_back(), Some(5));
+    assert_eq!(iter.len(), 5);
+    assert_eq!(iter.collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn extend_list() {
+    let mut list = RList::new();
+    list.extend(vec![1, 2]);
+    list.extend(&[3, 4]);
+    list.extend(std::iter::empty::<i32>());
+    assert_eq!(list.len(), 4);
+    assert_eq!(
+        list.iter().map(|data| data * 10).collect::<Vec<_>>(),
+        vec![10, 20, 30, 40]
+    );
+    assert_eq!(
+        list.into_iter().rev().collect::<RList<_>>().to_vec(),
+        vec![4, 3, 2, 1]
+    );
+}
+
+#[test]
+fn cursor_walk_and_wrap() {
+    let mut list: RList<u32> = (0..4).collect();
+    let mut cursor = list.cursor_back_mut();
+    assert_eq!((cursor.index(), cursor.current()), (Some(3), Some(3)));
+    cursor.move_next();
+    assert_eq!((cursor.index(), cursor.current()), (None, None));
+    cursor.move_next();
+    assert_eq!((cursor.index(), cursor.current()), (Some(0), Some(0)));
+    cursor.move_prev();
+    assert_eq!(cursor.index(), None);
+    cursor.move_prev();
+    assert_eq!(cursor.current(), Some(3));
+
+    for idx in [1, 3, 0, 2, 9, 2] {
+        cursor.seek(idx);
+        assert_eq!(cursor.current(), [0, 1, 2, 3].get(idx).copied());
+    }
+    *cursor.current_mut().unwrap() = 20;
+    assert_eq!(list.to_vec(), vec![0, 1, 20, 3]);
+
+    let mut empty: RList<u32> = RList::new();
+    let mut cursor = empty.cursor_front_mut();
+    cursor.move_next();
+    cursor.move_prev();
+    cursor.seek(0);
+    assert_eq!((cursor.index(), cursor.remove_current()), (None, None));
+}
+
+#[test]
+fn cursor_insert_and_remove() {
+    let mut list: RList<u32> = RList::new();
+    let mut cursor = list.cursor_front_mut();
+    // At the ghost position, before is the back & after is the front.
+    cursor.insert_before(2);
+    cursor.insert_after(1);
+    cursor.insert_before(3);
+    assert_eq!(cursor.index(), None);
+
+    cursor.move_prev();
+    cursor.insert_after(4);
+    cursor.move_next();
+    assert_eq!((cursor.index(), cursor.current()), (Some(3), Some(4)));
+    cursor.move_next();
+    assert_eq!(cursor.current(), None);
+
+    cursor.seek(0);
+    cursor.insert_before(0);
+    assert_eq!((cursor.index(), cursor.current()), (Some(1), Some(1)));
+    assert_eq!(cursor.remove_current(), Some(1));
+    assert_eq!((cursor.index(), cursor.current()), (Some(1), Some(2)));
+    cursor.seek(3);
+    assert_eq!(cursor.remove_current(), Some(4));
+    assert_eq!(cursor.index(), None);
+    cursor.insert_before(5);
+
+    assert_eq!(list.to_vec(), vec![0, 2, 3, 5]);
+    assert_eq!(list.iter().rev().collect::<Vec<_>>(), vec![5, 3, 2, 0]);
+    assert_eq!(
+        (list.front(), list.back(), list.len()),
+        (Some(0), Some(5), 4)
+    );
+}
+
+#[test]
+fn remove_matches() {
+    let mut list: RList<u8> = b"abacada".iter().copied().collect();
+    assert_eq!(list.remove_matches(&b'z', 0), 0);
+    assert_eq!(list.remove_matches(&b'a', 2), 2);
+    assert_eq!(list.to_vec(), b"bcada");
+    assert_eq!(list.remove_matches(&b'a', -1), 1);
+    assert_eq!(list.to_vec(), b"bcad");
+    assert_eq!(list.remove_matches(&b'a', isize::MIN), 1);
+    assert_eq!(list.to_vec(), b"bcd");
+    assert_eq!(list.remove_matches(&b'd', 5), 1);
+    assert_eq!(list.back(), Some(b'c'));
+    assert_eq!(list.remove_matches(&b'b', -5), 1);
+    assert_eq!(list.remove_matches(&b'c', 0), 1);
+    assert!(list.is_empty());
+    assert_eq!((list.front(), list.back()), (None, None));
+}
+
+#[test]
+fn move_between_lists() {
+    let mut src: RList<u32> = (0..3).collect();
+    let mut dst: RList<u32> = RList::new();
+    assert_eq!(src.move_element(&mut dst, End::Front, End::Back), Some(0));
+    assert_eq!(src.move_element(&mut dst, End::Front, End::Back), Some(1));
+    assert_eq!(src.move_element(&mut dst, End::Back, End::Front), Some(2));
+    assert_eq!(src.move_element(&mut dst, End::Back, End::Front), None);
+    assert!(src.is_empty());
+    assert_eq!((src.front(), src.back()), (None, None));
+
+    assert_eq!(dst.to_vec(), vec![2, 0, 1]);
+    assert_eq!(dst.iter().rev().collect::<Vec<_>>(), vec![1, 0, 2]);
+    assert_eq!(dst.move_element(&mut src, End::Front, End::Front), Some(2));
+    assert_eq!((src.to_vec(), dst.len()), (vec![2], 2));
+}
+
+#[test]
+fn positions_with_rank() {
+    let list: RList<u8> = b"aabxaxa".iter().copied().collect();
+    assert_eq!(list.positions(&b'a', 1, 0, 0), vec![0, 1, 4, 6]);
+    assert_eq!(list.positions(&b'a', 3, 0, 0), vec![4, 6]);
+    assert_eq!(list.positions(&b'a', 5, 0, 0), vec![]);
+    assert_eq!(list.positions(&b'a', -1, 0, 0), vec![6, 4, 1, 0]);
+    assert_eq!(list.positions(&b'a', -2, 2, 0), vec![4, 1]);
+    assert_eq!(list.positions(&b'a', isize::MIN, 0, 0), vec![]);
+    assert_eq!(list.positions(&b'x', -1, 1, 2), vec![5]);
+    assert_eq!(list.positions(&b'b', 1, 0, 2), vec![]);
+    assert_eq!(list.positions(&b'z', 1, 0, 0), vec![]);
+    assert_eq!(RList::new().positions(&0, -1, 0, 0), vec![]);
+}
+
+#[test]
+#[should_panic(expected = "LPOS rank can't be zero")]
+fn positions_with_zero_rank() {
+    RList::<u8>::new().positions(&0, 0, 0, 0);
+}
+
+#[test]
+fn retain_elements() {
+    let mut list: RList<u32> = (0..10).collect();
+    list.retain(|&data| data > 0 && data < 9 && data != 5);
+    assert_eq!(list.to_vec(), vec![1, 2, 3, 4, 6, 7, 8]);
+    assert_eq!(
+        (list.front(), list.back(), list.len()),
+        (Some(1), Some(8), 7)
+    );
+    list.retain(|_| false);
+    assert!(list.is_empty());
+    assert_eq!((list.front(), list.back()), (None, None));
+}
+
+#[test]
+fn drain_range() {
+    let mut list: RList<u32> = (0..10).collect();
+    {
+        let mut drain = list.drain(2..=4);
+        assert_eq!(drain.len(), 3);
+        assert_eq!(drain.next(), Some(2));
+        // The rest are removed on drop.
+    }
+    assert_eq!(list.to_vec(), vec![0, 1, 5, 6, 7, 8, 9]);
+
+    assert_eq!(list.drain(5..100).collect::<Vec<_>>(), vec![8, 9]);
+    assert_eq!(list.drain(3..3).count(), 0);
+    assert_eq!(list.drain(9..).count(), 0);
+    assert_eq!(list.drain(..1).collect::<Vec<_>>(), vec![0]);
+    assert_eq!(list.to_vec(), vec![1, 5, 6, 7]);
+    assert_eq!(list.back(), Some(7));
+
+    std::mem::forget(list.drain(1..3));
+    assert_eq!(list.to_vec(), vec![1, 5, 6, 7]);
+    assert_eq!(list.drain(..).count(), 4);
+    assert!(list.is_empty());
+}
+
+#[test]
+fn sort_stable() {
+    let mut list: RList<u32> = RList::new();
+    list.sort();
+    list.push_back(1);
+    list.sort();
+    assert_eq!(list.to_vec(), vec![1]);
+
+    // A pseudo-random sequence with lots of duplicates, tagged with original indexes.
+    let values: Vec<(u32, usize)> = (0..1000)
+        .map(|i| ((i * 7919 + 13) as u32 % 61, i))
+        .collect();
+    for len in [2, 3, 5, 64, 100, 1000] {
+        let mut list: RList<(u32, usize)> = values[..len].iter().copied().collect();
+        list.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut expected = values[..len].to_vec();
+        expected.sort_by_key(|a| a.0);
+        assert_eq!(list.to_vec(), expected);
+        assert_eq!(
+            list.iter().rev().collect::<Vec<_>>(),
+            expected.into_iter().rev().collect::<Vec<_>>()
+        );
+        assert_eq!(list.len(), len);
+    }
+
+    let mut list: RList<i32> = vec![3, -1, 2, -1, 0].into_iter().collect();
+    list.sort();
+    assert_eq!(list.to_vec(), vec![-1, -1, 0, 2, 3]);
+    assert_eq!((list.front(), list.back()), (Some(-1), Some(3)));
+}
+
+#[test]
+fn sort_with_panic() {
+    for limit in [0, 1, 5, 20, 50] {
+        let mut list: RList<u32> = (0..37).rev().collect();
+        let mut compared = 0;
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            list.sort_by(|a, b| {
+                compared += 1;
+                if compared > limit {
+                    panic!("comparator panicked");
+                }
+                a.cmp(b)
+            })
+        }));
+        assert!(result.is_err());
+
+        // ALL elements are kept, and links are valid in both directions.
+        let mut forward = list.to_vec();
+        let mut backward: Vec<_> = list.iter().rev().collect();
+        backward.reverse();
+        assert_eq!(forward, backward);
+        forward.sort();
+        assert_eq!(forward, (0..37).collect::<Vec<_>>());
+        assert_eq!(list.len(), 37);
+    }
+}
+
+#[test]
+fn notify_on_push() {
+    use std::sync::mpsc;
+
+    let (sender, receiver) = mpsc::channel();
+    let mut list: RList<u32> = RList::new();
+    list.on_push(move |len| sender.send(len).unwrap());
+
+    list.push_back(1);
+    list.push_front(0);
+    list.insert_after(0, 5);
+    list.extend(vec![6, 7]);
+    list.cursor_back_mut().insert_before(2);
+    assert_eq!(list.pop_back(), Some(7));
+    assert_eq!(
+        receiver.try_iter().collect::<Vec<_>>(),
+        vec![1, 2, 3, 4, 5, 6]
+    );
+
+    let mut src: RList<u32> = (0..2).collect();
+    src.move_element(&mut list, End::Front, End::Front);
+    assert_eq!(receiver.try_recv(), Ok(6));
+
+    let notifier = list.take_notifier();
+    list.push_back(8);
+    assert!(receiver.try_recv().is_err());
+    drop(notifier);
+    assert!(receiver.recv().is_err());
+}
+
+#[test]
+fn wake_blocked_pop() {
+    use std::sync::{Arc, Condvar, Mutex};
+
+    let ready = Arc::new((Mutex::new(false), Condvar::new()));
+    let list = Arc::new(Mutex::new(RList::new()));
+    {
+        let ready = Arc::clone(&ready);
+        list.lock().unwrap().on_push(move |_| {
+            *ready.0.lock().unwrap() = true;
+            ready.1.notify_one();
+        });
+    }
+
+    let pusher = {
+        let list = Arc::clone(&list);
+        std::thread::spawn(move || list.lock().unwrap().push_back(42u64))
+    };
+
+    let mut woken = ready.0.lock().unwrap();
+    while !*woken {
+        woken = ready.1.wait(woken).unwrap();
+    }
+    drop(woken);
+    pusher.join().unwrap();
+    assert_eq!(list.lock().unwrap().pop_front(), Some(42));
+}
+
+#[test]
+fn signed_indexes() {
+    let mut list: RList<i32> = (0..6).collect();
+    assert_eq!(list.get_signed(0), Some(0));
+    assert_eq!(list.get_signed(-1), Some(5));
+    assert_eq!(list.get_signed(-6), Some(0));
+    assert_eq!(list.get_signed(6), None);
+    assert_eq!(list.get_signed(isize::MIN), None);
+    assert_eq!(list.set_signed(-2, 40), Some(4));
+    assert_eq!(list.set_signed(-7, 0), None);
+
+    assert_eq!(list.range_signed(0, -1), vec![0, 1, 2, 3, 40, 5]);
+    assert_eq!(list.range_signed(-100, 1), vec![0, 1]);
+    assert_eq!(list.range_signed(4, 100), vec![40, 5]);
+    assert_eq!(list.range_signed(isize::MIN, isize::MAX).len(), 6);
+    assert_eq!(list.range_signed(3, 2), vec![]);
+    assert_eq!(list.range_signed(6, 10), vec![]);
+    assert_eq!(list.range_signed(0, -7), vec![]);
+
+    list.insert_before_signed(-1, 4);
+    list.insert_after_signed(-1, 6);
+    list.insert_after_signed(-100, -2);
+    list.insert_before_signed(-100, -3);
+    list.insert_before_signed(100, 7);
+    assert_eq!(list.to_vec(), vec![-3, -2, 0, 1, 2, 3, 40, 4, 5, 6, 7]);
+
+    list.trim_signed(2, -2);
+    assert_eq!(list.to_vec(), vec![0, 1, 2, 3, 40, 4, 5, 6]);
+    list.trim_signed(-100, 100);
+    assert_eq!(list.len(), 8);
+    list.trim_signed(-1, -2);
+    assert!(list.is_empty());
+}
+
+#[test]
+fn capped_list() {
+    let mut list: RList<u32> = (0..10).collect();
+    list.set_max_len(Some(MaxLen::Exact(6)));
+    assert_eq!(list.max_len(), Some(MaxLen::Exact(6)));
+    assert_eq!(list.to_vec(), vec![0, 1, 2, 3, 4, 5]);
+
+    list.push_back(6);
+    assert_eq!(list.to_vec(), vec![1, 2, 3, 4, 5, 6]);
+    list.push_front(0);
+    assert_eq!(list.to_vec(), vec![0, 1, 2, 3, 4, 5]);
+    list.insert_after(0, 10);
+    assert_eq!(list.to_vec(), vec![0, 10, 1, 2, 3, 4]);
+    list.extend(vec![7, 8]);
+    assert_eq!(list.to_vec(), vec![1, 2, 3, 4, 7, 8]);
+
+    let mut src: RList<u32> = vec![9].into_iter().collect();
+    src.move_element(&mut list, End::Back, End::Back);
+    assert_eq!(list.to_vec(), vec![2, 3, 4, 7, 8, 9]);
+
+    // Approximately, it's trimmed once exceeding the cap by 1/8.
+    list.set_max_len(Some(MaxLen::Approx(16)));
+    list.extend(10..20);
+    assert_eq!(list.len(), 16);
+    list.extend(20..22);
+    assert_eq!(list.len(), 18);
+    list.push_back(22);
+    assert_eq!(list.len(), 16);
+    assert_eq!(list.front(), Some(7));
+
+    list.set_max_len(None);
+    list.extend(0..100);
+    assert_eq!(list.len(), 116);
+}
+
+#[test]
+fn capped_cursor_insert() {
+    let mut list: RList<u32> = (0..4).collect();
+    list.set_max_len(Some(MaxLen::Exact(4)));
+
+    let mut cursor = list.cursor_back_mut();
+    cursor.insert_after(9);
+    assert_eq!((cursor.index(), cursor.current()), (Some(3), Some(3)));
+    cursor.insert_before(8);
+    // The back (pointed to) is evicted, thus the cursor is at the ghost position.
+    assert_eq!((cursor.index(), cursor.current()), (None, None));
+    cursor.insert_before(4);
+    // Pushed to the back, thus the front is evicted.
+    assert_eq!(cursor.index(), None);
+    cursor.move_prev();
+    assert_eq!((cursor.index(), cursor.current()), (Some(3), Some(4)));
+
+    cursor.seek(1);
+    cursor.insert_before(7);
+    assert_eq!((cursor.index(), cursor.current()), (Some(2), Some(2)));
+    assert_eq!(list.to_vec(), vec![1, 7, 2, 8]);
+}
+
+#[test]
+fn reverse_list() {
+    let mut list: RList<u32> = RList::new();
+    list.reverse();
+    assert!(list.is_empty());
+
+    list.extend(0..5);
+    assert_eq!(list.iter_rev().collect::<Vec<_>>(), vec![4, 3, 2, 1, 0]);
+    list.reverse();
+    assert_eq!(list.to_vec(), vec![4, 3, 2, 1, 0]);
+    assert_eq!((list.front(), list.back()), (Some(4), Some(0)));
+    assert_eq!(list.iter_rev().collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+
+    // Links are still consistent after reversed.
+    list.push_back(9);
+    list.insert_before(1, 8);
+    assert_eq!(list.pop_front(), Some(4));
+    assert_eq!(list.to_vec(), vec![8, 3, 2, 1, 0, 9]);
+    assert_eq!(list.range(4..10), vec![0, 9]);
+    assert_eq!(list.range(1..3), vec![3, 2]);
+    assert_eq!(list.range_signed(-2, -1), vec![0, 9]);
+}
+
+#[test]
+fn compare_and_clone() {
+    let list: RList<(u8, char)> = vec![(1, 'a'), (2, 'b')].into_iter().collect();
+    assert_eq!(format!("{:?}", list), "[(1, 'a'), (2, 'b')]");
+    assert_eq!(format!("{:?}", RList::<u8>::new()), "[]");
+
+    let mut snapshot = list.clone();
+    assert_eq!(snapshot, list);
+    snapshot.push_back((3, 'c'));
+    assert_ne!(snapshot, list);
+    assert_eq!(snapshot.pop_back(), Some((3, 'c')));
+    snapshot.set(0, (0, 'a'));
+    assert_ne!(snapshot, list);
+    assert_eq!(list.to_vec(), vec![(1, 'a'), (2, 'b')]);
+
+    let mut capped: RList<u8> = RList::new();
+    capped.set_max_len(Some(MaxLen::Exact(2)));
+    let mut capped = capped.clone();
+    capped.extend(0..4);
+    assert_eq!(capped, (2..4).collect());
+}
+
+#[test]
+fn swap_elements() {
+    let mut list: RList<u32> = (0..6).collect();
+    assert!(list.swap(1, 4));
+    assert!(list.swap(2, 2));
+    assert!(list.swap(5, 0));
+    assert!(!list.swap(6, 0));
+    assert_eq!(list.to_vec(), vec![5, 4, 2, 3, 1, 0]);
+
+    assert_eq!(list.swap_remove_back(0), Some(5));
+    assert_eq!(list.swap_remove_back(4), Some(1));
+    assert_eq!(list.swap_remove_back(4), None);
+    assert_eq!(list.to_vec(), vec![0, 4, 2, 3]);
+    assert_eq!(list.back(), Some(3));
+
+    let mut list: RList<u32> = vec![7].into_iter().collect();
+    assert_eq!(list.swap_remove_back(0), Some(7));
+    assert!(list.is_empty());
+    assert_eq!(list.swap_remove_back(0), None);
+}
+
+#[test]
+fn split_list() {
+    for len in 0..6 {
+        for idx in 0..=len + 1 {
+            let list: RList<usize> = (0..len).collect();
+            let (mut front, mut back) = list.split_at(idx);
+            let mid = std::cmp::min(idx, len);
+            assert_eq!(front.to_vec(), (0..mid).collect::<Vec<_>>());
+            assert_eq!(back.to_vec(), (mid..len).collect::<Vec<_>>());
+            assert_eq!(front.iter_rev().count(), mid);
+            assert_eq!(back.iter_rev().count(), len - mid);
+
+            // Both are independent lists.
+            front.push_back(100);
+            back.push_front(200);
+            assert_eq!(front.back(), Some(100));
+            assert_eq!(back.front(), Some(200));
+            assert_eq!(
+                back.pop_back(),
+                Some(
+                    len.checked_sub(1)
+                        .filter(|&back| back >= mid)
+                        .unwrap_or(200)
+                )
+            );
+        }
+    }
+}
+
+#[test]
+fn bulk_push() {
+    use std::sync::mpsc;
+
+    let mut list = RList::from(Vec::<u32>::new());
+    assert!(list.is_empty());
+    list.extend_from_slice(&[]);
+    assert!(list.is_empty());
+
+    let (sender, receiver) = mpsc::channel();
+    list.on_push(move |len| sender.send(len).unwrap());
+    list.extend_from_slice(&[1, 2, 3]);
+    list.push_front(0);
+    list.extend_from_slice(&[4, 5]);
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec![3, 4, 6]);
+    assert_eq!(list, RList::from(vec![0, 1, 2, 3, 4, 5]));
+    assert_eq!(list.iter_rev().collect::<Vec<_>>(), vec![5, 4, 3, 2, 1, 0]);
+
+    list.set_max_len(Some(MaxLen::Exact(4)));
+    list.extend_from_slice(&[6, 7, 8]);
+    assert_eq!(list.to_vec(), vec![3, 6, 7, 8]);
+    assert_eq!(list.pop_front(), Some(3));
+}
+
+#[test]
+fn memory_usage() {
+    use rtypes::{MemSized, RStr, RString};
+
+    let mut list: RList<u64> = RList::new();
+    let empty = list.mem_usage();
+    assert_eq!(empty, std::mem::size_of::<RList<u64>>());
+    list.extend(0..4);
+    let node = (list.mem_usage() - empty) / 4;
+    assert!(node >= 3 * 8 && node.is_multiple_of(16));
+    list.on_push(|_| {});
+    assert_eq!(list.mem_usage(), empty + 4 * node);
+    assert!(list.take_notifier().is_some());
+
+    // Payloads on the heap are counted as well.
+    let bytes = RString::from_str("hello world");
+    let strs: RList<(u8, Option<RStr>)> = (0..4)
+        .map(|i| (i, Some(RStr::new(bytes.as_bytes()))))
+        .collect();
+    assert_eq!(strs.iter().map(|data| data.heap_size()).sum::<usize>(), 0);
+    assert!(bytes.heap_size() >= bytes.capacity() + 16);
+    assert_eq!([1u8, 2].heap_size(), 0);
+}
@@ -0,0 +1,66 @@
+use std::collections::HashSet;
+
+use rtypes::{ParseNumError, RStr, RString};
+
+#[test]
+fn view_rstr() {
+    let s = RString::from_str("SET counter 42");
+    let view = s.as_rstr_view();
+    assert_eq!(view.len(), s.len());
+    assert_eq!(view.as_ptr(), s.as_ptr());
+    assert_eq!(view, s);
+    assert_eq!(s, view);
+
+    let value = view.rsub_rstr(view.rfind(b" ").unwrap() + 1);
+    assert_eq!(value.as_bytes(), b"42");
+    assert_eq!(value.as_ptr(), unsafe { s.as_ptr().add(12) });
+    assert_eq!(value.parse_i64(), Ok(42));
+    assert_eq!(value.parse_f64(), Ok(42.0));
+    assert_eq!(view.parse_i64(), Err(ParseNumError::Integer));
+
+    assert!(view.sub_rstr(4, 4).is_empty());
+    assert!(view.sub_rstr(100, 200).is_empty());
+    assert_eq!(view.sub_rstr(4, 100).as_bytes(), b"counter 42");
+    assert_eq!(view.lsub_rstr(3), RStr::from("SET"));
+    assert_eq!(value.to_rstr(), RString::from_str("42"));
+}
+
+#[test]
+fn search_rstr() {
+    let view = RStr::from(&b"a::b::::c"[..]);
+    assert_eq!(view.find(b"::"), Some(1));
+    assert_eq!(view.rfind(b"::"), Some(6));
+    assert!(view.contains(b"b::"));
+    assert!(!view.contains(b"d"));
+
+    let tokens = view.split(b"::");
+    assert_eq!(tokens.len(), 4);
+    assert_eq!(tokens[2], RStr::default());
+    assert_eq!(tokens[3], RStr::from("c"));
+    assert!(RStr::default().split(b",").is_empty());
+    assert!(view.split(b"").is_empty());
+}
+
+#[test]
+fn compare_and_hash_rstr() {
+    assert!(RStr::from("abc") < RStr::from("abd"));
+    assert!(RStr::from("ab") < RStr::from("abc"));
+    assert_eq!(
+        RStr::from("abc").cmp(&RStr::from("abc")),
+        std::cmp::Ordering::Equal
+    );
+
+    let set: HashSet<RStr<'_>> = vec![RStr::from("a"), RStr::from("b")].into_iter().collect();
+    assert!(set.contains(&b"a"[..]));
+    assert!(!set.contains(&b"c"[..]));
+
+    assert_eq!(RStr::from("key").to_string(), "key");
+    assert_eq!(RStr::from(&b"\xff"[..]).to_string(), "<Unreadable Bytes>");
+}
+
+#[test]
+fn repr_rstr_view() {
+    let view = RStr::from("a\"b\n");
+    assert_eq!(view.to_repr().as_bytes(), br#""a\"b\n""#);
+    assert!(format!("{:?}", view).ends_with(r#">"a\"b\n" }"#));
+}
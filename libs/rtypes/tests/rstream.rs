@@ -0,0 +1,342 @@
+use std::ops::Bound;
+
+use rtypes::{RStream, StreamEntry, StreamError, StreamId, StreamTrim, XAddId};
+
+fn id(ms: u64, seq: u64) -> StreamId {
+    StreamId::new(ms, seq)
+}
+
+fn fields(entry: &StreamEntry<'_>) -> Vec<(Vec<u8>, Vec<u8>)> {
+    entry
+        .fields()
+        .map(|(field, value)| (field.to_rstring().to_bytes(), value.to_rstring().to_bytes()))
+        .collect()
+}
+
+fn ids<'a>(iter: impl Iterator<Item = StreamEntry<'a>>) -> Vec<StreamId> {
+    iter.map(|entry| entry.id()).collect()
+}
+
+#[test]
+fn stream_ids() {
+    assert_eq!(
+        StreamId::from_bytes(b"1526919030474-55"),
+        Some(id(1526919030474, 55))
+    );
+    assert_eq!(StreamId::from_bytes(b"42"), Some(id(42, 0)));
+    assert_eq!(
+        StreamId::from_bytes(b"18446744073709551615-0"),
+        Some(id(u64::MAX, 0))
+    );
+    for &bad in &[
+        &b""[..],
+        b"-",
+        b"1-",
+        b"-1",
+        b"+1-1",
+        b"1-2-3",
+        b"18446744073709551616",
+    ] {
+        assert_eq!(StreamId::from_bytes(bad), None);
+    }
+    assert_eq!(id(5, 7).to_string(), "5-7");
+
+    assert_eq!(id(1, u64::MAX).next(), Some(id(2, 0)));
+    assert_eq!(StreamId::MAX.next(), None);
+    assert_eq!(id(2, 0).prev(), Some(id(1, u64::MAX)));
+    assert_eq!(StreamId::MIN.prev(), None);
+    assert!(id(1, 100) < id(2, 0));
+}
+
+#[test]
+fn add_entries() {
+    let mut stream = RStream::new();
+    assert_eq!(
+        stream.add(XAddId::Explicit(StreamId::MIN), &[("a", "1")]),
+        Err(StreamError::IdZero)
+    );
+    assert_eq!(stream.add(XAddId::AutoSeq(0), &[("a", "1")]), Ok(id(0, 1)));
+    assert_eq!(
+        stream.add(XAddId::Explicit(id(5, 5)), &[("a", "2")]),
+        Ok(id(5, 5))
+    );
+    assert_eq!(
+        stream.add(XAddId::Explicit(id(5, 5)), &[("a", "3")]),
+        Err(StreamError::IdTooSmall)
+    );
+    assert_eq!(
+        stream.add(XAddId::AutoSeq(4), &[("a", "3")]),
+        Err(StreamError::IdTooSmall)
+    );
+    assert_eq!(
+        stream.add(XAddId::AutoSeq(5), &[("b", "3"), ("c", "4")]),
+        Ok(id(5, 6))
+    );
+
+    let auto = stream.add(XAddId::Auto, &[("a", "5")]).unwrap();
+    assert!(auto > id(5, 6));
+    assert!(stream.add(XAddId::Auto, &[("a", "6")]).unwrap() > auto);
+    assert_eq!(stream.len(), 5);
+    assert_eq!(stream.entries_added(), 5);
+
+    // Fields (the same as the master ones or NOT) are decoded in the order added.
+    let entries: Vec<_> = stream.iter().collect();
+    assert_eq!(fields(&entries[0]), vec![(b"a".to_vec(), b"1".to_vec())]);
+    assert_eq!(
+        fields(&entries[2]),
+        vec![
+            (b"b".to_vec(), b"3".to_vec()),
+            (b"c".to_vec(), b"4".to_vec())
+        ]
+    );
+    assert_eq!(entries[2].len(), 2);
+    assert_eq!(fields(&entries[4]), vec![(b"a".to_vec(), b"6".to_vec())]);
+
+    let mut stream = RStream::new();
+    stream
+        .add(XAddId::Explicit(StreamId::MAX), &[("a", "1")])
+        .unwrap();
+    assert_eq!(
+        stream.add(XAddId::Auto, &[("a", "2")]),
+        Err(StreamError::IdExhausted)
+    );
+    assert_eq!(stream.last_id(), StreamId::MAX);
+}
+
+#[test]
+fn range_entries() {
+    // Entries span several nodes.
+    let mut stream = RStream::new();
+    for i in 1..=350u64 {
+        let value = i.to_string();
+        stream
+            .add(
+                XAddId::Explicit(id(i / 10, i % 10)),
+                &[("n", value.as_str())],
+            )
+            .unwrap();
+    }
+    assert_eq!(stream.len(), 350);
+    assert_eq!(stream.first_id(), Some(id(0, 1)));
+    assert_eq!(stream.iter().count(), 350);
+    assert_eq!(stream.iter().rev().count(), 350);
+
+    let all: Vec<_> = (1..=350u64).map(|i| id(i / 10, i % 10)).collect();
+    assert_eq!(ids(stream.iter()), all);
+    assert_eq!(
+        ids(stream.range(id(9, 5)..=id(12, 3))),
+        all[94..=122].to_vec()
+    );
+    assert_eq!(ids(stream.range(id(9, 5)..id(12, 3)).rev()), {
+        let mut rev = all[94..122].to_vec();
+        rev.reverse();
+        rev
+    });
+    assert_eq!(
+        ids(stream.range((Bound::Excluded(id(0, 9)), Bound::Included(id(1, 1))))),
+        vec![id(1, 0), id(1, 1)]
+    );
+    assert_eq!(ids(stream.range(id(20, 0)..)).len(), 151);
+    assert_eq!(ids(stream.range(id(99, 0)..)), vec![]);
+    assert_eq!(ids(stream.range(id(3, 0)..id(3, 0))), vec![]);
+    assert_eq!(ids(stream.range(id(3, 0)..=id(2, 0))), vec![]);
+
+    // Iterated from both ends.
+    let mut iter = stream.range(id(5, 0)..=id(25, 0));
+    assert_eq!(iter.next().map(|e| e.id()), Some(id(5, 0)));
+    assert_eq!(iter.next_back().map(|e| e.id()), Some(id(25, 0)));
+    assert_eq!(iter.count(), 199);
+
+    let entry = stream.range(id(12, 3)..).next().unwrap();
+    assert_eq!(fields(&entry), vec![(b"n".to_vec(), b"123".to_vec())]);
+}
+
+#[test]
+fn trim_entries() {
+    let new_stream = || {
+        let mut stream = RStream::new();
+        for i in 1..=250u64 {
+            stream
+                .add(XAddId::Explicit(id(i, 0)), &[("n", "v")])
+                .unwrap();
+        }
+        stream
+    };
+
+    // Whole nodes of 100 entries are removed ONLY, if approximate.
+    let mut stream = new_stream();
+    assert_eq!(stream.trim(StreamTrim::MaxLen(200), true), 0);
+    assert_eq!(stream.trim(StreamTrim::MaxLen(120), true), 100);
+    assert_eq!(stream.first_id(), Some(id(101, 0)));
+
+    let mut stream = new_stream();
+    assert_eq!(stream.trim(StreamTrim::MaxLen(120), false), 130);
+    assert_eq!(stream.len(), 120);
+    assert_eq!(stream.first_id(), Some(id(131, 0)));
+    assert_eq!(stream.iter().count(), 120);
+    assert_eq!(stream.trim(StreamTrim::MaxLen(120), false), 0);
+    assert_eq!(stream.trim(StreamTrim::MaxLen(0), false), 120);
+    assert!(stream.is_empty());
+    assert_eq!(stream.first_id(), None);
+    assert_eq!(stream.last_id(), id(250, 0));
+
+    let mut stream = new_stream();
+    assert_eq!(stream.trim(StreamTrim::MinId(id(150, 0)), true), 100);
+    assert_eq!(stream.trim(StreamTrim::MinId(id(150, 0)), false), 49);
+    assert_eq!(stream.first_id(), Some(id(150, 0)));
+    assert_eq!(stream.range(..id(160, 0)).count(), 10);
+    assert_eq!(stream.range(..id(160, 0)).rev().count(), 10);
+
+    // Entries are added after trimming.
+    stream.add(XAddId::Auto, &[("n", "v")]).unwrap();
+    assert_eq!(stream.len(), 102);
+}
+
+fn stream_of(n: u64) -> RStream {
+    let mut stream = RStream::new();
+    for i in 1..=n {
+        stream
+            .add(XAddId::Explicit(id(i, 0)), &[("n", "v")])
+            .unwrap();
+    }
+    stream
+}
+
+#[test]
+fn consumer_groups() {
+    let mut stream = stream_of(5);
+    assert_eq!(
+        stream.read_group(b"g", b"alice", None, false, 0).err(),
+        Some(StreamError::NoGroup)
+    );
+    stream.create_group(b"g", StreamId::MIN).unwrap();
+    assert_eq!(
+        stream.create_group(b"g", StreamId::MIN).err(),
+        Some(StreamError::BusyGroup)
+    );
+    let last_id = stream.last_id();
+    stream.create_group(b"tail", last_id).unwrap();
+
+    let read = stream
+        .read_group(b"g", b"alice", Some(2), false, 1000)
+        .unwrap();
+    assert_eq!(ids(read.into_iter()), vec![id(1, 0), id(2, 0)]);
+    let read = stream.read_group(b"g", b"bob", None, false, 2000).unwrap();
+    assert_eq!(ids(read.into_iter()), vec![id(3, 0), id(4, 0), id(5, 0)]);
+    assert!(stream
+        .read_group(b"g", b"bob", None, false, 2000)
+        .unwrap()
+        .is_empty());
+    assert!(stream
+        .read_group(b"tail", b"carol", None, true, 2000)
+        .unwrap()
+        .is_empty());
+
+    let group = stream.group(b"g").unwrap();
+    assert_eq!(group.last_id(), id(5, 0));
+    assert_eq!(group.pending_len(), 5);
+    let nack = group.pending(id(2, 0)).unwrap();
+    assert_eq!(nack.consumer(), b"alice");
+    assert_eq!((nack.delivery_time(), nack.delivery_count()), (1000, 1));
+    assert_eq!(group.pending_range(id(2, 0)..=id(3, 0)).count(), 2);
+    let names: Vec<_> = group.consumers().map(|(name, _)| name.to_bytes()).collect();
+    assert_eq!(names, vec![b"alice".to_vec(), b"bob".to_vec()]);
+    let bob = group.consumer(b"bob").unwrap();
+    assert_eq!(bob.seen_time(), 2000);
+    assert_eq!(
+        bob.pending_ids().collect::<Vec<_>>(),
+        vec![id(3, 0), id(4, 0), id(5, 0)]
+    );
+
+    // Pending entries are delivered again, with entries trimmed as `None`.
+    stream.trim(StreamTrim::MaxLen(4), false);
+    let read = stream
+        .read_pending(b"g", b"alice", StreamId::MIN, None, 3000)
+        .unwrap();
+    let read: Vec<_> = read
+        .into_iter()
+        .map(|(id, entry)| (id, entry.is_some()))
+        .collect();
+    assert_eq!(read, vec![(id(1, 0), false), (id(2, 0), true)]);
+    let nack = stream.group(b"g").unwrap().pending(id(2, 0)).unwrap();
+    assert_eq!((nack.delivery_time(), nack.delivery_count()), (3000, 2));
+
+    // Acknowledged entries are removed from the PEL.
+    let group = stream.group_mut(b"g").unwrap();
+    assert_eq!(group.ack(&[id(1, 0), id(3, 0), id(3, 0), id(9, 0)]), 2);
+    assert_eq!(group.pending_len(), 3);
+    assert_eq!(group.consumer(b"bob").unwrap().pending_len(), 2);
+    assert_eq!(group.remove_consumer(b"bob"), Some(2));
+    assert_eq!(group.remove_consumer(b"bob"), None);
+    assert_eq!(group.pending_len(), 1);
+    assert!(group.create_consumer(b"bob", 4000));
+    assert!(!group.create_consumer(b"bob", 4000));
+
+    // Entries are delivered again after the last ID is set back, WITHOUT acknowledged.
+    group.set_last_id(id(3, 0));
+    let read = stream
+        .read_group(b"g", b"bob", Some(1), true, 5000)
+        .unwrap();
+    assert_eq!(ids(read.into_iter()), vec![id(4, 0)]);
+    assert_eq!(stream.group(b"g").unwrap().pending_len(), 1);
+
+    assert!(stream.remove_group(b"tail"));
+    assert!(!stream.remove_group(b"tail"));
+    assert_eq!(stream.groups().count(), 1);
+}
+
+#[test]
+fn claim_pending() {
+    let mut stream = stream_of(31);
+    stream.create_group(b"g", StreamId::MIN).unwrap();
+    stream
+        .read_group(b"g", b"alice", Some(20), false, 1000)
+        .unwrap();
+    stream.read_group(b"g", b"bob", None, false, 5000).unwrap();
+
+    // ONLY entries idle long enough are claimed.
+    let claimed = stream
+        .claim(b"g", b"bob", 3000, &[id(1, 0), id(25, 0), id(99, 0)], 6000)
+        .unwrap();
+    assert_eq!(ids(claimed.into_iter()), vec![id(1, 0)]);
+    let group = stream.group(b"g").unwrap();
+    let nack = group.pending(id(1, 0)).unwrap();
+    assert_eq!(nack.consumer(), b"bob");
+    assert_eq!((nack.delivery_time(), nack.delivery_count()), (6000, 2));
+    assert_eq!(group.consumer(b"alice").unwrap().pending_len(), 19);
+    assert_eq!(group.consumer(b"bob").unwrap().pending_len(), 12);
+
+    // Entries trimmed are removed from the PEL once claimed.
+    stream.trim(StreamTrim::MinId(id(4, 0)), false);
+    let claimed = stream.claim(b"g", b"carol", 0, &[id(2, 0)], 7000).unwrap();
+    assert!(claimed.is_empty());
+    assert!(stream.group(b"g").unwrap().pending(id(2, 0)).is_none());
+
+    let result = stream
+        .auto_claim(b"g", b"carol", 3000, StreamId::MIN, 5, 7000)
+        .unwrap();
+    assert_eq!(result.deleted, vec![id(1, 0), id(3, 0)]);
+    assert_eq!(
+        ids(result.claimed.into_iter()),
+        (4..=8).map(|i| id(i, 0)).collect::<Vec<_>>()
+    );
+    assert_eq!(result.next, id(9, 0));
+
+    // At most 10 times of `count` are scanned.
+    let result = stream
+        .auto_claim(b"g", b"carol", 3000, id(9, 0), 1, 7000)
+        .unwrap();
+    assert_eq!(ids(result.claimed.into_iter()), vec![id(9, 0)]);
+    assert_eq!(result.next, id(10, 0));
+    let result = stream
+        .auto_claim(b"g", b"dave", 3000, id(21, 0), 1, 7000)
+        .unwrap();
+    assert!(result.claimed.is_empty());
+    assert_eq!(result.next, id(31, 0));
+    let result = stream
+        .auto_claim(b"g", b"dave", 0, id(31, 0), 1, 7000)
+        .unwrap();
+    assert_eq!(ids(result.claimed.into_iter()), vec![id(31, 0)]);
+    assert_eq!(result.next, StreamId::MIN);
+    assert_eq!(stream.group(b"g").unwrap().consumers().count(), 4);
+}
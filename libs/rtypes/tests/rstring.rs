@@ -1,4 +1,4 @@
-use rtypes::RString;
+use rtypes::{BitOp, ParseNumError, RString};
 
 #[test]
 fn create_rstr() {
@@ -56,3 +56,580 @@ fn basic_ops_on_rstr() {
     s.clear();
     assert_eq!(s, RString::new());
 }
+
+#[test]
+fn append_rstr_with_headroom() {
+    let mut s = RString::new();
+    let mut reallocs = 0;
+    for _ in 0..1000 {
+        let cap = s.capacity();
+        s.append_bytes(b"0123456789");
+        if s.capacity() != cap {
+            reallocs += 1;
+        }
+    }
+    assert_eq!(s.len(), 10000);
+    assert!(s.capacity() >= 10000);
+    assert!(reallocs <= 16);
+}
+
+#[test]
+fn rstr_as_single_pointer() {
+    assert_eq!(std::mem::size_of::<RString>(), std::mem::size_of::<usize>());
+
+    let mut s = RString::from_str("RString");
+    s.shrink_to_fit();
+    assert_eq!(s.len(), 7);
+    assert!(s.capacity() >= 7);
+    s.append_str(" Type");
+    assert_eq!(s, RString::from_str("RString Type"));
+}
+
+#[test]
+fn shrink_capacity() {
+    let mut s = RString::with_capacity(1000);
+    s.append_bytes(&[b'x'; 600]);
+    assert!(s.capacity() >= 1000);
+    s.shrink_to_fit();
+    assert!((600..1000).contains(&s.capacity()));
+    assert_eq!(s.as_bytes(), &[b'x'; 600][..]);
+
+    s.truncate(100);
+    s.shrink_to(200);
+    assert!((200..600).contains(&s.capacity()));
+    s.shrink_to(1000);
+    assert!(s.capacity() < 600);
+}
+
+#[test]
+fn borrow_rstr_as_bytes() {
+    fn count_spaces(bytes: &[u8]) -> usize {
+        bytes.iter().filter(|&&byte| byte == b' ').count()
+    }
+
+    let mut s = RString::from_str("Hello RString Type");
+    assert_eq!(count_spaces(&s), 2);
+    assert_eq!(s.as_ref(), b"Hello RString Type");
+    assert!(s.starts_with(b"Hello"));
+
+    assert_eq!(s[0], b'H');
+    assert_eq!(&s[6..13], b"RString");
+    assert_eq!(&s[14..], b"Type");
+    s[0] = b'h';
+    s[14..].copy_from_slice(b"TYPE");
+    assert_eq!(s, RString::from_str("hello RString TYPE"));
+
+    let mut map = std::collections::HashMap::new();
+    map.insert(RString::from_str("key"), 1);
+    assert_eq!(map.get(&b"key"[..]), Some(&1));
+
+    let mut set = std::collections::BTreeSet::new();
+    set.insert(RString::from_str("member"));
+    assert!(set.contains(&b"member"[..]));
+}
+
+#[test]
+#[should_panic]
+fn index_rstr_out_of_bounds() {
+    let s = RString::from_str("RString");
+    let _ = s[7];
+}
+
+#[test]
+fn write_fmt_into_rstr() {
+    use std::fmt::Write;
+
+    let mut s = RString::new();
+    write!(s, "*{}\r\n", 2).unwrap();
+    s.write_char('$').unwrap();
+    s.append_fmt(format_args!("{}\r\n{}\r\n", 5, "hello"));
+    assert_eq!(s.as_bytes(), b"*2\r\n$5\r\nhello\r\n");
+
+    s.clear();
+    s.append_fmt(format_args!("{:.2}", 1.5f64));
+    assert_eq!(s, RString::from_str("1.50"));
+}
+
+#[test]
+fn parse_integers_from_rstr() {
+    let parse = |s: &str| RString::from_str(s).parse_i64();
+
+    assert_eq!(parse("0"), Ok(0));
+    assert_eq!(parse("12345"), Ok(12345));
+    assert_eq!(parse("-1"), Ok(-1));
+    assert_eq!(parse("9223372036854775807"), Ok(i64::MAX));
+    assert_eq!(parse("-9223372036854775808"), Ok(i64::MIN));
+
+    for invalid in [
+        "",
+        "-",
+        "-0",
+        "007",
+        "+1",
+        " 1",
+        "1 ",
+        "1a",
+        "9223372036854775808",
+        "-9223372036854775809",
+        "99999999999999999999",
+        "123456789012345678901",
+    ] {
+        assert_eq!(parse(invalid), Err(ParseNumError::Integer), "{:?}", invalid);
+    }
+}
+
+#[test]
+fn parse_floats_from_rstr() {
+    let parse = |s: &str| RString::from_str(s).parse_f64();
+
+    assert_eq!(parse("10"), Ok(10f64));
+    assert_eq!(parse("-1.5"), Ok(-1.5));
+    assert_eq!(parse("1e3"), Ok(1000f64));
+    assert_eq!(parse("9007199254740993"), Ok(9007199254740992f64));
+    assert_eq!(parse("inf"), Ok(f64::INFINITY));
+
+    for invalid in ["", " 1", "1 ", "nan", "1.5x", "１"] {
+        assert_eq!(parse(invalid), Err(ParseNumError::Float), "{:?}", invalid);
+    }
+    assert_eq!(
+        ParseNumError::Float.to_string(),
+        "value is not a valid float"
+    );
+}
+
+#[test]
+fn format_integers_into_rstr() {
+    for value in [0, 7, -7, 10, 99, 100, -12345, i64::MAX, i64::MIN] {
+        assert_eq!(
+            RString::from_i64(value).as_bytes(),
+            value.to_string().as_bytes()
+        );
+    }
+
+    let mut s = RString::from_str("count:");
+    s.append_i64(-42);
+    assert_eq!(s, RString::from_str("count:-42"));
+    assert_eq!(s.rsub_rstr(6).parse_i64(), Ok(-42));
+}
+
+#[test]
+fn split_rstr_by_separator() {
+    let s = RString::from_str("key1::key2::::key3");
+    assert_eq!(
+        s.split(b"::"),
+        [
+            RString::from_str("key1"),
+            RString::from_str("key2"),
+            RString::new(),
+            RString::from_str("key3"),
+        ]
+    );
+    assert_eq!(s.split(b"|"), std::slice::from_ref(&s));
+    assert_eq!(RString::from_str("::").split(b"::").len(), 2);
+    assert!(s.split(b"").is_empty());
+    assert!(RString::new().split(b",").is_empty());
+}
+
+#[test]
+fn split_rstr_into_args() {
+    let split = |s: &[u8]| {
+        RString::from_bytes(s).split_args().map(|args| {
+            args.iter()
+                .map(|arg| arg.as_bytes().to_vec())
+                .collect::<Vec<_>>()
+        })
+    };
+
+    assert_eq!(split(b""), Some(vec![]));
+    assert_eq!(split(b" \t\r\n "), Some(vec![]));
+    assert_eq!(
+        split(b"  set  key value\n"),
+        Some(vec![b"set".to_vec(), b"key".to_vec(), b"value".to_vec()])
+    );
+    assert_eq!(
+        split(br#""a\"b\n\x4a\xZZ" '\'c\d' """#),
+        Some(vec![
+            b"a\"b\nJxZZ".to_vec(),
+            b"'c\\d".to_vec(),
+            b"".to_vec()
+        ])
+    );
+    assert_eq!(
+        split(b"mixed\"quoted part\" tail"),
+        Some(vec![b"mixedquoted part".to_vec(), b"tail".to_vec()])
+    );
+    assert_eq!(split(br#""unterminated"#), None);
+    assert_eq!(split(br#"'unterminated"#), None);
+    assert_eq!(split(br#""closed"tail"#), None);
+    assert_eq!(split(br#"'closed'tail"#), None);
+}
+
+#[test]
+fn change_rstr_case() {
+    let mut s = RString::from_bytes(b"Get\xffKEY_1");
+    s.make_lowercase();
+    assert_eq!(s.as_bytes(), b"get\xffkey_1");
+    s.make_uppercase();
+    assert_eq!(s.as_bytes(), b"GET\xffKEY_1");
+
+    let mut empty = RString::new();
+    empty.make_uppercase();
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn find_in_rstr() {
+    let s = RString::from_str("abcabcabd");
+    assert_eq!(s.find(b"abc"), Some(0));
+    assert_eq!(s.rfind(b"abc"), Some(3));
+    assert_eq!(s.find(b"abd"), Some(6));
+    assert_eq!(s.find(b"c"), Some(2));
+    assert_eq!(s.rfind(b"c"), Some(5));
+    assert_eq!(s.find(b"abcabcabdx"), None);
+    assert_eq!(s.find(b""), Some(0));
+    assert_eq!(s.rfind(b""), Some(s.len()));
+    assert!(s.contains(b"cab"));
+    assert!(!s.contains(b"abe"));
+
+    let empty = RString::new();
+    assert_eq!(empty.find(b"a"), None);
+    assert_eq!(empty.rfind(b"a"), None);
+    assert!(empty.contains(b""));
+}
+
+#[test]
+fn get_and_set_rstr_bits() {
+    let mut s = RString::from_str("`");
+    assert!(!s.get_bit(0));
+    assert!(s.get_bit(1) && s.get_bit(2));
+    assert!(!s.get_bit(8));
+
+    assert!(!s.set_bit(7, true));
+    assert_eq!(s.as_bytes(), b"a");
+    assert!(s.set_bit(7, true));
+    assert_eq!(s.len(), 1);
+
+    assert!(!s.set_bit(100, true));
+    assert_eq!(s.len(), 13);
+    assert!(s.get_bit(100));
+    assert_eq!(s.as_bytes()[12], 0x08);
+    assert_eq!(s.bit_count(1, 12), 0);
+    assert!(!s.set_bit(99, false));
+    assert_eq!(s.len(), 13);
+}
+
+#[test]
+fn count_rstr_bits() {
+    let mut s = RString::new();
+    assert_eq!(s.bit_count(0, 10), 0);
+
+    s.append_padding(0xff, 37);
+    s.append_padding(0x11, 3);
+    assert_eq!(s.bit_count(0, usize::MAX), 37 * 8 + 6);
+    assert_eq!(s.bit_count(30, 39), 7 * 8 + 4);
+    assert_eq!(s.bit_count(39, 30), 0);
+    assert_eq!(s.bit_count(40, 50), 0);
+}
+
+#[test]
+fn find_rstr_bits() {
+    let mut s = RString::new();
+    assert_eq!(s.bit_pos(true, 0), None);
+    assert_eq!(s.bit_pos(false, 0), None);
+
+    s.append_padding(0, 20);
+    assert_eq!(s.bit_pos(true, 0), None);
+    assert_eq!(s.bit_pos(false, 37), Some(37));
+    s.set_bit(150, true);
+    assert_eq!(s.bit_pos(true, 0), Some(150));
+    assert_eq!(s.bit_pos(true, 150), Some(150));
+    assert_eq!(s.bit_pos(true, 151), None);
+
+    let mut s = RString::new();
+    s.append_padding(0xff, 20);
+    assert_eq!(s.bit_pos(false, 0), None);
+    s.set_bit(77, false);
+    s.set_bit(130, false);
+    assert_eq!(s.bit_pos(false, 3), Some(77));
+    assert_eq!(s.bit_pos(false, 78), Some(130));
+    assert_eq!(s.bit_pos(true, 77), Some(78));
+}
+
+#[test]
+fn bitop_between_rstrs() {
+    let long = RString::from_bytes(&[0xaa; 21]);
+    let short = RString::from_bytes(&[0x0f; 10]);
+    let empty = RString::new();
+
+    let and = RString::bitop(BitOp::And, &[&long, &short]);
+    assert_eq!(and.len(), 21);
+    assert_eq!(&and[..10], &[0x0a; 10][..]);
+    assert_eq!(&and[10..], &[0; 11][..]);
+    assert_eq!(RString::bitop(BitOp::And, &[&short, &long]), and);
+
+    let or = RString::bitop(BitOp::Or, &[&short, &long, &empty]);
+    assert_eq!(&or[..10], &[0xaf; 10][..]);
+    assert_eq!(&or[10..], &[0xaa; 11][..]);
+
+    let xor = RString::bitop(BitOp::Xor, &[&long, &short, &short]);
+    assert_eq!(xor, long);
+
+    let not = RString::bitop(BitOp::Not, &[&long]);
+    assert_eq!(not.as_bytes(), &[0x55; 21][..]);
+    assert!(RString::bitop(BitOp::Not, &[&empty]).is_empty());
+
+    assert!(RString::bitop(BitOp::Or, &[]).is_empty());
+    assert!(RString::bitop(BitOp::And, &[&long, &empty])
+        .iter()
+        .all(|&byte| byte == 0));
+}
+
+#[test]
+#[should_panic(expected = "BITOP NOT with 2 sources")]
+fn bitop_not_with_sources() {
+    let s = RString::from_str("a");
+    RString::bitop(BitOp::Not, &[&s, &s]);
+}
+
+#[test]
+fn incr_rstr_number() {
+    let mut s = RString::from_str("-1");
+    assert_eq!(s.incr_by(1), Ok(0));
+    assert_eq!(s.as_bytes(), b"0");
+    assert_eq!(s.incr_by(i64::MIN), Ok(i64::MIN));
+    assert_eq!(s.incr_by(-1), Err(ParseNumError::Overflow));
+    assert_eq!(s.parse_i64(), Ok(i64::MIN));
+
+    let ptr = s.as_ptr();
+    assert_eq!(s.incr_by(i64::MAX), Ok(-1));
+    assert_eq!(s.as_ptr(), ptr);
+    assert_eq!(s.as_bytes(), b"-1");
+
+    let mut s = RString::from_str("1.5");
+    assert_eq!(s.incr_by(1), Err(ParseNumError::Integer));
+    assert_eq!(s.incr_by_float(1.5), Ok(3.0));
+    assert_eq!(s.as_bytes(), b"3");
+    assert_eq!(s.incr_by(2), Ok(5));
+    assert_eq!(s.incr_by_float(-5.25), Ok(-0.25));
+    assert_eq!(s.as_bytes(), b"-0.25");
+    assert_eq!(
+        s.incr_by_float(f64::INFINITY),
+        Err(ParseNumError::NotFinite)
+    );
+    assert_eq!(s.as_bytes(), b"-0.25");
+
+    let mut s = RString::from_str("abc");
+    assert_eq!(s.incr_by_float(1.0), Err(ParseNumError::Float));
+    assert_eq!(
+        ParseNumError::Overflow.to_string(),
+        "increment or decrement would overflow"
+    );
+}
+
+#[test]
+fn reserve_rstr_with_headroom() {
+    let mut s = RString::new();
+    let mut reallocs = 0;
+    for _ in 0..10000 {
+        let capacity = s.capacity();
+        s.append_str("x");
+        if s.capacity() != capacity {
+            reallocs += 1;
+        }
+    }
+    assert!(reallocs <= 16, "{} reallocations", reallocs);
+
+    // Replacing beyond the end grows with headroom, and NEVER shrinks.
+    let mut s = RString::with_capacity(64);
+    s.replace_str(0, "abc");
+    assert!(s.capacity() >= 64);
+    s.replace_str(100, "def");
+    assert_eq!(s.len(), 103);
+    assert!(s.capacity() >= 206);
+    assert_eq!(&s[..4], b"abc\0");
+
+    let mut s = RString::from_str("abc");
+    s.reserve_exact(13);
+    let capacity = s.capacity();
+    assert!(capacity >= 16);
+    s.reserve_exact(10);
+    assert_eq!(s.capacity(), capacity);
+    s.reserve(capacity);
+    assert!(s.capacity() >= (capacity + 3) * 2);
+}
+
+#[test]
+fn repr_rstr() {
+    assert_eq!(RString::new().to_repr().as_bytes(), b"\"\"");
+    assert_eq!(
+        RString::from_bytes(b"\\\"\n\r\t\x07\x08\x7f ok\x01")
+            .to_repr()
+            .as_bytes(),
+        br#""\\\"\n\r\t\a\b\x7f ok\x01""#
+    );
+
+    let s = RString::from_bytes(b"key\xff");
+    let debug = format!("{:?}", s);
+    assert!(debug.starts_with("{ len: 4, cap: "));
+    assert!(debug.ends_with(r#"> "key\xff" }"#), "{}", debug);
+}
+
+#[test]
+fn rstr_from_and_into_vec() {
+    let s = RString::from_vec(b"binary\x00value".to_vec());
+    assert_eq!(s.as_bytes(), b"binary\x00value");
+    assert_eq!(s.to_bytes(), b"binary\x00value");
+    assert_eq!(s.into_vec(), b"binary\x00value");
+
+    let s = RString::from_string(String::from("text"));
+    assert_eq!(s, RString::from(String::from("text")));
+    assert_eq!(Vec::from(s), b"text");
+
+    assert!(RString::from(Vec::new()).is_empty());
+    assert!(RString::new().into_vec().is_empty());
+}
+
+#[test]
+fn compare_rstr_in_constant_time() {
+    let secret = RString::from_bytes(b"s3cret\x00pass");
+    assert!(secret.eq_constant_time(&secret.clone()));
+    assert!(!secret.eq_constant_time(&RString::from_bytes(b"s3cret\x00pasS")));
+    assert!(!secret.eq_constant_time(&RString::from_bytes(b"s3cret")));
+    assert!(!secret.eq_constant_time(&RString::from_bytes(b"s3cret\x00pass!")));
+    assert!(!secret.eq_constant_time(&RString::new()));
+    assert!(RString::new().eq_constant_time(&RString::new()));
+}
+
+#[test]
+fn compress_rstr_with_lzf() {
+    // Pseudo-random bytes mixed with repeated runs & phrases.
+    let mut seed = 0x2545_f491u32;
+    let mut bytes = Vec::new();
+    for round in 0..2000 {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        match round % 4 {
+            0 => bytes.extend_from_slice(&seed.to_le_bytes()),
+            1 => bytes.resize(bytes.len() + seed as usize % 300, b'x'),
+            2 => bytes.extend_from_slice(b"user:1000:session"),
+            _ => bytes.extend_from_within(bytes.len() / 2..bytes.len() / 2 + 40),
+        }
+    }
+
+    for len in [2, 3, 4, 5, 31, 32, 33, 100, 1000, 9000, bytes.len()].iter() {
+        let s = RString::from_bytes(&bytes[..*len]);
+        if let Some(compressed) = s.compress_lzf() {
+            assert!(compressed.len() < s.len());
+            assert_eq!(
+                compressed.decompress_lzf(s.len()).as_ref(),
+                Some(&s),
+                "{}",
+                len
+            );
+            assert_eq!(compressed.decompress_lzf(s.len() - 1), None);
+            assert_eq!(compressed.decompress_lzf(s.len() + 1), None);
+        }
+    }
+
+    let s = RString::from_bytes(&bytes);
+    assert!(s.compress_lzf().unwrap().len() < s.len() / 2);
+    assert_eq!(RString::new().compress_lzf(), None);
+    assert_eq!(RString::from_str("a").compress_lzf(), None);
+    assert_eq!(RString::from_bytes(&bytes[..4]).compress_lzf(), None);
+}
+
+#[test]
+fn decompress_rstr_with_lzf() {
+    // Literal run "abc", then 6 bytes referenced from 3 bytes back.
+    let compressed = RString::from_bytes(b"\x02abc\x80\x02");
+    assert_eq!(
+        compressed.decompress_lzf(9),
+        Some(RString::from_str("abcabcabc"))
+    );
+
+    // Truncated literal run, out-of-range or truncated references.
+    assert_eq!(RString::from_bytes(b"\x05abc").decompress_lzf(6), None);
+    assert_eq!(
+        RString::from_bytes(b"\x02abc\x80\x05").decompress_lzf(9),
+        None
+    );
+    assert_eq!(RString::from_bytes(b"\x02abc\xe0").decompress_lzf(9), None);
+    assert_eq!(RString::new().decompress_lzf(0), Some(RString::new()));
+}
+
+#[test]
+fn format_into_rstr() {
+    let s = rtypes::rformat!("+OK\r\n");
+    assert_eq!(s.as_bytes(), b"+OK\r\n");
+
+    let (key, ttl) = ("session", -1);
+    let s = rtypes::rformat!("{}:{:>4}|{:x}", key, ttl, 255u8);
+    assert_eq!(s.as_bytes(), b"session:  -1|ff");
+    assert_eq!(
+        RString::from_fmt(format_args!("{}", 1.5)),
+        RString::from_str("1.5")
+    );
+    assert!(rtypes::rformat!("").is_empty());
+}
+
+#[test]
+fn collect_and_convert_rstr() {
+    use std::convert::TryFrom;
+
+    let s: RString = b"hello"
+        .iter()
+        .map(|byte| byte.to_ascii_uppercase())
+        .collect();
+    assert_eq!(s.as_bytes(), b"HELLO");
+    let s: RString = b"bytes".iter().collect();
+    assert_eq!(s.as_bytes(), b"bytes");
+
+    let mut s = RString::from("key");
+    s.extend(b":".iter());
+    s.extend((b'0'..=b'9').rev());
+    assert_eq!(s.as_bytes(), b"key:9876543210");
+    s.extend(std::iter::empty::<u8>());
+    assert_eq!(s.len(), 14);
+
+    assert_eq!(RString::from(&b"\x00\xff"[..]).as_bytes(), b"\x00\xff");
+    assert_eq!(String::try_from(s).unwrap(), "key:9876543210");
+    let err = String::try_from(RString::from(&b"bad\xff"[..])).unwrap_err();
+    assert_eq!(err.into_bytes(), b"bad\xff");
+}
+
+#[test]
+fn append_rstr_into_usable_slack() {
+    for requested in 1..=100 {
+        let mut s = RString::with_capacity(requested);
+        assert!(s.capacity() >= requested);
+
+        // Appending up to the usable size NEVER reallocates.
+        let (ptr, capacity) = (s.as_ptr(), s.capacity());
+        s.append_padding(b'x', s.avail());
+        assert!(s.is_full());
+        assert_eq!(s.as_ptr(), ptr);
+        assert_eq!(s.capacity(), capacity);
+    }
+}
+
+#[test]
+fn push_and_pop_rstr_bytes() {
+    let mut s = RString::new();
+    for byte in b"*3\r\n".iter() {
+        s.push(*byte);
+    }
+    assert_eq!(s.as_bytes(), b"*3\r\n");
+    assert_eq!(s.bytes().filter(u8::is_ascii_digit).count(), 1);
+
+    let chunks: Vec<_> = s.chunks(3).collect();
+    assert_eq!(chunks, [&b"*3\r"[..], &b"\n"[..]]);
+    assert_eq!(RString::new().chunks(3).count(), 0);
+
+    assert_eq!(s.pop(), Some(b'\n'));
+    assert_eq!(s.pop(), Some(b'\r'));
+    assert_eq!(s.as_bytes(), b"*3");
+    s.clear();
+    assert_eq!(s.pop(), None);
+    assert_eq!(s.bytes().next(), None);
+}
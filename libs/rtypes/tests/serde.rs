@@ -0,0 +1,41 @@
+#![cfg(feature = "serde")]
+
+use rtypes::{RString, SharedRStr};
+use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_tokens, Token};
+
+#[test]
+fn serde_rstr_as_bytes() {
+    assert_tokens(
+        &RString::from_bytes(b"\x00key\xff"),
+        &[Token::Bytes(b"\x00key\xff")],
+    );
+    assert_tokens(&RString::new(), &[Token::Bytes(b"")]);
+
+    let s = RString::from_str("value");
+    assert_de_tokens(&s, &[Token::BorrowedBytes(b"value")]);
+    assert_de_tokens(&s, &[Token::ByteBuf(b"value")]);
+    assert_de_tokens(&s, &[Token::Str("value")]);
+    assert_de_tokens(&s, &[Token::String("value")]);
+}
+
+#[test]
+fn deserialize_rstr_from_seq() {
+    assert_de_tokens(
+        &RString::from_str("ok"),
+        &[
+            Token::Seq { len: Some(2) },
+            Token::U8(b'o'),
+            Token::U8(b'k'),
+            Token::SeqEnd,
+        ],
+    );
+    assert_de_tokens_error::<RString>(
+        &[Token::I32(1)],
+        "invalid type: integer `1`, expected bytes or a string",
+    );
+}
+
+#[test]
+fn serde_shared_rstr() {
+    assert_tokens(&SharedRStr::from("key"), &[Token::Bytes(b"key")]);
+}
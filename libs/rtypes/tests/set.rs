@@ -0,0 +1,137 @@
+use std::collections::HashSet;
+
+use rtypes::{Encoding, EncodingLimits, RSet};
+
+fn members(set: &RSet) -> HashSet<Vec<u8>> {
+    set.iter().map(|member| member.to_bytes()).collect()
+}
+
+#[test]
+fn convert_encodings() {
+    // intset -> hashtable, with too many integers.
+    let mut set = RSet::new();
+    for i in 0..512 {
+        assert!(set.add(i.to_string().as_bytes()));
+    }
+    assert_eq!(set.encoding(), Encoding::IntSet);
+    assert!(!set.add(b"0"));
+    assert!(set.add(b"-1"));
+    assert_eq!(set.encoding(), Encoding::HashTable);
+    assert_eq!(set.len(), 513);
+    assert!(set.contains(b"511") && set.contains(b"-1"));
+
+    // intset -> listpack -> hashtable, with too many members.
+    let mut set = RSet::new();
+    set.add(b"1");
+    set.add(b"01");
+    assert_eq!(set.encoding(), Encoding::ListPack);
+    assert!(set.contains(b"1") && set.contains(b"01"));
+    for i in 2..128 {
+        set.add(format!("m{}", i).as_bytes());
+    }
+    assert_eq!(set.encoding(), Encoding::ListPack);
+    assert!(!set.add(b"m2"));
+    assert!(set.add(b"m128"));
+    assert_eq!(set.encoding(), Encoding::HashTable);
+    assert_eq!(set.len(), 129);
+
+    // listpack -> hashtable, with a long member.
+    let mut set = RSet::new();
+    set.add(b"a");
+    set.add(&[b'x'; 64]);
+    assert_eq!(set.encoding(), Encoding::ListPack);
+    set.add(&[b'x'; 65]);
+    assert_eq!(set.encoding(), Encoding::HashTable);
+
+    // intset -> hashtable, with a long member.
+    let mut set = RSet::new();
+    set.add(b"1");
+    set.add(&[b'x'; 65]);
+    assert_eq!(set.encoding(), Encoding::HashTable);
+    let expected: HashSet<_> = vec![b"1".to_vec(), vec![b'x'; 65]].into_iter().collect();
+    assert_eq!(members(&set), expected);
+}
+
+#[test]
+fn remove_and_pop() {
+    for &(count, encoding) in [
+        (10, Encoding::IntSet),
+        (100, Encoding::ListPack),
+        (1000, Encoding::HashTable),
+    ]
+    .iter()
+    {
+        let mut set = RSet::new();
+        for i in 0..count {
+            match encoding {
+                Encoding::IntSet => set.add(i.to_string().as_bytes()),
+                _ => set.add(format!("m{}", i).as_bytes()),
+            };
+        }
+        assert_eq!(set.encoding(), encoding);
+        let mut expected = members(&set);
+        assert_eq!(expected.len(), count);
+        assert_eq!(set.iter().len(), count);
+
+        let first = expected.iter().next().unwrap().clone();
+        assert!(set.remove(&first));
+        assert!(!set.remove(&first));
+        assert!(!set.contains(&first));
+        expected.remove(&first);
+
+        let member = set.random_member().unwrap().to_bytes();
+        assert!(expected.contains(&member));
+        while let Some(member) = set.pop_random() {
+            assert!(expected.remove(&member.to_bytes()));
+        }
+        assert!(expected.is_empty());
+        assert!(set.is_empty());
+        assert_eq!(set.encoding(), encoding);
+        assert_eq!(set.random_member(), None);
+    }
+}
+
+#[test]
+fn limit_boundaries() {
+    let limits = EncodingLimits {
+        set_max_intset_entries: 3,
+        set_max_listpack_entries: 4,
+        set_max_listpack_value: 5,
+        ..EncodingLimits::default()
+    };
+
+    // intset -> listpack, with a member NOT an integer.
+    let mut set = RSet::with_limits(&limits);
+    for member in ["1", "2", "3"].iter() {
+        set.add(member.as_bytes());
+    }
+    assert_eq!(set.encoding(), Encoding::IntSet);
+    set.add(b"xxxxx");
+    assert_eq!(set.encoding(), Encoding::ListPack);
+    set.add(b"y");
+    assert_eq!(set.encoding(), Encoding::HashTable);
+    assert_eq!(set.len(), 5);
+
+    // intset -> hashtable, once it has as many members as listpacks allow.
+    let mut set = RSet::with_limits(&limits);
+    for member in ["1", "2", "3"].iter() {
+        set.add(member.as_bytes());
+    }
+    set.add(b"4");
+    assert_eq!(set.encoding(), Encoding::HashTable);
+    let mut set = RSet::with_limits(&EncodingLimits {
+        set_max_listpack_entries: 3,
+        ..limits
+    });
+    for member in ["1", "2", "3"].iter() {
+        set.add(member.as_bytes());
+    }
+    set.add(b"x");
+    assert_eq!(set.encoding(), Encoding::HashTable);
+
+    // intset -> hashtable, with a long member.
+    let mut set = RSet::with_limits(&limits);
+    set.add(b"1");
+    set.add(b"xxxxxx");
+    assert_eq!(set.encoding(), Encoding::HashTable);
+}
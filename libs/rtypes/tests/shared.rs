@@ -0,0 +1,56 @@
+use std::collections::HashSet;
+
+use rtypes::{RString, SharedRStr};
+
+#[test]
+fn share_rstr() {
+    let key = SharedRStr::from(RString::from_str("user:1"));
+    let expires = key.clone();
+    let repl = key.clone();
+    assert_eq!(key.ref_count(), 3);
+    assert!(SharedRStr::ptr_eq(&key, &repl));
+    assert_eq!(key.as_ptr(), expires.as_ptr());
+
+    drop(expires);
+    assert_eq!(repl.ref_count(), 2);
+    assert_eq!(repl.into_rstr(), RString::from_str("user:1"));
+    assert!(key.is_unique());
+
+    // Converted WITHOUT copies while referenced exclusively.
+    let ptr = key.as_ptr();
+    assert_eq!(key.into_rstr().as_ptr(), ptr);
+}
+
+#[test]
+fn copy_shared_rstr_on_write() {
+    let mut key = SharedRStr::from(&b"key"[..]);
+    let ptr = key.as_ptr();
+    key.make_mut().append_str("1");
+    assert_eq!(key.as_ptr(), ptr);
+
+    let other = key.clone();
+    key.make_mut().make_uppercase();
+    assert!(!SharedRStr::ptr_eq(&key, &other));
+    assert_eq!(key.as_bytes(), b"KEY1");
+    assert_eq!(other.as_bytes(), b"key1");
+    assert!(key.is_unique() && other.is_unique());
+}
+
+#[test]
+fn lookup_shared_rstr_by_bytes() {
+    let mut keys = HashSet::new();
+    keys.insert(SharedRStr::from("a"));
+    keys.insert(SharedRStr::from("b"));
+    assert!(!keys.insert(SharedRStr::from("a")));
+    assert!(keys.contains(&b"a"[..]));
+    assert!(!keys.contains(&b"c"[..]));
+
+    assert!(SharedRStr::from("a") < SharedRStr::from("b"));
+    assert_eq!(SharedRStr::new(), SharedRStr::from(""));
+    assert_eq!(SharedRStr::from("key").to_string(), "key");
+
+    let shared = SharedRStr::from("key");
+    std::thread::spawn(move || assert_eq!(shared.len(), 3))
+        .join()
+        .unwrap();
+}
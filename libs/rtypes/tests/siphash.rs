@@ -0,0 +1,98 @@
+use std::hash::{BuildHasher, Hasher};
+
+use rtypes::{RDict, RString, SipBuildHasher, SipHasher13};
+
+const K0: u64 = 0x0706050403020100;
+const K1: u64 = 0x0f0e0d0c0b0a0908;
+
+/// Reference hashes of bytes `[0, len)` with the key bytes `[0, 16)`.
+const VECTORS: [(usize, u64); 7] = [
+    (0, 0xabac0158050fc4dc),
+    (1, 0xc9f49bf37d57ca93),
+    (7, 0xd3927d989bb11140),
+    (8, 0x369095118d299a8e),
+    (15, 0xd320d86d2a519956),
+    (16, 0xcc4fdd1a7d908b66),
+    (63, 0x9d199062b7bbb3a8),
+];
+
+#[test]
+fn reference_vectors() {
+    let msg: Vec<u8> = (0..64).collect();
+    for &(len, hash) in VECTORS.iter() {
+        assert_eq!(
+            SipHasher13::hash_bytes(K0, K1, &msg[..len]),
+            hash,
+            "{}",
+            len
+        );
+    }
+
+    // Written in pieces NOT aligned to words.
+    let mut hasher = SipHasher13::new_with_keys(K0, K1);
+    hasher.write(&msg[..3]);
+    hasher.write(&msg[3..12]);
+    hasher.write(&msg[12..13]);
+    assert_eq!(hasher.finish(), 0x306f760c1229ffa7);
+    assert_eq!(hasher.clone().finish(), 0x306f760c1229ffa7);
+
+    let mut hasher = SipHasher13::new_with_keys(K0, K1);
+    for piece in msg.chunks(5) {
+        hasher.write(piece);
+    }
+    assert_eq!(hasher.finish(), SipHasher13::hash_bytes(K0, K1, &msg));
+}
+
+#[test]
+fn keyed_builder() {
+    let builder = SipBuildHasher::with_keys(K0, K1);
+    assert_eq!(builder.hash_bytes(&[0]), 0xc9f49bf37d57ca93);
+    assert_eq!(
+        builder.hash_one(RString::from_str("key")),
+        builder.hash_one(b"key".as_ref())
+    );
+
+    // The per-process key is the same for ALL builders, but unlike a fixed key.
+    let (a, b) = (SipBuildHasher::new(), SipBuildHasher::default());
+    assert_eq!(a, b);
+    assert_ne!(a, builder);
+    assert_eq!(a.hash_bytes(b"key"), b.hash_bytes(b"key"));
+    assert_eq!(format!("{:?}", a), "SipBuildHasher { .. }");
+
+    let mut hasher = a.build_hasher();
+    hasher.write(b"key");
+    assert_eq!(hasher.finish(), b.hash_bytes(b"key"));
+}
+
+#[test]
+fn dict_with_keys() {
+    let mut dict = RDict::with_hasher(SipBuildHasher::with_keys(K0, K1));
+    for i in 0..100 {
+        dict.insert(RString::from_i64(i), i);
+    }
+    assert_eq!(dict.get(b"42".as_ref()), Some(&42));
+    assert_eq!(dict.hasher(), &SipBuildHasher::with_keys(K0, K1));
+}
+
+#[test]
+fn write_integers() {
+    // Integers are written in little-endian, just like bytes are.
+    for split in 0..8 {
+        let msg: Vec<u8> = (0..23).collect();
+        let mut hasher = SipHasher13::new_with_keys(K0, K1);
+        hasher.write(&msg[..split]);
+        hasher.write_u8(msg[split]);
+        hasher.write_u16(u16::from_le_bytes([msg[split + 1], msg[split + 2]]));
+        hasher.write_u32(u32::from_le_bytes([3, 4, 5, 6].map(|i| msg[split + i])));
+        hasher.write_u64(u64::from_le_bytes(
+            [7, 8, 9, 10, 11, 12, 13, 14].map(|i| msg[split + i]),
+        ));
+        hasher.write(&msg[split + 15..]);
+        assert_eq!(
+            hasher.finish(),
+            SipHasher13::hash_bytes(K0, K1, &msg),
+            "{}",
+            split
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use rtypes::{RSkipList, RString};
+
+fn elements<'a>(iter: impl Iterator<Item = (f64, &'a RString)>) -> Vec<(f64, String)> {
+    iter.map(|(score, member)| (score, text(member))).collect()
+}
+
+fn text(member: &RString) -> String {
+    String::from_utf8_lossy(member.as_bytes()).into_owned()
+}
+
+fn member(i: usize) -> RString {
+    RString::from_str(&format!("m{:04}", i))
+}
+
+/// Build a list of members `m0000`.. with scores `i / 2`, i.e. every 2 members share
+/// the same score.
+fn filled(len: usize) -> RSkipList {
+    let mut list = RSkipList::new();
+    // Inserted in an order other than the sorted one.
+    for i in (0..len)
+        .rev()
+        .step_by(2)
+        .chain((0..len).step_by(2).skip(len % 2))
+    {
+        list.insert((i / 2) as f64, member(i));
+    }
+    list
+}
+
+#[test]
+fn insert_and_delete() {
+    let mut list = filled(100);
+    assert_eq!(list.len(), 100);
+    let expected: Vec<_> = (0..100)
+        .map(|i| ((i / 2) as f64, text(&member(i))))
+        .collect();
+    assert_eq!(elements(list.iter()), expected);
+    assert_eq!(
+        elements(list.iter().rev()),
+        expected.iter().rev().cloned().collect::<Vec<_>>()
+    );
+    assert_eq!(list.first().map(|(score, _)| score), Some(0.0));
+    assert_eq!(list.last().map(|(score, _)| score), Some(49.0));
+
+    // NOT found, with either the score or the member mismatched.
+    assert_eq!(list.delete(1.0, b"m0001"), None);
+    assert_eq!(list.delete(0.0, b"m0002"), None);
+    assert_eq!(list.delete(0.0, b"m0001").unwrap().as_bytes(), b"m0001");
+    assert_eq!(list.delete(49.0, b"m0099").unwrap().as_bytes(), b"m0099");
+    assert_eq!(list.len(), 98);
+    assert_eq!(list.last().map(|(score, _)| score), Some(49.0));
+
+    for i in (0..100).filter(|&i| i != 1 && i != 99) {
+        assert!(list.delete((i / 2) as f64, member(i).as_bytes()).is_some());
+    }
+    assert!(list.is_empty());
+    assert_eq!((list.first(), list.last()), (None, None));
+    assert_eq!(format!("{:?}", list), "[]");
+
+    list.insert(f64::INFINITY, member(0));
+    list.insert(f64::NEG_INFINITY, member(1));
+    assert_eq!(
+        elements(list.iter()),
+        vec![
+            (f64::NEG_INFINITY, "m0001".to_string()),
+            (f64::INFINITY, "m0000".to_string())
+        ]
+    );
+}
+
+#[test]
+fn ranks() {
+    let mut list = filled(1000);
+    for i in 0..1000 {
+        assert_eq!(list.rank((i / 2) as f64, member(i).as_bytes()), Some(i));
+        let (score, found) = list.get_by_rank(i).unwrap();
+        assert_eq!((score, found), ((i / 2) as f64, &member(i)));
+    }
+    assert_eq!(list.rank(0.0, b"m0002"), None);
+    assert_eq!(list.rank(1000.0, b"m0000"), None);
+    assert_eq!(list.get_by_rank(1000), None);
+    assert_eq!(list.get_by_rank(usize::MAX), None);
+
+    // Ranks are kept consistent after deletions.
+    for i in (0..1000).step_by(3) {
+        list.delete((i / 2) as f64, member(i).as_bytes());
+    }
+    for (rank, i) in (0..1000).filter(|i| i % 3 != 0).enumerate() {
+        assert_eq!(list.rank((i / 2) as f64, member(i).as_bytes()), Some(rank));
+        assert_eq!(list.get_by_rank(rank).unwrap().1, &member(i));
+    }
+}
+
+#[test]
+fn update_scores() {
+    let mut list = filled(10);
+    // Updated in place.
+    assert!(list.update_score(2.0, b"m0005", 2.5));
+    assert_eq!(list.rank(2.5, b"m0005"), Some(5));
+    // Reinserted (as the next score is NOT greater), then ordered by members.
+    assert!(list.update_score(2.0, b"m0004", 2.5));
+    assert_eq!(list.rank(2.5, b"m0004"), Some(4));
+    assert_eq!(list.rank(2.5, b"m0005"), Some(5));
+    // Moved, to the back & to the front.
+    assert!(list.update_score(0.0, b"m0000", 100.0));
+    assert!(list.update_score(4.0, b"m0009", -1.0));
+    assert!(!list.update_score(0.0, b"m0000", 1.0));
+    assert_eq!(list.rank(100.0, b"m0000"), Some(9));
+    assert_eq!(list.rank(-1.0, b"m0009"), Some(0));
+    assert_eq!(
+        elements(list.range_by_rank(..3)),
+        vec![
+            (-1.0, "m0009".to_string()),
+            (0.0, "m0001".to_string()),
+            (1.0, "m0002".to_string())
+        ]
+    );
+    assert_eq!(list.len(), 10);
+}
+
+#[test]
+fn ranges() {
+    use std::ops::Bound;
+
+    let list = filled(20);
+    let scores =
+        |iter| -> Vec<f64> { elements(iter).into_iter().map(|(score, _)| score).collect() };
+
+    assert_eq!(
+        scores(list.range_by_score(2.0..=3.0)),
+        vec![2.0, 2.0, 3.0, 3.0]
+    );
+    assert_eq!(scores(list.range_by_score(2.0..3.0)), vec![2.0, 2.0]);
+    assert_eq!(scores(list.range_by_score(8.5..)), vec![9.0, 9.0]);
+    assert_eq!(scores(list.range_by_score(..0.5)), vec![0.0, 0.0]);
+    assert_eq!(
+        scores(list.range_by_score((Bound::Excluded(1.0), Bound::Included(2.0)))),
+        vec![2.0, 2.0]
+    );
+    assert_eq!(list.range_by_score(..).len(), 20);
+    assert_eq!(
+        list.range_by_score(2.0..=3.0).next_back().unwrap().1,
+        &member(7)
+    );
+
+    // Empty ranges.
+    assert_eq!(list.range_by_score(2.2..2.8).count(), 0);
+    assert_eq!(list.range_by_score(10.0..).count(), 0);
+    assert_eq!(list.range_by_score(..0.0).count(), 0);
+    assert_eq!(
+        list.range_by_score((Bound::Excluded(2.0), Bound::Excluded(2.0)))
+            .count(),
+        0
+    );
+    assert_eq!(
+        list.range_by_score((Bound::Included(3.0), Bound::Included(2.0)))
+            .count(),
+        0
+    );
+
+    assert_eq!(scores(list.range_by_rank(5..=7)), vec![2.0, 3.0, 3.0]);
+    assert_eq!(list.range_by_rank(18..100).len(), 2);
+    assert_eq!(list.range_by_rank(..).next_back().unwrap().1, &member(19));
+    assert_eq!(list.range_by_rank(20..).count(), 0);
+    assert_eq!(
+        list.range_by_rank((Bound::Excluded(5), Bound::Excluded(6)))
+            .count(),
+        0
+    );
+}
@@ -0,0 +1,42 @@
+use std::io::{BufRead, Read, Write};
+
+use rtypes::RString;
+
+#[test]
+fn write_into_rstr() {
+    let mut s = RString::from_str("REDIS");
+    s.write_all(b"0011").unwrap();
+    assert_eq!(s.write(b"\xfa").unwrap(), 1);
+    write!(s, "{}", 42).unwrap();
+    s.flush().unwrap();
+    assert_eq!(s.as_bytes(), b"REDIS0011\xfa42");
+
+    let mut src = &b"streamed"[..];
+    std::io::copy(&mut src, &mut s).unwrap();
+    assert!(s.ends_with(b"streamed"));
+}
+
+#[test]
+fn read_from_rstr() {
+    let s = RString::from_str("line 1\nline 2\nrest");
+    let mut reader = s.reader();
+
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"line");
+    assert_eq!(reader.position(), 4);
+
+    let lines: Vec<_> = reader.clone().lines().map(Result::unwrap).collect();
+    assert_eq!(lines, [" 1", "line 2", "rest"]);
+
+    reader.consume(3);
+    assert_eq!(reader.fill_buf().unwrap(), b"line 2\nrest");
+    let mut rest = Vec::new();
+    assert_eq!(reader.read_to_end(&mut rest).unwrap(), 11);
+    assert_eq!(reader.read(&mut buf).unwrap(), 0);
+    assert!(reader.remaining().is_empty());
+
+    reader.consume(10);
+    assert_eq!(reader.position(), s.len());
+    assert!(RString::new().reader().fill_buf().unwrap().is_empty());
+}
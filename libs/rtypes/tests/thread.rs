@@ -0,0 +1,48 @@
+use std::sync::mpsc;
+use std::thread;
+
+use rtypes::{RStr, RString, SharedRStr, Utf8RString};
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn rstr_types_are_send_and_sync() {
+    assert_send_sync::<RString>();
+    assert_send_sync::<RStr<'_>>();
+    assert_send_sync::<SharedRStr>();
+    assert_send_sync::<Utf8RString>();
+}
+
+#[test]
+fn free_rstr_on_another_thread() {
+    let (sender, receiver) = mpsc::channel::<RString>();
+    // A lazy-free thread deallocating strings allocated by others.
+    let lazy_free = thread::spawn(move || receiver.iter().map(|s| s.len()).sum::<usize>());
+
+    for idx in 0..64 {
+        let mut s = RString::with_capacity(idx);
+        s.append_padding(b'x', idx * 3);
+        sender.send(s).unwrap();
+    }
+    drop(sender);
+    assert_eq!(lazy_free.join().unwrap(), (0..64).map(|idx| idx * 3).sum());
+}
+
+#[test]
+fn read_rstr_from_threads() {
+    let s = RString::from_str("shared across threads");
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                assert_eq!(s.find(b"across"), Some(7));
+                assert_eq!(s.as_rstr_view().rsub_rstr(14), RStr::from("threads"));
+            });
+        }
+    });
+
+    let mut s = s;
+    thread::scope(|scope| {
+        scope.spawn(|| s.append_str("!"));
+    });
+    assert!(s.ends_with(b"!"));
+}
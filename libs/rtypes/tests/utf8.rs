@@ -0,0 +1,55 @@
+use std::fmt::Write;
+
+use rtypes::{RString, Utf8RString};
+
+#[test]
+fn validate_utf8_once() {
+    let s = RString::from_str("naïve");
+    assert_eq!(s.as_str(), Some("naïve"));
+    assert_eq!(s.char_len(), Some(5));
+
+    let ptr = s.as_ptr();
+    let s = Utf8RString::from_rstr(s).unwrap();
+    assert_eq!(s.as_rstr().as_ptr(), ptr);
+    assert_eq!(s.len(), 6);
+    assert_eq!(s.char_len(), 5);
+
+    let invalid = RString::from_bytes(b"na\xc3");
+    assert_eq!(invalid.as_str(), None);
+    assert_eq!(invalid.char_len(), None);
+    let invalid = Utf8RString::from_rstr(invalid).unwrap_err();
+    assert_eq!(invalid.as_bytes(), b"na\xc3");
+
+    assert_eq!(RString::from(s).as_bytes(), "naïve".as_bytes());
+}
+
+#[test]
+fn utf8_char_ops() {
+    let mut s = Utf8RString::from("añb");
+    s.push('€');
+    write!(s, "{}", 1).unwrap();
+    assert_eq!(s.as_str(), "añb€1");
+    assert_eq!(s.to_string(), "añb€1");
+    assert_eq!(format!("{:?}", s), "\"añb€1\"");
+
+    assert_eq!(s.char_range(0, 2), "añ");
+    assert_eq!(s.char_range(1, 4), "ñb€");
+    assert_eq!(s.char_range(3, 100), "€1");
+    assert_eq!(s.char_range(5, 6), "");
+    assert_eq!(s.char_range(2, 2), "");
+    assert_eq!(s.char_range(3, 1), "");
+    // Multibyte characters before `start`, i.e. byte offsets beyond characters.
+    let multi = Utf8RString::from("héllo");
+    assert_eq!(multi.char_range(2, 5), "llo");
+    let multi = Utf8RString::from("世界abc");
+    assert_eq!(multi.char_range(1, 2), "界");
+    assert_eq!(multi.char_range(2, 10), "abc");
+
+    s.truncate_chars(4);
+    assert_eq!(s.as_str(), "añb€");
+    s.truncate_chars(10);
+    assert_eq!(s.char_len(), 4);
+    s.clear();
+    assert!(s.is_empty());
+    assert_eq!(Utf8RString::new(), s);
+}
@@ -0,0 +1,245 @@
+use std::ops::Bound;
+
+use rtypes::{Encoding, EncodingLimits, ListPackValue, RZSet, ZAddFlags, ZAddOutcome};
+
+fn members<'a>(iter: impl Iterator<Item = (f64, ListPackValue<'a>)>) -> Vec<String> {
+    iter.map(|(_, member)| String::from_utf8_lossy(member.to_rstring().as_bytes()).into_owned())
+        .collect()
+}
+
+// Limits of sets in `listpack` (by DEFAULT), and in `skiplist`.
+fn both_limits() -> [EncodingLimits; 2] {
+    let skiplist = EncodingLimits {
+        zset_max_listpack_entries: 0,
+        ..EncodingLimits::default()
+    };
+    [EncodingLimits::default(), skiplist]
+}
+
+#[test]
+fn add_with_flags() {
+    for limits in both_limits().iter() {
+        add_with_flags_in(RZSet::with_limits(limits));
+    }
+}
+
+fn add_with_flags_in(mut zset: RZSet) {
+    assert_eq!(
+        zset.add(1.0, b"a", ZAddFlags::NONE),
+        ZAddOutcome::Added(1.0)
+    );
+    assert_eq!(
+        zset.add(1.0, b"a", ZAddFlags::NONE),
+        ZAddOutcome::Unchanged(1.0)
+    );
+    assert_eq!(
+        zset.add(2.0, b"a", ZAddFlags::NONE),
+        ZAddOutcome::Updated(2.0)
+    );
+
+    // NX & XX
+    assert_eq!(zset.add(3.0, b"a", ZAddFlags::NX), ZAddOutcome::Skipped);
+    assert_eq!(zset.add(3.0, b"b", ZAddFlags::XX), ZAddOutcome::Skipped);
+    assert_eq!(zset.add(3.0, b"b", ZAddFlags::NX), ZAddOutcome::Added(3.0));
+    assert_eq!(
+        zset.add(4.0, b"b", ZAddFlags::XX),
+        ZAddOutcome::Updated(4.0)
+    );
+
+    // GT & LT, which NEVER prevent new members from being added.
+    assert_eq!(zset.add(1.0, b"a", ZAddFlags::GT), ZAddOutcome::Skipped);
+    assert_eq!(zset.add(2.0, b"a", ZAddFlags::GT), ZAddOutcome::Skipped);
+    assert_eq!(
+        zset.add(5.0, b"a", ZAddFlags::GT),
+        ZAddOutcome::Updated(5.0)
+    );
+    assert_eq!(zset.add(6.0, b"a", ZAddFlags::LT), ZAddOutcome::Skipped);
+    assert_eq!(
+        zset.add(0.0, b"a", ZAddFlags::LT),
+        ZAddOutcome::Updated(0.0)
+    );
+    assert_eq!(zset.add(9.0, b"c", ZAddFlags::LT), ZAddOutcome::Added(9.0));
+    let flags = ZAddFlags::XX | ZAddFlags::GT;
+    assert_eq!(zset.add(9.0, b"d", flags), ZAddOutcome::Skipped);
+
+    // INCR
+    let flags = ZAddFlags::INCR;
+    assert_eq!(zset.add(1.5, b"a", flags), ZAddOutcome::Updated(1.5));
+    assert_eq!(zset.add(1.5, b"d", flags), ZAddOutcome::Added(1.5));
+    assert_eq!(zset.add(0.0, b"d", flags), ZAddOutcome::Unchanged(1.5));
+    assert_eq!(
+        zset.add(-1.0, b"d", flags | ZAddFlags::GT),
+        ZAddOutcome::Skipped
+    );
+
+    // NaN
+    assert_eq!(zset.add(f64::NAN, b"e", ZAddFlags::NONE), ZAddOutcome::NaN);
+    zset.add(f64::INFINITY, b"e", ZAddFlags::NONE);
+    assert_eq!(zset.add(f64::NEG_INFINITY, b"e", flags), ZAddOutcome::NaN);
+    assert_eq!(zset.score(b"e"), Some(f64::INFINITY));
+
+    assert_eq!(zset.len(), 5);
+    assert_eq!(members(zset.iter()), vec!["a", "d", "b", "c", "e"]);
+    assert!(ZAddOutcome::Added(1.0).is_changed());
+    assert!(!ZAddOutcome::Unchanged(1.0).is_changed());
+    assert_eq!(ZAddOutcome::Skipped.score(), None);
+}
+
+#[test]
+#[should_panic(expected = "incompatible flags")]
+fn add_with_incompatible_flags() {
+    let flags = ZAddFlags::NX | ZAddFlags::GT;
+    assert!(!flags.is_valid());
+    assert!(!(ZAddFlags::GT | ZAddFlags::LT).is_valid());
+    assert!(!(ZAddFlags::NX | ZAddFlags::XX).is_valid());
+    assert!((ZAddFlags::XX | ZAddFlags::LT | ZAddFlags::INCR).is_valid());
+    assert_eq!(format!("{:?}", flags), "{NX, GT}");
+
+    RZSet::new().add(1.0, b"a", flags);
+}
+
+#[test]
+fn remove_and_rank() {
+    for limits in both_limits().iter() {
+        remove_and_rank_in(RZSet::with_limits(limits));
+    }
+}
+
+fn remove_and_rank_in(mut zset: RZSet) {
+    for i in 0..100 {
+        zset.add(
+            (i % 10) as f64,
+            format!("m{:02}", i).as_bytes(),
+            ZAddFlags::NONE,
+        );
+    }
+    assert_eq!(zset.len(), 100);
+    // m00, m10, .., m90, then m01, ..
+    assert_eq!(zset.rank(b"m00"), Some(0));
+    assert_eq!(zset.rank(b"m01"), Some(10));
+    assert_eq!(zset.rev_rank(b"m99"), Some(0));
+    assert_eq!(zset.rank(b"m"), None);
+
+    for i in (0..100).step_by(10) {
+        assert_eq!(zset.remove(format!("m{:02}", i).as_bytes()), Some(0.0));
+    }
+    assert_eq!(zset.remove(b"m00"), None);
+    assert_eq!(zset.len(), 90);
+    assert!(!zset.contains(b"m10"));
+    assert_eq!(zset.rank(b"m01"), Some(0));
+    assert_eq!(zset.rev_rank(b"m01"), Some(89));
+
+    // Moved to the end.
+    zset.add(100.0, b"m01", ZAddFlags::NONE);
+    assert_eq!(zset.rank(b"m01"), Some(89));
+    assert_eq!(zset.score(b"m01"), Some(100.0));
+}
+
+#[test]
+fn ranges() {
+    for limits in both_limits().iter() {
+        ranges_in(limits);
+    }
+}
+
+fn ranges_in(limits: &EncodingLimits) {
+    let mut zset = RZSet::with_limits(limits);
+    for (score, member) in [(1.0, "a"), (2.0, "b"), (2.0, "c"), (3.0, "d")].iter() {
+        zset.add(*score, member.as_bytes(), ZAddFlags::NONE);
+    }
+    assert_eq!(members(zset.range_by_rank(1..=2)), vec!["b", "c"]);
+    assert_eq!(members(zset.range_by_rank(3..10)), vec!["d"]);
+    assert_eq!(members(zset.range_by_score(2.0..=2.0)), vec!["b", "c"]);
+    assert_eq!(
+        members(zset.range_by_score(2.0..).rev()),
+        vec!["d", "c", "b"]
+    );
+    assert_eq!(zset.range_by_score(1.5..2.0).len(), 0);
+
+    let mut zset = RZSet::with_limits(limits);
+    for member in ["a", "b", "ba", "c", "d"].iter() {
+        zset.add(0.0, member.as_bytes(), ZAddFlags::NONE);
+    }
+    let lex = |min: Bound<&'static [u8]>, max: Bound<&'static [u8]>| -> Vec<String> {
+        members(zset.range_by_lex((min, max)))
+    };
+    assert_eq!(
+        lex(Bound::Included(b"b"), Bound::Excluded(b"c")),
+        vec!["b", "ba"]
+    );
+    assert_eq!(
+        lex(Bound::Excluded(b"b"), Bound::Unbounded),
+        vec!["ba", "c", "d"]
+    );
+    assert_eq!(lex(Bound::Unbounded, Bound::Included(b"a")), vec!["a"]);
+    assert_eq!(
+        lex(Bound::Excluded(b"d"), Bound::Unbounded),
+        Vec::<String>::new()
+    );
+    assert_eq!(members(zset.range_by_lex(..)).len(), 5);
+}
+
+#[test]
+fn limit_boundaries() {
+    let limits = EncodingLimits {
+        zset_max_listpack_entries: 3,
+        zset_max_listpack_value: 5,
+        ..EncodingLimits::default()
+    };
+    let mut zset = RZSet::with_limits(&limits);
+    for (score, member) in [(3.0, "ccccc"), (1.0, "a"), (2.0, "b")].iter() {
+        zset.add(*score, member.as_bytes(), ZAddFlags::NONE);
+    }
+    assert_eq!(zset.encoding(), Encoding::ListPack);
+    // Neither updated NOR skipped members convert it.
+    zset.add(0.0, b"ccccc", ZAddFlags::NONE);
+    zset.add(0.0, b"dddddd", ZAddFlags::XX);
+    assert_eq!(zset.encoding(), Encoding::ListPack);
+    zset.add(4.0, b"d", ZAddFlags::NONE);
+    assert_eq!(zset.encoding(), Encoding::SkipList);
+    assert_eq!(members(zset.iter()), vec!["ccccc", "a", "b", "d"]);
+
+    let mut zset = RZSet::with_limits(&limits);
+    zset.add(1.0, b"a", ZAddFlags::NONE);
+    zset.add(2.0, b"bbbbbb", ZAddFlags::NONE);
+    assert_eq!(zset.encoding(), Encoding::SkipList);
+    assert_eq!(zset.rank(b"bbbbbb"), Some(1));
+
+    // Sets are NEVER converted back.
+    zset.remove(b"bbbbbb");
+    assert_eq!(zset.encoding(), Encoding::SkipList);
+}
+
+#[test]
+fn scores_in_listpack() {
+    let scores = [
+        -0.0,
+        0.0,
+        0.1,
+        -2.5e-300,
+        1e300,
+        9007199254740992.0,
+        18014398509481988.0,
+        -42.0,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+    ];
+    let mut zset = RZSet::new();
+    for (i, score) in scores.iter().enumerate() {
+        zset.add(*score, i.to_string().as_bytes(), ZAddFlags::NONE);
+    }
+    assert_eq!(zset.encoding(), Encoding::ListPack);
+    for (i, score) in scores.iter().enumerate() {
+        let found = zset.score(i.to_string().as_bytes()).unwrap();
+        assert_eq!(found.to_bits(), score.to_bits());
+    }
+    let ordered: Vec<_> = zset.iter().map(|(score, _)| score).collect();
+    let mut sorted = ordered.clone();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(ordered, sorted);
+    assert_eq!(
+        members(zset.range_by_score(-1.0..=0.0)),
+        vec!["3", "0", "1"]
+    );
+    assert_eq!(zset.iter().next_back().unwrap().0, f64::INFINITY);
+}
@@ -8,6 +8,8 @@ endif
 
 RCC   := cargo build --release
 RTEST := cargo test
+RBENCH := cargo bench
+RMIRI := cargo +nightly miri test
 RF := cargo fmt
 RFC := cargo fmt -- --check
 
@@ -40,6 +42,14 @@ fmt-check:
 test: build-dir
 	$(RTEST) --target-dir $(MOD_BUILD_DIR)
 
+.PHONY: bench
+bench: build-dir
+	$(RBENCH) --target-dir $(MOD_BUILD_DIR)
+
+.PHONY: miri
+miri: build-dir
+	$(RMIRI) --target-dir $(MOD_BUILD_DIR)/miri
+
 
 .PHONY: clean
 clean: